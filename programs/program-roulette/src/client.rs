@@ -0,0 +1,468 @@
+//! Off-chain assembly of the betting flow into ready-to-sign v0 transactions.
+//!
+//! `BetSessionBuilder` composes what a frontend otherwise has to get right by hand: creating the
+//! player's `PlayerBets` and associated token accounts when they are missing, the compute budget
+//! and priority fee, and any number of bets, packed into as few v0 messages as fit the network's
+//! transaction size limit. Signing and sending is left to the caller's wallet.
+
+use anchor_lang::solana_program::{
+    address_lookup_table::AddressLookupTableAccount,
+    hash::Hash,
+    instruction::Instruction,
+    message::{v0, CompileError, VersionedMessage},
+};
+use anchor_lang::prelude::{pubkey, Pubkey};
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use anchor_spl::associated_token::{self, get_associated_token_address_with_program_id};
+use crate::{seeds, state::{Bet, GameSession, PlayerBets, VaultAccount}};
+
+/// Largest serialized transaction the network accepts, signatures included.
+pub const MAX_TRANSACTION_SIZE: usize = 1232;
+
+/// The compute budget program, which sets a transaction's compute unit limit and price.
+pub const COMPUTE_BUDGET_PROGRAM_ID: Pubkey = pubkey!("ComputeBudget111111111111111111111111111111");
+
+/// Why a bet session could not be assembled.
+#[derive(Debug)]
+pub enum BetSessionError {
+    /// The session has no bets.
+    NoBets,
+    /// A single bet does not fit in a transaction, even with the lookup tables.
+    BetTooLarge,
+    Compile(CompileError),
+}
+
+impl From<CompileError> for BetSessionError {
+    fn from(error: CompileError) -> Self {
+        BetSessionError::Compile(error)
+    }
+}
+
+/// Builds the transactions placing a player's bets on a table for the round currently taking
+/// bets. The first bet goes through `join_and_bet`, which creates the `PlayerBets` account and
+/// the player's associated token account if needed and is a plain bet otherwise; the rest use
+/// `place_bet`. Bets are paid from the player's associated token account.
+pub struct BetSessionBuilder {
+    player: Pubkey,
+    game_session: Pubkey,
+    round: u64,
+    event_queue: Option<Pubkey>,
+    table_access_list: Option<Pubkey>,
+    vault: Pubkey,
+    token_mint: Pubkey,
+    vault_token_account: Pubkey,
+    token_program: Pubkey,
+    previous_round: Option<u64>,
+    compute_unit_limit: Option<u32>,
+    compute_unit_price: Option<u64>,
+    lookup_tables: Vec<AddressLookupTableAccount>,
+    bets: Vec<Bet>,
+}
+
+impl BetSessionBuilder {
+    /// A session for `player` on the table of `game_session`, staked with `vault` through
+    /// `token_program`, the program owning the vault's mint.
+    pub fn new(player: Pubkey, game_session: &GameSession, vault: &VaultAccount, token_program: Pubkey) -> Self {
+        let game_session_key = seeds::table_game_session(game_session.table_id).0;
+        Self {
+            player,
+            game_session: game_session_key,
+            round: game_session.current_round,
+            event_queue: game_session.event_queue_enabled.then(|| seeds::event_queue(&game_session_key).0),
+            table_access_list: game_session.access_list_enabled
+                .then(|| seeds::table_access_list(&game_session_key).0),
+            vault: seeds::vault(&vault.token_mint).0,
+            token_mint: vault.token_mint,
+            vault_token_account: vault.token_account,
+            token_program,
+            previous_round: None,
+            compute_unit_limit: None,
+            compute_unit_price: None,
+            lookup_tables: Vec::new(),
+            bets: Vec::new(),
+        }
+    }
+
+    /// The player's current `PlayerBets`, if the account exists. Bets of an earlier round that
+    /// were neither claimed nor refunded need their round's stats passed along, which the
+    /// builder then does.
+    pub fn player_bets(mut self, player_bets: &PlayerBets) -> Self {
+        self.previous_round = (player_bets.round != self.round && !player_bets.bets.is_empty())
+            .then_some(player_bets.round);
+        self
+    }
+
+    /// Compute units each transaction may use.
+    pub fn compute_unit_limit(mut self, units: u32) -> Self {
+        self.compute_unit_limit = Some(units);
+        self
+    }
+
+    /// Priority fee, in micro-lamports per compute unit.
+    pub fn compute_unit_price(mut self, micro_lamports: u64) -> Self {
+        self.compute_unit_price = Some(micro_lamports);
+        self
+    }
+
+    /// Lookup tables the transactions may load accounts from; see `lookup_table_addresses`.
+    pub fn lookup_tables(mut self, lookup_tables: Vec<AddressLookupTableAccount>) -> Self {
+        self.lookup_tables = lookup_tables;
+        self
+    }
+
+    pub fn bet(mut self, bet: Bet) -> Self {
+        self.bets.push(bet);
+        self
+    }
+
+    pub fn bets(mut self, bets: impl IntoIterator<Item = Bet>) -> Self {
+        self.bets.extend(bets);
+        self
+    }
+
+    /// The accounts every bet of the session shares, other than the player's own. A lookup
+    /// table holding them lets more bets fit in each transaction.
+    pub fn lookup_table_addresses(&self) -> Vec<Pubkey> {
+        let mut addresses = vec![
+            crate::ID,
+            self.game_session,
+            self.vault,
+            seeds::vault_exposure(&self.vault, &self.game_session).0,
+            self.vault_token_account,
+            self.token_mint,
+            seeds::protocol_stats().0,
+            seeds::round_stats(&self.game_session, self.round).0,
+            seeds::config().0,
+            self.token_program,
+            associated_token::ID,
+            system_program::ID,
+            COMPUTE_BUDGET_PROGRAM_ID,
+        ];
+        addresses.extend(self.event_queue);
+        addresses.extend(self.table_access_list);
+        addresses
+    }
+
+    /// The session's instructions, in order, before packing.
+    pub fn instructions(&self) -> Vec<Instruction> {
+        let mut instructions = self.compute_budget_instructions();
+        instructions.extend(self.bets.iter().enumerate().map(|(index, bet)| self.bet_instruction(index, bet)));
+        instructions
+    }
+
+    /// Packs the session into as few v0 messages as possible, each repeating the compute budget
+    /// instructions and holding as many bets as fit in `MAX_TRANSACTION_SIZE`. The messages must
+    /// land in order, since only the first one creates missing accounts. The table still refuses
+    /// bets beyond `Config::max_bets_per_round`.
+    pub fn build(&self, recent_blockhash: Hash) -> Result<Vec<VersionedMessage>, BetSessionError> {
+        self.pack(recent_blockhash, MAX_TRANSACTION_SIZE)
+    }
+
+    fn pack(&self, recent_blockhash: Hash, max_size: usize) -> Result<Vec<VersionedMessage>, BetSessionError> {
+        if self.bets.is_empty() {
+            return Err(BetSessionError::NoBets);
+        }
+        let prefix = self.compute_budget_instructions();
+        let mut messages = Vec::new();
+        let mut packed: Option<v0::Message> = None;
+        let mut instructions = prefix.clone();
+
+        for (index, bet) in self.bets.iter().enumerate() {
+            instructions.push(self.bet_instruction(index, bet));
+            let message = self.compile(&instructions, recent_blockhash)?;
+            if transaction_size(&message) <= max_size {
+                packed = Some(message);
+                continue;
+            }
+
+            // Close the current transaction and start the next one with this bet.
+            let bet_instruction = instructions.pop().unwrap();
+            messages.push(VersionedMessage::V0(packed.take().ok_or(BetSessionError::BetTooLarge)?));
+            instructions = prefix.clone();
+            instructions.push(bet_instruction);
+            let message = self.compile(&instructions, recent_blockhash)?;
+            if transaction_size(&message) > max_size {
+                return Err(BetSessionError::BetTooLarge);
+            }
+            packed = Some(message);
+        }
+        messages.extend(packed.map(VersionedMessage::V0));
+        Ok(messages)
+    }
+
+    fn compile(&self, instructions: &[Instruction], recent_blockhash: Hash) -> Result<v0::Message, CompileError> {
+        v0::Message::try_compile(&self.player, instructions, &self.lookup_tables, recent_blockhash)
+    }
+
+    fn compute_budget_instructions(&self) -> Vec<Instruction> {
+        let mut instructions = Vec::new();
+        if let Some(units) = self.compute_unit_limit {
+            // `ComputeBudgetInstruction::SetComputeUnitLimit`
+            let data = [&[2u8][..], &units.to_le_bytes()].concat();
+            instructions.push(Instruction::new_with_bytes(COMPUTE_BUDGET_PROGRAM_ID, &data, vec![]));
+        }
+        if let Some(micro_lamports) = self.compute_unit_price {
+            // `ComputeBudgetInstruction::SetComputeUnitPrice`
+            let data = [&[3u8][..], &micro_lamports.to_le_bytes()].concat();
+            instructions.push(Instruction::new_with_bytes(COMPUTE_BUDGET_PROGRAM_ID, &data, vec![]));
+        }
+        instructions
+    }
+
+    fn bet_instruction(&self, index: usize, bet: &Bet) -> Instruction {
+        let player_bets = seeds::player_bets(&self.game_session, &self.player).0;
+        let player_token_account =
+            get_associated_token_address_with_program_id(&self.player, &self.token_mint, &self.token_program);
+        let round_stats = seeds::round_stats(&self.game_session, self.round).0;
+        let player_stats = seeds::player_stats(&self.player, &self.token_mint).0;
+        let vault_exposure = seeds::vault_exposure(&self.vault, &self.game_session).0;
+        // Only the session's first bet can replace bets of an earlier round.
+        let previous_round_stats = self.previous_round
+            .filter(|_| index == 0)
+            .map(|round| seeds::round_stats(&self.game_session, round).0);
+
+        if index == 0 {
+            let accounts = crate::accounts::JoinAndBet {
+                player: self.player,
+                game_session: self.game_session,
+                player_bets,
+                vault: self.vault,
+                token_mint: self.token_mint,
+                protocol_stats: seeds::protocol_stats().0,
+                round_stats,
+                previous_round_stats,
+                config: seeds::config().0,
+                table_access_list: self.table_access_list,
+                event_queue: self.event_queue,
+                player_stats,
+                vault_exposure,
+                player_token_account,
+                vault_token_account: self.vault_token_account,
+                token_program: self.token_program,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            };
+            return Instruction {
+                program_id: crate::ID,
+                accounts: accounts.to_account_metas(None),
+                data: crate::instruction::JoinAndBet { bet: bet.clone() }.data(),
+            };
+        }
+
+        let accounts = crate::accounts::PlaceBets {
+            vault: self.vault,
+            game_session: self.game_session,
+            player_token_account,
+            vault_token_account: self.vault_token_account,
+            player: self.player,
+            player_bets,
+            token_mint: self.token_mint,
+            protocol_stats: seeds::protocol_stats().0,
+            round_stats,
+            previous_round_stats,
+            config: seeds::config().0,
+            table_access_list: self.table_access_list,
+            event_queue: self.event_queue,
+            player_stats,
+            vault_exposure,
+            token_program: self.token_program,
+            system_program: system_program::ID,
+        };
+        Instruction {
+            program_id: crate::ID,
+            accounts: accounts.to_account_metas(None),
+            data: crate::instruction::PlaceBet { bet: bet.clone() }.data(),
+        }
+    }
+}
+
+/// The transactions seeding a demo environment with the `fast-mode` instructions, in the order
+/// they must land: the protocol-level `GameSession`, `ProtocolStats` and `Config`, the table
+/// `table_id`, a vault of the test token holding `liquidity`, then one transaction per player
+/// funding them with `player_amount` and creating their `PlayerBets`. `admin` signs every
+/// transaction and pays all rent; for a table other than 0 it must be `GAME_ADMIN_PUBKEY`.
+/// Every address is derived from these arguments, so the same arguments reproduce the same
+/// environment. Instructions creating accounts that already exist fail, so seed a fresh cluster.
+#[cfg(feature = "fast-mode")]
+pub fn demo_environment_transactions(
+    admin: Pubkey,
+    token_program: Pubkey,
+    table_id: u64,
+    liquidity: u64,
+    players: &[Pubkey],
+    player_amount: u64
+) -> Vec<Vec<Instruction>> {
+    use anchor_lang::solana_program::sysvar;
+
+    let protocol_session = seeds::game_session().0;
+    let game_session = seeds::table_game_session(table_id).0;
+    let test_mint = seeds::test_mint().0;
+    let vault = seeds::vault(&test_mint).0;
+    let instruction = |accounts: &dyn ToAccountMetas, data: Vec<u8>| Instruction {
+        program_id: crate::ID,
+        accounts: accounts.to_account_metas(None),
+        data,
+    };
+    let initialize_game_session = |table_id: u64, game_session: Pubkey| instruction(
+        &crate::accounts::InitializeGameSession {
+            authority: admin,
+            game_session,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        crate::instruction::InitializeGameSession { table_id }.data()
+    );
+
+    let mut transactions = vec![vec![
+        initialize_game_session(crate::constants::LEGACY_TABLE_ID, protocol_session),
+        instruction(
+            &crate::accounts::InitializeProtocolStats {
+                authority: admin,
+                game_session: protocol_session,
+                protocol_stats: seeds::protocol_stats().0,
+                system_program: system_program::ID,
+            },
+            crate::instruction::InitializeProtocolStats {}.data()
+        ),
+        instruction(
+            &crate::accounts::InitializeConfig {
+                authority: admin,
+                game_session: protocol_session,
+                config: seeds::config().0,
+                system_program: system_program::ID,
+            },
+            crate::instruction::InitializeConfig {}.data()
+        ),
+    ]];
+    if table_id != crate::constants::LEGACY_TABLE_ID {
+        transactions.push(vec![initialize_game_session(table_id, game_session)]);
+    }
+    transactions.push(vec![instruction(
+        &crate::accounts::SeedDemoVault {
+            authority: admin,
+            game_session: protocol_session,
+            test_mint,
+            vault,
+            vault_token_account: get_associated_token_address_with_program_id(&vault, &test_mint, &token_program),
+            provider_state: seeds::provider_state(&vault, &admin).0,
+            protocol_stats: seeds::protocol_stats().0,
+            token_program,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        crate::instruction::SeedDemoVault { liquidity }.data()
+    )]);
+    transactions.extend(players.iter().map(|player| vec![instruction(
+        &crate::accounts::SeedDemoPlayer {
+            authority: admin,
+            game_session,
+            player: *player,
+            test_mint,
+            player_token_account: get_associated_token_address_with_program_id(player, &test_mint, &token_program),
+            player_bets: seeds::player_bets(&game_session, player).0,
+            protocol_stats: seeds::protocol_stats().0,
+            token_program,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        crate::instruction::SeedDemoPlayer { amount: player_amount }.data()
+    )]));
+    transactions
+}
+
+/// Serialized size of a transaction carrying `message` and its signatures.
+fn transaction_size(message: &v0::Message) -> usize {
+    let signatures = message.header.num_required_signatures as usize;
+    // The signature count is a compact-u16, a single byte for any realistic count.
+    1 + signatures * 64 + message.serialize().len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::{AnchorDeserialize, Discriminator};
+    use anchor_spl::token;
+
+    fn session(event_queue: bool, access_list: bool) -> BetSessionBuilder {
+        let game_session = GameSession {
+            table_id: 7,
+            current_round: 3,
+            event_queue_enabled: event_queue,
+            access_list_enabled: access_list,
+            ..Default::default()
+        };
+        let mut vault = VaultAccount::deserialize(&mut &[0u8; std::mem::size_of::<VaultAccount>()][..]).unwrap();
+        vault.token_mint = Pubkey::new_unique();
+        vault.token_account = Pubkey::new_unique();
+        BetSessionBuilder::new(Pubkey::new_unique(), &game_session, &vault, token::ID)
+            .compute_unit_limit(200_000)
+            .compute_unit_price(1_000)
+    }
+
+    fn red(amount: u64) -> Bet {
+        Bet { amount, bet_type: 8, numbers: [0; 4] }
+    }
+
+    /// The discriminators of the bet instructions in `message`, in order.
+    fn bet_discriminators(message: &VersionedMessage) -> Vec<Vec<u8>> {
+        let VersionedMessage::V0(message) = message else { panic!("not a v0 message") };
+        message.instructions
+            .iter()
+            .filter(|instruction| message.account_keys[instruction.program_id_index as usize] == crate::ID)
+            .map(|instruction| instruction.data[..8].to_vec())
+            .collect()
+    }
+
+    #[test]
+    fn single_bet_joins_in_one_message() {
+        let messages = session(false, false).bet(red(100)).build(Hash::default()).unwrap();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(bet_discriminators(&messages[0]), vec![crate::instruction::JoinAndBet::DISCRIMINATOR.to_vec()]);
+        let VersionedMessage::V0(message) = &messages[0] else { unreachable!() };
+        assert_eq!(message.instructions.len(), 3, "two compute budget instructions and the bet");
+    }
+
+    #[test]
+    fn bets_beyond_one_transaction_split_across_messages() {
+        let builder = session(false, false).bets((0..40).map(red));
+        let messages = builder.build(Hash::default()).unwrap();
+        assert!(messages.len() > 1);
+
+        let discriminators: Vec<_> = messages.iter().flat_map(bet_discriminators).collect();
+        assert_eq!(discriminators.len(), 40);
+        assert_eq!(discriminators[0], crate::instruction::JoinAndBet::DISCRIMINATOR);
+        assert!(discriminators[1..].iter().all(|data| data == crate::instruction::PlaceBet::DISCRIMINATOR));
+        for message in &messages {
+            let VersionedMessage::V0(message) = message else { unreachable!() };
+            assert!(transaction_size(message) <= MAX_TRANSACTION_SIZE);
+            assert_eq!(message.instructions[0].data[0], 2, "every message repeats the compute unit limit");
+        }
+    }
+
+    #[test]
+    fn bet_that_fits_no_transaction_is_too_large() {
+        let builder = session(true, true).bet(red(100));
+        assert!(matches!(builder.pack(Hash::default(), 256), Err(BetSessionError::BetTooLarge)));
+        assert!(matches!(session(false, false).build(Hash::default()), Err(BetSessionError::NoBets)));
+    }
+
+    #[test]
+    fn event_queue_and_access_list_are_passed_only_when_enabled() {
+        let builder = session(true, true).bets([red(100), red(200)]);
+        let game_session = seeds::table_game_session(7).0;
+        let event_queue = seeds::event_queue(&game_session).0;
+        let access_list = seeds::table_access_list(&game_session).0;
+        for instruction in &builder.instructions()[2..] {
+            let keys: Vec<_> = instruction.accounts.iter().map(|meta| meta.pubkey).collect();
+            assert!(keys.contains(&event_queue));
+            assert!(keys.contains(&access_list));
+        }
+        assert!(builder.lookup_table_addresses().contains(&event_queue));
+        assert!(builder.lookup_table_addresses().contains(&access_list));
+
+        let builder = session(false, false).bets([red(100), red(200)]);
+        for instruction in &builder.instructions()[2..] {
+            assert!(!instruction.accounts.iter().any(|meta| meta.pubkey == event_queue || meta.pubkey == access_list));
+        }
+    }
+}
@@ -3,8 +3,18 @@ use anchor_lang::prelude::*;
 pub const TREASURY_PUBKEY: Pubkey = pubkey!("DELAFDnkiiqu9amML7drYNLmcVALXboRQ2yf4DQeDTMr");
 pub const GAME_ADMIN_PUBKEY: Pubkey = pubkey!("RBAd8hvSpJMtBu5o2BJytCBvy9wy6UKJvebDf7wRw7A");
 pub const CREATE_VAULT_FEE_SOL_LAMPORTS: u64 = 237_000_000;
+/// Bond staked with `flag_round` against a vault with no other open dispute; it doubles with
+/// each dispute already open on the vault. Returned if the dispute is upheld, slashed to the
+/// `SolFeeVault` if it is rejected.
+pub const DISPUTE_BOND_LAMPORTS: u64 = 500_000_000;
 
-pub const MAX_BETS_PER_ROUND: usize = 6; // Example limit for space calculation
+/// Hard ceiling of bets per player per round. The effective limit is
+/// `Config::max_bets_per_round`, which may be raised up to this value.
+pub const MAX_BETS_PER_ROUND: usize = 12;
+
+/// `Config::max_bets_per_round` of a new config. `PlayerBets` accounts are created with room for
+/// this many bets and grow past it.
+pub const DEFAULT_MAX_BETS_PER_ROUND: usize = 6;
 
 /// Divisor for calculating liquidity provider rewards (~1.4%).
 pub const PROVIDER_DIVISOR: u64 = 71;
@@ -20,4 +30,317 @@ pub const MAX_BET_PERCENTAGE: u64 = 11;
 pub const MAX_BET_PERCENTAGE_DIVISOR: u64 = 100;
 
 /// Maximum valid numerical value for a bet type enum.
-pub const BET_TYPE_MAX: u8 = 15;
\ No newline at end of file
+pub const BET_TYPE_MAX: u8 = 16;
+
+/// Nominal duration of a slot, used to translate second-based durations into slot counts.
+pub const SLOT_DURATION_MS: u64 = 400;
+/// Denominator for all basis-point values.
+pub const BPS_DENOMINATOR: u64 = 10_000;
+/// Share of a liquidity provider's reward accrual paid to their registered referrer (5%).
+pub const LP_REFERRAL_BPS: u64 = 500;
+/// Default share of the nominal slot count a duration may fall short by (slots run slower
+/// than nominal when leaders skip them).
+#[cfg(not(feature = "fast-mode"))]
+pub const DEFAULT_SLOT_DRIFT_TOLERANCE_BPS: u16 = 2_500;
+/// `fast-mode` builds only check the clock, since local validators produce slots irregularly.
+#[cfg(feature = "fast-mode")]
+pub const DEFAULT_SLOT_DRIFT_TOLERANCE_BPS: u16 = 10_000;
+
+/// Default number of slots after `close_bets` after which an unsettled round can be voided by
+/// anyone (about one hour).
+pub const DEFAULT_VOID_TIMEOUT_SLOTS: u64 = 9_000;
+
+/// Slots over which the `RentPool` counts the `PlayerBets` accounts it sponsors (about one hour).
+pub const RENT_SPONSOR_WINDOW_SLOTS: u64 = 9_000;
+
+/// Most `PlayerBets` accounts the `RentPool` sponsors per `RENT_SPONSOR_WINDOW_SLOTS`, bounding
+/// how fast freshly made wallets can lock up its lamports.
+pub const MAX_SPONSORED_PER_WINDOW: u16 = 100;
+
+/// Default slots after settlement before `finalize_round` can mark a round final, the depth at
+/// which the cluster itself considers a slot finalized.
+pub const DEFAULT_FINALITY_SLOTS: u64 = 32;
+
+/// Seconds after a round started after which anyone may cancel it if it has not completed.
+pub const CANCEL_ROUND_TIMEOUT_SECS: u32 = 86_400;
+
+/// Slots that must pass after `close_bets` before `get_random` settles the round, so the slot
+/// hashes it mixes in were produced after the bets were final.
+pub const MIN_SETTLEMENT_SLOTS: u64 = 4;
+/// Number of most recent `SlotHashes` entries folded into the winning number.
+pub const SLOT_HASHES_MIXED: usize = 2;
+/// Slots between `lock_randomness_seed` and the target slot whose hash decides the round.
+pub const REVEAL_SLOT_DELAY: u64 = 8;
+
+/// Table id of the legacy `[GAME_SESSION_SEED]` session, which keeps its address as table 0.
+pub const LEGACY_TABLE_ID: u64 = 0;
+/// Current `GameSession::layout_version`; `migrate_session_to_table` and `migrate_game_session`
+/// upgrade older sessions. Version 2 added the table rules from `wheel_type` on, version 3
+/// `recent_numbers`, version 4 `access_list_enabled`, version 5 `bound_vault`, version 6
+/// `frozen`, version 7 `locked_server_seed` and version 8 the table's `random_initiators`.
+pub const GAME_SESSION_LAYOUT_VERSION: u8 = 8;
+/// Current `RoundStats::layout_version`; `migrate_round_stats` upgrades older round stats.
+/// Version 2 added `status`, version 3 `finality_payout_threshold` and version 4 the round's
+/// `fallback_slot` and `void_slot`.
+pub const ROUND_STATS_LAYOUT_VERSION: u8 = 4;
+/// Current `PlayerBets` layout version, kept in the account's last byte. Version 0 is the
+/// original layout, which also stored the token mint and sized bets with their padding.
+pub const PLAYER_BETS_LAYOUT_VERSION: u8 = 1;
+/// Current `VaultAccount::layout_version`; `migrate_vault` upgrades older vaults. Version 0 is
+/// the original, unversioned vault.
+pub const VAULT_LAYOUT_VERSION: u8 = 1;
+/// Current `ProviderState::layout_version`; `migrate_provider_state` upgrades older positions.
+/// Version 0 is the original, unversioned position.
+pub const PROVIDER_STATE_LAYOUT_VERSION: u8 = 1;
+/// Current `Config::layout_version`; `migrate_config` upgrades an older config.
+pub const CONFIG_LAYOUT_VERSION: u8 = 1;
+
+/// An announced large withdrawal may execute after this long even if no round has completed,
+/// so LP capital can never be locked by a stalled game.
+#[cfg(not(feature = "fast-mode"))]
+pub const LARGE_WITHDRAWAL_TIMEOUT_SECS: u32 = 86_400;
+#[cfg(feature = "fast-mode")]
+pub const LARGE_WITHDRAWAL_TIMEOUT_SECS: u32 = 10;
+
+/// Ceiling on the effective `min_random_delay_secs` in `fast-mode` builds.
+#[cfg(feature = "fast-mode")]
+pub const FAST_MODE_MAX_RANDOM_DELAY_SECS: u32 = 2;
+/// Most test tokens `test_faucet` mints per call in `fast-mode` builds.
+#[cfg(feature = "fast-mode")]
+pub const TEST_FAUCET_MAX_AMOUNT: u64 = 1_000_000_000_000;
+
+/// Number of key slots in the native admin multisig.
+pub const MAX_ADMIN_KEYS: usize = 3;
+
+/// Number of randomness oracles a table can aggregate in `MultiOracle` mode.
+pub const MAX_RANDOMNESS_ORACLES: usize = 3;
+
+/// Number of operators that can take turns drawing round randomness.
+pub const MAX_RANDOM_INITIATORS: usize = 8;
+
+/// Number of players a private table's `TableAccessList` can admit.
+pub const MAX_TABLE_ACCESS_LIST_LEN: usize = 64;
+
+/// Number of wallets allowed to bet on experimental bet types during their rollout.
+pub const MAX_BETA_TESTERS: usize = 16;
+
+/// Number of per-mint volume buckets tracked by `ProtocolStats`.
+pub const MAX_TRACKED_MINTS: usize = 16;
+
+/// Maximum number of recipients a vault's owner revenue can be split between.
+pub const MAX_OWNER_SHARES: usize = 4;
+
+/// Maximum length of a table's display name, in bytes.
+pub const MAX_TABLE_NAME_LEN: usize = 32;
+
+/// Maximum length of a round's label, in bytes.
+pub const MAX_ROUND_LABEL_LEN: usize = 32;
+/// Maximum length of a round's URI, in bytes.
+pub const MAX_ROUND_URI_LEN: usize = 128;
+
+/// Metaplex Core program, which owns the assets LP positions are wrapped into.
+pub const MPL_CORE_PROGRAM_ID: Pubkey = pubkey!("CoREENxT6tW1HoK8ypY1SxRMZTcVPm7R94rH4PZNhX7d");
+
+/// ORAO VRF program, used with the `orao-vrf` feature.
+pub const ORAO_VRF_PROGRAM_ID: Pubkey = pubkey!("VRFzZoJdhFWL8rkvu87LpKM3RbcVezpMEc6X5GVDr7y");
+/// Seed of ORAO's network configuration account.
+pub const ORAO_NETWORK_CONFIG_SEED: &[u8] = b"orao-vrf-network-configuration";
+/// Seed of ORAO's per-request randomness account.
+pub const ORAO_RANDOMNESS_SEED: &[u8] = b"orao-vrf-randomness-request";
+
+/// Seed, under the whitelisted strategy program and followed by the vault's address, of the
+/// authority that must own the token account holding that vault's strategy funds.
+pub const STRATEGY_CUSTODY_SEED: &[u8] = b"strategy-custody";
+
+/// Switchboard On-Demand program, which owns the randomness accounts `settle_round_vrf` reads.
+#[cfg(not(feature = "devnet"))]
+pub const SWITCHBOARD_ON_DEMAND_PROGRAM_ID: Pubkey =
+    pubkey!("SBondMDrcV3K4kxZR1HNVT7osZxAHVHgYXL5Ze1oMUv");
+#[cfg(feature = "devnet")]
+pub const SWITCHBOARD_ON_DEMAND_PROGRAM_ID: Pubkey =
+    pubkey!("Aio4gaXjXzJNVLtzwtNVmSqGKpANtXhybbkhtAC94ji2");
+/// Account discriminator of Switchboard's `RandomnessAccountData`.
+pub const SWITCHBOARD_RANDOMNESS_DISCRIMINATOR: [u8; 8] = [10, 66, 229, 135, 220, 239, 217, 114];
+
+/// Number of pockets on a single-zero wheel (0-36).
+pub const WHEEL_NUMBERS: usize = 37;
+/// Number of pockets on the largest supported wheel, the American one, which adds 00.
+pub const WHEEL_POCKETS: usize = 38;
+/// Pocket number standing for the American wheel's 00.
+pub const DOUBLE_ZERO: u8 = 37;
+
+/// Number of winning numbers kept in `GameSession::recent_numbers`.
+pub const RECENT_NUMBERS_LEN: usize = 20;
+/// Marks a slot of `GameSession::recent_numbers` no settled round has filled yet.
+pub const NO_RECENT_NUMBER: u8 = u8::MAX;
+
+/// Number of past rounds kept in a table's `RoundHistory`.
+pub const ROUND_HISTORY_LEN: usize = 128;
+
+/// Number of records a table's `EventQueue` holds before overwriting unconsumed ones. Kept
+/// within the 4 KiB Borsh reserves up front for a `Vec`, so loading the queue takes a single
+/// heap buffer rather than one per doubling.
+pub const EVENT_QUEUE_LEN: usize = 56;
+
+/// Size of the per-round unique-bettor bloom filter, in 64-bit words (1024 bits).
+pub const BETTOR_FILTER_WORDS: usize = 16;
+/// Bits set per bettor in the bloom filter.
+pub const BETTOR_FILTER_HASHES: usize = 3;
+
+// =================================================================================================
+// Bet Coverage Table
+// =================================================================================================
+
+/// Number of bet types (`0..=BET_TYPE_MAX`).
+pub const BET_TYPE_COUNT: usize = BET_TYPE_MAX as usize + 1;
+
+/// Payout multiplier of each bet type, stake included.
+pub const PAYOUT_MULTIPLIERS: [u64; BET_TYPE_COUNT] = [
+    36, // Straight
+    18, // Split
+    9, // Corner
+    12, // Street
+    6, // Six Line
+    9, // First Four
+    2, 2, 2, 2, 2, 2, // Red/Black/Even/Odd/Manque/Passe
+    3, 3, 3, 3, // Column/Dozens
+    7, // Basket (0, 00, 1, 2, 3)
+];
+
+pub const RED_NUMBERS: [u8; 18] = [1, 3, 5, 7, 9, 12, 14, 16, 18, 19, 21, 23, 25, 27, 30, 32, 34, 36];
+
+/// `COVERAGE_TABLE[bet_type][anchor]` is the mask of winning numbers (bit `n` for number `n`,
+/// bit `DOUBLE_ZERO` for 00) of a bet anchored at `numbers[0] == anchor`, or 0 if that anchor
+/// is invalid. Bet types that take no numbers use anchor 0. Splits are two-number bets and use
+/// `SPLIT_NEIGHBOURS` instead.
+///
+/// Placement validation and settlement both read this table through `bet_coverage`, so they
+/// can never disagree about what a bet covers.
+pub const COVERAGE_TABLE: [[u64; WHEEL_POCKETS]; BET_TYPE_COUNT] = build_coverage_table();
+
+/// `SPLIT_NEIGHBOURS[n]` is the mask of numbers adjacent to `n` on the layout, including 0
+/// with 1, 2 and 3, and 00 with 0, 2 and 3.
+pub const SPLIT_NEIGHBOURS: [u64; WHEEL_POCKETS] = build_split_neighbours();
+
+const fn bit(number: usize) -> u64 {
+    1u64 << number
+}
+
+/// Mask of every `step`-th number from `first` up to `last`.
+const fn range_mask(first: usize, last: usize, step: usize) -> u64 {
+    let mut mask = 0;
+    let mut number = first;
+    while number <= last {
+        mask |= bit(number);
+        number += step;
+    }
+    mask
+}
+
+const fn build_split_neighbours() -> [u64; WHEEL_POCKETS] {
+    let double_zero = DOUBLE_ZERO as usize;
+    let mut table = [0u64; WHEEL_POCKETS];
+    table[0] = range_mask(1, 3, 1) | bit(double_zero);
+    table[double_zero] = bit(0) | bit(2) | bit(3);
+    let mut number = 1;
+    while number <= 36 {
+        let mut mask = 0;
+        if number <= 3 {
+            mask |= bit(0);
+        }
+        if number == 2 || number == 3 {
+            mask |= bit(double_zero);
+        }
+        if number > 3 {
+            mask |= bit(number - 3);
+        }
+        if number + 3 <= 36 {
+            mask |= bit(number + 3);
+        }
+        if number % 3 != 1 {
+            mask |= bit(number - 1);
+        }
+        if number % 3 != 0 {
+            mask |= bit(number + 1);
+        }
+        table[number] = mask;
+        number += 1;
+    }
+    table
+}
+
+const fn build_coverage_table() -> [[u64; WHEEL_POCKETS]; BET_TYPE_COUNT] {
+    let mut table = [[0u64; WHEEL_POCKETS]; BET_TYPE_COUNT];
+
+    let mut red = 0;
+    let mut i = 0;
+    while i < RED_NUMBERS.len() {
+        red |= bit(RED_NUMBERS[i] as usize);
+        i += 1;
+    }
+    let all_numbers = range_mask(1, 36, 1);
+
+    let mut anchor = 0;
+    while anchor < WHEEL_POCKETS {
+        table[0][anchor] = bit(anchor); // Straight
+        if anchor >= 1 && anchor <= 32 && anchor % 3 != 0 {
+            table[2][anchor] = bit(anchor) | bit(anchor + 1) | bit(anchor + 3) | bit(anchor + 4); // Corner
+        }
+        if anchor >= 1 && anchor <= 34 && (anchor - 1) % 3 == 0 {
+            table[3][anchor] = range_mask(anchor, anchor + 2, 1); // Street
+        }
+        if anchor >= 1 && anchor <= 31 && (anchor - 1) % 3 == 0 {
+            table[4][anchor] = range_mask(anchor, anchor + 5, 1); // Six Line
+        }
+        if anchor >= 1 && anchor <= 3 {
+            table[12][anchor] = range_mask(anchor, 36, 3); // Column
+        }
+        anchor += 1;
+    }
+
+    table[5][0] = range_mask(0, 3, 1); // First Four
+    table[6][0] = red; // Red
+    table[7][0] = all_numbers & !red; // Black
+    table[8][0] = range_mask(2, 36, 2); // Even
+    table[9][0] = range_mask(1, 35, 2); // Odd
+    table[10][0] = range_mask(1, 18, 1); // Manque (1-18)
+    table[11][0] = range_mask(19, 36, 1); // Passe (19-36)
+    table[13][0] = range_mask(1, 12, 1); // P12 (Dozen 1)
+    table[14][0] = range_mask(13, 24, 1); // M12 (Dozen 2)
+    table[15][0] = range_mask(25, 36, 1); // D12 (Dozen 3)
+    table[16][0] = range_mask(0, 3, 1) | bit(DOUBLE_ZERO as usize); // Basket (0, 00, 1, 2, 3)
+    table
+}
+
+/// Coverage mask of a bet, or `None` if `numbers` are not a valid layout for `bet_type`. Only
+/// the leading numbers a bet type uses are read; the rest are ignored.
+pub const fn bet_coverage(bet_type: u8, numbers: &[u8; 4]) -> Option<u64> {
+    let bet_type = bet_type as usize;
+    if bet_type >= BET_TYPE_COUNT {
+        return None;
+    }
+    let (first, second) = (numbers[0] as usize, numbers[1] as usize);
+    let mask = match bet_type {
+        // Split: two adjacent numbers.
+        1 => {
+            if
+                first >= WHEEL_POCKETS ||
+                second >= WHEEL_POCKETS ||
+                SPLIT_NEIGHBOURS[first] & bit(second) == 0
+            {
+                return None;
+            }
+            bit(first) | bit(second)
+        }
+        // Bets anchored at their first number.
+        0 | 2 | 3 | 4 | 12 => {
+            if first >= WHEEL_POCKETS {
+                return None;
+            }
+            COVERAGE_TABLE[bet_type][first]
+        }
+        // Outside bets take no numbers.
+        _ => COVERAGE_TABLE[bet_type][0],
+    };
+    if mask == 0 { None } else { Some(mask) }
+}
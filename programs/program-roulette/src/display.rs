@@ -0,0 +1,43 @@
+//! Conversions between raw token amounts and their decimal-adjusted display values.
+//!
+//! Bets, payouts and liquidity are recorded in the mint's base units. Clients should convert
+//! them with `VaultAccount::mint_decimals` through this module rather than re-implementing the
+//! scaling, which is easy to get wrong with floats.
+
+/// Decimal-adjusted value of `raw`, for charts and arithmetic. Not exact above 2^53 base
+/// units; use `format_amount` for display.
+pub fn to_ui_amount(raw: u64, decimals: u8) -> f64 {
+    raw as f64 / 10f64.powi(decimals as i32)
+}
+
+/// `raw` as an exact decimal string, without trailing zeros: 1_500_000 with 6 decimals is
+/// `"1.5"`.
+pub fn format_amount(raw: u64, decimals: u8) -> String {
+    let decimals = decimals as usize;
+    let digits = format!("{:0>width$}", raw, width = decimals + 1);
+    let (whole, fraction) = digits.split_at(digits.len() - decimals);
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        whole.to_string()
+    } else {
+        format!("{}.{}", whole, fraction)
+    }
+}
+
+/// `format_amount` followed by the token's symbol, e.g. `"1.5 USDC"`.
+pub fn format_amount_with_symbol(raw: u64, decimals: u8, symbol: &str) -> String {
+    format!("{} {}", format_amount(raw, decimals), symbol)
+}
+
+/// Parses a decimal string such as `"1.5"` into base units. Fails on malformed input, on more
+/// fractional digits than `decimals` and on amounts that overflow a `u64`.
+pub fn parse_amount(amount: &str, decimals: u8) -> Option<u64> {
+    let (whole, fraction) = amount.split_once('.').unwrap_or((amount, ""));
+    if (whole.is_empty() && fraction.is_empty()) || fraction.len() > decimals as usize {
+        return None;
+    }
+    if !whole.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    format!("{}{:0<width$}", whole, fraction, width = decimals as usize).parse().ok()
+}
@@ -70,4 +70,269 @@ pub enum RouletteError {
     ProviderLimitReached,
     #[msg("Only the game authority can perform this operation.")]
     AdminOnly,
-}
\ No newline at end of file
+    #[msg("The minimum delay between closing bets and generating the random number has not elapsed.")]
+    RandomDelayNotElapsed,
+    #[msg("The claim window for this round has expired.")]
+    ClaimWindowExpired,
+    #[msg("Invalid timing configuration.")]
+    InvalidTimingConfig,
+    #[msg("Withdrawals of this size must be announced with request_large_withdrawal first.")]
+    LargeWithdrawalNotAnnounced,
+    #[msg("The announced withdrawal is not unlocked yet.")]
+    LargeWithdrawalLocked,
+    #[msg("Basis-point value exceeds 10000.")]
+    InvalidBasisPoints,
+    #[msg("Not enough admin keys signed this instruction.")]
+    AdminThresholdNotMet,
+    #[msg("Invalid admin multisig: an admin key repeats or the threshold exceeds the number of admin keys.")]
+    InvalidAdminMultisig,
+    #[msg("The player has winning bets in this round; use claim_my_winnings.")]
+    RoundHasWinnings,
+    #[msg("This vault is not accepting bets in the current round.")]
+    VaultNotAcceptingBets,
+    #[msg("Bet amount must be a multiple of the vault's chip size.")]
+    InvalidChipAmount,
+    #[msg("Revealed server seed does not match the round's commitment.")]
+    SeedRevealMismatch,
+    #[msg("A referrer is already registered for this liquidity provider.")]
+    ReferrerAlreadySet,
+    #[msg("A liquidity provider cannot refer themselves.")]
+    SelfReferral,
+    #[msg("The registered hedging program must be passed first in remaining accounts.")]
+    HedgingProgramMismatch,
+    #[msg("Owner fees of this vault must be withdrawn through the protocol token swap.")]
+    RakeMustBeSwapped,
+    #[msg("Protocol token rake is not enabled for this vault.")]
+    RakeSwapNotEnabled,
+    #[msg("The rake swap returned less than the minimum amount out.")]
+    RakeSwapSlippage,
+    #[msg("The rake swap did not consume the full owner fee.")]
+    RakeSwapIncomplete,
+    #[msg("The vault cannot cover this claim in full; pass the next payout debt record.")]
+    PayoutDebtRecordRequired,
+    #[msg("A payout debt record was passed, but the claim is fully covered.")]
+    NoPayoutShortfall,
+    #[msg("Payout debt records must be repaid in order, oldest first.")]
+    PayoutDebtOutOfOrder,
+    #[msg("No outstanding payout debt was repaid.")]
+    NoOutstandingDebt,
+    #[msg("The jackpot can only be published for a completed round.")]
+    JackpotRoundNotCompleted,
+    #[msg("The Merkle proof does not match the published jackpot root.")]
+    InvalidJackpotProof,
+    #[msg("The claim exceeds the remaining jackpot funds.")]
+    JackpotExhausted,
+    #[msg("The table name is too long.")]
+    TableNameTooLong,
+    #[msg("The player bets belong to a different table.")]
+    TableMismatch,
+    #[msg("The bet limit must be between 1 and the MAX_BETS_PER_ROUND ceiling.")]
+    InvalidMaxBetsPerRound,
+    #[msg("The source account does not hold enough lamports for this transfer.")]
+    InsufficientLamports,
+    #[msg("The transfer would leave the source account below its rent-exempt minimum.")]
+    SourceNotRentExempt,
+    #[msg("The destination account would not be rent-exempt after the transfer.")]
+    DestinationNotRentExempt,
+    #[msg("The destination must be a system-owned wallet.")]
+    DestinationNotSystemOwned,
+    #[msg("No yield strategy program is whitelisted, or a different one was passed.")]
+    StrategyProgramMismatch,
+    #[msg("The deployment exceeds the vault's strategy allocation.")]
+    StrategyAllocationExceeded,
+    #[msg("Liquidity cannot be deployed while bets are closed and awaiting settlement.")]
+    StrategyDeployDuringSettlement,
+    #[msg("The vault has liquidity deployed to its yield strategy; recall it first.")]
+    StrategyFundsNotRecalled,
+    #[msg("The strategy did not take exactly the deployed amount.")]
+    StrategyDepositMismatch,
+    #[msg("Winnings for this round were already claimed or verified.")]
+    ClaimAlreadyRecorded,
+    #[msg("A VRF request is pending for this round; settle it through its VRF backend.")]
+    VrfRequestPending,
+    #[msg("Randomness account is not a valid Switchboard randomness account for this round.")]
+    InvalidRandomnessAccount,
+    #[msg("Randomness must be committed in the same transaction as the request.")]
+    RandomnessAlreadyRevealed,
+    #[msg("Randomness has not been revealed in the current slot.")]
+    RandomnessNotRevealed,
+    #[msg("LP position is already wrapped.")]
+    LpPositionAlreadyWrapped,
+    #[msg("LP position is not wrapped.")]
+    LpPositionNotWrapped,
+    #[msg("LP position is wrapped or controlled by another account; only its controller can add to it.")]
+    LpPositionNotControlled,
+    #[msg("Signer is neither the owner nor a delegate approved for the bet amount of the token account.")]
+    BetSourceNotAuthorized,
+    #[msg("Test faucet amount exceeds the per-call limit.")]
+    TestFaucetLimitExceeded,
+    #[msg("SlotHashes sysvar is missing or holds too few entries.")]
+    InvalidSlotHashes,
+    #[msg("A vault accounting invariant does not hold.")]
+    VaultInvariantViolated,
+    #[msg("Only completed rounds can be disputed.")]
+    RoundNotDisputable,
+    #[msg("Reserve distribution is blocked while a round dispute against the vault is open.")]
+    RoundDisputeOpen,
+    #[msg("Randomness seed is locked; settle the round with reveal_winning_number.")]
+    RandomnessSeedLocked,
+    #[msg("Randomness seed has not been locked for this round.")]
+    RandomnessSeedNotLocked,
+    #[msg("The reveal target slot has not passed yet.")]
+    RevealSlotNotReached,
+    #[msg("Game session already uses the current layout.")]
+    SessionAlreadyMigrated,
+    #[msg("Stored round result does not re-derive from its inputs.")]
+    RoundResultMismatch,
+    #[msg("Randomness mode is not available in this build.")]
+    RandomnessModeUnavailable,
+    #[msg("Randomness mode cannot change while a round is being settled.")]
+    RandomnessModeLocked,
+    #[msg("The round result account is required in InternalHash mode.")]
+    RoundResultRequired,
+    #[msg("Randomness oracles must be distinct, and at least one must be registered.")]
+    InvalidOracleConfig,
+    #[msg("Not every registered randomness oracle has been fulfilled for this round.")]
+    OracleQuorumNotMet,
+    #[msg("Confidential payouts are not enabled for this vault.")]
+    ConfidentialPayoutsDisabled,
+    #[msg("The vault mint is not a Token-2022 mint with confidential transfers.")]
+    ConfidentialTransferMintRequired,
+    #[msg("The round can only be voided once its settlement has timed out.")]
+    VoidTimeoutNotReached,
+    #[msg("The round was not voided.")]
+    RoundNotVoided,
+    #[msg("No bets to refund for this round.")]
+    NothingToRefund,
+    #[msg("Random initiators must be distinct, non-default keys, at most the maximum.")]
+    InvalidRandomInitiators,
+    #[msg("Signer is not the random initiator whose turn it is this round.")]
+    NotRoundInitiator,
+    #[msg("Portfolio accounts must be (ProviderState, VaultAccount) pairs of the given provider.")]
+    InvalidPortfolioAccounts,
+    #[msg("The player's bets this round could pay out more than the vault's per-player cap.")]
+    PlayerPayoutCapExceeded,
+    #[msg("Only the game admin can close bets before the betting window has elapsed.")]
+    BettingWindowOpen,
+    #[msg("The fallback randomness mode must differ from the primary one.")]
+    InvalidRandomnessFallback,
+    #[msg("The crank reward needs the vault, its mint and token account, the cranker's token account and the token program.")]
+    CrankRewardAccountsRequired,
+    #[msg("The round has not accepted bets for its minimum betting duration yet.")]
+    MinBettingDurationNotElapsed,
+    #[msg("The cooldown after the last round has not elapsed yet.")]
+    RoundCooldownActive,
+    #[msg("Only the game authority can cancel a round before the cancel timeout.")]
+    CancelTimeoutNotReached,
+    #[msg("Only a round that is taking bets or being settled can be cancelled.")]
+    RoundNotCancellable,
+    #[msg("The round was not cancelled.")]
+    RoundNotCancelled,
+    #[msg("The game is paused.")]
+    ProgramPaused,
+    #[msg("The bet would take the vault's payout for one winning number above its table maximum.")]
+    TableMaximumExceeded,
+
+    #[msg("The rent pool account is required for sponsored player accounts.")]
+    RentPoolRequired,
+    #[msg("The rent pool cannot cover the account rent and stay rent-exempt.")]
+    RentPoolDepleted,
+    #[msg("Rent sponsorship is only for wallets that cannot pay the account rent themselves.")]
+    SponsorshipNotNeeded,
+    #[msg("Only vaults of the wrapped SOL mint can fund the rent pool.")]
+    NativeMintRequired,
+
+    #[msg("Owner share recipients must be distinct, with weights summing to 10000 bps.")]
+    InvalidOwnerShares,
+    #[msg("A removed owner share recipient still has revenue to withdraw.")]
+    OwnerShareOutstanding,
+    #[msg("The owner revenue of this vault is split; recipients withdraw their own shares.")]
+    OwnerRevenueShared,
+    #[msg("The signer is not an owner share recipient of this vault.")]
+    NotOwnerShareRecipient,
+
+    #[msg("The player bets account already uses the current layout.")]
+    PlayerBetsAlreadyMigrated,
+
+    #[msg("Claim or refund the bets of your previous round first, or pass its round stats if they lost.")]
+    UnclaimedPreviousRound,
+
+    #[msg("Bets imprisoned by a zero must be claimed with claim_my_winnings.")]
+    EnPrisonClaimRequired,
+
+    #[msg("This table has an event queue, which must be passed.")]
+    EventQueueRequired,
+
+    /// No longer returned since `spin` was removed; kept so later error codes do not shift.
+    #[msg("Spin requires InternalHash randomness, no random delay and no hedging program.")]
+    SpinUnavailable,
+
+    #[msg("The round could not be settled yet.")]
+    RoundNotSettled,
+
+    #[msg("Restarting needs the next round's stats and the protocol stats.")]
+    NextRoundAccountsRequired,
+
+    #[msg("The associated token program is required to create the payout token account.")]
+    AssociatedTokenProgramRequired,
+
+    #[msg("This payout needs the round to be finalized with finalize_round first.")]
+    RoundNotFinalized,
+
+    #[msg("Not enough slots have passed since settlement to finalize the round.")]
+    FinalityNotReached,
+
+    #[msg("The round has not reached the table's minimum bets or bettors yet.")]
+    ParticipationTooLow,
+
+    #[msg("The next scheduled round cannot start before its slot.")]
+    RoundNotDue,
+    #[msg("This bet type is in beta and open to allowlisted wallets only.")]
+    BetTypeInBeta,
+    #[msg("Experimental bet types must be existing bet types.")]
+    InvalidExperimentalBetTypes,
+    #[msg("Beta testers must be distinct, non-default keys, at most the maximum.")]
+    InvalidBetaTesters,
+    #[msg("No bet of the current round at that index can ride.")]
+    InvalidRidingBet,
+    #[msg("Letting winnings ride needs the next round's stats, the protocol stats, the player stats, the config and the vault's exposure.")]
+    RideAccountsRequired,
+    #[msg("This table is private and the player is not on its access list.")]
+    NotOnAccessList,
+    #[msg("A table access list holds distinct players, at most the maximum.")]
+    InvalidTableAccessList,
+    #[msg("This table only accepts bets from its bound vault.")]
+    VaultNotBoundToTable,
+    #[msg("The table is frozen.")]
+    TableFrozen,
+    #[msg("A round label or URI exceeds its maximum length.")]
+    RoundMetadataTooLong,
+    #[msg("Only the table's operators can label a round.")]
+    RoundMetadataOperatorOnly,
+    #[msg("The round stats already use the current layout.")]
+    RoundStatsAlreadyMigrated,
+    #[msg("Finality slots cannot exceed the cluster's own finalization depth.")]
+    FinalitySlotsTooHigh,
+    #[msg("The current round does not settle with this randomness mode.")]
+    RandomnessModeMismatch,
+    #[msg("The randomness fallback timeout must be shorter than the void timeout.")]
+    FallbackAfterVoidTimeout,
+    #[msg("The vault already uses the current layout.")]
+    VaultAlreadyMigrated,
+    #[msg("The liquidity position already uses the current layout.")]
+    ProviderStateAlreadyMigrated,
+    #[msg("The config already uses the current layout.")]
+    ConfigAlreadyMigrated,
+    #[msg("The position has a referrer, whose token account must be passed.")]
+    ReferrerTokenAccountRequired,
+    #[msg("At least one vault must be passed to report its exposure to the hedging program.")]
+    NoHedgedVaults,
+    #[msg("The strategy token account is not held in the strategy's custody for this vault.")]
+    StrategyCustodyMismatch,
+    #[msg("The rent pool has sponsored its limit of accounts for now; try again later.")]
+    SponsorshipLimitReached,
+    #[msg("The vault's exposure to the table must be passed to carry bets into the round in play.")]
+    VaultExposureRequired,
+    #[msg("The round's randomness may already be known; a stuck round must be voided instead.")]
+    RandomnessMayBeKnown,
+}
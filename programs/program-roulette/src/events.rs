@@ -1,15 +1,33 @@
+//! Events emitted by the program.
+//!
+//! Every event starts with `event_seq`, taken from `GameSession::event_seq`, which increases by
+//! one per emitted event across all instructions. Indexers can detect gaps in it and backfill the
+//! missing range deterministically.
+
 use anchor_lang::prelude::*;
-use crate::state::Bet;
+use crate::constants::{BET_TYPE_COUNT, MAX_OWNER_SHARES, SLOT_HASHES_MIXED, WHEEL_POCKETS};
+use crate::state::{Bet, BetRejectionReason, RandomnessMode, SettlementFailure, VaultInvariant, WheelType};
 
 #[event]
 pub struct RoundStarted {
+    pub event_seq: u64,
     pub round: u64,
     pub starter: Pubkey,
     pub start_time: i64,
+    pub seed_commitment: [u8; 32],
+    /// Participation the round needs before betting can close.
+    pub min_total_bets: u32,
+    pub min_unique_bettors: u32,
+    /// Earliest start of the next round on scheduled tables, 0 otherwise.
+    pub next_round_slot: u64,
+    /// The round's label and URI, as stored on its `RoundStats`; empty when not given.
+    pub label: String,
+    pub uri: String,
 }
 
 #[event]
 pub struct WinningsClaimed {
+    pub event_seq: u64,
     pub round: u64,
     pub player: Pubkey,
     pub token_mint: Pubkey,
@@ -17,15 +35,32 @@ pub struct WinningsClaimed {
     pub timestamp: i64,
 }
 
+/// Even-money bets of `round` lost to a zero and carried into `round + 1` by the En Prison rule.
+#[event]
+pub struct BetsImprisoned {
+    pub event_seq: u64,
+    pub player: Pubkey,
+    pub token_mint: Pubkey,
+    pub round: u64,
+    pub imprisoned_bets: u8,
+    /// Stake of the imprisoned bets, returned if they win in the next round.
+    pub stake: u64,
+}
+
 #[event]
 pub struct BetsClosed {
+    pub event_seq: u64,
     pub round: u64,
     pub closer: Pubkey,
     pub close_time: i64,
+    /// Participation the round closed with.
+    pub bet_count: u32,
+    pub unique_bettors: u32,
 }
 
 #[event]
 pub struct RandomGenerated {
+    pub event_seq: u64,
     pub round: u64,
     pub initiator: Pubkey,
     pub winning_number: u8,
@@ -34,10 +69,16 @@ pub struct RandomGenerated {
     pub last_bettor: Pubkey,
     pub hash_result: [u8; 32],
     pub hash_prefix_u64: u64,
+    pub server_seed: [u8; 32],
+    /// Most recent slot hashes mixed into `hash_result`, newest first.
+    pub slot_hashes: [[u8; 32]; SLOT_HASHES_MIXED],
+    /// `GameSession::entropy_accumulator` after the round's last bet.
+    pub entropy_accumulator: [u8; 32],
 }
 
 #[event]
 pub struct LiquidityProvided {
+    pub event_seq: u64,
     pub provider: Pubkey,
     pub token_mint: Pubkey,
     pub amount: u64,
@@ -46,6 +87,7 @@ pub struct LiquidityProvided {
 
 #[event]
 pub struct LiquidityWithdrawn {
+    pub event_seq: u64,
     pub provider: Pubkey,
     pub token_mint: Pubkey,
     pub amount: u64,
@@ -54,15 +96,21 @@ pub struct LiquidityWithdrawn {
 
 #[event]
 pub struct BetPlaced {
+    pub event_seq: u64,
     pub player: Pubkey,
     pub token_mint: Pubkey,
     pub round: u64,
     pub bet: Bet,
+    /// Part of the stake credited to liquidity providers.
+    pub provider_fee: u64,
+    /// Part of the stake charged as protocol revenue.
+    pub owner_fee: u64,
     pub timestamp: i64,
 }
 
 #[event]
 pub struct ProviderRevenueWithdrawn {
+    pub event_seq: u64,
     pub provider: Pubkey,
     pub token_mint: Pubkey,
     pub amount: u64,
@@ -71,7 +119,651 @@ pub struct ProviderRevenueWithdrawn {
 
 #[event]
 pub struct PayoutReserveDistributed {
+    pub event_seq: u64,
     pub token_mint: Pubkey,
     pub amount_distributed: u64,
     pub timestamp: i64,
-}
\ No newline at end of file
+}
+
+#[event]
+pub struct BetRejected {
+    pub event_seq: u64,
+    pub player: Pubkey,
+    pub token_mint: Pubkey,
+    pub round: u64,
+    pub bet_type: u8,
+    pub requested_amount: u64,
+    pub max_allowed: u64,
+    pub reason: BetRejectionReason,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LargeWithdrawalRequested {
+    pub event_seq: u64,
+    pub provider: Pubkey,
+    pub token_mint: Pubkey,
+    pub amount: u64,
+    /// The table `unlock_round` belongs to.
+    pub game_session: Pubkey,
+    /// `u64::MAX` when only the timeout unlocks the withdrawal.
+    pub unlock_round: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SettlementRetryNeeded {
+    pub event_seq: u64,
+    pub round: u64,
+    pub initiator: Pubkey,
+    pub reason: SettlementFailure,
+    pub retry_count: u32,
+    pub slot: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VaultParticipationChanged {
+    pub event_seq: u64,
+    pub token_mint: Pubkey,
+    pub participating: bool,
+    /// First round the change applies to.
+    pub effective_round: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LpReferrerSet {
+    pub event_seq: u64,
+    pub provider: Pubkey,
+    pub referrer: Pubkey,
+    pub token_mint: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LpReferralRewardsClaimed {
+    pub event_seq: u64,
+    pub referrer: Pubkey,
+    pub provider: Pubkey,
+    pub token_mint: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ExposureReported {
+    pub event_seq: u64,
+    pub round: u64,
+    pub token_mint: Pubkey,
+    pub hedging_program: Pubkey,
+    pub net_exposure: [i64; WHEEL_POCKETS],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OwnerRevenueSwapped {
+    pub event_seq: u64,
+    pub token_mint: Pubkey,
+    pub amount_in: u64,
+    pub protocol_token_mint: Pubkey,
+    pub amount_out: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PayoutDebtRecorded {
+    pub event_seq: u64,
+    pub token_mint: Pubkey,
+    pub player: Pubkey,
+    pub round: u64,
+    pub debt_index: u64,
+    pub amount_owed: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PayoutDebtRepaid {
+    pub event_seq: u64,
+    pub token_mint: Pubkey,
+    pub player: Pubkey,
+    pub repayer: Pubkey,
+    pub debt_index: u64,
+    pub amount: u64,
+    pub remaining_owed: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct JackpotPublished {
+    pub event_seq: u64,
+    pub round: u64,
+    pub token_mint: Pubkey,
+    pub merkle_root: [u8; 32],
+    pub total_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct JackpotClaimed {
+    pub event_seq: u64,
+    pub round: u64,
+    pub player: Pubkey,
+    pub token_mint: Pubkey,
+    pub index: u64,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TableMetadataUpdated {
+    pub event_seq: u64,
+    pub game_session: Pubkey,
+    pub name: String,
+    pub description_hash: [u8; 32],
+    pub banner_mint: Option<Pubkey>,
+}
+
+#[event]
+pub struct StrategyDeployed {
+    pub event_seq: u64,
+    pub token_mint: Pubkey,
+    pub strategy_program: Pubkey,
+    pub amount: u64,
+    pub strategy_principal: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct StrategyRecalled {
+    pub event_seq: u64,
+    pub token_mint: Pubkey,
+    pub strategy_program: Pubkey,
+    pub principal: u64,
+    pub returned: u64,
+    pub yield_amount: u64,
+    pub loss: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SolFeesWithdrawn {
+    pub event_seq: u64,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub total_withdrawn: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AlreadyClaimed {
+    pub event_seq: u64,
+    pub round: u64,
+    pub player: Pubkey,
+    pub token_mint: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ClaimVerified {
+    pub event_seq: u64,
+    pub round: u64,
+    pub player: Pubkey,
+    pub token_mint: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VrfRandomnessRequested {
+    pub event_seq: u64,
+    pub round: u64,
+    pub randomness_account: Pubkey,
+    pub seed_slot: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct VrfRoundSettled {
+    pub event_seq: u64,
+    pub round: u64,
+    pub initiator: Pubkey,
+    pub randomness_account: Pubkey,
+    pub randomness_value: [u8; 32],
+    pub winning_number: u8,
+    pub slot: u64,
+}
+
+#[event]
+pub struct LpPositionWrapped {
+    pub event_seq: u64,
+    pub provider_state: Pubkey,
+    pub position_asset: Pubkey,
+    pub holder: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LpPositionUnwrapped {
+    pub event_seq: u64,
+    pub provider_state: Pubkey,
+    pub position_asset: Pubkey,
+    pub holder: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OraoRandomnessRequested {
+    pub event_seq: u64,
+    pub round: u64,
+    pub seed: [u8; 32],
+    pub randomness_request: Pubkey,
+    pub slot: u64,
+}
+
+#[event]
+pub struct OraoRoundSettled {
+    pub event_seq: u64,
+    pub round: u64,
+    pub initiator: Pubkey,
+    pub seed: [u8; 32],
+    pub randomness: [u8; 64],
+    pub winning_number: u8,
+    pub slot: u64,
+}
+
+#[event]
+pub struct VaultInvariantViolated {
+    pub event_seq: u64,
+    pub token_mint: Pubkey,
+    pub invariant: VaultInvariant,
+    /// Smallest value the checked side must have.
+    pub required: u64,
+    pub actual: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RoundDisputed {
+    pub event_seq: u64,
+    pub round: u64,
+    pub token_mint: Pubkey,
+    pub flagger: Pubkey,
+    pub reason_hash: [u8; 32],
+    pub bond: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RoundDisputeResolved {
+    pub event_seq: u64,
+    pub round: u64,
+    pub token_mint: Pubkey,
+    pub flagger: Pubkey,
+    /// Whether the dispute was found valid; if not, the bond was slashed.
+    pub upheld: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RandomnessSeedLocked {
+    pub event_seq: u64,
+    pub round: u64,
+    pub locker: Pubkey,
+    pub target_slot: u64,
+    pub slot: u64,
+    /// The round's server seed, public from the lock on. When the initiator missed the spin
+    /// timeout, the round's seed commitment stands in for it.
+    pub server_seed: [u8; 32],
+}
+
+#[event]
+pub struct WinningNumberRevealed {
+    pub event_seq: u64,
+    pub round: u64,
+    pub initiator: Pubkey,
+    pub winning_number: u8,
+    pub target_slot: u64,
+    /// Hash of the first slot at or after `target_slot`.
+    pub slot_hash: [u8; 32],
+    pub hash_result: [u8; 32],
+    pub server_seed: [u8; 32],
+    pub entropy_accumulator: [u8; 32],
+    pub slot: u64,
+}
+
+#[event]
+pub struct RoundVoided {
+    pub event_seq: u64,
+    pub round: u64,
+    pub target_slot: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct GameSessionMigrated {
+    pub event_seq: u64,
+    pub game_session: Pubkey,
+    pub table_id: u64,
+    pub current_round: u64,
+    pub from_layout_version: u8,
+    pub layout_version: u8,
+}
+
+/// Per-bet-type totals of a settled round, indexed by bet type, for realized house edge
+/// analytics. Payouts are gross, stake included.
+#[event]
+pub struct RoundBetTypeStats {
+    pub event_seq: u64,
+    pub round: u64,
+    pub winning_number: u8,
+    pub total_staked: [u64; BET_TYPE_COUNT],
+    pub total_paid: [u64; BET_TYPE_COUNT],
+}
+
+#[event]
+pub struct RandomnessModeChanged {
+    pub event_seq: u64,
+    pub previous_mode: RandomnessMode,
+    pub mode: RandomnessMode,
+    pub round: u64,
+}
+
+#[event]
+pub struct RandomnessOraclesUpdated {
+    pub event_seq: u64,
+    pub oracles: Vec<Pubkey>,
+    /// Oracles that must be fulfilled to settle: all of them.
+    pub quorum: u8,
+}
+
+#[event]
+pub struct MultiOracleRoundSettled {
+    pub event_seq: u64,
+    pub round: u64,
+    pub initiator: Pubkey,
+    /// Oracles whose values were combined, in registration order.
+    pub oracles: Vec<Pubkey>,
+    pub combined_value: [u8; 32],
+    pub winning_number: u8,
+    pub slot: u64,
+}
+
+#[event]
+pub struct RoundSettlementTimedOut {
+    pub event_seq: u64,
+    pub round: u64,
+    pub caller: Pubkey,
+    pub bets_closed_slot: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct BetsRefunded {
+    pub event_seq: u64,
+    pub round: u64,
+    pub player: Pubkey,
+    pub token_mint: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RandomnessFallbackActivated {
+    pub event_seq: u64,
+    pub round: u64,
+    pub from_mode: RandomnessMode,
+    pub to_mode: RandomnessMode,
+    pub slot: u64,
+}
+
+#[event]
+pub struct RandomnessFallbackChanged {
+    pub event_seq: u64,
+    pub fallback_mode: Option<RandomnessMode>,
+    pub timeout_slots: u64,
+}
+
+#[event]
+pub struct CrankRewardPaid {
+    pub event_seq: u64,
+    pub round: u64,
+    pub cranker: Pubkey,
+    pub token_mint: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct RoundCancelled {
+    pub event_seq: u64,
+    pub round: u64,
+    pub canceller: Pubkey,
+    pub slot: u64,
+}
+
+#[event]
+pub struct GamePauseChanged {
+    /// Protocol-wide sequence from `Config::event_seq`, apart from the tables' sequences.
+    pub event_seq: u64,
+    pub paused: bool,
+    pub authority: Pubkey,
+    pub slot: u64,
+}
+
+#[event]
+pub struct OperatorChanged {
+    pub event_seq: u64,
+    pub previous_operator: Pubkey,
+    pub operator: Pubkey,
+}
+
+#[event]
+pub struct WheelTypeChanged {
+    pub event_seq: u64,
+    pub previous_wheel_type: WheelType,
+    pub wheel_type: WheelType,
+    /// First round spun on the new wheel.
+    pub from_round: u64,
+}
+
+#[event]
+pub struct EnPrisonChanged {
+    pub event_seq: u64,
+    pub en_prison: bool,
+    /// First round played under the new rule.
+    pub from_round: u64,
+}
+
+/// Emitted when owner fees of a wrapped-SOL vault are unwrapped into the rent pool.
+#[event]
+pub struct RentPoolFunded {
+    pub event_seq: u64,
+    pub token_mint: Pubkey,
+    pub amount: u64,
+    pub pool_balance: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OwnerSharesChanged {
+    pub event_seq: u64,
+    pub token_mint: Pubkey,
+    pub recipients: [Pubkey; MAX_OWNER_SHARES],
+    pub weights_bps: [u16; MAX_OWNER_SHARES],
+}
+
+#[event]
+pub struct OwnerShareWithdrawn {
+    pub event_seq: u64,
+    pub token_mint: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EventQueueInitialized {
+    pub event_seq: u64,
+    pub event_queue: Pubkey,
+    pub consumer: Pubkey,
+}
+
+#[event]
+pub struct EventQueueConsumerChanged {
+    pub event_seq: u64,
+    pub previous_consumer: Pubkey,
+    pub consumer: Pubkey,
+}
+
+#[event]
+pub struct FinalityConfigChanged {
+    pub event_seq: u64,
+    pub finality_slots: u64,
+    pub finality_payout_threshold: u64,
+}
+
+#[event]
+pub struct RoundFinalized {
+    pub event_seq: u64,
+    pub round: u64,
+    pub settled_slot: u64,
+    /// Slot the round was finalized in.
+    pub slot: u64,
+}
+
+#[event]
+pub struct ParticipationMinimumsChanged {
+    pub event_seq: u64,
+    pub min_total_bets: u32,
+    pub min_unique_bettors: u32,
+    /// First round the minimums apply to.
+    pub from_round: u64,
+}
+
+#[event]
+pub struct RoundScheduleChanged {
+    pub event_seq: u64,
+    pub round_interval_slots: u64,
+    pub next_round_slot: u64,
+    /// Whether anyone may now start the table's rounds.
+    pub open_to_any_starter: bool,
+}
+
+/// Emitted next to `BetPlaced` for bets at or above the vault's `large_bet_threshold`.
+#[event]
+pub struct LargeBetPlaced {
+    pub event_seq: u64,
+    pub player: Pubkey,
+    pub token_mint: Pubkey,
+    pub round: u64,
+    pub bet_type: u8,
+    pub amount: u64,
+    pub threshold: u64,
+}
+
+/// Emitted next to `WinningsClaimed` for payouts at or above the vault's `large_win_threshold`.
+/// `amount` is the full payout, including any part recorded as payout debt.
+#[event]
+pub struct LargeWinClaimed {
+    pub event_seq: u64,
+    pub round: u64,
+    pub player: Pubkey,
+    pub token_mint: Pubkey,
+    pub amount: u64,
+    pub threshold: u64,
+}
+
+/// A player marked one of their bets of `round` to let its winnings ride, or took it back.
+#[event]
+pub struct BetRidingChanged {
+    pub event_seq: u64,
+    pub player: Pubkey,
+    pub round: u64,
+    pub bet_index: u8,
+    pub riding: bool,
+}
+
+/// Winnings of riding bets of `round` re-staked on the same layouts in `round + 1` instead of
+/// being paid out.
+#[event]
+pub struct WinningsRidden {
+    pub event_seq: u64,
+    pub player: Pubkey,
+    pub token_mint: Pubkey,
+    pub round: u64,
+    pub ridden_bets: u8,
+    pub amount: u64,
+    /// Consecutive rounds winnings have been carried into, this one included.
+    pub streak: u16,
+    /// Winnings re-staked over the whole streak.
+    pub streak_staked: u64,
+}
+
+/// A table's access list was set, making it private, or removed, making it public again.
+#[event]
+pub struct TableAccessListUpdated {
+    pub event_seq: u64,
+    pub game_session: Pubkey,
+    /// Players admitted from now on; empty when the list was removed.
+    pub players: Vec<Pubkey>,
+    pub enabled: bool,
+}
+
+/// A table was bound to a single vault, or unbound to accept any vault again.
+#[event]
+pub struct TableVaultBound {
+    pub event_seq: u64,
+    pub game_session: Pubkey,
+    pub bound_vault: Option<Pubkey>,
+}
+
+/// A table was frozen by `freeze_table`, or thawed by `unfreeze_table` when `frozen` is false.
+#[event]
+pub struct TableFrozen {
+    pub event_seq: u64,
+    pub table_id: u64,
+    pub frozen: bool,
+    pub authority: Pubkey,
+    pub slot: u64,
+}
+
+/// A round's stats were upgraded to the current layout by `migrate_round_stats`.
+#[event]
+pub struct RoundStatsMigrated {
+    pub game_session: Pubkey,
+    pub round: u64,
+    pub from_layout_version: u8,
+    pub layout_version: u8,
+}
+
+/// A vault was upgraded to the current layout by `migrate_vault`.
+#[event]
+pub struct VaultMigrated {
+    pub vault: Pubkey,
+    pub from_layout_version: u8,
+    pub layout_version: u8,
+}
+
+/// A liquidity position was upgraded to the current layout by `migrate_provider_state`.
+#[event]
+pub struct ProviderStateMigrated {
+    pub vault: Pubkey,
+    pub provider: Pubkey,
+    pub from_layout_version: u8,
+    pub layout_version: u8,
+}
+
+/// The protocol config was upgraded to the current layout by `migrate_config`.
+#[event]
+pub struct ConfigMigrated {
+    pub from_layout_version: u8,
+    pub layout_version: u8,
+}
+
+#[event]
+pub struct EventQueueClosed {
+    pub event_seq: u64,
+    pub event_queue: Pubkey,
+    /// Records still pending when the queue was closed, which are lost.
+    pub pending: u64,
+}
@@ -0,0 +1,78 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::invoke;
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{
+        confidential_transfer::{instruction::deposit, ConfidentialTransferMint},
+        BaseStateWithExtensions,
+        StateWithExtensions,
+    },
+};
+use anchor_spl::token_interface::TokenAccount;
+use crate::{errors::RouletteError, instructions::player::{claim_my_winnings, ClaimMyWinnings}};
+
+// Token-2022 confidential transfers need zero-knowledge proofs from the holder of the source
+// account's ElGamal key, which the vault PDA cannot produce. Payouts therefore still leave the
+// vault as public transfers, and the player, who signs the claim, deposits them into their
+// pending confidential balance in the same instruction. Bets keep using public balances.
+
+// =================================================================================================
+// Confidential Claim
+// =================================================================================================
+
+/// `claim_my_winnings` for vaults with `confidential_payouts`: the payout is moved from the
+/// player's public balance into their pending confidential balance right after the transfer.
+/// The player's token account must be configured for confidential transfers; they apply the
+/// pending balance themselves afterwards.
+pub fn claim_my_winnings_confidential(ctx: Context<ClaimMyWinnings>, round_to_claim: u64) -> Result<()> {
+    require!(ctx.accounts.vault.confidential_payouts, RouletteError::ConfidentialPayoutsDisabled);
+    require_keys_eq!(
+        ctx.accounts.token_program.key(),
+        spl_token_2022::ID,
+        RouletteError::ConfidentialTransferMintRequired
+    );
+    {
+        let mint_info = ctx.accounts.token_mint.to_account_info();
+        let mint_data = mint_info.try_borrow_data()?;
+        let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+        require!(
+            mint.get_extension::<ConfidentialTransferMint>().is_ok(),
+            RouletteError::ConfidentialTransferMintRequired
+        );
+    }
+
+    let player = ctx.accounts.player.to_account_info();
+    let player_token_account = ctx.accounts.player_token_account.to_account_info();
+    let token_mint = ctx.accounts.token_mint.to_account_info();
+    let decimals = ctx.accounts.token_mint.decimals;
+    let balance_before = token_balance(&player_token_account)?;
+
+    claim_my_winnings(ctx, round_to_claim)?;
+
+    // Measured rather than recomputed, so transfer fees and partial payouts are accounted for,
+    // and a retried claim deposits nothing.
+    let received = token_balance(&player_token_account)?.saturating_sub(balance_before);
+    if received == 0 {
+        return Ok(());
+    }
+
+    invoke(
+        &deposit(
+            &spl_token_2022::ID,
+            player_token_account.key,
+            token_mint.key,
+            received,
+            decimals,
+            player.key,
+            &[]
+        )?,
+        &[player_token_account, token_mint, player]
+    )?;
+    msg!("Deposited {} into the confidential pending balance", received);
+
+    Ok(())
+}
+
+fn token_balance(token_account: &AccountInfo) -> Result<u64> {
+    Ok(TokenAccount::try_deserialize(&mut &token_account.data.borrow()[..])?.amount)
+}
@@ -0,0 +1,493 @@
+use anchor_lang::prelude::*;
+use crate::{
+    constants::{
+        BET_TYPE_COUNT,
+        CONFIG_LAYOUT_VERSION,
+        DEFAULT_MAX_BETS_PER_ROUND,
+        MAX_ADMIN_KEYS,
+        MAX_BETA_TESTERS,
+        MAX_BETS_PER_ROUND,
+        TREASURY_PUBKEY,
+    },
+    errors::RouletteError,
+    events::{ConfigMigrated, SolFeesWithdrawn},
+    instructions::{lamports::debit_program_account, stats::grow_account},
+    seeds::{CONFIG_SEED, GAME_SESSION_SEED, SOL_FEE_VAULT_SEED},
+    state::*,
+};
+
+// =================================================================================================
+// Config Initialization
+// =================================================================================================
+
+pub fn initialize_config(ctx: Context<InitializeConfig>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.admin_keys = [Pubkey::default(); MAX_ADMIN_KEYS];
+    config.admin_threshold = 0;
+    config.bump = ctx.bumps.config;
+    config.round_stats_payer = RentPayer::Cranker;
+    config.hedging_program = Pubkey::default();
+    config.protocol_token_mint = Pubkey::default();
+    config.rake_swap_program = Pubkey::default();
+    config.max_bets_per_round = DEFAULT_MAX_BETS_PER_ROUND as u8;
+    config.strategy_program = Pubkey::default();
+    config.sol_fee_recipient = TREASURY_PUBKEY;
+    config.experimental_bet_types = 0;
+    config.beta_testers = [Pubkey::default(); MAX_BETA_TESTERS];
+    config.beta_tester_count = 0;
+    config.paused = false;
+    config.event_seq = 0;
+    config.layout_version = CONFIG_LAYOUT_VERSION;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [GAME_SESSION_SEED],
+        bump = game_session.bump,
+        constraint = authority.key() == game_session.authority @ RouletteError::AdminOnly
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + std::mem::size_of::<Config>(),
+        seeds = [CONFIG_SEED],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Upgrades the config in place to the current layout after a program upgrade added fields to
+/// `Config`: the account is grown at the payer's expense and the new fields are backfilled.
+/// Anyone can migrate it.
+pub fn migrate_config(ctx: Context<MigrateConfig>) -> Result<()> {
+    let info = ctx.accounts.config.to_account_info();
+    let new_len = 8 + std::mem::size_of::<Config>();
+    if info.data_len() < new_len {
+        grow_account(
+            &info,
+            new_len,
+            RentPayer::Cranker,
+            None,
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.system_program
+        )?;
+    }
+
+    let mut data = info.try_borrow_mut_data()?;
+    let mut config = Config::try_deserialize(&mut &data[..])?;
+    let from_layout_version = config.layout_version;
+    require!(from_layout_version < CONFIG_LAYOUT_VERSION, RouletteError::ConfigAlreadyMigrated);
+    config.backfill_layout(from_layout_version);
+    config.layout_version = CONFIG_LAYOUT_VERSION;
+
+    emit!(ConfigMigrated {
+        from_layout_version,
+        layout_version: config.layout_version,
+    });
+
+    config.try_serialize(&mut &mut data[..])?;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigrateConfig<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: An outdated config does not deserialize until it has been grown; its address is
+    /// checked by the seeds.
+    #[account(mut, owner = crate::ID, seeds = [CONFIG_SEED], bump)]
+    pub config: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// =================================================================================================
+// Admin Multisig
+// =================================================================================================
+
+/// Replaces the native admin multisig. Needs the approval of the current multisig (if enabled)
+/// and of the new one, whose keys co-sign via `remaining_accounts`, so a typo in the new keys
+/// cannot lock the admin out.
+pub fn set_admin_multisig(
+    ctx: Context<SetAdminMultisig>,
+    admin_keys: [Pubkey; MAX_ADMIN_KEYS],
+    admin_threshold: u8
+) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.require_admin_approval(ctx.remaining_accounts)?;
+
+    // Every key counts towards the threshold once, so none may repeat.
+    let mut distinct_keys = 0usize;
+    for (i, key) in admin_keys.iter().enumerate() {
+        if *key != Pubkey::default() {
+            require!(!admin_keys[..i].contains(key), RouletteError::InvalidAdminMultisig);
+            distinct_keys += 1;
+        }
+    }
+    require!(
+        (admin_threshold as usize) <= distinct_keys,
+        RouletteError::InvalidAdminMultisig
+    );
+
+    config.admin_keys = admin_keys;
+    config.admin_threshold = admin_threshold;
+    config.require_admin_approval(ctx.remaining_accounts)
+}
+
+#[derive(Accounts)]
+pub struct SetAdminMultisig<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [GAME_SESSION_SEED],
+        bump = game_session.bump,
+        constraint = authority.key() == game_session.authority @ RouletteError::AdminOnly
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    #[account(mut, seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+}
+
+// =================================================================================================
+// Round Stats Rent Payer
+// =================================================================================================
+
+/// Selects who funds `RoundStats` growth from the next round on.
+pub fn set_round_stats_payer(ctx: Context<SetRoundStatsPayer>, payer: RentPayer) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.require_admin_approval(ctx.remaining_accounts)?;
+    config.round_stats_payer = payer;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetRoundStatsPayer<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [GAME_SESSION_SEED],
+        bump = game_session.bump,
+        constraint = authority.key() == game_session.authority @ RouletteError::AdminOnly
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    #[account(mut, seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+}
+
+// =================================================================================================
+// Hedging Hook
+// =================================================================================================
+
+/// Registers the program that receives each vault's net exposure at `close_bets`.
+/// `Pubkey::default()` disables the hook.
+pub fn set_hedging_program(ctx: Context<SetHedgingProgram>, hedging_program: Pubkey) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.require_admin_approval(ctx.remaining_accounts)?;
+    config.hedging_program = hedging_program;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetHedgingProgram<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [GAME_SESSION_SEED],
+        bump = game_session.bump,
+        constraint = authority.key() == game_session.authority @ RouletteError::AdminOnly
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    #[account(mut, seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+}
+
+// =================================================================================================
+// Protocol Token Rake
+// =================================================================================================
+
+/// Sets the token owner fees are consolidated into and the AMM program allowed to swap them.
+pub fn set_rake_swap_config(
+    ctx: Context<SetRakeSwapConfig>,
+    protocol_token_mint: Pubkey,
+    rake_swap_program: Pubkey
+) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.require_admin_approval(ctx.remaining_accounts)?;
+    config.protocol_token_mint = protocol_token_mint;
+    config.rake_swap_program = rake_swap_program;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetRakeSwapConfig<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [GAME_SESSION_SEED],
+        bump = game_session.bump,
+        constraint = authority.key() == game_session.authority @ RouletteError::AdminOnly
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    #[account(mut, seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+}
+
+// =================================================================================================
+// Bet Limit
+// =================================================================================================
+
+/// Sets how many bets a player may place per round, up to the `MAX_BETS_PER_ROUND` ceiling.
+/// `PlayerBets` accounts grow to hold bets past `DEFAULT_MAX_BETS_PER_ROUND` as they are placed.
+pub fn set_max_bets_per_round(ctx: Context<SetMaxBetsPerRound>, max_bets_per_round: u8) -> Result<()> {
+    require!(
+        max_bets_per_round > 0 && (max_bets_per_round as usize) <= MAX_BETS_PER_ROUND,
+        RouletteError::InvalidMaxBetsPerRound
+    );
+    let config = &mut ctx.accounts.config;
+    config.require_admin_approval(ctx.remaining_accounts)?;
+    config.max_bets_per_round = max_bets_per_round;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetMaxBetsPerRound<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [GAME_SESSION_SEED],
+        bump = game_session.bump,
+        constraint = authority.key() == game_session.authority @ RouletteError::AdminOnly
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    #[account(mut, seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+}
+
+// =================================================================================================
+// Yield Strategy
+// =================================================================================================
+
+/// Whitelists the yield strategy program idle vault liquidity may be deployed to, or disables
+/// deployments with the default key.
+pub fn set_strategy_program(ctx: Context<SetStrategyProgram>, strategy_program: Pubkey) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.require_admin_approval(ctx.remaining_accounts)?;
+    config.strategy_program = strategy_program;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetStrategyProgram<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [GAME_SESSION_SEED],
+        bump = game_session.bump,
+        constraint = authority.key() == game_session.authority @ RouletteError::AdminOnly
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    #[account(mut, seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+}
+
+// =================================================================================================
+// Beta Bet Types
+// =================================================================================================
+
+/// Marks bet types as experimental (bit `n` for bet type `n`), restricting them to
+/// `beta_testers` while they are battle-tested. Clearing a bit makes the type generally
+/// available.
+pub fn set_experimental_bet_types(
+    ctx: Context<SetExperimentalBetTypes>,
+    experimental_bet_types: u32
+) -> Result<()> {
+    require!(
+        experimental_bet_types >> BET_TYPE_COUNT == 0,
+        RouletteError::InvalidExperimentalBetTypes
+    );
+    let config = &mut ctx.accounts.config;
+    config.require_admin_approval(ctx.remaining_accounts)?;
+    config.experimental_bet_types = experimental_bet_types;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetExperimentalBetTypes<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [GAME_SESSION_SEED],
+        bump = game_session.bump,
+        constraint = authority.key() == game_session.authority @ RouletteError::AdminOnly
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    #[account(mut, seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+}
+
+/// Sets the wallets allowed to bet on experimental bet types. A count of 0 closes every
+/// experimental bet type to all players.
+pub fn set_beta_testers(
+    ctx: Context<SetBetaTesters>,
+    beta_testers: [Pubkey; MAX_BETA_TESTERS],
+    count: u8
+) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.require_admin_approval(ctx.remaining_accounts)?;
+
+    let allowlist = beta_testers
+        .get(..count as usize)
+        .ok_or(RouletteError::InvalidBetaTesters)?;
+    for (i, key) in allowlist.iter().enumerate() {
+        require!(
+            *key != Pubkey::default() && !allowlist[..i].contains(key),
+            RouletteError::InvalidBetaTesters
+        );
+    }
+
+    config.beta_testers = beta_testers;
+    config.beta_tester_count = count;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetBetaTesters<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [GAME_SESSION_SEED],
+        bump = game_session.bump,
+        constraint = authority.key() == game_session.authority @ RouletteError::AdminOnly
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    #[account(mut, seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+}
+
+// =================================================================================================
+// SOL Fees
+// =================================================================================================
+
+/// Creates the program-owned `SolFeeVault` that collects vault-creation fees.
+pub fn initialize_sol_fee_vault(ctx: Context<InitializeSolFeeVault>) -> Result<()> {
+    let sol_fee_vault = &mut ctx.accounts.sol_fee_vault;
+    sol_fee_vault.total_collected = 0;
+    sol_fee_vault.total_withdrawn = 0;
+    sol_fee_vault.bump = ctx.bumps.sol_fee_vault;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeSolFeeVault<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [GAME_SESSION_SEED],
+        bump = game_session.bump,
+        constraint = authority.key() == game_session.authority @ RouletteError::AdminOnly
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + std::mem::size_of::<SolFeeVault>(),
+        seeds = [SOL_FEE_VAULT_SEED],
+        bump
+    )]
+    pub sol_fee_vault: Account<'info, SolFeeVault>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Sets the wallet `withdraw_sol_fees` pays out to.
+pub fn set_sol_fee_recipient(ctx: Context<SetSolFeeRecipient>, recipient: Pubkey) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.require_admin_approval(ctx.remaining_accounts)?;
+    config.sol_fee_recipient = recipient;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetSolFeeRecipient<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [GAME_SESSION_SEED],
+        bump = game_session.bump,
+        constraint = authority.key() == game_session.authority @ RouletteError::AdminOnly
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    #[account(mut, seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+}
+
+/// Pays `amount` of collected SOL fees to `Config::sol_fee_recipient`. The fee vault keeps its
+/// rent-exempt minimum.
+pub fn withdraw_sol_fees(ctx: Context<WithdrawSolFees>, amount: u64) -> Result<()> {
+    require!(amount > 0, RouletteError::AmountMustBeGreaterThanZero);
+    ctx.accounts.config.require_admin_approval(ctx.remaining_accounts)?;
+
+    debit_program_account(
+        &ctx.accounts.sol_fee_vault.to_account_info(),
+        &ctx.accounts.recipient.to_account_info(),
+        amount
+    )?;
+    let sol_fee_vault = &mut ctx.accounts.sol_fee_vault;
+    sol_fee_vault.total_withdrawn = sol_fee_vault.total_withdrawn
+        .checked_add(amount)
+        .ok_or(RouletteError::ArithmeticOverflow)?;
+
+    emit!(SolFeesWithdrawn {
+        event_seq: ctx.accounts.game_session.next_event_seq(),
+        recipient: ctx.accounts.recipient.key(),
+        amount,
+        total_withdrawn: sol_fee_vault.total_withdrawn,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct WithdrawSolFees<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GAME_SESSION_SEED],
+        bump = game_session.bump,
+        constraint = authority.key() == game_session.authority @ RouletteError::AdminOnly
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(mut, seeds = [SOL_FEE_VAULT_SEED], bump = sol_fee_vault.bump)]
+    pub sol_fee_vault: Account<'info, SolFeeVault>,
+
+    /// CHECK: Must be the governed `Config::sol_fee_recipient`.
+    #[account(mut, address = config.sol_fee_recipient @ RouletteError::Unauthorized)]
+    pub recipient: UncheckedAccount<'info>,
+}
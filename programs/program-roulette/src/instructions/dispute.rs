@@ -0,0 +1,164 @@
+use anchor_lang::prelude::*;
+use crate::instructions::lamports::{debit_program_account, transfer_lamports};
+use crate::{
+    errors::RouletteError,
+    events::*,
+    seeds::{GAME_SESSION_SEED, ROUND_DISPUTE_SEED, SOL_FEE_VAULT_SEED, VAULT_EXPOSURE_SEED, VAULT_SEED},
+    state::*,
+};
+
+// =================================================================================================
+// Flag Round
+// =================================================================================================
+
+/// Flags a vault's part in a completed round as suspicious, e.g. for suspected entropy
+/// manipulation. The vault must have taken bets on the table. The flagger stakes the vault's
+/// `dispute_bond` into the dispute account, and the vault's reserve distribution is blocked
+/// until the authority resolves it; other vaults are unaffected.
+pub fn flag_round(ctx: Context<FlagRound>, round: u64, reason_hash: [u8; 32]) -> Result<()> {
+    require!(
+        round > 0 && round <= ctx.accounts.game_session.last_completed_round,
+        RouletteError::RoundNotDisputable
+    );
+
+    let bond = ctx.accounts.vault.dispute_bond();
+    transfer_lamports(
+        &ctx.accounts.flagger.to_account_info(),
+        &ctx.accounts.round_dispute.to_account_info(),
+        bond,
+        false,
+        &ctx.accounts.system_program
+    )?;
+
+    let timestamp = Clock::get()?.unix_timestamp;
+    let dispute = &mut ctx.accounts.round_dispute;
+    dispute.game_session = ctx.accounts.game_session.key();
+    dispute.round = round;
+    dispute.vault = ctx.accounts.vault.key();
+    dispute.flagger = ctx.accounts.flagger.key();
+    dispute.reason_hash = reason_hash;
+    dispute.bond = bond;
+    dispute.created_at = timestamp;
+    dispute.bump = ctx.bumps.round_dispute;
+
+    let vault = &mut ctx.accounts.vault;
+    vault.open_disputes = vault.open_disputes
+        .checked_add(1)
+        .ok_or(RouletteError::ArithmeticOverflow)?;
+
+    emit!(RoundDisputed {
+        event_seq: ctx.accounts.game_session.next_event_seq(),
+        round,
+        token_mint: vault.token_mint,
+        flagger: dispute.flagger,
+        reason_hash,
+        bond: dispute.bond,
+        timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(round: u64)]
+pub struct FlagRound<'info> {
+    #[account(mut)]
+    pub flagger: Signer<'info>,
+
+    #[account(mut, seeds = [GAME_SESSION_SEED, game_session.table_seed().as_slice()], bump = game_session.bump)]
+    pub game_session: Account<'info, GameSession>,
+
+    /// The disputed vault.
+    #[account(mut, seeds = [VAULT_SEED, vault.token_mint.as_ref()], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    /// The vault's exposure to the table, which exists once the vault has taken bets on it.
+    #[account(
+        seeds = [VAULT_EXPOSURE_SEED, vault.key().as_ref(), game_session.key().as_ref()],
+        bump = vault_exposure.bump
+    )]
+    pub vault_exposure: Account<'info, VaultExposure>,
+
+    /// One dispute per round and vault; a second flag fails until the first is resolved.
+    #[account(
+        init,
+        payer = flagger,
+        space = 8 + std::mem::size_of::<RoundDispute>(),
+        seeds = [ROUND_DISPUTE_SEED, game_session.key().as_ref(), &round.to_le_bytes(), vault.key().as_ref()],
+        bump
+    )]
+    pub round_dispute: Account<'info, RoundDispute>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// =================================================================================================
+// Resolve Dispute
+// =================================================================================================
+
+/// Authority-only. Closes a dispute, returning its rent to the flagger. An upheld dispute also
+/// returns the bond; a rejected one slashes the bond to the `SolFeeVault`.
+pub fn resolve_dispute(ctx: Context<ResolveDispute>, _round: u64, upheld: bool) -> Result<()> {
+    let dispute = &ctx.accounts.round_dispute;
+    if !upheld {
+        debit_program_account(
+            &dispute.to_account_info(),
+            &ctx.accounts.sol_fee_vault.to_account_info(),
+            dispute.bond
+        )?;
+        let sol_fee_vault = &mut ctx.accounts.sol_fee_vault;
+        sol_fee_vault.total_collected = sol_fee_vault.total_collected
+            .checked_add(dispute.bond)
+            .ok_or(RouletteError::ArithmeticOverflow)?;
+    }
+
+    let vault = &mut ctx.accounts.vault;
+    vault.open_disputes = vault.open_disputes.saturating_sub(1);
+
+    emit!(RoundDisputeResolved {
+        event_seq: ctx.accounts.game_session.next_event_seq(),
+        round: dispute.round,
+        token_mint: vault.token_mint,
+        flagger: dispute.flagger,
+        upheld,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    // round_dispute is closed to the flagger by Anchor via the `close` constraint.
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(round: u64)]
+pub struct ResolveDispute<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GAME_SESSION_SEED, game_session.table_seed().as_slice()],
+        bump = game_session.bump,
+        constraint = authority.key() == game_session.authority @ RouletteError::AdminOnly
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    /// The disputed vault.
+    #[account(mut, seeds = [VAULT_SEED, vault.token_mint.as_ref()], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(
+        mut,
+        close = flagger,
+        has_one = flagger,
+        seeds = [ROUND_DISPUTE_SEED, game_session.key().as_ref(), &round.to_le_bytes(), vault.key().as_ref()],
+        bump = round_dispute.bump
+    )]
+    pub round_dispute: Account<'info, RoundDispute>,
+
+    /// CHECK: Checked against `round_dispute.flagger`; receives the rent, plus the bond if upheld.
+    #[account(mut)]
+    pub flagger: UncheckedAccount<'info>,
+
+    /// Receives the bond of a rejected dispute.
+    #[account(mut, seeds = [SOL_FEE_VAULT_SEED], bump = sol_fee_vault.bump)]
+    pub sol_fee_vault: Account<'info, SolFeeVault>,
+}
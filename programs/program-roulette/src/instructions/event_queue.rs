@@ -0,0 +1,167 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::set_return_data;
+use crate::{
+    errors::RouletteError,
+    events::*,
+    seeds::{EVENT_QUEUE_SEED, GAME_SESSION_SEED},
+    state::*,
+};
+
+// Consumers read `EventQueue` records straight from the account data and acknowledge them with
+// `consume_events`, usually by CPI signed with one of their PDAs. Records are only ever written
+// by this program, so a consumer needs no trust in whoever sent the transaction that wrote them.
+
+// =================================================================================================
+// Event Queue Setup
+// =================================================================================================
+
+/// Creates the table's `EventQueue` with `consumer` as the only key allowed to acknowledge
+/// records. From then on, bets, settlements and claims on the table must pass the queue.
+pub fn initialize_event_queue(ctx: Context<InitializeEventQueue>, consumer: Pubkey) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+    let event_queue = &mut ctx.accounts.event_queue;
+    event_queue.game_session = game_session.key();
+    event_queue.consumer = consumer;
+    event_queue.head = 0;
+    event_queue.tail = 0;
+    event_queue.dropped = 0;
+    event_queue.entries = Vec::new();
+    event_queue.bump = ctx.bumps.event_queue;
+    game_session.event_queue_enabled = true;
+
+    emit!(EventQueueInitialized {
+        event_seq: game_session.next_event_seq(),
+        event_queue: event_queue.key(),
+        consumer,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeEventQueue<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GAME_SESSION_SEED, game_session.table_seed().as_slice()],
+        bump = game_session.bump,
+        constraint = authority.key() == game_session.authority @ RouletteError::AdminOnly
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = EventQueue::SPACE,
+        seeds = [EVENT_QUEUE_SEED, game_session.key().as_ref()],
+        bump
+    )]
+    pub event_queue: Account<'info, EventQueue>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Hands the queue to another consumer. Pending records stay pending.
+pub fn set_event_queue_consumer(ctx: Context<SetEventQueueConsumer>, consumer: Pubkey) -> Result<()> {
+    let event_queue = &mut ctx.accounts.event_queue;
+    let previous_consumer = event_queue.consumer;
+    event_queue.consumer = consumer;
+
+    emit!(EventQueueConsumerChanged {
+        event_seq: ctx.accounts.game_session.next_event_seq(),
+        previous_consumer,
+        consumer,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetEventQueueConsumer<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GAME_SESSION_SEED, game_session.table_seed().as_slice()],
+        bump = game_session.bump,
+        constraint = authority.key() == game_session.authority @ RouletteError::AdminOnly
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    #[account(mut, seeds = [EVENT_QUEUE_SEED, game_session.key().as_ref()], bump = event_queue.bump)]
+    pub event_queue: Account<'info, EventQueue>,
+}
+
+/// Closes the table's queue and returns its rent to the authority. Bets, settlements and
+/// claims on the table no longer pass a queue, and records still pending are lost. A new queue
+/// can be created again with `initialize_event_queue`.
+pub fn close_event_queue(ctx: Context<CloseEventQueue>) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+    game_session.event_queue_enabled = false;
+
+    emit!(EventQueueClosed {
+        event_seq: game_session.next_event_seq(),
+        event_queue: ctx.accounts.event_queue.key(),
+        pending: ctx.accounts.event_queue.pending(),
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseEventQueue<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GAME_SESSION_SEED, game_session.table_seed().as_slice()],
+        bump = game_session.bump,
+        constraint = authority.key() == game_session.authority @ RouletteError::AdminOnly
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    #[account(
+        mut,
+        seeds = [EVENT_QUEUE_SEED, game_session.key().as_ref()],
+        bump = event_queue.bump,
+        close = authority
+    )]
+    pub event_queue: Account<'info, EventQueue>,
+}
+
+// =================================================================================================
+// Event Queue Consumption
+// =================================================================================================
+
+/// Acknowledges up to `count` of the oldest pending records, freeing their slots. The number
+/// actually acknowledged is returned as return data.
+pub fn consume_events(ctx: Context<ConsumeEvents>, count: u64) -> Result<()> {
+    let consumed = ctx.accounts.event_queue.consume(count);
+    set_return_data(&consumed.to_le_bytes());
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ConsumeEvents<'info> {
+    pub consumer: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = consumer.key() == event_queue.consumer @ RouletteError::Unauthorized
+    )]
+    pub event_queue: Account<'info, EventQueue>,
+}
+
+/// Appends `event` to the table's queue, if it has one. Tables with a queue must pass it, so
+/// consumers never miss a record.
+pub(crate) fn push_event(
+    game_session: &GameSession,
+    event_queue: Option<&mut Account<EventQueue>>,
+    event: QueuedEvent
+) -> Result<()> {
+    match event_queue {
+        Some(event_queue) => event_queue.push(event),
+        None => require!(!game_session.event_queue_enabled, RouletteError::EventQueueRequired),
+    }
+    Ok(())
+}
@@ -0,0 +1,268 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{self, Mint, MintTo, TokenAccount, TokenInterface};
+use crate::instructions::player::init_player_bets;
+use crate::instructions::vault::{initialize_provider_state, initialize_vault_state};
+use crate::{
+    constants::TEST_FAUCET_MAX_AMOUNT,
+    errors::RouletteError,
+    events::LiquidityProvided,
+    seeds::{
+        GAME_SESSION_SEED,
+        PLAYER_BETS_SEED,
+        PROTOCOL_STATS_SEED,
+        PROVIDER_STATE_SEED,
+        TEST_MINT_SEED,
+        VAULT_SEED,
+    },
+    state::*,
+};
+
+// =================================================================================================
+// Test Faucet
+// =================================================================================================
+
+/// Mints `amount` of the program's test token to the caller, creating the mint and the caller's
+/// token account on first use. With it, an integration environment bootstraps a funded test
+/// vault in two calls: `test_faucet`, then `initialize_and_provide_liquidity` for the test mint.
+pub fn test_faucet(ctx: Context<TestFaucet>, amount: u64) -> Result<()> {
+    require!(amount > 0, RouletteError::AmountMustBeGreaterThanZero);
+    require!(amount <= TEST_FAUCET_MAX_AMOUNT, RouletteError::TestFaucetLimitExceeded);
+
+    mint_test_tokens(
+        &ctx.accounts.token_program,
+        &ctx.accounts.test_mint,
+        ctx.bumps.test_mint,
+        ctx.accounts.recipient_token_account.to_account_info(),
+        amount
+    )
+}
+
+/// Mints `amount` of the test token to `to`, signing as the test mint.
+fn mint_test_tokens<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    test_mint: &InterfaceAccount<'info, Mint>,
+    test_mint_bump: u8,
+    to: AccountInfo<'info>,
+    amount: u64
+) -> Result<()> {
+    let seeds = &[TEST_MINT_SEED, &[test_mint_bump]];
+    let signer_seeds = &[&seeds[..]];
+    token_interface::mint_to(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            MintTo {
+                mint: test_mint.to_account_info(),
+                to,
+                authority: test_mint.to_account_info(),
+            },
+            signer_seeds
+        ),
+        amount
+    )
+}
+
+#[derive(Accounts)]
+pub struct TestFaucet<'info> {
+    #[account(mut)]
+    pub recipient: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = recipient,
+        seeds = [TEST_MINT_SEED],
+        bump,
+        mint::decimals = 9,
+        mint::authority = test_mint,
+        mint::token_program = token_program
+    )]
+    pub test_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = recipient,
+        associated_token::mint = test_mint,
+        associated_token::authority = recipient,
+        associated_token::token_program = token_program
+    )]
+    pub recipient_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+// =================================================================================================
+// Demo Seeding
+// =================================================================================================
+
+/// Admin-only. Creates the test token's vault, with its token account at the vault's associated
+/// address, and mints `liquidity` straight into it as the admin's position. Every account is a
+/// PDA or an associated token account, so a seeded environment has the same addresses on every
+/// cluster. The vault creation fee is waived.
+pub fn seed_demo_vault(ctx: Context<SeedDemoVault>, liquidity: u64) -> Result<()> {
+    require!(liquidity > 0, RouletteError::AmountMustBeGreaterThanZero);
+    require!(liquidity <= TEST_FAUCET_MAX_AMOUNT, RouletteError::TestFaucetLimitExceeded);
+
+    mint_test_tokens(
+        &ctx.accounts.token_program,
+        &ctx.accounts.test_mint,
+        ctx.bumps.test_mint,
+        ctx.accounts.vault_token_account.to_account_info(),
+        liquidity
+    )?;
+
+    let vault = &mut ctx.accounts.vault;
+    initialize_vault_state(vault, &ctx.accounts.test_mint, ctx.accounts.vault_token_account.key(), ctx.bumps.vault);
+    vault.total_liquidity = liquidity;
+    vault.total_provider_capital = liquidity;
+
+    vault.admit_provider()?;
+    let provider_state = &mut ctx.accounts.provider_state;
+    initialize_provider_state(provider_state, vault.key(), ctx.accounts.authority.key(), ctx.bumps.provider_state);
+    let now = Clock::get()?.unix_timestamp;
+    provider_state.record_deposit(liquidity, now)?;
+
+    let stats = &mut ctx.accounts.protocol_stats;
+    stats.total_vaults = stats.total_vaults.saturating_add(1);
+
+    emit!(LiquidityProvided {
+        event_seq: ctx.accounts.game_session.next_event_seq(),
+        provider: ctx.accounts.authority.key(),
+        token_mint: vault.token_mint,
+        amount: liquidity,
+        timestamp: now,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SeedDemoVault<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GAME_SESSION_SEED],
+        bump = game_session.bump,
+        constraint = authority.key() == game_session.authority @ RouletteError::AdminOnly
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        seeds = [TEST_MINT_SEED],
+        bump,
+        mint::decimals = 9,
+        mint::authority = test_mint,
+        mint::token_program = token_program
+    )]
+    pub test_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + std::mem::size_of::<VaultAccount>(),
+        seeds = [VAULT_SEED, test_mint.key().as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(
+        init,
+        payer = authority,
+        associated_token::mint = test_mint,
+        associated_token::authority = vault,
+        associated_token::token_program = token_program
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + std::mem::size_of::<ProviderState>(),
+        seeds = [PROVIDER_STATE_SEED, vault.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub provider_state: Account<'info, ProviderState>,
+
+    #[account(mut, seeds = [PROTOCOL_STATS_SEED], bump = protocol_stats.bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Authority-only. Mints `amount` of the test token to `player` and creates their `PlayerBets`
+/// on the table, both paid by the authority, so a demo player can bet without any setup of
+/// their own. Calling it again for the same player only mints more.
+pub fn seed_demo_player(ctx: Context<SeedDemoPlayer>, amount: u64) -> Result<()> {
+    require!(amount > 0, RouletteError::AmountMustBeGreaterThanZero);
+    require!(amount <= TEST_FAUCET_MAX_AMOUNT, RouletteError::TestFaucetLimitExceeded);
+
+    mint_test_tokens(
+        &ctx.accounts.token_program,
+        &ctx.accounts.test_mint,
+        ctx.bumps.test_mint,
+        ctx.accounts.player_token_account.to_account_info(),
+        amount
+    )?;
+
+    // `init_if_needed` leaves existing accounts untouched, so only fill in a fresh one.
+    let accounts = ctx.accounts;
+    if accounts.player_bets.player == Pubkey::default() {
+        init_player_bets(
+            &mut accounts.player_bets,
+            accounts.player.key(),
+            accounts.game_session.key(),
+            ctx.bumps.player_bets
+        )?;
+        accounts.protocol_stats.total_players = accounts.protocol_stats.total_players.saturating_add(1);
+    }
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SeedDemoPlayer<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [GAME_SESSION_SEED, game_session.table_seed().as_slice()],
+        bump = game_session.bump,
+        constraint = authority.key() == game_session.authority @ RouletteError::AdminOnly
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    pub player: SystemAccount<'info>,
+
+    #[account(mut, seeds = [TEST_MINT_SEED], bump)]
+    pub test_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        associated_token::mint = test_mint,
+        associated_token::authority = player,
+        associated_token::token_program = token_program
+    )]
+    pub player_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = PlayerBets::INITIAL_SPACE,
+        seeds = [PLAYER_BETS_SEED, game_session.key().as_ref(), player.key().as_ref()],
+        bump
+    )]
+    pub player_bets: Account<'info, PlayerBets>,
+
+    #[account(mut, seeds = [PROTOCOL_STATS_SEED], bump = protocol_stats.bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
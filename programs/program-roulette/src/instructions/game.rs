@@ -1,9 +1,59 @@
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program::hash;
+use anchor_lang::solana_program::{
+    hash,
+    instruction::{AccountMeta, Instruction},
+    program::invoke,
+    sysvar,
+};
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+use crate::instructions::event_queue::push_event;
+use crate::instructions::stats::grow_account;
 use crate::{
-    constants::GAME_ADMIN_PUBKEY,
+    constants::{
+        BET_TYPE_COUNT,
+        GAME_ADMIN_PUBKEY,
+        DEFAULT_FINALITY_SLOTS,
+        DEFAULT_SLOT_DRIFT_TOLERANCE_BPS,
+        DEFAULT_VOID_TIMEOUT_SLOTS,
+        CANCEL_ROUND_TIMEOUT_SECS,
+        BPS_DENOMINATOR,
+        MAX_RANDOMNESS_ORACLES,
+        MAX_RANDOM_INITIATORS,
+        MAX_ROUND_LABEL_LEN,
+        MAX_ROUND_URI_LEN,
+        MAX_TABLE_ACCESS_LIST_LEN,
+        MAX_TABLE_NAME_LEN,
+        GAME_SESSION_LAYOUT_VERSION,
+        LEGACY_TABLE_ID,
+        ROUND_STATS_LAYOUT_VERSION,
+        MIN_SETTLEMENT_SLOTS,
+        NO_RECENT_NUMBER,
+        RECENT_NUMBERS_LEN,
+        REVEAL_SLOT_DELAY,
+        SLOT_HASHES_MIXED,
+        SWITCHBOARD_ON_DEMAND_PROGRAM_ID,
+        SWITCHBOARD_RANDOMNESS_DISCRIMINATOR,
+        WHEEL_POCKETS,
+    },
     errors::RouletteError,
     events::*,
+    seeds::{
+        CONFIG_SEED,
+        EVENT_QUEUE_SEED,
+        GAME_SESSION_SEED,
+        OPERATOR_STATS_SEED,
+        PROTOCOL_STATS_SEED,
+        RENT_POOL_SEED,
+        ROUND_HISTORY_SEED,
+        ROUND_RESULT_SEED,
+        ROUND_STATS_SEED,
+        TABLE_ACCESS_LIST_SEED,
+        TABLE_METADATA_SEED,
+        VAULT_EXPOSURE_SEED,
+        VAULT_SEED,
+        WHEEL_HISTOGRAM_SEED,
+        table_seed,
+    },
     state::*,
 };
 
@@ -11,7 +61,10 @@ use crate::{
 // Game Initialization
 // =================================================================================================
 
-pub fn initialize_game_session(ctx: Context<InitializeGameSession>) -> Result<()> {
+/// Creates the game session of table `table_id`. Tables run independent rounds in parallel
+/// against the shared vaults. Table `LEGACY_TABLE_ID` lives at the original address; creating
+/// any other table is reserved to the game admin.
+pub fn initialize_game_session(ctx: Context<InitializeGameSession>, table_id: u64) -> Result<()> {
     let game_session = &mut ctx.accounts.game_session;
     
     game_session.authority = *ctx.accounts.authority.key;
@@ -25,50 +78,417 @@ pub fn initialize_game_session(ctx: Context<InitializeGameSession>) -> Result<()
     game_session.bump = ctx.bumps.game_session;
     game_session.last_bettor = None;
     game_session.last_completed_round = 0;
+    game_session.round_start_slot = 0;
+    game_session.bets_closed_slot = 0;
+    game_session.last_completed_timestamp = 0;
+    game_session.last_completed_slot = 0;
+    game_session.min_random_delay_secs = 0;
+    game_session.claim_window_secs = 0;
+    game_session.void_timeout_slots = DEFAULT_VOID_TIMEOUT_SLOTS;
+    game_session.last_voided_round = 0;
+    game_session.last_cancelled_round = 0;
+    game_session.operator = Pubkey::default();
+    game_session.event_queue_enabled = false;
+    game_session.finality_slots = DEFAULT_FINALITY_SLOTS;
+    game_session.finality_payout_threshold = 0;
+    game_session.min_total_bets = 0;
+    game_session.min_unique_bettors = 0;
+    game_session.round_bet_count = 0;
+    game_session.round_unique_bettors = 0;
+    game_session.round_interval_slots = 0;
+    game_session.next_round_slot = 0;
+    game_session.recent_numbers = [NO_RECENT_NUMBER; RECENT_NUMBERS_LEN];
+    game_session.access_list_enabled = false;
+    game_session.bound_vault = None;
+    game_session.frozen = false;
+    game_session.locked_server_seed = [0; 32];
+    game_session.wheel_type = WheelType::European;
+    game_session.en_prison = false;
+    game_session.max_betting_duration_secs = 0;
+    game_session.min_betting_duration_secs = 0;
+    game_session.cooldown_between_rounds_secs = 0;
+    game_session.fallback_randomness_mode = None;
+    game_session.fallback_timeout_slots = 0;
+    game_session.spin_timeout_secs = 0;
+    game_session.slot_drift_tolerance_bps = DEFAULT_SLOT_DRIFT_TOLERANCE_BPS;
+    game_session.last_settlement_failure = None;
+    game_session.settlement_retries = 0;
+    game_session.seed_commitment = [0; 32];
+    game_session.randomness_account = None;
+    game_session.randomness_seed_slot = 0;
+    game_session.orao_request_seed = None;
+    game_session.event_seq = 0;
+    game_session.entropy_accumulator = [0; 32];
+    game_session.reveal_target_slot = 0;
+    game_session.round_fallback_slot = 0;
+    game_session.round_void_slot = 0;
+    game_session.fallback_active = false;
+    game_session.table_id = table_id;
+    game_session.layout_version = GAME_SESSION_LAYOUT_VERSION;
+    game_session.randomness_mode = RandomnessMode::InternalHash;
+    game_session.randomness_oracles = [Pubkey::default(); MAX_RANDOMNESS_ORACLES];
+    game_session.oracle_quorum = 0;
+    game_session.random_initiators = [Pubkey::default(); MAX_RANDOM_INITIATORS];
+    game_session.random_initiator_count = 0;
     Ok(())
 }
 
 #[derive(Accounts)]
+#[instruction(table_id: u64)]
 pub struct InitializeGameSession<'info> {
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = table_id == LEGACY_TABLE_ID || authority.key() == GAME_ADMIN_PUBKEY @ RouletteError::AdminOnly
+    )]
     pub authority: Signer<'info>,
 
-    #[account(init, payer = authority, space = 117, seeds = [b"game_session"], bump)] // 85 + 32 = 117
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + std::mem::size_of::<GameSession>(),
+        seeds = [GAME_SESSION_SEED, table_seed(table_id).as_slice()],
+        bump
+    )]
     pub game_session: Account<'info, GameSession>,
 
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
 
+// =================================================================================================
+// Game Session Migration
+// =================================================================================================
+
+/// Authority-only. Upgrades the legacy session at `[GAME_SESSION_SEED]` in place to the current
+/// layout as table `LEGACY_TABLE_ID`. The account is grown to the current size, with new fields
+/// starting at their defaults, and every existing field, including `current_round`, is kept. Its
+/// address does not change, so `PlayerBets` and claim records seeded by it stay valid.
+pub fn migrate_session_to_table(ctx: Context<MigrateSessionToTable>) -> Result<()> {
+    migrate_session(
+        &ctx.accounts.game_session.to_account_info(),
+        &ctx.accounts.authority,
+        &ctx.accounts.system_program,
+        LEGACY_TABLE_ID
+    )
+}
+
+#[derive(Accounts)]
+pub struct MigrateSessionToTable<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: The legacy session may not deserialize under the current layout until it has been
+    /// grown; its authority is read from the raw data and its address is checked by the seeds.
+    #[account(mut, seeds = [GAME_SESSION_SEED], bump)]
+    pub game_session: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Authority-only. Upgrades table `table_id`'s session in place to the current layout after a
+/// program upgrade added fields to `GameSession`: the account is grown to the current size and
+/// the new fields are backfilled with their defaults. Every existing field is kept.
+pub fn migrate_game_session(ctx: Context<MigrateGameSession>, table_id: u64) -> Result<()> {
+    migrate_session(
+        &ctx.accounts.game_session.to_account_info(),
+        &ctx.accounts.authority,
+        &ctx.accounts.system_program,
+        table_id
+    )
+}
+
+#[derive(Accounts)]
+#[instruction(table_id: u64)]
+pub struct MigrateGameSession<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: An outdated session may not deserialize under the current layout until it has
+    /// been grown; its authority is read from the raw data and its address is checked by the
+    /// seeds.
+    #[account(mut, seeds = [GAME_SESSION_SEED, table_seed(table_id).as_slice()], bump)]
+    pub game_session: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Grows the session at `session_info` to the current layout, at the authority's expense, and
+/// backfills every field added after its `layout_version`.
+fn migrate_session<'info>(
+    session_info: &AccountInfo<'info>,
+    authority: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    table_id: u64
+) -> Result<()> {
+    {
+        let data = session_info.try_borrow_data()?;
+        let session_authority = data
+            .get(8..40)
+            .map(|bytes| Pubkey::try_from(bytes).unwrap())
+            .ok_or(RouletteError::AdminOnly)?;
+        require_keys_eq!(session_authority, authority.key(), RouletteError::AdminOnly);
+    }
+
+    let new_len = 8 + std::mem::size_of::<GameSession>();
+    if session_info.data_len() < new_len {
+        grow_account(
+            session_info,
+            new_len,
+            RentPayer::Cranker,
+            None,
+            &authority.to_account_info(),
+            system_program
+        )?;
+    }
+
+    let mut data = session_info.try_borrow_mut_data()?;
+    let mut game_session = GameSession::try_deserialize(&mut &data[..])?;
+    let from_layout_version = game_session.layout_version;
+    require!(
+        from_layout_version < GAME_SESSION_LAYOUT_VERSION,
+        RouletteError::SessionAlreadyMigrated
+    );
+    game_session.table_id = table_id;
+    game_session.backfill_layout(from_layout_version);
+    game_session.layout_version = GAME_SESSION_LAYOUT_VERSION;
+
+    emit!(GameSessionMigrated {
+        event_seq: game_session.next_event_seq(),
+        game_session: session_info.key(),
+        table_id: game_session.table_id,
+        current_round: game_session.current_round,
+        from_layout_version,
+        layout_version: game_session.layout_version,
+    });
+
+    game_session.try_serialize(&mut &mut data[..])?;
+    Ok(())
+}
+
+// =================================================================================================
+// Round Stats Migration
+// =================================================================================================
+
+/// Upgrades the stats of `round` in place to the current layout after a program upgrade added
+/// fields to `RoundStats`: the account is grown to the current size and the new fields are
+/// backfilled with their defaults. Anyone can migrate; the growth is paid like round stats
+/// rent, by the signer or by the `RentPool` under `Config::round_stats_payer`.
+pub fn migrate_round_stats(ctx: Context<MigrateRoundStats>, round: u64) -> Result<()> {
+    let stats_info = ctx.accounts.round_stats.to_account_info();
+    if stats_info.data_len() < RoundStats::SPACE {
+        grow_account(
+            &stats_info,
+            RoundStats::SPACE,
+            ctx.accounts.config.round_stats_payer,
+            ctx.accounts.rent_pool.as_ref(),
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.system_program
+        )?;
+    }
+
+    let mut data = stats_info.try_borrow_mut_data()?;
+    let mut round_stats = RoundStats::try_deserialize(&mut &data[..])?;
+    let from_layout_version = round_stats.layout_version;
+    require!(
+        from_layout_version < ROUND_STATS_LAYOUT_VERSION,
+        RouletteError::RoundStatsAlreadyMigrated
+    );
+    round_stats.backfill_layout(from_layout_version, &ctx.accounts.game_session);
+    round_stats.layout_version = ROUND_STATS_LAYOUT_VERSION;
+
+    emit!(RoundStatsMigrated {
+        game_session: ctx.accounts.game_session.key(),
+        round,
+        from_layout_version,
+        layout_version: round_stats.layout_version,
+    });
+
+    round_stats.try_serialize(&mut &mut data[..])?;
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(round: u64)]
+pub struct MigrateRoundStats<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(seeds = [GAME_SESSION_SEED, game_session.table_seed().as_slice()], bump = game_session.bump)]
+    pub game_session: Account<'info, GameSession>,
+
+    /// CHECK: Outdated stats may not deserialize under the current layout until they have been
+    /// grown; the address is checked by the seeds and the owner by the deserialization.
+    #[account(
+        mut,
+        owner = crate::ID,
+        seeds = [ROUND_STATS_SEED, game_session.key().as_ref(), &round.to_le_bytes()],
+        bump
+    )]
+    pub round_stats: UncheckedAccount<'info>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    /// Funds the growth when `Config::round_stats_payer` is `Protocol`.
+    #[account(mut, seeds = [RENT_POOL_SEED], bump = rent_pool.bump)]
+    pub rent_pool: Option<Account<'info, RentPool>>,
+
+    pub system_program: Program<'info, System>,
+}
+
 // =================================================================================================
 // Game Start
 // =================================================================================================
 
-pub fn start_new_round(ctx: Context<StartNewRound>) -> Result<()> {
-    let game_session = &mut ctx.accounts.game_session;
-    let current_time = Clock::get()?.unix_timestamp;
+/// Starts a round committed to `seed_commitment`, the SHA-256 of a server seed that must be
+/// revealed at settlement. Since the commitment predates every bet, the operator cannot pick a
+/// seed with knowledge of the bets. The optional `label` and `uri`, e.g. a tournament name and
+/// a stream link, are stored on the round's stats so its results can be attributed to a
+/// promotion. Only the table's operators can set them, not starters of open tables.
+pub fn start_new_round(
+    ctx: Context<StartNewRound>,
+    seed_commitment: [u8; 32],
+    label: Option<String>,
+    uri: Option<String>
+) -> Result<()> {
+    let (label, uri) = (label.unwrap_or_default(), uri.unwrap_or_default());
+    require!(
+        label.len() <= MAX_ROUND_LABEL_LEN && uri.len() <= MAX_ROUND_URI_LEN,
+        RouletteError::RoundMetadataTooLong
+    );
+    require!(
+        (label.is_empty() && uri.is_empty()) ||
+            ctx.accounts.game_session.is_round_operator(ctx.accounts.starter.key),
+        RouletteError::RoundMetadataOperatorOnly
+    );
+    let clock = Clock::get()?;
+    open_round(
+        RoundStartAccounts {
+            game_session: &mut ctx.accounts.game_session,
+            round_stats: &mut ctx.accounts.round_stats,
+            round_stats_bump: ctx.bumps.round_stats,
+            protocol_stats: &mut ctx.accounts.protocol_stats,
+            operator_stats: &mut ctx.accounts.operator_stats,
+            operator_stats_bump: ctx.bumps.operator_stats,
+            config: &ctx.accounts.config,
+            starter: ctx.accounts.starter.key(),
+        },
+        seed_commitment,
+        label,
+        uri,
+        &clock
+    )
+}
+
+/// Accounts opening a round updates, shared by `start_new_round` and `settle_and_restart`.
+struct RoundStartAccounts<'a, 'info> {
+    game_session: &'a mut Account<'info, GameSession>,
+    round_stats: &'a mut Account<'info, RoundStats>,
+    round_stats_bump: u8,
+    protocol_stats: &'a mut Account<'info, ProtocolStats>,
+    operator_stats: &'a mut Account<'info, OperatorStats>,
+    operator_stats_bump: u8,
+    config: &'a Config,
+    starter: Pubkey,
+}
+
+/// Opens the next round for bets with `seed_commitment`, once the current one is over, the
+/// cooldown has elapsed and, on scheduled tables, the scheduled slot has come.
+fn open_round(
+    accounts: RoundStartAccounts,
+    seed_commitment: [u8; 32],
+    label: String,
+    uri: String,
+    clock: &Clock
+) -> Result<()> {
+    let RoundStartAccounts {
+        game_session,
+        round_stats,
+        round_stats_bump,
+        protocol_stats,
+        operator_stats,
+        operator_stats_bump,
+        config,
+        starter,
+    } = accounts;
+    let current_time = clock.unix_timestamp;
 
     require!(
-        game_session.round_status == RoundStatus::NotStarted ||
-            game_session.round_status == RoundStatus::Completed,
+        matches!(
+            game_session.round_status,
+            RoundStatus::NotStarted | RoundStatus::Completed | RoundStatus::Voided | RoundStatus::Cancelled
+        ),
         RouletteError::RoundInProgress
     );
-
+    require!(!config.paused, RouletteError::ProgramPaused);
+    require!(!game_session.frozen, RouletteError::TableFrozen);
+    require!(game_session.cooldown_elapsed(clock), RouletteError::RoundCooldownActive);
+    if game_session.round_interval_slots > 0 {
+        require!(clock.slot >= game_session.next_round_slot, RouletteError::RoundNotDue);
+        game_session.schedule_next_round(clock.slot);
+    }
 
     game_session.current_round = game_session.current_round
         .checked_add(1)
         .ok_or(RouletteError::ArithmeticOverflow)?;
     
     game_session.round_start_time = current_time;
+    game_session.round_start_slot = clock.slot;
     game_session.round_status = RoundStatus::AcceptingBets;
     game_session.bets_closed_timestamp = 0;
+    game_session.bets_closed_slot = 0;
     game_session.get_random_timestamp = 0;
     game_session.last_bettor = None; // Reset last bettor for the new round
+    game_session.last_settlement_failure = None;
+    game_session.settlement_retries = 0;
+    game_session.seed_commitment = seed_commitment;
+    game_session.randomness_account = None;
+    game_session.randomness_seed_slot = 0;
+    game_session.orao_request_seed = None;
+    game_session.entropy_accumulator = [0; 32];
+    game_session.reveal_target_slot = 0;
+    game_session.round_fallback_slot = 0;
+    game_session.round_void_slot = 0;
+    game_session.fallback_active = false;
+    game_session.round_bet_count = 0;
+    game_session.round_unique_bettors = 0;
+
+    round_stats.round = game_session.current_round;
+    round_stats.total_wagered = 0;
+    round_stats.bet_count = 0;
+    round_stats.rent_payer = config.round_stats_payer;
+    round_stats.bump = round_stats_bump;
+    round_stats.unique_bettors = 0;
+    round_stats.bettor_filter = BettorFilter::default();
+    round_stats.winning_number = None;
+    round_stats.settled_timestamp = 0;
+    round_stats.settled_slot = 0;
+    round_stats.bet_type_wagered = [0; BET_TYPE_COUNT];
+    round_stats.fee_schedule = FeeSchedule::CURRENT;
+    round_stats.en_prison = game_session.en_prison;
+    round_stats.finalized = false;
+    round_stats.bet_type_exposure = vec![[0; WHEEL_POCKETS]; BET_TYPE_COUNT];
+    round_stats.label = label;
+    round_stats.uri = uri;
+    round_stats.layout_version = ROUND_STATS_LAYOUT_VERSION;
+    round_stats.status = RoundStatus::AcceptingBets;
+
+    protocol_stats.total_rounds = protocol_stats.total_rounds.saturating_add(1);
+
+    operator_stats.touch(starter, operator_stats_bump, clock.slot);
+    operator_stats.rounds_started = operator_stats.rounds_started.saturating_add(1);
 
     emit!(RoundStarted {
+        event_seq: game_session.next_event_seq(),
         round: game_session.current_round,
-        starter: *ctx.accounts.starter.key,
+        starter,
         start_time: current_time,
+        seed_commitment,
+        min_total_bets: game_session.min_total_bets,
+        min_unique_bettors: game_session.min_unique_bettors,
+        next_round_slot: game_session.next_round_slot,
+        label: round_stats.label.clone(),
+        uri: round_stats.uri.clone(),
     });
     Ok(())
 }
@@ -77,15 +497,41 @@ pub fn start_new_round(ctx: Context<StartNewRound>) -> Result<()> {
 pub struct StartNewRound<'info> {
     #[account(
         mut, 
-        seeds = [b"game_session"], 
+        seeds = [GAME_SESSION_SEED, game_session.table_seed().as_slice()],
         bump = game_session.bump,
-        constraint = starter.key() == GAME_ADMIN_PUBKEY @ RouletteError::AdminOnly
+        constraint = game_session.is_round_operator(&starter.key()) || game_session.open_to_any_starter()
+            @ RouletteError::AdminOnly
     )]
     pub game_session: Account<'info, GameSession>,
 
     #[account(mut)]
     pub starter: Signer<'info>,
 
+    #[account(mut, seeds = [PROTOCOL_STATS_SEED], bump = protocol_stats.bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+
+    #[account(
+        init_if_needed,
+        payer = starter,
+        space = 8 + std::mem::size_of::<OperatorStats>(),
+        seeds = [OPERATOR_STATS_SEED, starter.key().as_ref()],
+        bump
+    )]
+    pub operator_stats: Account<'info, OperatorStats>,
+
+    /// Stats of the round being started.
+    #[account(
+        init,
+        payer = starter,
+        space = RoundStats::SPACE,
+        seeds = [ROUND_STATS_SEED, game_session.key().as_ref(), &(game_session.current_round + 1).to_le_bytes()],
+        bump
+    )]
+    pub round_stats: Account<'info, RoundStats>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
     pub system_program: Program<'info, System>,
 }
 
@@ -93,11 +539,76 @@ pub struct StartNewRound<'info> {
 // Game Close Bets
 // =================================================================================================
 
-pub fn close_bets(ctx: Context<CloseBets>) -> Result<()> {
-    let game_session = &mut ctx.accounts.game_session;
-    let current_time = Clock::get()?.unix_timestamp;
+/// Closes betting. When a hedging program is registered, every `VaultExposure` passed in
+/// `remaining_accounts` (after the hedging program itself) has the vault's net exposure for the
+/// round reported to it by CPI, atomically with the close.
+pub fn close_bets<'info>(ctx: Context<'_, '_, 'info, 'info, CloseBets<'info>>) -> Result<()> {
+    let clock = Clock::get()?;
+    let current_time = clock.unix_timestamp;
+    close_betting(
+        &mut ctx.accounts.game_session,
+        &mut ctx.accounts.operator_stats,
+        ctx.accounts.closer.key(),
+        ctx.bumps.operator_stats,
+        &clock
+    )?;
+    ctx.accounts.round_stats.record_settlement_deadlines(&ctx.accounts.game_session);
+
+    let round = ctx.accounts.game_session.current_round;
+    let hedging_program = ctx.accounts.config.hedging_program;
+    if hedging_program != Pubkey::default() {
+        // Persist the closed status first so the hedging program reads the final state.
+        ctx.accounts.game_session.exit(&crate::ID)?;
+        report_exposure(
+            &mut ctx.accounts.game_session,
+            ctx.remaining_accounts,
+            hedging_program,
+            round,
+            current_time
+        )?;
+    }
+
+    emit!(BetsClosed {
+        event_seq: ctx.accounts.game_session.next_event_seq(),
+        round,
+        closer: *ctx.accounts.closer.key,
+        close_time: current_time,
+        bet_count: ctx.accounts.game_session.round_bet_count,
+        unique_bettors: ctx.accounts.game_session.round_unique_bettors,
+    });
+    Ok(())
+}
+
+/// Switches the current round to the fallback mode if its deadline has passed.
+fn activate_fallback(game_session: &mut GameSession, slot: u64) {
+    if game_session.activate_fallback_if_due(slot) {
+        emit!(RandomnessFallbackActivated {
+            event_seq: game_session.next_event_seq(),
+            round: game_session.current_round,
+            from_mode: game_session.randomness_mode,
+            to_mode: game_session.active_randomness_mode(),
+            slot,
+        });
+    }
+}
 
+/// Checks that the current round settles with `mode`, after switching it to the fallback mode
+/// if due, so a backend's own instructions only act on rounds that backend decides.
+pub(crate) fn require_randomness_mode(game_session: &mut GameSession, mode: RandomnessMode) -> Result<()> {
+    activate_fallback(game_session, Clock::get()?.slot);
+    require!(game_session.active_randomness_mode() == mode, RouletteError::RandomnessModeMismatch);
+    Ok(())
+}
 
+/// Checks that `closer` may close the current round's betting now and moves the round to
+/// `BetsClosed`.
+fn close_betting(
+    game_session: &mut GameSession,
+    operator_stats: &mut OperatorStats,
+    closer: Pubkey,
+    operator_stats_bump: u8,
+    clock: &Clock
+) -> Result<()> {
     require!(
         game_session.round_status == RoundStatus::AcceptingBets,
         RouletteError::BetsNotAccepted
@@ -106,16 +617,25 @@ pub fn close_bets(ctx: Context<CloseBets>) -> Result<()> {
         game_session.last_bettor.is_some(),
         RouletteError::CannotCloseBetsWithoutBets
     );
-
+    require!(
+        game_session.min_betting_duration_elapsed(clock),
+        RouletteError::MinBettingDurationNotElapsed
+    );
+    require!(game_session.participation_met(), RouletteError::ParticipationTooLow);
+    // Anyone can close once the betting window is over, so rounds keep moving without the
+    // admin's bot.
+    require!(
+        game_session.is_round_operator(&closer) || game_session.betting_window_elapsed(clock),
+        RouletteError::BettingWindowOpen
+    );
 
     game_session.round_status = RoundStatus::BetsClosed;
-    game_session.bets_closed_timestamp = current_time;
+    game_session.bets_closed_timestamp = clock.unix_timestamp;
+    game_session.bets_closed_slot = clock.slot;
+    game_session.record_settlement_deadlines();
 
-    emit!(BetsClosed {
-        round: game_session.current_round,
-        closer: *ctx.accounts.closer.key,
-        close_time: current_time,
-    });
+    operator_stats.touch(closer, operator_stats_bump, clock.slot);
+    operator_stats.rounds_closed = operator_stats.rounds_closed.saturating_add(1);
     Ok(())
 }
 
@@ -123,47 +643,366 @@ pub fn close_bets(ctx: Context<CloseBets>) -> Result<()> {
 pub struct CloseBets<'info> {
     #[account(
         mut, 
-        seeds = [b"game_session"], 
-        bump = game_session.bump,
-        constraint = closer.key() == GAME_ADMIN_PUBKEY @ RouletteError::AdminOnly
+        seeds = [GAME_SESSION_SEED, game_session.table_seed().as_slice()],
+        bump = game_session.bump
     )]
     pub game_session: Account<'info, GameSession>,
 
+    /// The game admin, or anyone once the betting window has elapsed.
     #[account(mut)]
     pub closer: Signer<'info>,
 
+    /// Stats of the closing round, which record its fallback and void deadlines.
+    #[account(
+        mut,
+        seeds = [ROUND_STATS_SEED, game_session.key().as_ref(), &game_session.current_round.to_le_bytes()],
+        bump = round_stats.bump
+    )]
+    pub round_stats: Account<'info, RoundStats>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        init_if_needed,
+        payer = closer,
+        space = 8 + std::mem::size_of::<OperatorStats>(),
+        seeds = [OPERATOR_STATS_SEED, closer.key().as_ref()],
+        bump
+    )]
+    pub operator_stats: Account<'info, OperatorStats>,
+
     pub system_program: Program<'info, System>,
 }
 
+/// Sends one `report_exposure` CPI per vault exposure to the table in `remaining_accounts[1..]`.
+/// `remaining_accounts[0]` must be the registered hedging program.
+fn report_exposure<'info>(
+    game_session: &mut Account<'info, GameSession>,
+    remaining_accounts: &'info [AccountInfo<'info>],
+    hedging_program: Pubkey,
+    round: u64,
+    timestamp: i64
+) -> Result<()> {
+    let (program_info, exposure_infos) = remaining_accounts
+        .split_first()
+        .ok_or(RouletteError::HedgingProgramMismatch)?;
+    require_keys_eq!(program_info.key(), hedging_program, RouletteError::HedgingProgramMismatch);
+    require!(!exposure_infos.is_empty(), RouletteError::NoHedgedVaults);
+
+    let discriminator = hash::hash(b"global:report_exposure").to_bytes();
+
+    for exposure_info in exposure_infos {
+        let exposure = Account::<VaultExposure>::try_from(exposure_info)?;
+        require_keys_eq!(exposure.game_session, game_session.key(), RouletteError::VaultMismatch);
+        let expected_exposure = Pubkey::create_program_address(
+            &[VAULT_EXPOSURE_SEED, exposure.vault.as_ref(), exposure.game_session.as_ref(), &[exposure.bump]],
+            &crate::ID
+        ).map_err(|_| RouletteError::VaultPDAMismatch)?;
+        require_keys_eq!(expected_exposure, exposure_info.key(), RouletteError::VaultPDAMismatch);
+
+        let report = ExposureReport {
+            round,
+            token_mint: exposure.token_mint,
+            round_wagered: if exposure.tracks(round) { exposure.round_wagered } else { 0 },
+            net_exposure: exposure.net_exposure(round)?,
+        };
+
+        let mut data = discriminator[..8].to_vec();
+        data.extend_from_slice(&report.try_to_vec()?);
+        let instruction = Instruction {
+            program_id: hedging_program,
+            accounts: vec![
+                AccountMeta::new_readonly(game_session.key(), false),
+                AccountMeta::new_readonly(exposure_info.key(), false),
+            ],
+            data,
+        };
+        invoke(
+            &instruction,
+            &[game_session.to_account_info(), exposure_info.clone(), program_info.clone()]
+        )?;
+
+        emit!(ExposureReported {
+            event_seq: game_session.next_event_seq(),
+            round,
+            token_mint: report.token_mint,
+            hedging_program,
+            net_exposure: report.net_exposure,
+            timestamp,
+        });
+    }
+
+    Ok(())
+}
+
 // =================================================================================================
 // Game Get Random
 // =================================================================================================
 
-pub fn get_random(ctx: Context<GetRandom>) -> Result<()> {
+/// Settles the current round with the table's `randomness_mode` backend, so operators can
+/// switch randomness sources without changing their keeper. Once the round's fallback slot
+/// has passed, it switches to `fallback_randomness_mode` instead.
+///
+/// Past the spin timeout anyone may call it in place of the round's initiator, and a cranker
+/// who settles the round is paid the passed vault's `crank_reward`. Without the server seed, a
+/// first such call locks the round to a future slot hash and a later one reveals it.
+pub fn get_random(mut ctx: Context<GetRandom>, server_seed: [u8; 32]) -> Result<()> {
+    draw_random(&mut ctx, server_seed)
+}
+
+/// `get_random`, then opens the next round with `next_seed_commitment` in the same transaction,
+/// so the table never idles between rounds. Only the table's operators can restart, and the
+/// draw still needs the round's initiator until the spin timeout. Fails instead of deferring
+/// when the round cannot settle yet, and when the cooldown between rounds is not zero.
+pub fn settle_and_restart(
+    mut ctx: Context<GetRandom>,
+    server_seed: [u8; 32],
+    next_seed_commitment: [u8; 32]
+) -> Result<()> {
+    let starter = ctx.accounts.random_initiator.key();
+    require!(ctx.accounts.game_session.is_round_operator(&starter), RouletteError::AdminOnly);
+
+    draw_random(&mut ctx, server_seed)?;
+    require!(
+        ctx.accounts.game_session.round_status == RoundStatus::Completed,
+        RouletteError::RoundNotSettled
+    );
+
+    let accounts = ctx.accounts;
+    let (Some(round_stats), Some(protocol_stats)) = (
+        accounts.next_round_stats.as_mut(),
+        accounts.protocol_stats.as_mut(),
+    ) else {
+        return err!(RouletteError::NextRoundAccountsRequired);
+    };
+    open_round(
+        RoundStartAccounts {
+            game_session: &mut accounts.game_session,
+            round_stats,
+            round_stats_bump: ctx.bumps.next_round_stats.unwrap_or_default(),
+            protocol_stats,
+            operator_stats: &mut accounts.operator_stats,
+            operator_stats_bump: ctx.bumps.operator_stats,
+            config: &accounts.config,
+            starter,
+        },
+        next_seed_commitment,
+        String::new(),
+        String::new(),
+        &Clock::get()?
+    )
+}
+
+/// Body of `get_random`, shared with `settle_and_restart`.
+fn draw_random(ctx: &mut Context<GetRandom>, server_seed: [u8; 32]) -> Result<()> {
+    let clock = Clock::get()?;
+    let slot = clock.slot;
+    let game_session = &mut ctx.accounts.game_session;
+    let initiator = ctx.accounts.random_initiator.key();
+    let is_initiator = game_session.random_initiator_for(game_session.current_round) == initiator;
+    require!(
+        is_initiator ||
+            game_session.round_status == RoundStatus::SeedLocked ||
+            game_session.spin_timeout_elapsed(&clock),
+        RouletteError::NotRoundInitiator
+    );
+    activate_fallback(game_session, slot);
+
+    // Anyone but the initiator lacks the server seed, so a round still waiting on it past the
+    // spin timeout is locked to a future slot hash in its place, with the public commitment
+    // standing in for the seed. Withholding the draw then only hands the outcome to a hash nobody
+    // knows yet, rather than voiding a round the house would have lost.
+    let mode = game_session.active_randomness_mode();
+    if !is_initiator && mode.uses_server_seed() && game_session.round_status == RoundStatus::BetsClosed {
+        msg!("Round {}: the initiator did not draw before the spin timeout", game_session.current_round);
+        let seed_commitment = game_session.seed_commitment;
+        return lock_target_slot(game_session, initiator, seed_commitment);
+    }
+
+    let seed_locked = game_session.round_status == RoundStatus::SeedLocked;
+    match mode {
+        RandomnessMode::InternalHash | RandomnessMode::CommitReveal if seed_locked => {
+            let slot_hashes = ctx.accounts.slot_hashes.to_account_info();
+            reveal_from_slot_hash(ctx.accounts.settlement_accounts(&ctx.bumps), &slot_hashes)
+        }
+        RandomnessMode::InternalHash => settle_internal_hash(ctx, server_seed),
+        RandomnessMode::SwitchboardVrf => {
+            let randomness_account = ctx.accounts.randomness_account
+                .clone()
+                .ok_or(RouletteError::InvalidRandomnessAccount)?;
+            settle_from_switchboard(ctx.accounts.settlement_accounts(&ctx.bumps), &randomness_account)
+        }
+        #[cfg(feature = "orao-vrf")]
+        RandomnessMode::OraoVrf => {
+            let randomness_request = ctx.accounts.randomness_account
+                .clone()
+                .ok_or(RouletteError::InvalidRandomnessAccount)?;
+            crate::instructions::orao::settle_from_orao(
+                ctx.accounts.settlement_accounts(&ctx.bumps),
+                &randomness_request
+            )
+        }
+        #[cfg(not(feature = "orao-vrf"))]
+        RandomnessMode::OraoVrf => err!(RouletteError::RandomnessModeUnavailable),
+        RandomnessMode::MultiOracle => {
+            let oracle_accounts = ctx.remaining_accounts;
+            settle_from_oracles(ctx.accounts.settlement_accounts(&ctx.bumps), oracle_accounts)
+        }
+        RandomnessMode::CommitReveal => {
+            lock_seed(&mut ctx.accounts.game_session, ctx.accounts.random_initiator.key(), server_seed)
+        }
+    }?;
+
+    if !is_initiator && ctx.accounts.game_session.round_status == RoundStatus::Completed {
+        pay_crank_reward(ctx.accounts)?;
+    }
+    Ok(())
+}
+
+/// Pays the cranker up to the vault's `crank_reward` out of its owner revenue. The vault must
+/// have taken bets in the settled round, so a crank cannot drain the owner revenue of vaults
+/// that had no stake in it. Optional: without a vault the crank goes unpaid.
+fn pay_crank_reward(accounts: &mut GetRandom) -> Result<()> {
+    let Some(vault) = accounts.crank_vault.as_mut() else {
+        return Ok(());
+    };
+    let game_session = &accounts.game_session;
+    let took_bets = accounts.crank_vault_exposure.as_ref().is_some_and(|exposure| {
+        exposure.vault == vault.key() &&
+            exposure.game_session == game_session.key() &&
+            exposure.tracks(game_session.current_round)
+    });
+    require!(game_session.accepts_vault(&vault.key()) && took_bets, RouletteError::VaultMismatch);
+    let (Some(token_mint), Some(vault_token_account), Some(cranker_token_account), Some(token_program)) = (
+        accounts.crank_token_mint.as_ref(),
+        accounts.crank_vault_token_account.as_ref(),
+        accounts.cranker_token_account.as_ref(),
+        accounts.token_program.as_ref(),
+    ) else {
+        return err!(RouletteError::CrankRewardAccountsRequired);
+    };
+    require_keys_eq!(token_mint.key(), vault.token_mint, RouletteError::InvalidTokenAccount);
+    require_keys_eq!(vault_token_account.key(), vault.token_account, RouletteError::VaultMismatch);
+    require_keys_eq!(cranker_token_account.mint, vault.token_mint, RouletteError::InvalidTokenAccount);
+
+    let amount = vault.crank_reward
+        .min(vault.owner_reward)
+        .min(vault.at_risk_liquidity());
+    if amount == 0 {
+        return Ok(());
+    }
+
+    let seeds = &[VAULT_SEED, vault.token_mint.as_ref(), &[vault.bump]];
+    let signer_seeds = &[&seeds[..]];
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            TransferChecked {
+                from: vault_token_account.to_account_info(),
+                mint: token_mint.to_account_info(),
+                to: cranker_token_account.to_account_info(),
+                authority: vault.to_account_info(),
+            },
+            signer_seeds
+        ),
+        amount,
+        token_mint.decimals,
+    )?;
+
+    vault.owner_reward -= amount;
+    vault.total_liquidity = vault.total_liquidity
+        .checked_sub(amount)
+        .ok_or(RouletteError::ArithmeticOverflow)?;
+
+    let game_session = &mut accounts.game_session;
+    emit!(CrankRewardPaid {
+        event_seq: game_session.next_event_seq(),
+        round: game_session.current_round,
+        cranker: accounts.random_initiator.key(),
+        token_mint: vault.token_mint,
+        amount,
+    });
+
+    Ok(())
+}
+
+/// `RandomnessMode::InternalHash`: hashes the revealed server seed with on-chain entropy and
+/// persists every input in the round's `RoundResult`. Settlement waits `MIN_SETTLEMENT_SLOTS`
+/// after the close.
+fn settle_internal_hash(ctx: &mut Context<GetRandom>, server_seed: [u8; 32]) -> Result<()> {
     let game_session = &mut ctx.accounts.game_session;
     let clock = Clock::get()?;
     let current_time = clock.unix_timestamp;
     let current_slot = clock.slot;
 
 
+    require!(
+        game_session.round_status != RoundStatus::SeedLocked,
+        RouletteError::RandomnessSeedLocked
+    );
     require!(
         game_session.round_status == RoundStatus::BetsClosed,
         RouletteError::RandomBeforeClosing
     );
 
     require!(game_session.last_bettor.is_some(), RouletteError::NoBetsPlacedInRound);
+    require!(!game_session.vrf_pending(), RouletteError::VrfRequestPending);
+
+    let operator_stats = &mut ctx.accounts.operator_stats;
+    operator_stats.touch(ctx.accounts.random_initiator.key(), ctx.bumps.operator_stats, current_slot);
+    require!(
+        hash::hash(&server_seed).to_bytes() == game_session.seed_commitment,
+        RouletteError::SeedRevealMismatch
+    );
+
+    // Retryable conditions are recorded instead of reverting, so keepers get a
+    // `SettlementRetryNeeded` event rather than having to parse failed transaction logs.
+    let delay_elapsed = game_session.duration_elapsed(
+        &clock,
+        game_session.bets_closed_timestamp,
+        game_session.bets_closed_slot,
+        game_session.random_delay_secs()
+    );
+    let deferral = if !delay_elapsed {
+        Some(SettlementFailure::RandomDelayNotElapsed)
+    } else if current_slot.saturating_sub(game_session.bets_closed_slot) < MIN_SETTLEMENT_SLOTS {
+        Some(SettlementFailure::SlotDelayNotElapsed)
+    } else {
+        None
+    };
+    if let Some(reason) = deferral {
+        operator_stats.settlement_deferrals = operator_stats.settlement_deferrals.saturating_add(1);
+        return record_settlement_failure(
+            game_session,
+            ctx.accounts.random_initiator.key(),
+            reason,
+            &clock
+        );
+    }
     let last_bettor_key = game_session.last_bettor.unwrap();
+    let slot_hashes = recent_slot_hashes(&ctx.accounts.slot_hashes)?;
 
-    // Generate random number using SHA256
-    let hash_input_bytes: &[&[u8]] = &[
-        &last_bettor_key.to_bytes()[..],
-        &current_time.to_le_bytes()[..],
-        &current_slot.to_le_bytes()[..],
-    ];
-    let hash_result_obj = hash::hashv(hash_input_bytes);
-    let hash_bytes = hash_result_obj.to_bytes();
+    // Generate random number using SHA256 over the inputs persisted in the round result
+    let round_result = ctx.accounts.round_result
+        .as_mut()
+        .ok_or(RouletteError::RoundResultRequired)?;
+    round_result.game_session = game_session.key();
+    round_result.round = game_session.current_round;
+    round_result.last_bettor = last_bettor_key;
+    round_result.generation_time = current_time;
+    round_result.slot = current_slot;
+    round_result.server_seed = server_seed;
+    round_result.seed_commitment = game_session.seed_commitment;
+    round_result.entropy_accumulator = game_session.entropy_accumulator;
+    round_result.slot_hashes = slot_hashes;
+    round_result.bump = ctx.bumps.round_result.unwrap_or_default();
+    round_result.wheel_type = game_session.wheel_type;
+    let hash_bytes = round_result.compute_hash();
     let hash_prefix_u64 = u64::from_le_bytes(hash_bytes[0..8].try_into().unwrap());
-    let winning_number = (hash_prefix_u64 % 37) as u8; // Modulo 37 for 0-36
+    let winning_number = RoundResult::winning_number_for(&hash_bytes, game_session.wheel_type);
+    round_result.hash_result = hash_bytes;
+    round_result.winning_number = winning_number;
 
     msg!(
         "Round {} | Hash {:?} | Winning Number {}",
@@ -172,36 +1011,1661 @@ pub fn get_random(ctx: Context<GetRandom>) -> Result<()> {
         winning_number
     );
 
-    // Update game session
-    game_session.winning_number = Some(winning_number);
-    game_session.round_status = RoundStatus::Completed;
-    game_session.last_completed_round = game_session.current_round;
-    game_session.get_random_timestamp = current_time;
+    complete_round(
+        game_session,
+        operator_stats,
+        SettlementRecords {
+            wheel_histogram: &mut ctx.accounts.wheel_histogram,
+            wheel_histogram_bump: ctx.bumps.wheel_histogram,
+            round_history: &mut ctx.accounts.round_history,
+            round_history_bump: ctx.bumps.round_history,
+            event_queue: ctx.accounts.event_queue.as_mut(),
+        },
+        &mut ctx.accounts.round_stats,
+        winning_number,
+        &clock
+    )?;
 
     emit!(RandomGenerated {
+        event_seq: game_session.next_event_seq(),
         round: game_session.current_round,
         initiator: *ctx.accounts.random_initiator.key,
-        winning_number: winning_number,
+        winning_number,
         generation_time: current_time,
         slot: current_slot,
         last_bettor: last_bettor_key,
         hash_result: hash_bytes,
-        hash_prefix_u64: hash_prefix_u64,
+        hash_prefix_u64,
+        server_seed,
+        slot_hashes,
+        entropy_accumulator: game_session.entropy_accumulator,
+    });
+
+    Ok(())
+}
+
+/// Reads the `SLOT_HASHES_MIXED` most recent entries of the `SlotHashes` sysvar, newest first.
+/// The sysvar is too large to deserialize on-chain, so the leading entries are read in place:
+/// an 8-byte entry count followed by `(slot: u64, hash: [u8; 32])` entries.
+fn recent_slot_hashes(slot_hashes: &AccountInfo) -> Result<[[u8; 32]; SLOT_HASHES_MIXED]> {
+    const ENTRY_LEN: usize = 8 + 32;
+    let data = slot_hashes.try_borrow_data()?;
+    let count = data
+        .get(..8)
+        .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()) as usize)
+        .ok_or(RouletteError::InvalidSlotHashes)?;
+    require!(
+        count >= SLOT_HASHES_MIXED && data.len() >= 8 + SLOT_HASHES_MIXED * ENTRY_LEN,
+        RouletteError::InvalidSlotHashes
+    );
+
+    let mut hashes = [[0u8; 32]; SLOT_HASHES_MIXED];
+    for (i, hash) in hashes.iter_mut().enumerate() {
+        let start = 8 + i * ENTRY_LEN + 8;
+        hash.copy_from_slice(&data[start..start + 32]);
+    }
+    Ok(hashes)
+}
+
+// =================================================================================================
+// Game Two-Phase Randomness
+// =================================================================================================
+
+/// First phase of the two-phase alternative to `get_random`: reveals the server seed and fixes
+/// a target slot `REVEAL_SLOT_DELAY` slots ahead, whose hash will decide the round. Nobody
+/// knows that hash when the round is locked, including the caller, and with the seed already
+/// on-chain the operator cannot withhold the reveal once the outcome is known.
+pub fn lock_randomness_seed(ctx: Context<LockRandomnessSeed>, server_seed: [u8; 32]) -> Result<()> {
+    require_randomness_mode(&mut ctx.accounts.game_session, RandomnessMode::CommitReveal)?;
+    lock_seed(&mut ctx.accounts.game_session, ctx.accounts.random_initiator.key(), server_seed)
+}
+
+fn lock_seed(game_session: &mut GameSession, locker: Pubkey, server_seed: [u8; 32]) -> Result<()> {
+    require!(
+        game_session.round_status == RoundStatus::BetsClosed,
+        RouletteError::RandomBeforeClosing
+    );
+    require!(game_session.last_bettor.is_some(), RouletteError::NoBetsPlacedInRound);
+    require!(!game_session.vrf_pending(), RouletteError::VrfRequestPending);
+    require!(
+        hash::hash(&server_seed).to_bytes() == game_session.seed_commitment,
+        RouletteError::SeedRevealMismatch
+    );
+    lock_target_slot(game_session, locker, server_seed)
+}
+
+/// Fixes the round's outcome to the hash of a slot `REVEAL_SLOT_DELAY` slots ahead, mixed with
+/// `server_seed`, so anyone can reveal it once that slot has passed.
+fn lock_target_slot(game_session: &mut GameSession, locker: Pubkey, server_seed: [u8; 32]) -> Result<()> {
+    let clock = Clock::get()?;
+    let target_slot = clock.slot
+        .checked_add(REVEAL_SLOT_DELAY)
+        .ok_or(RouletteError::ArithmeticOverflow)?;
+    game_session.round_status = RoundStatus::SeedLocked;
+    game_session.reveal_target_slot = target_slot;
+    game_session.locked_server_seed = server_seed;
+
+    emit!(RandomnessSeedLocked {
+        event_seq: game_session.next_event_seq(),
+        round: game_session.current_round,
+        locker,
+        target_slot,
+        slot: clock.slot,
+        server_seed,
     });
 
     Ok(())
 }
 
 #[derive(Accounts)]
-pub struct GetRandom<'info> {
+pub struct LockRandomnessSeed<'info> {
     #[account(
-        mut, 
-        seeds = [b"game_session"], 
+        mut,
+        seeds = [GAME_SESSION_SEED, game_session.table_seed().as_slice()],
         bump = game_session.bump,
-        constraint = random_initiator.key() == GAME_ADMIN_PUBKEY @ RouletteError::AdminOnly
+        constraint = game_session.random_initiator_for(game_session.current_round) == random_initiator.key()
+            @ RouletteError::NotRoundInitiator
     )]
     pub game_session: Account<'info, GameSession>,
 
+    pub random_initiator: Signer<'info>,
+}
+
+/// Second phase, permissionless: settles the round from the hash of the first slot at or after
+/// the target slot, together with the server seed revealed at the lock and the round's entropy
+/// accumulator. If that hash has already aged out of the `SlotHashes` sysvar, the round is
+/// voided instead.
+pub fn reveal_winning_number(ctx: Context<RevealWinningNumber>) -> Result<()> {
+    require_randomness_mode(&mut ctx.accounts.game_session, RandomnessMode::CommitReveal)?;
+    let slot_hashes = ctx.accounts.slot_hashes.to_account_info();
+    let accounts = SettlementAccounts {
+        game_session: &mut ctx.accounts.game_session,
+        operator_stats: &mut ctx.accounts.operator_stats,
+        operator_stats_bump: ctx.bumps.operator_stats,
+        records: SettlementRecords {
+            wheel_histogram: &mut ctx.accounts.wheel_histogram,
+            wheel_histogram_bump: ctx.bumps.wheel_histogram,
+            round_history: &mut ctx.accounts.round_history,
+            round_history_bump: ctx.bumps.round_history,
+            event_queue: ctx.accounts.event_queue.as_mut(),
+        },
+        round_stats: &mut ctx.accounts.round_stats,
+        initiator: ctx.accounts.random_initiator.key(),
+    };
+    reveal_from_slot_hash(accounts, &slot_hashes)
+}
+
+fn reveal_from_slot_hash(accounts: SettlementAccounts, slot_hashes: &AccountInfo) -> Result<()> {
+    let game_session = accounts.game_session;
+    let clock = Clock::get()?;
+
+    require!(
+        game_session.round_status == RoundStatus::SeedLocked,
+        RouletteError::RandomnessSeedNotLocked
+    );
+    let target_slot = game_session.reveal_target_slot;
+    require!(clock.slot > target_slot, RouletteError::RevealSlotNotReached);
+    let server_seed = game_session.locked_server_seed;
+
+    let Some(slot_hash) = slot_hash_at_or_after(slot_hashes, target_slot)? else {
+        game_session.void_current_round(accounts.round_stats);
+        msg!("Round {} voided: hash of slot {} is no longer available", game_session.current_round, target_slot);
+        emit!(RoundVoided {
+            event_seq: game_session.next_event_seq(),
+            round: game_session.current_round,
+            target_slot,
+            slot: clock.slot,
+        });
+        return Ok(());
+    };
+
+    let last_bettor_key = game_session.last_bettor.ok_or(RouletteError::NoBetsPlacedInRound)?;
+    let hash_bytes = hash::hashv(&[
+        last_bettor_key.as_ref(),
+        &server_seed,
+        &game_session.entropy_accumulator,
+        &slot_hash,
+        &target_slot.to_le_bytes(),
+    ]).to_bytes();
+    let winning_number = RoundResult::winning_number_for(&hash_bytes, game_session.wheel_type);
+
+    accounts.operator_stats.touch(accounts.initiator, accounts.operator_stats_bump, clock.slot);
+    complete_round(
+        game_session,
+        accounts.operator_stats,
+        accounts.records,
+        accounts.round_stats,
+        winning_number,
+        &clock
+    )?;
+
+    emit!(WinningNumberRevealed {
+        event_seq: game_session.next_event_seq(),
+        round: game_session.current_round,
+        initiator: accounts.initiator,
+        winning_number,
+        target_slot,
+        slot_hash,
+        hash_result: hash_bytes,
+        server_seed,
+        entropy_accumulator: game_session.entropy_accumulator,
+        slot: clock.slot,
+    });
+
+    Ok(())
+}
+
+/// Hash of the earliest `SlotHashes` entry whose slot is at or after `target_slot`, or `None`
+/// if the sysvar no longer reaches back to `target_slot`. Entries are sorted newest first.
+fn slot_hash_at_or_after(slot_hashes: &AccountInfo, target_slot: u64) -> Result<Option<[u8; 32]>> {
+    const ENTRY_LEN: usize = 8 + 32;
+    let data = slot_hashes.try_borrow_data()?;
+    let count = data
+        .get(..8)
+        .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()) as usize)
+        .ok_or(RouletteError::InvalidSlotHashes)?;
+    require!(data.len() >= 8 + count * ENTRY_LEN, RouletteError::InvalidSlotHashes);
+
+    let mut found = None;
+    for entry in data[8..8 + count * ENTRY_LEN].chunks_exact(ENTRY_LEN) {
+        let slot = u64::from_le_bytes(entry[..8].try_into().unwrap());
+        if slot < target_slot {
+            return Ok(found);
+        }
+        found = Some(entry[8..].try_into().unwrap());
+    }
+    // Every entry is at or after the target, so an earlier one at the target may have aged out.
+    Ok(None)
+}
+
+#[derive(Accounts)]
+pub struct RevealWinningNumber<'info> {
+    #[account(mut, seeds = [GAME_SESSION_SEED, game_session.table_seed().as_slice()], bump = game_session.bump)]
+    pub game_session: Account<'info, GameSession>,
+
+    /// Anyone may reveal; the signer pays for any settlement record created on the way.
     #[account(mut)]
     pub random_initiator: Signer<'info>,
-}
\ No newline at end of file
+
+    #[account(
+        init_if_needed,
+        payer = random_initiator,
+        space = 8 + std::mem::size_of::<OperatorStats>(),
+        seeds = [OPERATOR_STATS_SEED, random_initiator.key().as_ref()],
+        bump
+    )]
+    pub operator_stats: Account<'info, OperatorStats>,
+
+    /// Stats of the round being settled, reported per bet type; records the winning number.
+    #[account(
+        mut,
+        seeds = [ROUND_STATS_SEED, game_session.key().as_ref(), &game_session.current_round.to_le_bytes()],
+        bump = round_stats.bump
+    )]
+    pub round_stats: Account<'info, RoundStats>,
+
+    #[account(
+        init_if_needed,
+        payer = random_initiator,
+        space = 8 + std::mem::size_of::<WheelHistogram>(),
+        seeds = [WHEEL_HISTOGRAM_SEED, game_session.key().as_ref()],
+        bump
+    )]
+    pub wheel_histogram: Account<'info, WheelHistogram>,
+
+    #[account(
+        init_if_needed,
+        payer = random_initiator,
+        space = RoundHistory::SPACE,
+        seeds = [ROUND_HISTORY_SEED, game_session.key().as_ref()],
+        bump
+    )]
+    pub round_history: Account<'info, RoundHistory>,
+
+    /// The table's event queue. Required once the table has one.
+    #[account(mut, seeds = [EVENT_QUEUE_SEED, game_session.key().as_ref()], bump = event_queue.bump)]
+    pub event_queue: Option<Account<'info, EventQueue>>,
+
+    /// CHECK: The `SlotHashes` sysvar, read in place by `slot_hash_at_or_after`.
+    #[account(address = sysvar::slot_hashes::ID)]
+    pub slot_hashes: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Accounts every settlement path updates, so the dedicated settlement instructions and the
+/// `get_random` dispatch share one implementation per backend.
+pub(crate) struct SettlementAccounts<'a, 'info> {
+    pub game_session: &'a mut Account<'info, GameSession>,
+    pub operator_stats: &'a mut Account<'info, OperatorStats>,
+    pub operator_stats_bump: u8,
+    pub records: SettlementRecords<'a, 'info>,
+    pub round_stats: &'a mut Account<'info, RoundStats>,
+    pub initiator: Pubkey,
+}
+
+/// Per-table records every settlement appends to, created by a table's first settlement.
+pub(crate) struct SettlementRecords<'a, 'info> {
+    pub wheel_histogram: &'a mut Account<'info, WheelHistogram>,
+    pub wheel_histogram_bump: u8,
+    pub round_history: &'a mut Account<'info, RoundHistory>,
+    pub round_history_bump: u8,
+    pub event_queue: Option<&'a mut Account<'info, EventQueue>>,
+}
+
+/// Moves the current round to `Completed` with `winning_number`, updates the settlement
+/// statistics and reports the round's per-bet-type stakes and payouts.
+pub(crate) fn complete_round(
+    game_session: &mut Account<GameSession>,
+    operator_stats: &mut OperatorStats,
+    records: SettlementRecords,
+    round_stats: &mut RoundStats,
+    winning_number: u8,
+    clock: &Clock
+) -> Result<()> {
+    game_session.winning_number = Some(winning_number);
+    game_session.round_status = RoundStatus::Completed;
+    game_session.last_completed_round = game_session.current_round;
+    game_session.get_random_timestamp = clock.unix_timestamp;
+    game_session.last_completed_timestamp = clock.unix_timestamp;
+    game_session.last_completed_slot = clock.slot;
+    game_session.last_settlement_failure = None;
+    game_session.record_recent_number(winning_number);
+
+    operator_stats.record_settlement(
+        clock.unix_timestamp.saturating_sub(game_session.bets_closed_timestamp).max(0) as u64,
+        clock.slot.saturating_sub(game_session.bets_closed_slot)
+    );
+
+    let SettlementRecords {
+        wheel_histogram,
+        wheel_histogram_bump,
+        round_history,
+        round_history_bump,
+        event_queue,
+    } = records;
+    wheel_histogram.game_session = game_session.key();
+    wheel_histogram.bump = wheel_histogram_bump;
+    wheel_histogram.record(winning_number);
+    round_history.game_session = game_session.key();
+    round_history.bump = round_history_bump;
+    round_history.record_round(game_session.current_round, winning_number, round_stats.total_wagered);
+    round_stats.record_settlement(winning_number, clock, game_session.finality_payout_threshold);
+
+    emit!(RoundBetTypeStats {
+        event_seq: game_session.next_event_seq(),
+        round: game_session.current_round,
+        winning_number,
+        total_staked: round_stats.bet_type_wagered,
+        total_paid: round_stats.bet_type_payouts(winning_number),
+    });
+    // Claims and later bets load the settled round's stats, and the exposure table is most of
+    // their heap.
+    round_stats.bet_type_exposure = Vec::new();
+
+    push_event(game_session, event_queue, QueuedEvent {
+        kind: QueuedEventKind::Settlement,
+        round: game_session.current_round,
+        amount: round_stats.total_wagered,
+        detail: winning_number,
+        slot: clock.slot,
+        ..Default::default()
+    })
+}
+
+/// Records a retryable settlement failure on the session and emits `SettlementRetryNeeded`.
+/// Returns `Ok` so the record persists; the round stays in `BetsClosed` until a retry succeeds.
+fn record_settlement_failure(
+    game_session: &mut GameSession,
+    initiator: Pubkey,
+    reason: SettlementFailure,
+    clock: &Clock
+) -> Result<()> {
+    game_session.last_settlement_failure = Some(reason);
+    game_session.settlement_retries = game_session.settlement_retries.saturating_add(1);
+
+    msg!("Round {} settlement deferred: {:?}", game_session.current_round, reason);
+
+    emit!(SettlementRetryNeeded {
+        event_seq: game_session.next_event_seq(),
+        round: game_session.current_round,
+        initiator,
+        reason,
+        retry_count: game_session.settlement_retries,
+        slot: clock.slot,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct GetRandom<'info> {
+    #[account(mut, seeds = [GAME_SESSION_SEED, game_session.table_seed().as_slice()], bump = game_session.bump)]
+    pub game_session: Account<'info, GameSession>,
+
+    /// Decides whose turn it is to draw this round's randomness; checked in the handler since
+    /// anyone may draw after the spin timeout.
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub random_initiator: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = random_initiator,
+        space = 8 + std::mem::size_of::<OperatorStats>(),
+        seeds = [OPERATOR_STATS_SEED, random_initiator.key().as_ref()],
+        bump
+    )]
+    pub operator_stats: Account<'info, OperatorStats>,
+
+    /// Stats of the round being settled, reported per bet type; records the winning number.
+    #[account(
+        mut,
+        seeds = [ROUND_STATS_SEED, game_session.key().as_ref(), &game_session.current_round.to_le_bytes()],
+        bump = round_stats.bump
+    )]
+    pub round_stats: Account<'info, RoundStats>,
+
+    #[account(
+        init_if_needed,
+        payer = random_initiator,
+        space = 8 + std::mem::size_of::<WheelHistogram>(),
+        seeds = [WHEEL_HISTOGRAM_SEED, game_session.key().as_ref()],
+        bump
+    )]
+    pub wheel_histogram: Account<'info, WheelHistogram>,
+
+    #[account(
+        init_if_needed,
+        payer = random_initiator,
+        space = RoundHistory::SPACE,
+        seeds = [ROUND_HISTORY_SEED, game_session.key().as_ref()],
+        bump
+    )]
+    pub round_history: Account<'info, RoundHistory>,
+
+    /// The table's event queue. Required once the table has one.
+    #[account(mut, seeds = [EVENT_QUEUE_SEED, game_session.key().as_ref()], bump = event_queue.bump)]
+    pub event_queue: Option<Account<'info, EventQueue>>,
+
+    /// Settlement proof of the current round, required in `InternalHash` mode.
+    /// `init_if_needed` because a deferred attempt leaves it in place for the retry.
+    #[account(
+        init_if_needed,
+        payer = random_initiator,
+        space = 8 + std::mem::size_of::<RoundResult>(),
+        seeds = [
+            ROUND_RESULT_SEED,
+            game_session.key().as_ref(),
+            &game_session.current_round.to_le_bytes()
+        ],
+        bump
+    )]
+    pub round_result: Option<Account<'info, RoundResult>>,
+
+    /// CHECK: Required in the VRF modes: the bound Switchboard randomness account or the ORAO
+    /// request account. Checked by the backend's settlement.
+    pub randomness_account: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: The `SlotHashes` sysvar, read in place by `recent_slot_hashes`.
+    #[account(address = sysvar::slot_hashes::ID)]
+    pub slot_hashes: UncheckedAccount<'info>,
+
+    /// Vault paying the crank reward out of its owner revenue; only used when someone other
+    /// than the round's initiator settles, and must have taken bets in the round. The accounts
+    /// below are checked against it.
+    #[account(mut, seeds = [VAULT_SEED, crank_vault.token_mint.as_ref()], bump = crank_vault.bump)]
+    pub crank_vault: Option<Account<'info, VaultAccount>>,
+
+    /// The crank vault's exposure to the table, showing it took bets in the round.
+    pub crank_vault_exposure: Option<Account<'info, VaultExposure>>,
+
+    pub crank_token_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut)]
+    pub crank_vault_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub cranker_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    /// Stats of the next round, required by `settle_and_restart` only.
+    #[account(
+        init,
+        payer = random_initiator,
+        space = RoundStats::SPACE,
+        seeds = [ROUND_STATS_SEED, game_session.key().as_ref(), &(game_session.current_round + 1).to_le_bytes()],
+        bump
+    )]
+    pub next_round_stats: Option<Account<'info, RoundStats>>,
+
+    /// Required by `settle_and_restart` only.
+    #[account(mut, seeds = [PROTOCOL_STATS_SEED], bump = protocol_stats.bump)]
+    pub protocol_stats: Option<Account<'info, ProtocolStats>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> GetRandom<'info> {
+    fn settlement_accounts(&mut self, bumps: &GetRandomBumps) -> SettlementAccounts<'_, 'info> {
+        SettlementAccounts {
+            game_session: &mut self.game_session,
+            operator_stats: &mut self.operator_stats,
+            operator_stats_bump: bumps.operator_stats,
+            records: SettlementRecords {
+                wheel_histogram: &mut self.wheel_histogram,
+                wheel_histogram_bump: bumps.wheel_histogram,
+                round_history: &mut self.round_history,
+                round_history_bump: bumps.round_history,
+                event_queue: self.event_queue.as_mut(),
+            },
+            round_stats: &mut self.round_stats,
+            initiator: self.random_initiator.key(),
+        }
+    }
+}
+
+/// Permissionless. Re-derives a round's settlement hash and winning number from the inputs
+/// stored in its `RoundResult`, and checks the server seed against its commitment.
+pub fn verify_round_result(ctx: Context<VerifyRoundResult>, _round: u64) -> Result<()> {
+    require!(ctx.accounts.round_result.verify(), RouletteError::RoundResultMismatch);
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(round: u64)]
+pub struct VerifyRoundResult<'info> {
+    #[account(seeds = [GAME_SESSION_SEED, game_session.table_seed().as_slice()], bump = game_session.bump)]
+    pub game_session: Account<'info, GameSession>,
+
+    #[account(
+        seeds = [ROUND_RESULT_SEED, game_session.key().as_ref(), &round.to_le_bytes()],
+        bump = round_result.bump
+    )]
+    pub round_result: Account<'info, RoundResult>,
+}
+
+// =================================================================================================
+// Game Switchboard VRF
+// =================================================================================================
+
+/// Fields of a Switchboard On-Demand `RandomnessAccountData` account used for settlement.
+struct SwitchboardRandomness {
+    seed_slot: u64,
+    reveal_slot: u64,
+    value: [u8; 32],
+}
+
+impl SwitchboardRandomness {
+    // Offsets into the account data, after the 8-byte discriminator: authority (32),
+    // queue (32), seed_slothash (32), seed_slot (8), oracle (32), reveal_slot (8), value (32).
+    const SEED_SLOT_OFFSET: usize = 8 + 96;
+    const REVEAL_SLOT_OFFSET: usize = Self::SEED_SLOT_OFFSET + 8 + 32;
+    const VALUE_OFFSET: usize = Self::REVEAL_SLOT_OFFSET + 8;
+
+    fn load(account: &AccountInfo) -> Result<Self> {
+        require_keys_eq!(
+            *account.owner,
+            SWITCHBOARD_ON_DEMAND_PROGRAM_ID,
+            RouletteError::InvalidRandomnessAccount
+        );
+        let data = account.try_borrow_data()?;
+        require!(
+            data.len() >= Self::VALUE_OFFSET + 32 &&
+                data[..8] == SWITCHBOARD_RANDOMNESS_DISCRIMINATOR,
+            RouletteError::InvalidRandomnessAccount
+        );
+
+        let read_u64 = |offset: usize| u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+        Ok(Self {
+            seed_slot: read_u64(Self::SEED_SLOT_OFFSET),
+            reveal_slot: read_u64(Self::REVEAL_SLOT_OFFSET),
+            value: data[Self::VALUE_OFFSET..Self::VALUE_OFFSET + 32].try_into().unwrap(),
+        })
+    }
+}
+
+/// Binds the current round to a Switchboard On-Demand randomness account. Must be sent in the
+/// same transaction as the Switchboard commit, after bets are closed, so the committed slot hash
+/// is unknown to everyone when the round's bets are final.
+pub fn request_vrf_randomness(ctx: Context<RequestVrfRandomness>) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+    let clock = Clock::get()?;
+    require_randomness_mode(game_session, RandomnessMode::SwitchboardVrf)?;
+
+    require!(
+        game_session.round_status == RoundStatus::BetsClosed,
+        RouletteError::RandomBeforeClosing
+    );
+    require!(game_session.last_bettor.is_some(), RouletteError::NoBetsPlacedInRound);
+    require!(!game_session.vrf_pending(), RouletteError::VrfRequestPending);
+
+    let randomness = SwitchboardRandomness::load(&ctx.accounts.randomness_account)?;
+    require!(
+        randomness.seed_slot == clock.slot.saturating_sub(1),
+        RouletteError::RandomnessAlreadyRevealed
+    );
+
+    game_session.randomness_account = Some(ctx.accounts.randomness_account.key());
+    game_session.randomness_seed_slot = randomness.seed_slot;
+
+    emit!(VrfRandomnessRequested {
+        event_seq: game_session.next_event_seq(),
+        round: game_session.current_round,
+        randomness_account: ctx.accounts.randomness_account.key(),
+        seed_slot: randomness.seed_slot,
+        slot: clock.slot,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RequestVrfRandomness<'info> {
+    #[account(
+        mut,
+        seeds = [GAME_SESSION_SEED, game_session.table_seed().as_slice()],
+        bump = game_session.bump,
+        constraint = authority.key() == game_session.authority @ RouletteError::AdminOnly
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    pub authority: Signer<'info>,
+
+    /// CHECK: Switchboard On-Demand randomness account; owner and layout are checked in the
+    /// handler.
+    pub randomness_account: UncheckedAccount<'info>,
+}
+
+/// Settles the current round from the revealed value of the randomness account bound by
+/// `request_vrf_randomness`. Must be sent in the same transaction as the Switchboard reveal,
+/// since Switchboard only vouches for a value in its reveal slot.
+pub fn settle_round_vrf(ctx: Context<SettleRoundVrf>) -> Result<()> {
+    require_randomness_mode(&mut ctx.accounts.game_session, RandomnessMode::SwitchboardVrf)?;
+    let randomness_account = ctx.accounts.randomness_account.to_account_info();
+    let accounts = SettlementAccounts {
+        game_session: &mut ctx.accounts.game_session,
+        operator_stats: &mut ctx.accounts.operator_stats,
+        operator_stats_bump: ctx.bumps.operator_stats,
+        records: SettlementRecords {
+            wheel_histogram: &mut ctx.accounts.wheel_histogram,
+            wheel_histogram_bump: ctx.bumps.wheel_histogram,
+            round_history: &mut ctx.accounts.round_history,
+            round_history_bump: ctx.bumps.round_history,
+            event_queue: ctx.accounts.event_queue.as_mut(),
+        },
+        round_stats: &mut ctx.accounts.round_stats,
+        initiator: ctx.accounts.random_initiator.key(),
+    };
+    settle_from_switchboard(accounts, &randomness_account)
+}
+
+fn settle_from_switchboard(accounts: SettlementAccounts, randomness_account: &AccountInfo) -> Result<()> {
+    let game_session = accounts.game_session;
+    let clock = Clock::get()?;
+
+    require!(
+        game_session.round_status == RoundStatus::BetsClosed,
+        RouletteError::RandomBeforeClosing
+    );
+    require!(
+        game_session.randomness_account == Some(randomness_account.key()),
+        RouletteError::InvalidRandomnessAccount
+    );
+
+    let randomness = SwitchboardRandomness::load(randomness_account)?;
+    require!(
+        randomness.seed_slot == game_session.randomness_seed_slot,
+        RouletteError::InvalidRandomnessAccount
+    );
+    require!(randomness.reveal_slot == clock.slot, RouletteError::RandomnessNotRevealed);
+
+    let winning_number = RoundResult::winning_number_for(&randomness.value, game_session.wheel_type);
+
+    msg!("Round {} | VRF Winning Number {}", game_session.current_round, winning_number);
+
+    accounts.operator_stats.touch(accounts.initiator, accounts.operator_stats_bump, clock.slot);
+    complete_round(
+        game_session,
+        accounts.operator_stats,
+        accounts.records,
+        accounts.round_stats,
+        winning_number,
+        &clock
+    )?;
+
+    emit!(VrfRoundSettled {
+        event_seq: game_session.next_event_seq(),
+        round: game_session.current_round,
+        initiator: accounts.initiator,
+        randomness_account: randomness_account.key(),
+        randomness_value: randomness.value,
+        winning_number,
+        slot: clock.slot,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SettleRoundVrf<'info> {
+    #[account(mut, seeds = [GAME_SESSION_SEED, game_session.table_seed().as_slice()], bump = game_session.bump)]
+    pub game_session: Account<'info, GameSession>,
+
+    #[account(mut)]
+    pub random_initiator: Signer<'info>,
+
+    /// CHECK: Must be the randomness account bound to the round; owner and layout are checked
+    /// in the handler.
+    pub randomness_account: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = random_initiator,
+        space = 8 + std::mem::size_of::<OperatorStats>(),
+        seeds = [OPERATOR_STATS_SEED, random_initiator.key().as_ref()],
+        bump
+    )]
+    pub operator_stats: Account<'info, OperatorStats>,
+
+    /// Stats of the round being settled, reported per bet type; records the winning number.
+    #[account(
+        mut,
+        seeds = [ROUND_STATS_SEED, game_session.key().as_ref(), &game_session.current_round.to_le_bytes()],
+        bump = round_stats.bump
+    )]
+    pub round_stats: Account<'info, RoundStats>,
+
+    #[account(
+        init_if_needed,
+        payer = random_initiator,
+        space = 8 + std::mem::size_of::<WheelHistogram>(),
+        seeds = [WHEEL_HISTOGRAM_SEED, game_session.key().as_ref()],
+        bump
+    )]
+    pub wheel_histogram: Account<'info, WheelHistogram>,
+
+    #[account(
+        init_if_needed,
+        payer = random_initiator,
+        space = RoundHistory::SPACE,
+        seeds = [ROUND_HISTORY_SEED, game_session.key().as_ref()],
+        bump
+    )]
+    pub round_history: Account<'info, RoundHistory>,
+
+    /// The table's event queue. Required once the table has one.
+    #[account(mut, seeds = [EVENT_QUEUE_SEED, game_session.key().as_ref()], bump = event_queue.bump)]
+    pub event_queue: Option<Account<'info, EventQueue>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// =================================================================================================
+// Game Timing Configuration
+// =================================================================================================
+
+pub fn update_timing_config(
+    ctx: Context<UpdateTimingConfig>,
+    min_random_delay_secs: u32,
+    claim_window_secs: u32,
+    slot_drift_tolerance_bps: u16,
+    void_timeout_slots: u64,
+    spin_timeout_secs: u32
+) -> Result<()> {
+    require!(
+        (slot_drift_tolerance_bps as u64) <= BPS_DENOMINATOR,
+        RouletteError::InvalidTimingConfig
+    );
+
+    let game_session = &mut ctx.accounts.game_session;
+    require!(
+        fallback_precedes_void(game_session.fallback_randomness_mode, game_session.fallback_timeout_slots, void_timeout_slots),
+        RouletteError::FallbackAfterVoidTimeout
+    );
+    game_session.min_random_delay_secs = min_random_delay_secs;
+    game_session.claim_window_secs = claim_window_secs;
+    game_session.slot_drift_tolerance_bps = slot_drift_tolerance_bps;
+    game_session.void_timeout_slots = void_timeout_slots;
+    game_session.spin_timeout_secs = spin_timeout_secs;
+    Ok(())
+}
+
+/// Sets how long rounds accept bets and rest between each other. `close_bets` waits for
+/// `min_betting_duration_secs` and becomes permissionless after `max_betting_duration_secs`
+/// (0 = admin only); `start_new_round` waits `cooldown_between_rounds_secs` after a completed
+/// round.
+pub fn update_round_timing(
+    ctx: Context<UpdateTimingConfig>,
+    min_betting_duration_secs: u32,
+    max_betting_duration_secs: u32,
+    cooldown_between_rounds_secs: u32
+) -> Result<()> {
+    require!(
+        max_betting_duration_secs == 0 || min_betting_duration_secs <= max_betting_duration_secs,
+        RouletteError::InvalidTimingConfig
+    );
+
+    let game_session = &mut ctx.accounts.game_session;
+    game_session.min_betting_duration_secs = min_betting_duration_secs;
+    game_session.max_betting_duration_secs = max_betting_duration_secs;
+    game_session.cooldown_between_rounds_secs = cooldown_between_rounds_secs;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateTimingConfig<'info> {
+    #[account(
+        mut,
+        seeds = [GAME_SESSION_SEED, game_session.table_seed().as_slice()],
+        bump = game_session.bump,
+        constraint = authority.key() == game_session.authority @ RouletteError::AdminOnly
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    pub authority: Signer<'info>,
+}
+
+// =================================================================================================
+// Game Void Round
+// =================================================================================================
+
+/// Permissionless. Voids a round whose winning number was not drawn by the void deadline
+/// recorded at `close_bets`, whichever randomness backend it was waiting on, so players' stakes
+/// are never stuck behind an unresponsive operator or oracle. This is the last step of the
+/// randomness fallback chain.
+pub fn void_round(ctx: Context<VoidRound>) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+    let clock = Clock::get()?;
+
+    require!(
+        matches!(game_session.round_status, RoundStatus::BetsClosed | RoundStatus::SeedLocked),
+        RouletteError::RandomBeforeClosing
+    );
+    let void_slot = ctx.accounts.round_stats.void_slot;
+    require!(
+        void_slot > 0 && clock.slot >= void_slot,
+        RouletteError::VoidTimeoutNotReached
+    );
+
+    game_session.void_current_round(&mut ctx.accounts.round_stats);
+    msg!("Round {} voided: not settled by slot {}", game_session.current_round, void_slot);
+
+    emit!(RoundSettlementTimedOut {
+        event_seq: game_session.next_event_seq(),
+        round: game_session.current_round,
+        caller: ctx.accounts.caller.key(),
+        bets_closed_slot: game_session.bets_closed_slot,
+        slot: clock.slot,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct VoidRound<'info> {
+    #[account(mut, seeds = [GAME_SESSION_SEED, game_session.table_seed().as_slice()], bump = game_session.bump)]
+    pub game_session: Account<'info, GameSession>,
+
+    /// Stats of the voided round, which record the void for `refund_voided_bets`.
+    #[account(
+        mut,
+        seeds = [ROUND_STATS_SEED, game_session.key().as_ref(), &game_session.current_round.to_le_bytes()],
+        bump = round_stats.bump
+    )]
+    pub round_stats: Account<'info, RoundStats>,
+
+    pub caller: Signer<'info>,
+}
+
+// =================================================================================================
+// Game Cancel Round
+// =================================================================================================
+
+/// Calls off the current round before it completes, e.g. after an incident, so players can take
+/// back their stakes with `refund_bet`. The game authority can cancel while the round accepts
+/// bets. Anyone can cancel it after `CANCEL_ROUND_TIMEOUT_SECS` have passed since it started, but
+/// only while its randomness cannot be known yet (see `GameSession::cancellable_by_anyone`); a
+/// stuck round past that point is left to `void_round`.
+pub fn cancel_round(ctx: Context<CancelRound>) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+    let clock = Clock::get()?;
+    let canceller = ctx.accounts.canceller.key();
+
+    require!(
+        matches!(
+            game_session.round_status,
+            RoundStatus::AcceptingBets | RoundStatus::BetsClosed | RoundStatus::SeedLocked
+        ),
+        RouletteError::RoundNotCancellable
+    );
+    if !(canceller == game_session.authority && game_session.round_status == RoundStatus::AcceptingBets) {
+        require!(game_session.cancellable_by_anyone(), RouletteError::RandomnessMayBeKnown);
+        require!(
+            game_session.duration_elapsed(
+                &clock,
+                game_session.round_start_time,
+                game_session.round_start_slot,
+                CANCEL_ROUND_TIMEOUT_SECS
+            ),
+            RouletteError::CancelTimeoutNotReached
+        );
+    }
+
+    game_session.cancel_current_round(&mut ctx.accounts.round_stats);
+    msg!("Round {} cancelled by {}", game_session.current_round, canceller);
+
+    emit!(RoundCancelled {
+        event_seq: game_session.next_event_seq(),
+        round: game_session.current_round,
+        canceller,
+        slot: clock.slot,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CancelRound<'info> {
+    #[account(mut, seeds = [GAME_SESSION_SEED, game_session.table_seed().as_slice()], bump = game_session.bump)]
+    pub game_session: Account<'info, GameSession>,
+
+    /// Stats of the cancelled round, which record the cancellation for `refund_bet`.
+    #[account(
+        mut,
+        seeds = [ROUND_STATS_SEED, game_session.key().as_ref(), &game_session.current_round.to_le_bytes()],
+        bump = round_stats.bump
+    )]
+    pub round_stats: Account<'info, RoundStats>,
+
+    pub canceller: Signer<'info>,
+}
+
+// =================================================================================================
+// Game Pause
+// =================================================================================================
+
+/// Emergency circuit breaker for incident response, covering the whole game. While paused,
+/// `start_new_round` and bets on every table fail with `ProgramPaused`, as do liquidity
+/// deposits and strategy deployments. A round already in progress can still be closed and
+/// settled, and withdrawals and claims are never blocked. `freeze_table` stops one table only.
+/// Reserved to the game admin, with the co-signatures of the admin multisig once it is enabled.
+pub fn set_pause(ctx: Context<SetPause>, paused: bool) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.require_admin_approval(ctx.remaining_accounts)?;
+    config.paused = paused;
+
+    emit!(GamePauseChanged {
+        event_seq: config.next_event_seq(),
+        paused,
+        authority: ctx.accounts.authority.key(),
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetPause<'info> {
+    #[account(mut, seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(address = GAME_ADMIN_PUBKEY @ RouletteError::AdminOnly)]
+    pub authority: Signer<'info>,
+}
+
+/// Freezes one table, for instance while investigating it: `start_new_round` and bets on it
+/// fail with `TableFrozen`. Unlike `set_pause`, deposits are not blocked, and other tables,
+/// claims, refunds and withdrawals carry on. A round in progress can still be closed, settled
+/// or cancelled.
+pub fn freeze_table(ctx: Context<FreezeTable>, table_id: u64) -> Result<()> {
+    set_frozen(ctx, table_id, true)
+}
+
+/// Lifts `freeze_table`.
+pub fn unfreeze_table(ctx: Context<FreezeTable>, table_id: u64) -> Result<()> {
+    set_frozen(ctx, table_id, false)
+}
+
+fn set_frozen(ctx: Context<FreezeTable>, table_id: u64, frozen: bool) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+    game_session.frozen = frozen;
+
+    emit!(TableFrozen {
+        event_seq: game_session.next_event_seq(),
+        table_id,
+        frozen,
+        authority: ctx.accounts.authority.key(),
+        slot: Clock::get()?.slot,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(table_id: u64)]
+pub struct FreezeTable<'info> {
+    #[account(
+        mut,
+        seeds = [GAME_SESSION_SEED, table_seed(table_id).as_slice()],
+        bump = game_session.bump,
+        constraint = authority.key() == game_session.authority @ RouletteError::AdminOnly
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    pub authority: Signer<'info>,
+}
+
+// =================================================================================================
+// Game Operator
+// =================================================================================================
+
+/// Sets the table's operator, a key that may start, close and settle rounds without any access
+/// to funds, so a round bot can run with low privileges. `Pubkey::default()` removes it.
+pub fn set_operator(ctx: Context<SetOperator>, operator: Pubkey) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+    let previous_operator = game_session.operator;
+    game_session.operator = operator;
+
+    emit!(OperatorChanged {
+        event_seq: game_session.next_event_seq(),
+        previous_operator,
+        operator,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetOperator<'info> {
+    #[account(
+        mut,
+        seeds = [GAME_SESSION_SEED, game_session.table_seed().as_slice()],
+        bump = game_session.bump,
+        constraint = authority.key() == game_session.authority @ RouletteError::AdminOnly
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    pub authority: Signer<'info>,
+}
+
+// =================================================================================================
+// Random Initiator Rotation
+// =================================================================================================
+
+/// Sets the operators that take turns drawing the table's round randomness: round N must be
+/// settled by `random_initiators[N % count]`, so no single operator controls the timing of
+/// every draw. A count of 0 hands every round back to `GAME_ADMIN_PUBKEY`. Each table keeps
+/// its own rotation.
+pub fn set_random_initiators(
+    ctx: Context<SetRandomInitiators>,
+    random_initiators: [Pubkey; MAX_RANDOM_INITIATORS],
+    count: u8
+) -> Result<()> {
+    ctx.accounts.config.require_admin_approval(ctx.remaining_accounts)?;
+
+    let rotation = random_initiators
+        .get(..count as usize)
+        .ok_or(RouletteError::InvalidRandomInitiators)?;
+    for (i, key) in rotation.iter().enumerate() {
+        require!(
+            *key != Pubkey::default() && !rotation[..i].contains(key),
+            RouletteError::InvalidRandomInitiators
+        );
+    }
+
+    let game_session = &mut ctx.accounts.game_session;
+    game_session.random_initiators = random_initiators;
+    game_session.random_initiator_count = count;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetRandomInitiators<'info> {
+    #[account(
+        mut,
+        seeds = [GAME_SESSION_SEED, game_session.table_seed().as_slice()],
+        bump = game_session.bump,
+        constraint = authority.key() == game_session.authority @ RouletteError::AdminOnly
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+}
+
+// =================================================================================================
+// Game Wheel Type and Rules
+// =================================================================================================
+
+/// Switches the table between the single-zero European wheel and the double-zero American one.
+/// Only allowed between rounds, so every bet of a round is placed and settled on the same wheel.
+pub fn set_wheel_type(ctx: Context<UpdateTableConfig>, wheel_type: WheelType) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+    require!(
+        matches!(
+            game_session.round_status,
+            RoundStatus::NotStarted | RoundStatus::Completed | RoundStatus::Voided | RoundStatus::Cancelled
+        ),
+        RouletteError::RoundInProgress
+    );
+    let previous_wheel_type = game_session.wheel_type;
+    game_session.wheel_type = wheel_type;
+
+    emit!(WheelTypeChanged {
+        event_seq: game_session.next_event_seq(),
+        previous_wheel_type,
+        wheel_type,
+        from_round: game_session.current_round.saturating_add(1),
+    });
+    Ok(())
+}
+
+/// Turns the En Prison rule on or off: with it, even-money bets lost to a zero are imprisoned
+/// into the next round and get their stake back if they win there. Only allowed between rounds;
+/// each round keeps the rule it started with.
+pub fn set_en_prison(ctx: Context<UpdateTableConfig>, en_prison: bool) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+    require!(
+        matches!(
+            game_session.round_status,
+            RoundStatus::NotStarted | RoundStatus::Completed | RoundStatus::Voided | RoundStatus::Cancelled
+        ),
+        RouletteError::RoundInProgress
+    );
+    game_session.en_prison = en_prison;
+
+    emit!(EnPrisonChanged {
+        event_seq: game_session.next_event_seq(),
+        en_prison,
+        from_round: game_session.current_round.saturating_add(1),
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateTableConfig<'info> {
+    #[account(
+        mut,
+        seeds = [GAME_SESSION_SEED, game_session.table_seed().as_slice()],
+        bump = game_session.bump,
+        constraint = authority.key() == game_session.authority @ RouletteError::AdminOnly
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    pub authority: Signer<'info>,
+}
+
+/// Sets the bets and distinct bettors a round needs before betting can close (0 = no minimum).
+/// Only allowed between rounds, so the current round keeps the minimums it started with. A
+/// round that never reaches them can be called off with `cancel_round`.
+pub fn set_participation_minimums(
+    ctx: Context<UpdateTableConfig>,
+    min_total_bets: u32,
+    min_unique_bettors: u32
+) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+    require!(
+        matches!(
+            game_session.round_status,
+            RoundStatus::NotStarted | RoundStatus::Completed | RoundStatus::Voided | RoundStatus::Cancelled
+        ),
+        RouletteError::RoundInProgress
+    );
+    game_session.min_total_bets = min_total_bets;
+    game_session.min_unique_bettors = min_unique_bettors;
+
+    emit!(ParticipationMinimumsChanged {
+        event_seq: game_session.next_event_seq(),
+        min_total_bets,
+        min_unique_bettors,
+        from_round: game_session.current_round.saturating_add(1),
+    });
+    Ok(())
+}
+
+/// Runs the table on a fixed cadence: rounds start every `round_interval_slots` from
+/// `next_round_slot` on, and no earlier (0 turns the schedule off). Starts that are missed are
+/// skipped rather than caught up. Tables whose randomness does not rely on the operator's server
+/// seed can then be started by anyone.
+pub fn set_round_schedule(
+    ctx: Context<UpdateTableConfig>,
+    round_interval_slots: u64,
+    next_round_slot: u64
+) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+    game_session.round_interval_slots = round_interval_slots;
+    game_session.next_round_slot = next_round_slot;
+
+    emit!(RoundScheduleChanged {
+        event_seq: game_session.next_event_seq(),
+        round_interval_slots,
+        next_round_slot,
+        open_to_any_starter: game_session.open_to_any_starter(),
+    });
+    Ok(())
+}
+
+// =================================================================================================
+// Game Round Finality
+// =================================================================================================
+
+/// Sets how many slots after settlement a round can be finalized, at most
+/// `DEFAULT_FINALITY_SLOTS`, and the payout from which claims wait for it (0 = never). The
+/// threshold applies to rounds settled from then on; settled rounds keep theirs.
+pub fn set_finality_config(
+    ctx: Context<SetFinalityConfig>,
+    finality_slots: u64,
+    finality_payout_threshold: u64
+) -> Result<()> {
+    require!(finality_slots <= DEFAULT_FINALITY_SLOTS, RouletteError::FinalitySlotsTooHigh);
+    let game_session = &mut ctx.accounts.game_session;
+    game_session.finality_slots = finality_slots;
+    game_session.finality_payout_threshold = finality_payout_threshold;
+
+    emit!(FinalityConfigChanged {
+        event_seq: game_session.next_event_seq(),
+        finality_slots,
+        finality_payout_threshold,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetFinalityConfig<'info> {
+    #[account(
+        mut,
+        seeds = [GAME_SESSION_SEED, game_session.table_seed().as_slice()],
+        bump = game_session.bump,
+        constraint = authority.key() == game_session.authority @ RouletteError::AdminOnly
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    pub authority: Signer<'info>,
+}
+
+/// Permissionless. Marks a settled round final once `finality_slots` have passed since its
+/// settlement slot, which unlocks claims at or above `finality_payout_threshold`. A no-op on
+/// rounds already final.
+pub fn finalize_round(ctx: Context<FinalizeRound>, round: u64) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+    let round_stats = &mut ctx.accounts.round_stats;
+    let slot = Clock::get()?.slot;
+
+    require!(round_stats.winning_number.is_some(), RouletteError::ClaimRoundMismatchOrNotCompleted);
+    if round_stats.finalized {
+        return Ok(());
+    }
+    require!(
+        slot.saturating_sub(round_stats.settled_slot) >= game_session.finality_slots,
+        RouletteError::FinalityNotReached
+    );
+    round_stats.finalized = true;
+
+    emit!(RoundFinalized {
+        event_seq: game_session.next_event_seq(),
+        round,
+        settled_slot: round_stats.settled_slot,
+        slot,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(round: u64)]
+pub struct FinalizeRound<'info> {
+    #[account(mut, seeds = [GAME_SESSION_SEED, game_session.table_seed().as_slice()], bump = game_session.bump)]
+    pub game_session: Account<'info, GameSession>,
+
+    #[account(
+        mut,
+        seeds = [ROUND_STATS_SEED, game_session.key().as_ref(), &round.to_le_bytes()],
+        bump = round_stats.bump
+    )]
+    pub round_stats: Account<'info, RoundStats>,
+}
+
+// =================================================================================================
+// Game Randomness Mode
+// =================================================================================================
+
+/// Selects the backend `get_random` settles with. Cannot change while a round is being settled,
+/// so a round never mixes backends.
+pub fn set_randomness_mode(ctx: Context<SetRandomnessMode>, mode: RandomnessMode) -> Result<()> {
+    #[cfg(not(feature = "orao-vrf"))]
+    require!(mode != RandomnessMode::OraoVrf, RouletteError::RandomnessModeUnavailable);
+
+    let game_session = &mut ctx.accounts.game_session;
+    require!(
+        !matches!(game_session.round_status, RoundStatus::BetsClosed | RoundStatus::SeedLocked),
+        RouletteError::RandomnessModeLocked
+    );
+    require!(
+        mode != RandomnessMode::MultiOracle || game_session.oracle_quorum > 0,
+        RouletteError::InvalidOracleConfig
+    );
+    let previous_mode = game_session.randomness_mode;
+    game_session.randomness_mode = mode;
+
+    emit!(RandomnessModeChanged {
+        event_seq: game_session.next_event_seq(),
+        previous_mode,
+        mode,
+        round: game_session.current_round,
+    });
+    Ok(())
+}
+
+/// Whether a round reaches its fallback mode before it can be voided, so the fallback gets a
+/// chance to settle it. Holds trivially without a fallback or a void timeout.
+fn fallback_precedes_void(fallback_mode: Option<RandomnessMode>, fallback_timeout_slots: u64, void_timeout_slots: u64) -> bool {
+    fallback_mode.is_none() || void_timeout_slots == 0 || fallback_timeout_slots < void_timeout_slots
+}
+
+/// Sets the mode a round falls back to when the primary `randomness_mode` has not settled it
+/// within `timeout_slots` of `close_bets`, e.g. VRF first, then commit-reveal, then `void_round`.
+pub fn set_randomness_fallback(
+    ctx: Context<SetRandomnessMode>,
+    fallback_mode: Option<RandomnessMode>,
+    timeout_slots: u64
+) -> Result<()> {
+    #[cfg(not(feature = "orao-vrf"))]
+    require!(fallback_mode != Some(RandomnessMode::OraoVrf), RouletteError::RandomnessModeUnavailable);
+
+    let game_session = &mut ctx.accounts.game_session;
+    require!(
+        !matches!(game_session.round_status, RoundStatus::BetsClosed | RoundStatus::SeedLocked),
+        RouletteError::RandomnessModeLocked
+    );
+    require!(
+        fallback_mode != Some(game_session.randomness_mode) && (fallback_mode.is_none() || timeout_slots > 0),
+        RouletteError::InvalidRandomnessFallback
+    );
+    require!(
+        fallback_precedes_void(fallback_mode, timeout_slots, game_session.void_timeout_slots),
+        RouletteError::FallbackAfterVoidTimeout
+    );
+    require!(
+        fallback_mode != Some(RandomnessMode::MultiOracle) || game_session.oracle_quorum > 0,
+        RouletteError::InvalidOracleConfig
+    );
+    game_session.fallback_randomness_mode = fallback_mode;
+    game_session.fallback_timeout_slots = timeout_slots;
+
+    emit!(RandomnessFallbackChanged {
+        event_seq: game_session.next_event_seq(),
+        fallback_mode,
+        timeout_slots,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetRandomnessMode<'info> {
+    #[account(
+        mut,
+        seeds = [GAME_SESSION_SEED, game_session.table_seed().as_slice()],
+        bump = game_session.bump,
+        constraint = authority.key() == game_session.authority @ RouletteError::AdminOnly
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    pub authority: Signer<'info>,
+}
+
+// =================================================================================================
+// Game Randomness Oracles
+// =================================================================================================
+
+/// Registers the Switchboard randomness accounts aggregated in `MultiOracle` mode, all of which
+/// must be fulfilled to settle. Like the mode, cannot change while a round is being settled, so
+/// a round's oracle set is fixed when its bets close.
+pub fn set_randomness_oracles(
+    ctx: Context<SetRandomnessOracles>,
+    oracles: [Pubkey; MAX_RANDOMNESS_ORACLES]
+) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+    require!(
+        !matches!(game_session.round_status, RoundStatus::BetsClosed | RoundStatus::SeedLocked),
+        RouletteError::RandomnessModeLocked
+    );
+
+    let registered: Vec<Pubkey> = oracles.into_iter().filter(|key| *key != Pubkey::default()).collect();
+    let distinct = registered.iter().enumerate().all(|(i, key)| !registered[..i].contains(key));
+    require!(distinct && !registered.is_empty(), RouletteError::InvalidOracleConfig);
+    let quorum = registered.len() as u8;
+
+    game_session.randomness_oracles = oracles;
+    game_session.oracle_quorum = quorum;
+
+    emit!(RandomnessOraclesUpdated {
+        event_seq: game_session.next_event_seq(),
+        oracles: registered,
+        quorum,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetRandomnessOracles<'info> {
+    #[account(
+        mut,
+        seeds = [GAME_SESSION_SEED, game_session.table_seed().as_slice()],
+        bump = game_session.bump,
+        constraint = authority.key() == game_session.authority @ RouletteError::AdminOnly
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    pub authority: Signer<'info>,
+}
+
+/// `RandomnessMode::MultiOracle`: settles from the registered oracles, passed as remaining
+/// accounts in registration order. An oracle counts as fulfilled once it was committed after
+/// `close_bets` and has revealed since. Every oracle must be fulfilled and their values are
+/// hashed together, so a single compromised oracle cannot choose the outcome, and the settler
+/// cannot pick between outcomes by settling before or after a slow oracle reveals.
+fn settle_from_oracles(accounts: SettlementAccounts, oracle_accounts: &[AccountInfo]) -> Result<()> {
+    let game_session = accounts.game_session;
+    let clock = Clock::get()?;
+
+    require!(
+        game_session.round_status == RoundStatus::BetsClosed,
+        RouletteError::RandomBeforeClosing
+    );
+    require!(game_session.last_bettor.is_some(), RouletteError::NoBetsPlacedInRound);
+
+    let registered: Vec<Pubkey> = game_session.randomness_oracles
+        .into_iter()
+        .filter(|key| *key != Pubkey::default())
+        .collect();
+    require!(
+        game_session.oracle_quorum > 0 && oracle_accounts.len() == registered.len(),
+        RouletteError::InvalidOracleConfig
+    );
+
+    let mut oracles = Vec::with_capacity(registered.len());
+    let mut values: Vec<[u8; 32]> = Vec::with_capacity(registered.len());
+    for (key, account) in registered.iter().zip(oracle_accounts) {
+        require_keys_eq!(account.key(), *key, RouletteError::InvalidRandomnessAccount);
+        let randomness = SwitchboardRandomness::load(account)?;
+        if randomness.seed_slot > game_session.bets_closed_slot && randomness.reveal_slot > randomness.seed_slot {
+            oracles.push(*key);
+            values.push(randomness.value);
+        }
+    }
+    require!(values.len() == registered.len(), RouletteError::OracleQuorumNotMet);
+
+    let round_bytes = game_session.current_round.to_le_bytes();
+    let mut inputs: Vec<&[u8]> = vec![&round_bytes];
+    inputs.extend(values.iter().map(|value| value.as_slice()));
+    let combined_value = hash::hashv(&inputs).to_bytes();
+    let winning_number = RoundResult::winning_number_for(&combined_value, game_session.wheel_type);
+
+    msg!("Round {} | {} oracles | Winning Number {}", game_session.current_round, oracles.len(), winning_number);
+
+    accounts.operator_stats.touch(accounts.initiator, accounts.operator_stats_bump, clock.slot);
+    complete_round(
+        game_session,
+        accounts.operator_stats,
+        accounts.records,
+        accounts.round_stats,
+        winning_number,
+        &clock
+    )?;
+
+    emit!(MultiOracleRoundSettled {
+        event_seq: game_session.next_event_seq(),
+        round: game_session.current_round,
+        initiator: accounts.initiator,
+        oracles,
+        combined_value,
+        winning_number,
+        slot: clock.slot,
+    });
+
+    Ok(())
+}
+
+// =================================================================================================
+// Table Metadata
+// =================================================================================================
+
+/// Creates or replaces the table's display metadata.
+pub fn set_table_metadata(
+    ctx: Context<SetTableMetadata>,
+    name: String,
+    description_hash: [u8; 32],
+    banner_mint: Option<Pubkey>
+) -> Result<()> {
+    require!(name.len() <= MAX_TABLE_NAME_LEN, RouletteError::TableNameTooLong);
+
+    let metadata = &mut ctx.accounts.table_metadata;
+    metadata.game_session = ctx.accounts.game_session.key();
+    metadata.name = name;
+    metadata.description_hash = description_hash;
+    metadata.banner_mint = banner_mint;
+    metadata.bump = ctx.bumps.table_metadata;
+
+    emit!(TableMetadataUpdated {
+        event_seq: ctx.accounts.game_session.next_event_seq(),
+        game_session: metadata.game_session,
+        name: metadata.name.clone(),
+        description_hash,
+        banner_mint,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetTableMetadata<'info> {
+    #[account(
+        mut,
+        seeds = [GAME_SESSION_SEED, game_session.table_seed().as_slice()],
+        bump = game_session.bump,
+        constraint = authority.key() == game_session.authority @ RouletteError::AdminOnly
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = TableMetadata::SPACE,
+        seeds = [TABLE_METADATA_SEED, game_session.key().as_ref()],
+        bump
+    )]
+    pub table_metadata: Account<'info, TableMetadata>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// =================================================================================================
+// Table Access List
+// =================================================================================================
+
+/// Creates or replaces the table's access list, making the table private: from then on only
+/// `players` may bet on it. Send it together with `initialize_game_session` to open a table as
+/// private from the start.
+pub fn set_table_access_list(ctx: Context<SetTableAccessList>, players: Vec<Pubkey>) -> Result<()> {
+    require!(players.len() <= MAX_TABLE_ACCESS_LIST_LEN, RouletteError::InvalidTableAccessList);
+    for (i, player) in players.iter().enumerate() {
+        require!(!players[..i].contains(player), RouletteError::InvalidTableAccessList);
+    }
+
+    let game_session = &mut ctx.accounts.game_session;
+    let access_list = &mut ctx.accounts.table_access_list;
+    access_list.game_session = game_session.key();
+    access_list.players = players;
+    access_list.bump = ctx.bumps.table_access_list;
+    game_session.access_list_enabled = true;
+
+    emit!(TableAccessListUpdated {
+        event_seq: game_session.next_event_seq(),
+        game_session: access_list.game_session,
+        players: access_list.players.clone(),
+        enabled: true,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetTableAccessList<'info> {
+    #[account(
+        mut,
+        seeds = [GAME_SESSION_SEED, game_session.table_seed().as_slice()],
+        bump = game_session.bump,
+        constraint = authority.key() == game_session.authority @ RouletteError::AdminOnly
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = TableAccessList::SPACE,
+        seeds = [TABLE_ACCESS_LIST_SEED, game_session.key().as_ref()],
+        bump
+    )]
+    pub table_access_list: Account<'info, TableAccessList>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Removes the table's access list, opening the table to every player again, and returns its
+/// rent to the authority.
+pub fn close_table_access_list(ctx: Context<CloseTableAccessList>) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+    game_session.access_list_enabled = false;
+
+    emit!(TableAccessListUpdated {
+        event_seq: game_session.next_event_seq(),
+        game_session: game_session.key(),
+        players: Vec::new(),
+        enabled: false,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseTableAccessList<'info> {
+    #[account(
+        mut,
+        seeds = [GAME_SESSION_SEED, game_session.table_seed().as_slice()],
+        bump = game_session.bump,
+        constraint = authority.key() == game_session.authority @ RouletteError::AdminOnly
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [TABLE_ACCESS_LIST_SEED, game_session.key().as_ref()],
+        bump = table_access_list.bump
+    )]
+    pub table_access_list: Account<'info, TableAccessList>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+// =================================================================================================
+// Table Bound Vault
+// =================================================================================================
+
+/// Binds the table to `vault`, so it only accepts bets staked with that vault, or unbinds it
+/// when no vault is passed. Bets already placed in the current round are unaffected.
+pub fn set_bound_vault(ctx: Context<SetBoundVault>) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+    game_session.bound_vault = ctx.accounts.vault.as_ref().map(|vault| vault.key());
+
+    emit!(TableVaultBound {
+        event_seq: game_session.next_event_seq(),
+        game_session: game_session.key(),
+        bound_vault: game_session.bound_vault,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetBoundVault<'info> {
+    #[account(
+        mut,
+        seeds = [GAME_SESSION_SEED, game_session.table_seed().as_slice()],
+        bump = game_session.bump,
+        constraint = authority.key() == game_session.authority @ RouletteError::AdminOnly
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    /// The vault to bind the table to; omitted to unbind it.
+    pub vault: Option<Account<'info, VaultAccount>>,
+
+    pub authority: Signer<'info>,
+}
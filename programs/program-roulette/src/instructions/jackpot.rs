@@ -0,0 +1,215 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+use crate::{
+    errors::RouletteError,
+    events::*,
+    seeds::{GAME_SESSION_SEED, JACKPOT_CLAIM_SEED, JACKPOT_SEED},
+    state::*,
+};
+
+// =================================================================================================
+// Publish Jackpot Root
+// =================================================================================================
+
+/// Funds the jackpot of a completed round and publishes the Merkle root of its winners.
+/// The funds are moved into `jackpot_token_account`, which must be owned by the jackpot PDA.
+pub fn publish_jackpot_root(
+    ctx: Context<PublishJackpotRoot>,
+    round: u64,
+    merkle_root: [u8; 32],
+    total_amount: u64
+) -> Result<()> {
+    require!(
+        round > 0 && round <= ctx.accounts.game_session.last_completed_round,
+        RouletteError::JackpotRoundNotCompleted
+    );
+    require!(total_amount > 0, RouletteError::AmountMustBeGreaterThanZero);
+
+    token_interface::transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.authority_token_account.to_account_info(),
+                mint: ctx.accounts.token_mint.to_account_info(),
+                to: ctx.accounts.jackpot_token_account.to_account_info(),
+                authority: ctx.accounts.authority.to_account_info(),
+            },
+        ),
+        total_amount,
+        ctx.accounts.token_mint.decimals,
+    )?;
+
+    let jackpot = &mut ctx.accounts.jackpot;
+    jackpot.round = round;
+    jackpot.token_mint = ctx.accounts.token_mint.key();
+    jackpot.token_account = ctx.accounts.jackpot_token_account.key();
+    jackpot.merkle_root = merkle_root;
+    jackpot.total_amount = total_amount;
+    jackpot.claimed_amount = 0;
+    jackpot.bump = ctx.bumps.jackpot;
+
+    emit!(JackpotPublished {
+        event_seq: ctx.accounts.game_session.next_event_seq(),
+        round,
+        token_mint: jackpot.token_mint,
+        merkle_root,
+        total_amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(round: u64)]
+pub struct PublishJackpotRoot<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GAME_SESSION_SEED, game_session.table_seed().as_slice()],
+        bump = game_session.bump,
+        constraint = authority.key() == game_session.authority @ RouletteError::AdminOnly
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + std::mem::size_of::<Jackpot>(),
+        seeds = [JACKPOT_SEED, game_session.key().as_ref(), &round.to_le_bytes()],
+        bump
+    )]
+    pub jackpot: Account<'info, Jackpot>,
+
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = authority_token_account.mint == token_mint.key() @ RouletteError::InvalidTokenAccount
+    )]
+    pub authority_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Token account holding the jackpot funds, owned by the `jackpot` PDA.
+    #[account(
+        mut,
+        constraint = jackpot_token_account.owner == jackpot.key() @ RouletteError::InvalidTokenAccountOwner,
+        constraint = jackpot_token_account.mint == token_mint.key() @ RouletteError::InvalidTokenAccount
+    )]
+    pub jackpot_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+// =================================================================================================
+// Claim Jackpot
+// =================================================================================================
+
+/// Pays the leaf `(index, player, amount)` of a round's jackpot after checking its Merkle proof.
+/// The claimant pays the rent of their own `JackpotClaim` receipt.
+pub fn claim_jackpot(
+    ctx: Context<ClaimJackpot>,
+    round: u64,
+    index: u64,
+    amount: u64,
+    proof: Vec<[u8; 32]>
+) -> Result<()> {
+    let player_key = ctx.accounts.player.key();
+    let jackpot = &mut ctx.accounts.jackpot;
+
+    require!(
+        jackpot.verify(&proof, Jackpot::leaf(index, &player_key, amount)),
+        RouletteError::InvalidJackpotProof
+    );
+    let claimed_amount = jackpot.claimed_amount
+        .checked_add(amount)
+        .ok_or(RouletteError::ArithmeticOverflow)?;
+    require!(claimed_amount <= jackpot.total_amount, RouletteError::JackpotExhausted);
+
+    let game_session_key = ctx.accounts.game_session.key();
+    let round_bytes = round.to_le_bytes();
+    let seeds = &[JACKPOT_SEED, game_session_key.as_ref(), &round_bytes, &[jackpot.bump]];
+    let signer_seeds = &[&seeds[..]];
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.jackpot_token_account.to_account_info(),
+                mint: ctx.accounts.token_mint.to_account_info(),
+                to: ctx.accounts.player_token_account.to_account_info(),
+                authority: jackpot.to_account_info(),
+            },
+            signer_seeds
+        ),
+        amount,
+        ctx.accounts.token_mint.decimals,
+    )?;
+    jackpot.claimed_amount = claimed_amount;
+
+    let claim = &mut ctx.accounts.jackpot_claim;
+    claim.jackpot = jackpot.key();
+    claim.index = index;
+    claim.player = player_key;
+    claim.amount = amount;
+    claim.bump = ctx.bumps.jackpot_claim;
+
+    emit!(JackpotClaimed {
+        event_seq: ctx.accounts.game_session.next_event_seq(),
+        round,
+        player: player_key,
+        token_mint: jackpot.token_mint,
+        index,
+        amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(round: u64, index: u64)]
+pub struct ClaimJackpot<'info> {
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    #[account(mut, seeds = [GAME_SESSION_SEED, game_session.table_seed().as_slice()], bump = game_session.bump)]
+    pub game_session: Account<'info, GameSession>,
+
+    #[account(
+        mut,
+        seeds = [JACKPOT_SEED, game_session.key().as_ref(), &round.to_le_bytes()],
+        bump = jackpot.bump
+    )]
+    pub jackpot: Account<'info, Jackpot>,
+
+    /// Fails to initialize if this leaf was already claimed.
+    #[account(
+        init,
+        payer = player,
+        space = 8 + std::mem::size_of::<JackpotClaim>(),
+        seeds = [JACKPOT_CLAIM_SEED, jackpot.key().as_ref(), &index.to_le_bytes()],
+        bump
+    )]
+    pub jackpot_claim: Account<'info, JackpotClaim>,
+
+    #[account(address = jackpot.token_mint @ RouletteError::InvalidTokenAccount)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        address = jackpot.token_account @ RouletteError::InvalidTokenAccount
+    )]
+    pub jackpot_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = player_token_account.mint == token_mint.key() @ RouletteError::InvalidTokenAccount,
+        constraint = player_token_account.owner == player.key() @ RouletteError::InvalidTokenAccountOwner
+    )]
+    pub player_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
+use crate::errors::RouletteError;
+
+// =================================================================================================
+// Lamport Transfer Checks
+// =================================================================================================
+
+/// Checks that `account` may lose `amount` lamports: it must hold them, and must afterwards be
+/// either empty or still rent-exempt, as the runtime requires.
+pub(crate) fn require_debitable(account: &AccountInfo, amount: u64) -> Result<()> {
+    let remaining = account.lamports()
+        .checked_sub(amount)
+        .ok_or(RouletteError::InsufficientLamports)?;
+    require!(
+        remaining == 0 || remaining >= Rent::get()?.minimum_balance(account.data_len()),
+        RouletteError::SourceNotRentExempt
+    );
+    Ok(())
+}
+
+/// Checks that `account` is rent-exempt once it has received `amount` lamports.
+pub(crate) fn require_rent_exempt_after_credit(account: &AccountInfo, amount: u64) -> Result<()> {
+    let balance = account.lamports()
+        .checked_add(amount)
+        .ok_or(RouletteError::ArithmeticOverflow)?;
+    require!(
+        balance >= Rent::get()?.minimum_balance(account.data_len()),
+        RouletteError::DestinationNotRentExempt
+    );
+    Ok(())
+}
+
+/// Moves `amount` lamports out of a program-owned account, which must stay rent-exempt.
+pub(crate) fn debit_program_account(from: &AccountInfo, to: &AccountInfo, amount: u64) -> Result<()> {
+    let remaining = from.lamports()
+        .checked_sub(amount)
+        .ok_or(RouletteError::InsufficientLamports)?;
+    require!(
+        remaining >= Rent::get()?.minimum_balance(from.data_len()),
+        RouletteError::SourceNotRentExempt
+    );
+    require_rent_exempt_after_credit(to, amount)?;
+
+    **from.try_borrow_mut_lamports()? = remaining;
+    **to.try_borrow_mut_lamports()? += amount;
+    Ok(())
+}
+
+/// System transfer of `amount` lamports with the rent checks above applied to both sides. If
+/// `system_owned_destination` is set, `to` must also be a plain system account (a wallet).
+pub(crate) fn transfer_lamports<'info>(
+    from: &AccountInfo<'info>,
+    to: &AccountInfo<'info>,
+    amount: u64,
+    system_owned_destination: bool,
+    system_program: &Program<'info, System>
+) -> Result<()> {
+    if system_owned_destination {
+        require_keys_eq!(*to.owner, system_program::ID, RouletteError::DestinationNotSystemOwned);
+    }
+    require_debitable(from, amount)?;
+    require_rent_exempt_after_credit(to, amount)?;
+
+    system_program::transfer(
+        CpiContext::new(system_program.to_account_info(), Transfer {
+            from: from.clone(),
+            to: to.clone(),
+        }),
+        amount
+    )
+}
@@ -0,0 +1,177 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program::invoke,
+};
+use crate::{
+    constants::MPL_CORE_PROGRAM_ID,
+    errors::RouletteError,
+    events::*,
+    seeds::{GAME_SESSION_SEED, PROVIDER_STATE_SEED, VAULT_SEED},
+    state::*,
+};
+
+// Metaplex Core is called through raw instructions: `CreateV1` (0) and `BurnV1` (12). Optional
+// accounts that are not used are passed as the Core program id itself.
+
+/// Instruction index of Metaplex Core's `CreateV1`.
+const MPL_CORE_CREATE_V1: u8 = 0;
+/// Instruction index of Metaplex Core's `BurnV1`.
+const MPL_CORE_BURN_V1: u8 = 12;
+
+// =================================================================================================
+// Wrap LP Position
+// =================================================================================================
+
+/// Mints a Metaplex Core asset representing the position to the provider. While it exists,
+/// whoever holds the asset controls the position's withdrawals, so the position can be traded
+/// or pledged as collateral.
+pub fn wrap_lp_position(ctx: Context<WrapLpPosition>, name: String, uri: String) -> Result<()> {
+    let provider_state = &mut ctx.accounts.provider_state;
+
+    let mut data = vec![MPL_CORE_CREATE_V1, 0]; // `DataState::AccountState`
+    data.extend_from_slice(&name.try_to_vec()?);
+    data.extend_from_slice(&uri.try_to_vec()?);
+    data.push(0); // No plugins.
+
+    let core_program = ctx.accounts.mpl_core_program.to_account_info();
+    invoke(
+        &Instruction {
+            program_id: MPL_CORE_PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new(ctx.accounts.position_asset.key(), true),
+                AccountMeta::new_readonly(MPL_CORE_PROGRAM_ID, false),
+                AccountMeta::new_readonly(MPL_CORE_PROGRAM_ID, false),
+                AccountMeta::new(ctx.accounts.liquidity_provider.key(), true),
+                AccountMeta::new_readonly(ctx.accounts.liquidity_provider.key(), false),
+                AccountMeta::new_readonly(provider_state.key(), false),
+                AccountMeta::new_readonly(ctx.accounts.system_program.key(), false),
+                AccountMeta::new_readonly(MPL_CORE_PROGRAM_ID, false),
+            ],
+            data,
+        },
+        &[
+            ctx.accounts.position_asset.to_account_info(),
+            ctx.accounts.liquidity_provider.to_account_info(),
+            provider_state.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            core_program,
+        ]
+    )?;
+
+    provider_state.position_asset = Some(ctx.accounts.position_asset.key());
+
+    emit!(LpPositionWrapped {
+        event_seq: ctx.accounts.game_session.next_event_seq(),
+        provider_state: provider_state.key(),
+        position_asset: ctx.accounts.position_asset.key(),
+        holder: ctx.accounts.liquidity_provider.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct WrapLpPosition<'info> {
+    #[account(mut, seeds = [GAME_SESSION_SEED], bump = game_session.bump)]
+    pub game_session: Account<'info, GameSession>,
+
+    #[account(seeds = [VAULT_SEED, vault.token_mint.as_ref()], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(
+        mut,
+        constraint = provider_state.vault == vault.key() @ RouletteError::VaultMismatch,
+        constraint = provider_state.position_asset.is_none() @ RouletteError::LpPositionAlreadyWrapped,
+        constraint = provider_state.is_controlled_by(&liquidity_provider.key(), None) @ RouletteError::Unauthorized,
+        seeds = [PROVIDER_STATE_SEED, vault.key().as_ref(), provider_state.provider.as_ref()],
+        bump = provider_state.bump
+    )]
+    pub provider_state: Account<'info, ProviderState>,
+
+    /// CHECK: New keypair for the asset, created by Metaplex Core.
+    #[account(mut)]
+    pub position_asset: Signer<'info>,
+
+    #[account(mut)]
+    pub liquidity_provider: Signer<'info>,
+
+    /// CHECK: Metaplex Core program.
+    #[account(address = MPL_CORE_PROGRAM_ID)]
+    pub mpl_core_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// =================================================================================================
+// Unwrap LP Position
+// =================================================================================================
+
+/// Burns the position asset, handing direct control of the position to its holder.
+pub fn unwrap_lp_position(ctx: Context<UnwrapLpPosition>) -> Result<()> {
+    let holder = ctx.accounts.holder.key();
+    let core_program = ctx.accounts.mpl_core_program.to_account_info();
+    invoke(
+        &Instruction {
+            program_id: MPL_CORE_PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new(ctx.accounts.position_asset.key(), false),
+                AccountMeta::new_readonly(MPL_CORE_PROGRAM_ID, false),
+                AccountMeta::new(holder, true),
+                AccountMeta::new_readonly(holder, true),
+                AccountMeta::new_readonly(ctx.accounts.system_program.key(), false),
+                AccountMeta::new_readonly(MPL_CORE_PROGRAM_ID, false),
+            ],
+            data: vec![MPL_CORE_BURN_V1, 0], // No compression proof.
+        },
+        &[
+            ctx.accounts.position_asset.to_account_info(),
+            ctx.accounts.holder.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            core_program,
+        ]
+    )?;
+
+    let provider_state = &mut ctx.accounts.provider_state;
+    let position_asset = provider_state.position_asset.take().unwrap();
+    provider_state.controller = (holder != provider_state.provider).then_some(holder);
+
+    emit!(LpPositionUnwrapped {
+        event_seq: ctx.accounts.game_session.next_event_seq(),
+        provider_state: provider_state.key(),
+        position_asset,
+        holder,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UnwrapLpPosition<'info> {
+    #[account(mut, seeds = [GAME_SESSION_SEED], bump = game_session.bump)]
+    pub game_session: Account<'info, GameSession>,
+
+    #[account(
+        mut,
+        constraint = provider_state.position_asset.is_some() @ RouletteError::LpPositionNotWrapped,
+        constraint = provider_state.is_controlled_by(&holder.key(), Some(&position_asset)) @ RouletteError::Unauthorized,
+        seeds = [PROVIDER_STATE_SEED, provider_state.vault.as_ref(), provider_state.provider.as_ref()],
+        bump = provider_state.bump
+    )]
+    pub provider_state: Account<'info, ProviderState>,
+
+    /// CHECK: Must be the position's asset; checked through `is_controlled_by`.
+    #[account(mut)]
+    pub position_asset: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub holder: Signer<'info>,
+
+    /// CHECK: Metaplex Core program.
+    #[account(address = MPL_CORE_PROGRAM_ID)]
+    pub mpl_core_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -1,7 +1,34 @@
+pub mod config;
+pub mod dispute;
+pub mod event_queue;
+#[cfg(feature = "fast-mode")]
+pub mod fast_mode;
 pub mod game;
+pub mod jackpot;
+pub mod lp_position;
+#[cfg(feature = "orao-vrf")]
+pub mod orao;
+#[cfg(feature = "confidential-payouts")]
+pub mod confidential;
+pub(crate) mod lamports;
 pub mod player;
+pub mod stats;
+pub mod strategy;
 pub mod vault;
 
+pub use config::*;
+pub use dispute::*;
+pub use event_queue::*;
+#[cfg(feature = "fast-mode")]
+pub use fast_mode::*;
 pub use game::*;
+pub use jackpot::*;
+pub use lp_position::*;
+#[cfg(feature = "orao-vrf")]
+pub use orao::*;
+#[cfg(feature = "confidential-payouts")]
+pub use confidential::*;
 pub use player::*;
+pub use stats::*;
+pub use strategy::*;
 pub use vault::*;
\ No newline at end of file
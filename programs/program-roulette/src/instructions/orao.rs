@@ -0,0 +1,267 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    hash,
+    instruction::{AccountMeta, Instruction},
+    program::invoke,
+};
+use crate::{
+    constants::{ORAO_NETWORK_CONFIG_SEED, ORAO_RANDOMNESS_SEED, ORAO_VRF_PROGRAM_ID},
+    errors::RouletteError,
+    events::*,
+    instructions::game::{complete_round, require_randomness_mode, SettlementAccounts, SettlementRecords},
+    seeds::{
+        EVENT_QUEUE_SEED,
+        GAME_SESSION_SEED,
+        OPERATOR_STATS_SEED,
+        ROUND_HISTORY_SEED,
+        ROUND_STATS_SEED,
+        WHEEL_HISTOGRAM_SEED,
+    },
+    state::*,
+};
+
+// ORAO is called through its Anchor `request` instruction: discriminator of `global:request`
+// followed by the 32-byte seed. Its randomness account holds, after the discriminator, the
+// seed (32 bytes) and the randomness (64 bytes), which stays zeroed until fulfilled.
+
+const RANDOMNESS_SEED_OFFSET: usize = 8;
+const RANDOMNESS_VALUE_OFFSET: usize = RANDOMNESS_SEED_OFFSET + 32;
+
+// =================================================================================================
+// ORAO Request
+// =================================================================================================
+
+/// Requests ORAO randomness for the current round once bets are closed. The request seed is
+/// derived from the table and round, so each round has exactly one request account.
+pub fn request_random_orao(ctx: Context<RequestRandomOrao>) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+    require_randomness_mode(game_session, RandomnessMode::OraoVrf)?;
+
+    require!(
+        game_session.round_status == RoundStatus::BetsClosed,
+        RouletteError::RandomBeforeClosing
+    );
+    require!(game_session.last_bettor.is_some(), RouletteError::NoBetsPlacedInRound);
+    require!(!game_session.vrf_pending(), RouletteError::VrfRequestPending);
+
+    let seed = hash::hashv(&[
+        game_session.key().as_ref(),
+        &game_session.current_round.to_le_bytes(),
+    ]).to_bytes();
+    let (request_key, _) = Pubkey::find_program_address(
+        &[ORAO_RANDOMNESS_SEED, &seed],
+        &ORAO_VRF_PROGRAM_ID
+    );
+    require_keys_eq!(
+        ctx.accounts.randomness_request.key(),
+        request_key,
+        RouletteError::InvalidRandomnessAccount
+    );
+
+    let mut data = hash::hash(b"global:request").to_bytes()[..8].to_vec();
+    data.extend_from_slice(&seed);
+    invoke(
+        &Instruction {
+            program_id: ORAO_VRF_PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new(ctx.accounts.authority.key(), true),
+                AccountMeta::new(ctx.accounts.network_state.key(), false),
+                AccountMeta::new(ctx.accounts.treasury.key(), false),
+                AccountMeta::new(request_key, false),
+                AccountMeta::new_readonly(ctx.accounts.system_program.key(), false),
+            ],
+            data,
+        },
+        &[
+            ctx.accounts.authority.to_account_info(),
+            ctx.accounts.network_state.to_account_info(),
+            ctx.accounts.treasury.to_account_info(),
+            ctx.accounts.randomness_request.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            ctx.accounts.orao_program.to_account_info(),
+        ]
+    )?;
+
+    game_session.orao_request_seed = Some(seed);
+
+    emit!(OraoRandomnessRequested {
+        event_seq: game_session.next_event_seq(),
+        round: game_session.current_round,
+        seed,
+        randomness_request: request_key,
+        slot: Clock::get()?.slot,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RequestRandomOrao<'info> {
+    #[account(
+        mut,
+        seeds = [GAME_SESSION_SEED, game_session.table_seed().as_slice()],
+        bump = game_session.bump,
+        constraint = authority.key() == game_session.authority @ RouletteError::AdminOnly
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    /// Pays the ORAO request fee.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: ORAO network configuration; seeds checked here, contents by ORAO.
+    #[account(mut, seeds = [ORAO_NETWORK_CONFIG_SEED], bump, seeds::program = ORAO_VRF_PROGRAM_ID)]
+    pub network_state: UncheckedAccount<'info>,
+
+    /// CHECK: ORAO fee treasury; checked by ORAO against `network_state`.
+    #[account(mut)]
+    pub treasury: UncheckedAccount<'info>,
+
+    /// CHECK: Randomness request account created by ORAO; address checked in the handler.
+    #[account(mut)]
+    pub randomness_request: UncheckedAccount<'info>,
+
+    /// CHECK: ORAO VRF program.
+    #[account(address = ORAO_VRF_PROGRAM_ID)]
+    pub orao_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// =================================================================================================
+// ORAO Fulfill
+// =================================================================================================
+
+/// Settles the current round from its fulfilled ORAO request. Permissionless: the value is fixed
+/// by ORAO, so anyone can relay it.
+pub fn fulfill_random_orao(ctx: Context<FulfillRandomOrao>) -> Result<()> {
+    require_randomness_mode(&mut ctx.accounts.game_session, RandomnessMode::OraoVrf)?;
+    let randomness_request = ctx.accounts.randomness_request.to_account_info();
+    let accounts = SettlementAccounts {
+        game_session: &mut ctx.accounts.game_session,
+        operator_stats: &mut ctx.accounts.operator_stats,
+        operator_stats_bump: ctx.bumps.operator_stats,
+        records: SettlementRecords {
+            wheel_histogram: &mut ctx.accounts.wheel_histogram,
+            wheel_histogram_bump: ctx.bumps.wheel_histogram,
+            round_history: &mut ctx.accounts.round_history,
+            round_history_bump: ctx.bumps.round_history,
+            event_queue: ctx.accounts.event_queue.as_mut(),
+        },
+        round_stats: &mut ctx.accounts.round_stats,
+        initiator: ctx.accounts.random_initiator.key(),
+    };
+    settle_from_orao(accounts, &randomness_request)
+}
+
+pub(crate) fn settle_from_orao(accounts: SettlementAccounts, request: &AccountInfo) -> Result<()> {
+    let game_session = accounts.game_session;
+    let clock = Clock::get()?;
+
+    require!(
+        game_session.round_status == RoundStatus::BetsClosed,
+        RouletteError::RandomBeforeClosing
+    );
+    let seed = game_session.orao_request_seed.ok_or(RouletteError::InvalidRandomnessAccount)?;
+
+    let (request_key, _) = Pubkey::find_program_address(
+        &[ORAO_RANDOMNESS_SEED, &seed],
+        &ORAO_VRF_PROGRAM_ID
+    );
+    require!(
+        request.key() == request_key && *request.owner == ORAO_VRF_PROGRAM_ID,
+        RouletteError::InvalidRandomnessAccount
+    );
+
+    let randomness: [u8; 64] = {
+        let data = request.try_borrow_data()?;
+        require!(
+            data.len() >= RANDOMNESS_VALUE_OFFSET + 64 &&
+                data[RANDOMNESS_SEED_OFFSET..RANDOMNESS_VALUE_OFFSET] == seed,
+            RouletteError::InvalidRandomnessAccount
+        );
+        data[RANDOMNESS_VALUE_OFFSET..RANDOMNESS_VALUE_OFFSET + 64].try_into().unwrap()
+    };
+    require!(randomness != [0; 64], RouletteError::RandomnessNotRevealed);
+
+    let value_prefix_u64 = u64::from_le_bytes(randomness[0..8].try_into().unwrap());
+    let winning_number = (value_prefix_u64 % (game_session.wheel_type.pockets() as u64)) as u8;
+
+    msg!("Round {} | ORAO Winning Number {}", game_session.current_round, winning_number);
+
+    accounts.operator_stats.touch(accounts.initiator, accounts.operator_stats_bump, clock.slot);
+    complete_round(
+        game_session,
+        accounts.operator_stats,
+        accounts.records,
+        accounts.round_stats,
+        winning_number,
+        &clock
+    )?;
+
+    emit!(OraoRoundSettled {
+        event_seq: game_session.next_event_seq(),
+        round: game_session.current_round,
+        initiator: accounts.initiator,
+        seed,
+        randomness,
+        winning_number,
+        slot: clock.slot,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct FulfillRandomOrao<'info> {
+    #[account(mut, seeds = [GAME_SESSION_SEED, game_session.table_seed().as_slice()], bump = game_session.bump)]
+    pub game_session: Account<'info, GameSession>,
+
+    #[account(mut)]
+    pub random_initiator: Signer<'info>,
+
+    /// CHECK: The round's ORAO randomness account; address, owner and layout are checked in the
+    /// handler.
+    pub randomness_request: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = random_initiator,
+        space = 8 + std::mem::size_of::<OperatorStats>(),
+        seeds = [OPERATOR_STATS_SEED, random_initiator.key().as_ref()],
+        bump
+    )]
+    pub operator_stats: Account<'info, OperatorStats>,
+
+    /// Stats of the round being settled, reported per bet type; records the winning number.
+    #[account(
+        mut,
+        seeds = [ROUND_STATS_SEED, game_session.key().as_ref(), &game_session.current_round.to_le_bytes()],
+        bump = round_stats.bump
+    )]
+    pub round_stats: Account<'info, RoundStats>,
+
+    #[account(
+        init_if_needed,
+        payer = random_initiator,
+        space = 8 + std::mem::size_of::<WheelHistogram>(),
+        seeds = [WHEEL_HISTOGRAM_SEED, game_session.key().as_ref()],
+        bump
+    )]
+    pub wheel_histogram: Account<'info, WheelHistogram>,
+
+    #[account(
+        init_if_needed,
+        payer = random_initiator,
+        space = RoundHistory::SPACE,
+        seeds = [ROUND_HISTORY_SEED, game_session.key().as_ref()],
+        bump
+    )]
+    pub round_history: Account<'info, RoundHistory>,
+
+    /// The table's event queue. Required once the table has one.
+    #[account(mut, seeds = [EVENT_QUEUE_SEED, game_session.key().as_ref()], bump = event_queue.bump)]
+    pub event_queue: Option<Account<'info, EventQueue>>,
+
+    pub system_program: Program<'info, System>,
+}
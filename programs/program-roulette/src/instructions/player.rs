@@ -1,9 +1,31 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{program::set_return_data, program_option::COption};
+use anchor_spl::associated_token::{self, AssociatedToken};
 use anchor_spl::token_interface::{self, TokenAccount, TokenInterface, TransferChecked, Mint};
+use crate::instructions::event_queue::push_event;
+use crate::instructions::lamports::debit_program_account;
+use crate::instructions::stats::{create_pda_account, grow_account};
 use crate::{
     constants::*,
     errors::RouletteError,
     events::*,
+    seeds::{
+        CARRYOVER_SEED,
+        CONFIG_SEED,
+        EVENT_QUEUE_SEED,
+        GAME_SESSION_SEED,
+        PAYOUT_DEBT_SEED,
+        PENDING_CLAIM_SEED,
+        PLAYER_BETS_SEED,
+        PLAYER_STATS_SEED,
+        PROTOCOL_STATS_SEED,
+        RENT_POOL_SEED,
+        ROUND_HISTORY_SEED,
+        ROUND_STATS_SEED,
+        TABLE_ACCESS_LIST_SEED,
+        VAULT_EXPOSURE_SEED,
+        VAULT_SEED,
+    },
     state::*,
 };
 
@@ -11,15 +33,75 @@ use crate::{
 // Player Initialization
 // =================================================================================================
 
-pub fn initialize_player_bets(ctx: Context<InitializePlayerBets>) -> Result<()> {
-    let player_bets = &mut ctx.accounts.player_bets;
-    player_bets.player = ctx.accounts.player.key();
+/// Creates the player's `PlayerBets` account. With `sponsored`, the `RentPool` pays its rent,
+/// so a wallet holding only SPL tokens can start playing. Sponsorship is limited to wallets
+/// that cannot pay the rent themselves, to one account per table and to
+/// `MAX_SPONSORED_PER_WINDOW` accounts per window, and closing a sponsored account returns the
+/// rent to the pool rather than the player.
+pub fn initialize_player_bets(ctx: Context<InitializePlayerBets>, sponsored: bool) -> Result<()> {
+    if sponsored {
+        let rent_pool = ctx.accounts.rent_pool.as_mut().ok_or(RouletteError::RentPoolRequired)?;
+        rent_pool.record_sponsorship(Clock::get()?.slot)?;
+    }
+    let accounts = &ctx.accounts;
+    let player_key = accounts.player.key();
+    let game_session_key = accounts.game_session.key();
+    let bump = ctx.bumps.player_bets;
+    let player_bets_info = accounts.player_bets.to_account_info();
+
+    let rent_pool = if sponsored {
+        let rent_pool = accounts.rent_pool.as_ref().ok_or(RouletteError::RentPoolRequired)?;
+        require!(
+            accounts.player.lamports() < Rent::get()?.minimum_balance(PlayerBets::INITIAL_SPACE),
+            RouletteError::SponsorshipNotNeeded
+        );
+        Some(rent_pool)
+    } else {
+        None
+    };
+    create_pda_account(
+        &player_bets_info,
+        PlayerBets::INITIAL_SPACE,
+        rent_pool,
+        &accounts.player.to_account_info(),
+        &accounts.system_program,
+        &[PLAYER_BETS_SEED, game_session_key.as_ref(), player_key.as_ref(), &[bump]]
+    )?;
+
+    let player_bets = PlayerBets {
+        player: player_key,
+        game_session: game_session_key,
+        round: 0,
+        vault: Pubkey::default(),
+        bets: Vec::new(),
+        claimed_round: 0,
+        bump,
+        rent_sponsored: sponsored,
+        imprisoned_count: 0,
+    };
+    player_bets.try_serialize(&mut &mut player_bets_info.try_borrow_mut_data()?[..])?;
+    PlayerBets::stamp_layout_version(&player_bets_info)?;
+
+    let stats = &mut ctx.accounts.protocol_stats;
+    stats.total_players = stats.total_players.saturating_add(1);
+    Ok(())
+}
+
+pub(crate) fn init_player_bets(
+    player_bets: &mut Account<PlayerBets>,
+    player: Pubkey,
+    game_session: Pubkey,
+    bump: u8
+) -> Result<()> {
+    player_bets.player = player;
+    player_bets.game_session = game_session;
     player_bets.round = 0; // Initial round is 0
     player_bets.vault = Pubkey::default(); // Will be set on first bet
-    player_bets.token_mint = Pubkey::default(); // Will be set on first bet
     player_bets.bets = Vec::with_capacity(MAX_BETS_PER_ROUND);
-    player_bets.bump = ctx.bumps.player_bets;
-    Ok(())
+    player_bets.bump = bump;
+    player_bets.rent_sponsored = false;
+    player_bets.imprisoned_count = 0;
+    PlayerBets::stamp_layout_version(&player_bets.to_account_info())
 }
 
 #[derive(Accounts)]
@@ -27,17 +109,23 @@ pub struct InitializePlayerBets<'info> {
     #[account(mut)]
     pub player: Signer<'info>,
 
-    #[account(seeds = [b"game_session"], bump = game_session.bump)]
+    #[account(seeds = [GAME_SESSION_SEED, game_session.table_seed().as_slice()], bump = game_session.bump)]
     pub game_session: Account<'info, GameSession>,
 
+    /// CHECK: Created and initialized by the handler, which lets the rent pool fund it.
     #[account(
-        init,
-        payer = player,
-        space = 8 + 32 + 8 + 32 + 32 + (4 + std::mem::size_of::<Bet>() * MAX_BETS_PER_ROUND) + 1,
-        seeds = [b"player_bets", game_session.key().as_ref(), player.key().as_ref()],
+        mut,
+        seeds = [PLAYER_BETS_SEED, game_session.key().as_ref(), player.key().as_ref()],
         bump
     )]
-    pub player_bets: Account<'info, PlayerBets>,
+    pub player_bets: UncheckedAccount<'info>,
+
+    #[account(mut, seeds = [PROTOCOL_STATS_SEED], bump = protocol_stats.bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+
+    /// Funds the rent of sponsored accounts; only required with `sponsored`.
+    #[account(mut, seeds = [RENT_POOL_SEED], bump = rent_pool.bump)]
+    pub rent_pool: Option<Account<'info, RentPool>>,
 
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
@@ -47,11 +135,16 @@ pub struct InitializePlayerBets<'info> {
 // Player Close Account
 // =================================================================================================
 
+/// Closes the player's `PlayerBets` account. The rent goes back to the player, or to the
+/// `RentPool` if it sponsored the account.
 pub fn close_player_bets_account(ctx: Context<ClosePlayerBetsAccount>) -> Result<()> {
-    let _player_key = ctx.accounts.player.key();
-    let _player_bets_key = ctx.accounts.player_bets.key();
-
-    Ok(())
+    let accounts = &ctx.accounts;
+    let destination = if accounts.player_bets.rent_sponsored {
+        accounts.rent_pool.as_ref().ok_or(RouletteError::RentPoolRequired)?.to_account_info()
+    } else {
+        accounts.player.to_account_info()
+    };
+    accounts.player_bets.close(destination)
 }
 
 #[derive(Accounts)]
@@ -61,14 +154,81 @@ pub struct ClosePlayerBetsAccount<'info> {
 
     #[account(
         mut, // Account data will be wiped, and lamports transferred.
-        seeds = [b"player_bets", game_session.key().as_ref(), player.key().as_ref()],
+        seeds = [PLAYER_BETS_SEED, game_session.key().as_ref(), player.key().as_ref()],
         bump = player_bets.bump, // Make sure we are closing the correct PDA
-        close = player // Return lamports to the player signer.
     )]
     pub player_bets: Account<'info, PlayerBets>,
 
-    #[account(seeds = [b"game_session"], bump = game_session.bump)]
+    #[account(seeds = [GAME_SESSION_SEED, game_session.table_seed().as_slice()], bump = game_session.bump)]
+    pub game_session: Account<'info, GameSession>,
+
+    /// Receives the rent back when the account was sponsored.
+    #[account(mut, seeds = [RENT_POOL_SEED], bump = rent_pool.bump)]
+    pub rent_pool: Option<Account<'info, RentPool>>,
+}
+
+// =================================================================================================
+// Player Bets Migration
+// =================================================================================================
+
+/// Rewrites a `PlayerBets` account of the original program (`LegacyPlayerBets`, layout
+/// version 0) in the current, smaller layout and shrinks it, refunding the freed rent to the
+/// player. The layout is told apart by the version byte at the end of the account rather than
+/// by its size. Original accounts cannot be used for betting or claims until then.
+pub fn migrate_player_bets(ctx: Context<MigratePlayerBets>) -> Result<()> {
+    let info = ctx.accounts.player_bets.to_account_info();
+    {
+        let data = info.try_borrow_data()?;
+        require!(
+            data.starts_with(PlayerBets::DISCRIMINATOR),
+            RouletteError::InvalidPlayerBetsAccount
+        );
+    }
+    match PlayerBets::layout_version(&info)? {
+        0 => {}
+        PLAYER_BETS_LAYOUT_VERSION => return err!(RouletteError::PlayerBetsAlreadyMigrated),
+        _ => return err!(RouletteError::InvalidPlayerBetsAccount),
+    }
+
+    let legacy = {
+        let data = info.try_borrow_data()?;
+        LegacyPlayerBets::deserialize(&mut &data[PlayerBets::DISCRIMINATOR.len()..])?
+    };
+    require_keys_eq!(legacy.player, ctx.accounts.player.key(), RouletteError::Unauthorized);
+    let player_bets = PlayerBets::from_legacy(legacy, ctx.accounts.game_session.key());
+
+    info.realloc(PlayerBets::INITIAL_SPACE, false)?;
+    {
+        let mut data = info.try_borrow_mut_data()?;
+        data.fill(0);
+        player_bets.try_serialize(&mut &mut data[..])?;
+    }
+    PlayerBets::stamp_layout_version(&info)?;
+
+    let freed = info.lamports().saturating_sub(Rent::get()?.minimum_balance(PlayerBets::INITIAL_SPACE));
+    if freed > 0 {
+        debit_program_account(&info, &ctx.accounts.player.to_account_info(), freed)?;
+    }
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigratePlayerBets<'info> {
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    #[account(seeds = [GAME_SESSION_SEED, game_session.table_seed().as_slice()], bump = game_session.bump)]
     pub game_session: Account<'info, GameSession>,
+
+    /// CHECK: Does not deserialize under the current layout; owner, discriminator and player are
+    /// checked by the handler, and the address by the seeds.
+    #[account(
+        mut,
+        owner = crate::ID,
+        seeds = [PLAYER_BETS_SEED, game_session.key().as_ref(), player.key().as_ref()],
+        bump
+    )]
+    pub player_bets: UncheckedAccount<'info>,
 }
 
 // =================================================================================================
@@ -76,36 +236,133 @@ pub struct ClosePlayerBetsAccount<'info> {
 // =================================================================================================
 
 pub fn place_bet(ctx: Context<PlaceBets>, bet: Bet) -> Result<()> {
-    let game_session = &mut ctx.accounts.game_session;
-    let player_bets = &mut ctx.accounts.player_bets;
-    let player = &ctx.accounts.player;
-    let vault_key = ctx.accounts.vault.key();
-    let vault = &mut ctx.accounts.vault;
+    let accounts = ctx.accounts;
+    process_bet(
+        BetAccounts {
+            game_session: &mut accounts.game_session,
+            player_bets: &mut accounts.player_bets,
+            vault: &mut accounts.vault,
+            vault_exposure: &mut accounts.vault_exposure,
+            vault_exposure_bump: ctx.bumps.vault_exposure,
+            player: &accounts.player,
+            player_token_account: accounts.player_token_account.to_account_info(),
+            vault_token_account: accounts.vault_token_account.to_account_info(),
+            token_mint: &accounts.token_mint,
+            token_program: &accounts.token_program,
+            protocol_stats: &mut accounts.protocol_stats,
+            round_stats: &mut accounts.round_stats,
+            previous_round_stats: accounts.previous_round_stats.as_deref(),
+            player_stats: &mut accounts.player_stats,
+            player_stats_bump: ctx.bumps.player_stats,
+            config: &accounts.config,
+            table_access_list: accounts.table_access_list.as_deref(),
+            event_queue: accounts.event_queue.as_mut(),
+            system_program: &accounts.system_program,
+        },
+        bet
+    )
+}
 
-    require!(
-        game_session.round_status == RoundStatus::AcceptingBets,
-        RouletteError::BetsNotAccepted
-    );
-    require!(bet.bet_type <= BET_TYPE_MAX, RouletteError::InvalidBet);
+/// The accounts touched when a bet is staked, shared by `place_bet` and `join_and_bet`.
+struct BetAccounts<'a, 'info> {
+    game_session: &'a mut Account<'info, GameSession>,
+    player_bets: &'a mut Account<'info, PlayerBets>,
+    vault: &'a mut Account<'info, VaultAccount>,
+    vault_exposure: &'a mut Account<'info, VaultExposure>,
+    vault_exposure_bump: u8,
+    player: &'a Signer<'info>,
+    player_token_account: AccountInfo<'info>,
+    vault_token_account: AccountInfo<'info>,
+    token_mint: &'a InterfaceAccount<'info, Mint>,
+    token_program: &'a Interface<'info, TokenInterface>,
+    protocol_stats: &'a mut Account<'info, ProtocolStats>,
+    round_stats: &'a mut Account<'info, RoundStats>,
+    previous_round_stats: Option<&'a RoundStats>,
+    player_stats: &'a mut Account<'info, PlayerStats>,
+    player_stats_bump: u8,
+    config: &'a Config,
+    table_access_list: Option<&'a TableAccessList>,
+    event_queue: Option<&'a mut Account<'info, EventQueue>>,
+    system_program: &'a Program<'info, System>,
+}
 
-    // Check that the bet amount does not exceed 3% of the vault's total liquidity.
-    let max_bet_amount = (vault.total_liquidity as u128)
-        .checked_mul(MAX_BET_PERCENTAGE as u128)
-        .ok_or(RouletteError::ArithmeticOverflow)?
-        .checked_div(MAX_BET_PERCENTAGE_DIVISOR as u128)
-        .ok_or(RouletteError::ArithmeticOverflow)? as u64;
+fn process_bet(accounts: BetAccounts<'_, '_>, bet: Bet) -> Result<()> {
+    let BetAccounts {
+        game_session,
+        player_bets,
+        vault,
+        vault_exposure,
+        vault_exposure_bump,
+        player,
+        player_token_account,
+        vault_token_account,
+        token_mint,
+        token_program,
+        protocol_stats,
+        round_stats,
+        previous_round_stats,
+        player_stats,
+        player_stats_bump,
+        config,
+        table_access_list,
+        event_queue,
+        system_program,
+    } = accounts;
+    let vault_key = vault.key();
+    let fee_schedule = round_stats.fee_schedule;
+    vault_exposure.bind(vault, game_session.key(), vault_exposure_bump);
 
-    require!(
-        bet.amount <= max_bet_amount,
-        RouletteError::BetAmountExceedsLimit
-    );
+    // Same evaluation as `check_bet`, so the preflight and the real placement never disagree.
+    let check = evaluate_bet(
+        game_session,
+        player_bets,
+        previous_round_stats,
+        vault,
+        &bet,
+        &fee_schedule,
+        BetRules { config, access_list: table_access_list, exposure: Some(vault_exposure) }
+    )?;
+    if let Some(reason) = check.rejection {
+        return Err(reason.error().into());
+    }
+
+    let bet_amount = bet.amount;
+    validate_bet_source(&player_token_account, token_program.key, player.key, &token_mint.key(), bet_amount)?;
 
     // Handle first bet in round / round switch
-    if player_bets.round != game_session.current_round {
-        player_bets.bets.clear(); // Clear previous round's bets
+    if player_bets.carries_imprisoned_into(previous_round_stats, game_session.current_round) {
+        // Even-money bets a zero sent to prison play on in this round, from the same vault.
+        let round = player_bets.round;
+        let imprisoned = player_bets.imprison_bets(previous_round_stats.unwrap());
+        carry_imprisoned_bets(game_session, player_bets, vault, Some(vault_exposure), round, imprisoned)?;
+    } else if player_bets.round != game_session.current_round {
+        // Bets of a voided or cancelled round are refunded before they make way.
+        if let Some(round_stats) = previous_round_stats.filter(|stats| {
+            stats.is_refundable() && player_bets.claimed_round < player_bets.round && !player_bets.bets.is_empty()
+        }) {
+            require_keys_eq!(vault_key, player_bets.vault, RouletteError::VaultMismatch);
+            let round = player_bets.round;
+            let amount = refund_stake(RefundAccounts {
+                player_bets,
+                round_stats,
+                vault,
+                vault_token_account: vault_token_account.clone(),
+                player_token_account: player_token_account.clone(),
+                token_mint,
+                token_program,
+            })?;
+            emit!(BetsRefunded {
+                event_seq: game_session.next_event_seq(),
+                round,
+                player: *player.key,
+                token_mint: vault.token_mint,
+                amount,
+                timestamp: Clock::get()?.unix_timestamp,
+            });
+        }
+        player_bets.clear_bets(); // Clear previous round's bets
         player_bets.round = game_session.current_round;
         player_bets.vault = vault_key; // Set vault for this round
-        player_bets.token_mint = vault.token_mint; // Set mint for this round
         if player_bets.player == Pubkey::default() {
             // Ensure player is set (first ever call)
             player_bets.player = *player.key;
@@ -115,36 +372,100 @@ pub fn place_bet(ctx: Context<PlaceBets>, bet: Bet) -> Result<()> {
         require_keys_eq!(vault_key, player_bets.vault, RouletteError::VaultMismatch);
     }
 
-    // Check bet vector capacity
-    if player_bets.bets.len() >= MAX_BETS_PER_ROUND {
-        return err!(RouletteError::InvalidNumberOfBets); // Or MaxBetsInAccountReached
-    }
-
     // Transfer bet amount
-    let bet_amount = bet.amount;
-    require!(bet_amount > 0, RouletteError::InvalidBet); // Bet amount cannot be zero
     token_interface::transfer_checked(
-        CpiContext::new(ctx.accounts.token_program.to_account_info(), TransferChecked {
-            from: ctx.accounts.player_token_account.to_account_info(),
-            mint: ctx.accounts.token_mint.to_account_info(),
-            to: ctx.accounts.vault_token_account.to_account_info(),
+        CpiContext::new(token_program.to_account_info(), TransferChecked {
+            from: player_token_account,
+            mint: token_mint.to_account_info(),
+            to: vault_token_account,
             authority: player.to_account_info(),
         }),
         bet_amount,
-        ctx.accounts.token_mint.decimals,
+        token_mint.decimals,
     )?;
 
+    // Accounts have room for `DEFAULT_MAX_BETS_PER_ROUND` bets and grow one bet at a time past
+    // it, at the player's expense, when the table allows more.
+    let player_bets_info = player_bets.to_account_info();
+    let needed_space = PlayerBets::space(player_bets.bets.len() + 1);
+    if player_bets_info.data_len() < needed_space {
+        grow_account(
+            &player_bets_info,
+            needed_space,
+            RentPayer::Cranker,
+            None,
+            &player.to_account_info(),
+            system_program
+        )?;
+        PlayerBets::stamp_layout_version(&player_bets_info)?;
+    }
+
+    let coverage = stake_bet(
+        StakeAccounts {
+            game_session,
+            player_bets,
+            vault,
+            vault_exposure,
+            player: &player.to_account_info(),
+            protocol_stats,
+            round_stats,
+            player_stats,
+            player_stats_bump,
+            mint_decimals: token_mint.decimals,
+            event_queue,
+        },
+        bet
+    )?;
+
+    // Lets UIs warn about redundant chips without the chain rejecting them.
+    set_return_data(&coverage.try_to_vec()?);
+    Ok(())
+}
+
+/// The accounts a staked bet is booked into, shared by bets paid from the player's tokens and
+/// by winnings let ride into the next round.
+struct StakeAccounts<'a, 'info> {
+    game_session: &'a mut Account<'info, GameSession>,
+    player_bets: &'a mut Account<'info, PlayerBets>,
+    vault: &'a mut Account<'info, VaultAccount>,
+    vault_exposure: &'a mut VaultExposure,
+    player: &'a AccountInfo<'info>,
+    protocol_stats: &'a mut Account<'info, ProtocolStats>,
+    round_stats: &'a mut Account<'info, RoundStats>,
+    player_stats: &'a mut Account<'info, PlayerStats>,
+    player_stats_bump: u8,
+    mint_decimals: u8,
+    event_queue: Option<&'a mut Account<'info, EventQueue>>,
+}
+
+/// Books `bet`, whose stake the vault already holds, into the current round: liquidity, fees,
+/// exposure, round and player stats, and the player's bets.
+fn stake_bet(accounts: StakeAccounts<'_, '_>, bet: Bet) -> Result<BetCoverage> {
+    let StakeAccounts {
+        game_session,
+        player_bets,
+        vault,
+        vault_exposure,
+        player,
+        protocol_stats,
+        round_stats,
+        player_stats,
+        player_stats_bump,
+        mint_decimals,
+        event_queue,
+    } = accounts;
+    let fee_schedule = round_stats.fee_schedule;
+    let bet_amount = bet.amount;
+
     // Update vault liquidity
     vault.total_liquidity = vault.total_liquidity
         .checked_add(bet_amount)
         .ok_or(RouletteError::ArithmeticOverflow)?;
 
     // Distribute rewards
-    let provider_revenue = bet_amount / PROVIDER_DIVISOR;
-    let owner_revenue = bet_amount / OWNER_DIVISOR;
-    vault.owner_reward = vault.owner_reward
-        .checked_add(owner_revenue)
-        .ok_or(RouletteError::ArithmeticOverflow)?;
+    let provider_revenue = fee_schedule.provider_fee(bet_amount);
+    let owner_revenue = fee_schedule.owner_fee(bet_amount);
+    vault.credit_owner_reward(owner_revenue)?;
 
     // Update reward index
     if vault.total_provider_capital > 0 {
@@ -159,31 +480,201 @@ pub fn place_bet(ctx: Context<PlaceBets>, bet: Bet) -> Result<()> {
             .ok_or(RouletteError::ArithmeticOverflow)?;
     }
 
+    protocol_stats.record_volume(vault.token_mint, bet_amount)?;
+
+    let coverage_mask = PlayerBets::coverage_mask(bet.bet_type, &bet.numbers);
+    let covered_before = player_bets.bets
+        .iter()
+        .fold(0u64, |mask, b| mask | PlayerBets::coverage_mask(b.bet_type, &b.numbers));
+    vault_exposure.record_bet(
+        game_session.current_round,
+        coverage_mask,
+        bet_amount,
+        fee_schedule.payout_multiplier(bet.bet_type)
+    )?;
+    let coverage = BetCoverage {
+        coverage_mask,
+        overlap_mask: coverage_mask & covered_before,
+        fully_overlapped: coverage_mask != 0 && coverage_mask & !covered_before == 0,
+    };
+
+    round_stats.record_bet(*player.key, bet_amount)?;
+    round_stats.record_bet_type(bet.bet_type, coverage_mask, bet_amount)?;
+    game_session.round_bet_count = round_stats.bet_count;
+    game_session.round_unique_bettors = round_stats.unique_bettors;
+
+    // Vaults created before `mint_decimals` was recorded pick it up on their next bet.
+    vault.mint_decimals = mint_decimals;
+
+    if player_stats.player == Pubkey::default() {
+        player_stats.player = *player.key;
+        player_stats.token_mint = vault.token_mint;
+        player_stats.bump = player_stats_bump;
+    }
+    player_stats.record_bet(bet_amount, provider_revenue, owner_revenue);
+
     // Add bet to player's account
     player_bets.bets.push(bet.clone());
 
-    // Record the last bettor
+    // Record the last bettor and fold the bet into the round's entropy
     game_session.last_bettor = Some(*player.key);
+    let slot = Clock::get()?.slot;
+    game_session.absorb_bet_entropy(player.key, &bet, slot)?;
+    push_event(game_session, event_queue, QueuedEvent {
+        kind: QueuedEventKind::Bet,
+        round: game_session.current_round,
+        player: *player.key,
+        amount: bet_amount,
+        detail: bet.bet_type,
+        slot,
+        ..Default::default()
+    })?;
 
+    let bet_type = bet.bet_type;
     emit!(BetPlaced {
+        event_seq: game_session.next_event_seq(),
         player: *player.key,
         token_mint: vault.token_mint,
         round: game_session.current_round,
         bet,
+        provider_fee: provider_revenue,
+        owner_fee: owner_revenue,
         timestamp: Clock::get()?.unix_timestamp,
     });
+    if vault.is_large_bet(bet_amount) {
+        emit!(LargeBetPlaced {
+            event_seq: game_session.next_event_seq(),
+            player: *player.key,
+            token_mint: vault.token_mint,
+            round: game_session.current_round,
+            bet_type,
+            amount: bet_amount,
+            threshold: vault.large_bet_threshold,
+        });
+    }
+
+    Ok(coverage)
+}
+
+/// Checks that `player` may spend `amount` from the token account funding a bet, either as its
+/// owner or as a delegate approved for at least `amount`. Smart wallets (e.g. Squads) often sign
+/// with a PDA that differs from the wallet owning the tokens; approving that signer as delegate
+/// lets them bet without first moving funds into an account the signer owns.
+fn validate_bet_source(
+    token_account: &AccountInfo,
+    token_program: &Pubkey,
+    player: &Pubkey,
+    mint: &Pubkey,
+    amount: u64
+) -> Result<()> {
+    require_keys_eq!(*token_account.owner, *token_program, RouletteError::InvalidTokenAccount);
+    let source = TokenAccount::try_deserialize(&mut &token_account.data.borrow()[..])?;
+    require_keys_eq!(source.mint, *mint, RouletteError::InvalidTokenAccount);
+
+    let is_owner = source.owner == *player;
+    let is_delegate = source.delegate == COption::Some(*player) && source.delegated_amount >= amount;
+    require!(is_owner || is_delegate, RouletteError::BetSourceNotAuthorized);
     Ok(())
 }
 
+/// Protocol and table rules a bet is checked against, besides its round's fee schedule.
+#[derive(Clone, Copy)]
+struct BetRules<'a> {
+    config: &'a Config,
+    /// The table's access list, if one was passed.
+    access_list: Option<&'a TableAccessList>,
+    /// The vault's exposure to the table, once it has taken a bet there.
+    exposure: Option<&'a VaultExposure>,
+}
+
+/// Evaluates a prospective bet against everything `place_bet` enforces before moving funds.
+fn evaluate_bet(
+    game_session: &GameSession,
+    player_bets: &PlayerBets,
+    previous_round_stats: Option<&RoundStats>,
+    vault: &Account<VaultAccount>,
+    bet: &Bet,
+    fee_schedule: &FeeSchedule,
+    rules: BetRules
+) -> Result<BetCheck> {
+    let BetRules { config, access_list, exposure } = rules;
+    let max_bet_amount = vault.max_bet_amount()?;
+    let same_round = player_bets.round == game_session.current_round;
+
+    let rejection = if config.paused {
+        Some(BetRejectionReason::ProgramPaused)
+    } else if game_session.frozen {
+        Some(BetRejectionReason::TableFrozen)
+    } else if game_session.round_status != RoundStatus::AcceptingBets {
+        Some(BetRejectionReason::BetsNotAccepted)
+    } else if !game_session.admits(&player_bets.player, access_list) {
+        Some(BetRejectionReason::NotOnAccessList)
+    } else if !same_round && !player_bets.previous_bets_settled(previous_round_stats, game_session, &Clock::get()?)? {
+        Some(BetRejectionReason::UnclaimedPreviousRound)
+    } else if !vault.allows(VaultOperation::PlaceBet, game_session.current_round) {
+        Some(BetRejectionReason::VaultOptedOut)
+    } else if !game_session.accepts_vault(&vault.key()) {
+        Some(BetRejectionReason::VaultNotBound)
+    } else if
+        !PlayerBets::is_valid_bet(bet.bet_type, &bet.numbers) ||
+        !game_session.wheel_type.covers(PlayerBets::coverage_mask(bet.bet_type, &bet.numbers))
+    {
+        // Bets touching 00 are only valid on an American wheel.
+        Some(BetRejectionReason::InvalidGeometry)
+    } else if !config.allows_bet_type(bet.bet_type, &player_bets.player) {
+        Some(BetRejectionReason::BetTypeInBeta)
+    } else if bet.amount == 0 {
+        Some(BetRejectionReason::ZeroAmount)
+    } else if !vault.is_chip_multiple(bet.amount) {
+        Some(BetRejectionReason::InvalidChipAmount)
+    } else if bet.amount > max_bet_amount {
+        Some(BetRejectionReason::ExceedsMaxBet)
+    } else if
+        (same_round || player_bets.carries_imprisoned_into(previous_round_stats, game_session.current_round)) &&
+        player_bets.vault != vault.key()
+    {
+        // A player bets from a single vault per round, including any imprisoned bets.
+        Some(BetRejectionReason::VaultMismatch)
+    } else if same_round && player_bets.bets.len() >= config.bet_limit() {
+        Some(BetRejectionReason::TooManyBets)
+    } else if vault.max_player_payout > 0 && player_round_max_payout(player_bets, bet, same_round, fee_schedule)? > vault.max_player_payout {
+        Some(BetRejectionReason::ExceedsPlayerPayoutCap)
+    } else if vault.table_maximum > 0 && VaultExposure::outcome_exposure_with(
+        exposure,
+        game_session.current_round,
+        PlayerBets::coverage_mask(bet.bet_type, &bet.numbers),
+        bet.amount,
+        fee_schedule.payout_multiplier(bet.bet_type)
+    )? > vault.table_maximum {
+        Some(BetRejectionReason::ExceedsTableMaximum)
+    } else {
+        None
+    };
+
+    Ok(BetCheck { max_bet_amount, rejection })
+}
+
+/// The most the player's bets this round, including `bet`, could pay out together.
+fn player_round_max_payout(
+    player_bets: &PlayerBets,
+    bet: &Bet,
+    same_round: bool,
+    fee_schedule: &FeeSchedule
+) -> Result<u64> {
+    let earlier_bets = if same_round { player_bets.bets.as_slice() } else { &[] };
+    PlayerBets::max_payout(earlier_bets.iter().chain(std::iter::once(bet)), fee_schedule)
+}
+
 #[derive(Accounts)]
 pub struct PlaceBets<'info> {
     #[account(mut)]
     pub vault: Account<'info, VaultAccount>,
 
-    #[account(mut, seeds = [b"game_session"], bump = game_session.bump)]
+    #[account(mut, seeds = [GAME_SESSION_SEED, game_session.table_seed().as_slice()], bump = game_session.bump)]
     pub game_session: Account<'info, GameSession>,
 
-    /// CHECK: Validated in instruction logic (is TokenAccount).
+    /// CHECK: Validated in instruction logic: a token account of the vault's mint that the
+    /// player owns or is an approved delegate of.
     #[account(mut)]
     pub player_token_account: AccountInfo<'info>,
 
@@ -199,7 +690,7 @@ pub struct PlaceBets<'info> {
 
     #[account(
         mut,
-        seeds = [b"player_bets", game_session.key().as_ref(), player.key().as_ref()],
+        seeds = [PLAYER_BETS_SEED, game_session.key().as_ref(), player.key().as_ref()],
         bump = player_bets.bump // Verify bump of existing account
     )]
     pub player_bets: Account<'info, PlayerBets>,
@@ -208,155 +699,1435 @@ pub struct PlaceBets<'info> {
     #[account(address = vault.token_mint @ RouletteError::InvalidTokenAccount)]
     pub token_mint: InterfaceAccount<'info, Mint>,
 
+    #[account(mut, seeds = [PROTOCOL_STATS_SEED], bump = protocol_stats.bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+
+    #[account(
+        mut,
+        seeds = [ROUND_STATS_SEED, game_session.key().as_ref(), &game_session.current_round.to_le_bytes()],
+        bump = round_stats.bump
+    )]
+    pub round_stats: Account<'info, RoundStats>,
+
+    /// Stats of the round the player's recorded bets belong to, needed when betting in a new
+    /// round while those bets were neither claimed nor refunded: their winning number proves
+    /// that nothing is lost by replacing them, and a voided or cancelled round has them
+    /// refunded first.
+    #[account(
+        seeds = [ROUND_STATS_SEED, game_session.key().as_ref(), &player_bets.round.to_le_bytes()],
+        bump = previous_round_stats.bump
+    )]
+    pub previous_round_stats: Option<Account<'info, RoundStats>>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    /// The table's access list; required to bet once the table is private.
+    #[account(seeds = [TABLE_ACCESS_LIST_SEED, game_session.key().as_ref()], bump = table_access_list.bump)]
+    pub table_access_list: Option<Account<'info, TableAccessList>>,
+
+    /// The table's event queue. Required once the table has one.
+    #[account(mut, seeds = [EVENT_QUEUE_SEED, game_session.key().as_ref()], bump = event_queue.bump)]
+    pub event_queue: Option<Account<'info, EventQueue>>,
+
+    #[account(
+        init_if_needed,
+        payer = player,
+        space = 8 + std::mem::size_of::<PlayerStats>(),
+        seeds = [PLAYER_STATS_SEED, player.key().as_ref(), token_mint.key().as_ref()],
+        bump
+    )]
+    pub player_stats: Account<'info, PlayerStats>,
+
+    /// The vault's exposure to the table, created by the first bet the vault takes on it.
+    #[account(
+        init_if_needed,
+        payer = player,
+        space = VaultExposure::SPACE,
+        seeds = [VAULT_EXPOSURE_SEED, vault.key().as_ref(), game_session.key().as_ref()],
+        bump
+    )]
+    pub vault_exposure: Account<'info, VaultExposure>,
+
     pub token_program: Interface<'info, TokenInterface>,
+
+    pub system_program: Program<'info, System>,
 }
 
 // =================================================================================================
-// Player Claim Winnings
+// Player Join And Bet
 // =================================================================================================
 
-pub fn claim_my_winnings(ctx: Context<ClaimMyWinnings>, round_to_claim: u64) -> Result<()> {
-    let game_session = &ctx.accounts.game_session;
-    let player_bets_account = &mut ctx.accounts.player_bets;
-    let vault = &mut ctx.accounts.vault;
-    let player_token_account_info = &ctx.accounts.player_token_account;
-    let vault_token_account_info = &ctx.accounts.vault_token_account;
-    let player_key = ctx.accounts.player.key();
+/// Onboards a new wallet in a single transaction: creates the `PlayerBets` account and the
+/// player's associated token account when they are missing, then places the first bet.
+pub fn join_and_bet(ctx: Context<JoinAndBet>, bet: Bet) -> Result<()> {
+    let accounts = ctx.accounts;
 
-    let round_claimed = round_to_claim;
+    // `init_if_needed` leaves existing accounts untouched, so only fill in a fresh one.
+    if accounts.player_bets.player == Pubkey::default() {
+        init_player_bets(
+            &mut accounts.player_bets,
+            accounts.player.key(),
+            accounts.game_session.key(),
+            ctx.bumps.player_bets
+        )?;
+        accounts.protocol_stats.total_players = accounts.protocol_stats.total_players.saturating_add(1);
+    }
 
-    require!(
-        round_claimed <= game_session.last_completed_round,
-        RouletteError::ClaimRoundMismatchOrNotCompleted
-    );
+    process_bet(
+        BetAccounts {
+            game_session: &mut accounts.game_session,
+            player_bets: &mut accounts.player_bets,
+            vault: &mut accounts.vault,
+            vault_exposure: &mut accounts.vault_exposure,
+            vault_exposure_bump: ctx.bumps.vault_exposure,
+            player: &accounts.player,
+            player_token_account: accounts.player_token_account.to_account_info(),
+            vault_token_account: accounts.vault_token_account.to_account_info(),
+            token_mint: &accounts.token_mint,
+            token_program: &accounts.token_program,
+            protocol_stats: &mut accounts.protocol_stats,
+            round_stats: &mut accounts.round_stats,
+            previous_round_stats: accounts.previous_round_stats.as_deref(),
+            player_stats: &mut accounts.player_stats,
+            player_stats_bump: ctx.bumps.player_stats,
+            config: &accounts.config,
+            table_access_list: accounts.table_access_list.as_deref(),
+            event_queue: accounts.event_queue.as_mut(),
+            system_program: &accounts.system_program,
+        },
+        bet
+    )
+}
 
-    require!(
-        round_claimed == game_session.last_completed_round && game_session.winning_number.is_some(),
-        RouletteError::ClaimRoundMismatchOrNotCompleted
-    );
+#[derive(Accounts)]
+pub struct JoinAndBet<'info> {
+    #[account(mut)]
+    pub player: Signer<'info>,
 
-    require!(
-        player_bets_account.round == round_claimed,
-        RouletteError::BetsRoundMismatch
-    );
+    #[account(mut, seeds = [GAME_SESSION_SEED, game_session.table_seed().as_slice()], bump = game_session.bump)]
+    pub game_session: Account<'info, GameSession>,
 
-    let winning_number = game_session.winning_number.unwrap();
+    #[account(
+        init_if_needed,
+        payer = player,
+        space = PlayerBets::INITIAL_SPACE,
+        seeds = [PLAYER_BETS_SEED, game_session.key().as_ref(), player.key().as_ref()],
+        bump
+    )]
+    pub player_bets: Account<'info, PlayerBets>,
 
-    //New check: 
-    require!(
-        player_bets_account.claimed_round < round_to_claim,
-        RouletteError::Unauthorized
-    );
+    #[account(mut)]
+    pub vault: Account<'info, VaultAccount>,
 
-    let player_token_account: TokenAccount = TokenAccount::try_deserialize(
-        &mut &player_token_account_info.data.borrow()[..]
-    )?;
-    let vault_token_account: TokenAccount = TokenAccount::try_deserialize(
-        &mut &vault_token_account_info.data.borrow()[..]
-    )?;
-    require_keys_eq!(
-        vault_token_account_info.key(),
-        vault.token_account,
-        RouletteError::InvalidTokenAccount
-    );
-    require_eq!(vault_token_account.mint, vault.token_mint, RouletteError::InvalidTokenAccount);
-    require_eq!(player_token_account.mint, vault.token_mint, RouletteError::InvalidTokenAccount);
-    require_keys_eq!(
-        player_token_account.owner,
-        player_key,
-        RouletteError::InvalidTokenAccount
-    );
+    /// The mint of the token. Needed for transfer_checked and decimals.
+    #[account(address = vault.token_mint @ RouletteError::InvalidTokenAccount)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
 
-    let mut total_payout: u64 = 0;
-    for bet in player_bets_account.bets.iter() {
-        if PlayerBets::is_bet_winner(bet.bet_type, &bet.numbers, winning_number) {
-            let payout_multiplier = PlayerBets::calculate_payout_multiplier(bet.bet_type);
-            let payout_for_bet = bet.amount
-                .checked_mul(payout_multiplier)
-                .ok_or(RouletteError::ArithmeticOverflow)?;
-            total_payout = total_payout
-                .checked_add(payout_for_bet)
-                .ok_or(RouletteError::ArithmeticOverflow)?;
-        }
-    }
+    #[account(mut, seeds = [PROTOCOL_STATS_SEED], bump = protocol_stats.bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
 
-    let actual_payout = total_payout.min(vault.total_liquidity);
+    #[account(
+        mut,
+        seeds = [ROUND_STATS_SEED, game_session.key().as_ref(), &game_session.current_round.to_le_bytes()],
+        bump = round_stats.bump
+    )]
+    pub round_stats: Account<'info, RoundStats>,
 
-    if total_payout == 0 {
-         player_bets_account.claimed_round = round_to_claim;
-         return err!(RouletteError::NoWinningsFound);
-    }
+    /// Stats of the round the player's recorded bets belong to, needed when betting in a new
+    /// round while those bets were neither claimed nor refunded: their winning number proves
+    /// that nothing is lost by replacing them, and a voided or cancelled round has them
+    /// refunded first.
+    #[account(
+        seeds = [ROUND_STATS_SEED, game_session.key().as_ref(), &player_bets.round.to_le_bytes()],
+        bump = previous_round_stats.bump
+    )]
+    pub previous_round_stats: Option<Account<'info, RoundStats>>,
 
-    require!(actual_payout > 0, RouletteError::InsufficientLiquidity);
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
 
-    let seeds = &[b"vault".as_ref(), vault.token_mint.as_ref(), &[vault.bump]];
-    let signer_seeds = &[&seeds[..]];
-    token_interface::transfer_checked(
-        CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            TransferChecked {
-                from: vault_token_account_info.to_account_info(),
-                mint: ctx.accounts.token_mint.to_account_info(),
-                to: player_token_account_info.to_account_info(),
-                authority: vault.to_account_info(),
-            },
-            signer_seeds
-        ),
-        actual_payout,
-        ctx.accounts.token_mint.decimals,
-    )?;
+    /// The table's access list; required to bet once the table is private.
+    #[account(seeds = [TABLE_ACCESS_LIST_SEED, game_session.key().as_ref()], bump = table_access_list.bump)]
+    pub table_access_list: Option<Account<'info, TableAccessList>>,
 
-    vault.total_liquidity = vault.total_liquidity
-        .checked_sub(actual_payout)
-        .ok_or(RouletteError::ArithmeticOverflow)?;
+    /// The table's event queue. Required once the table has one.
+    #[account(mut, seeds = [EVENT_QUEUE_SEED, game_session.key().as_ref()], bump = event_queue.bump)]
+    pub event_queue: Option<Account<'info, EventQueue>>,
 
-    if total_payout > actual_payout && vault.total_liquidity == 0 {
-        // Consider if this specific alert should be an event if it's critical for off-chain monitoring
-    }
+    #[account(
+        init_if_needed,
+        payer = player,
+        space = 8 + std::mem::size_of::<PlayerStats>(),
+        seeds = [PLAYER_STATS_SEED, player.key().as_ref(), token_mint.key().as_ref()],
+        bump
+    )]
+    pub player_stats: Account<'info, PlayerStats>,
 
-    player_bets_account.claimed_round = round_to_claim;
+    /// The vault's exposure to the table, created by the first bet the vault takes on it.
+    #[account(
+        init_if_needed,
+        payer = player,
+        space = VaultExposure::SPACE,
+        seeds = [VAULT_EXPOSURE_SEED, vault.key().as_ref(), game_session.key().as_ref()],
+        bump
+    )]
+    pub vault_exposure: Account<'info, VaultExposure>,
 
-    emit!(WinningsClaimed {
-        round: round_claimed,
-        player: player_key,
-        token_mint: vault.token_mint,
-        amount: actual_payout,
-        timestamp: Clock::get()?.unix_timestamp,
-    });
+    /// The player's associated token account, created on the fly if it does not exist yet.
+    #[account(
+        init_if_needed,
+        payer = player,
+        associated_token::mint = token_mint,
+        associated_token::authority = player,
+        associated_token::token_program = token_program
+    )]
+    pub player_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Validated by the constraint `vault_token_account.key() == vault.token_account`.
+    #[account(
+        mut,
+        constraint = vault_token_account.key() == vault.token_account @ RouletteError::InvalidTokenAccount,
+    )]
+    pub vault_token_account: AccountInfo<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+// =================================================================================================
+// Player Check Bet (Non-Reverting)
+// =================================================================================================
+
+/// Non-reverting preflight for a prospective bet: validates round status, bet geometry and limits
+/// exactly as `place_bet` would, without moving funds. The result (allowed maximum and the
+/// rejection reason, if any) is returned via return data so UIs can disable illegal chips before
+/// the user signs. Bets refused on limit grounds also emit `BetRejected`, giving operators
+/// telemetry on the demand the current limits turn away.
+pub fn check_bet(ctx: Context<CheckBet>, bet: Bet) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+    let vault = &ctx.accounts.vault;
+
+    let check = evaluate_bet(
+        game_session,
+        &ctx.accounts.player_bets,
+        ctx.accounts.previous_round_stats.as_deref(),
+        vault,
+        &bet,
+        &ctx.accounts.round_stats.fee_schedule,
+        BetRules {
+            config: &ctx.accounts.config,
+            access_list: ctx.accounts.table_access_list.as_deref(),
+            exposure: ctx.accounts.vault_exposure.as_deref(),
+        }
+    )?;
+
+    if let Some(reason) = check.rejection.filter(BetRejectionReason::is_limit) {
+        emit!(BetRejected {
+            event_seq: game_session.next_event_seq(),
+            player: ctx.accounts.player.key(),
+            token_mint: vault.token_mint,
+            round: game_session.current_round,
+            bet_type: bet.bet_type,
+            requested_amount: bet.amount,
+            max_allowed: check.max_bet_amount,
+            reason,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+    }
+
+    set_return_data(&check.try_to_vec()?);
 
     Ok(())
 }
 
 #[derive(Accounts)]
-pub struct ClaimMyWinnings<'info> {
-    #[account(mut)]
+pub struct CheckBet<'info> {
     pub player: Signer<'info>,
 
-    #[account(seeds = [b"game_session"], bump = game_session.bump)]
+    #[account(mut, seeds = [GAME_SESSION_SEED, game_session.table_seed().as_slice()], bump = game_session.bump)]
     pub game_session: Account<'info, GameSession>,
 
     #[account(
-        mut,
-        seeds = [b"player_bets", game_session.key().as_ref(), player.key().as_ref()],
-        bump = player_bets.bump,
-        constraint = player_bets.player == player.key() @ RouletteError::Unauthorized,
+        seeds = [PLAYER_BETS_SEED, game_session.key().as_ref(), player.key().as_ref()],
+        bump = player_bets.bump
     )]
     pub player_bets: Account<'info, PlayerBets>,
 
-    #[account(mut, seeds = [b"vault", player_bets.token_mint.as_ref()], bump = vault.bump)]
+    /// Stats of the current round, whose fee schedule the bet is checked against.
+    #[account(
+        seeds = [ROUND_STATS_SEED, game_session.key().as_ref(), &game_session.current_round.to_le_bytes()],
+        bump = round_stats.bump
+    )]
+    pub round_stats: Account<'info, RoundStats>,
+
+    /// Stats of the round the player's recorded bets belong to, needed when betting in a new
+    /// round while those bets were neither claimed nor refunded: their winning number proves
+    /// that nothing is lost by replacing them, and a voided or cancelled round has them
+    /// refunded first.
+    #[account(
+        seeds = [ROUND_STATS_SEED, game_session.key().as_ref(), &player_bets.round.to_le_bytes()],
+        bump = previous_round_stats.bump
+    )]
+    pub previous_round_stats: Option<Account<'info, RoundStats>>,
+
     pub vault: Account<'info, VaultAccount>,
 
-    /// CHECK: Validated manually + via constraint below.
-    #[account(mut, constraint = vault_token_account.key() == vault.token_account)]
-    pub vault_token_account: AccountInfo<'info>,
+    /// The vault's exposure to the table; absent until the vault has taken a bet on it.
+    #[account(
+        seeds = [VAULT_EXPOSURE_SEED, vault.key().as_ref(), game_session.key().as_ref()],
+        bump = vault_exposure.bump
+    )]
+    pub vault_exposure: Option<Account<'info, VaultExposure>>,
 
-    /// CHECK: Validated manually (mint, owner).
-    #[account(mut)]
-    pub player_token_account: AccountInfo<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
 
-    /// The mint of the token. Needed for transfer_checked and decimals.
-    #[account(address = vault.token_mint @ RouletteError::InvalidTokenAccount)]
-    pub token_mint: InterfaceAccount<'info, Mint>,
+    /// The table's access list; required to bet once the table is private.
+    #[account(seeds = [TABLE_ACCESS_LIST_SEED, game_session.key().as_ref()], bump = table_access_list.bump)]
+    pub table_access_list: Option<Account<'info, TableAccessList>>,
+}
 
-    pub token_program: Interface<'info, TokenInterface>,
+// =================================================================================================
+// Player Position (Read-Only via Simulation)
+// =================================================================================================
+
+/// Returns the player's `PlayerPosition` via return data: the stake of their recorded bets,
+/// the payout still claimable once the round is settled, and the claim deadline.
+pub fn get_player_position(ctx: Context<GetPlayerPosition>) -> Result<()> {
+    let game_session = &ctx.accounts.game_session;
+    let player_bets = &ctx.accounts.player_bets;
+    let round_stats = &ctx.accounts.round_stats;
+
+    let settled = round_stats.winning_number.is_some();
+    let pending_payout = match round_stats.winning_number {
+        Some(winning_number) if player_bets.claimed_round < player_bets.round => {
+            player_bets.total_payout(winning_number, &round_stats.fee_schedule)?
+        }
+        _ => 0,
+    };
+
+    let position = PlayerPosition {
+        round: player_bets.round,
+        bets_total: player_bets.total_staked()?,
+        settled,
+        pending_payout,
+        claim_deadline: if settled { game_session.claim_deadline(round_stats) } else { None },
+    };
+    set_return_data(&position.try_to_vec()?);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct GetPlayerPosition<'info> {
+    #[account(seeds = [GAME_SESSION_SEED, game_session.table_seed().as_slice()], bump = game_session.bump)]
+    pub game_session: Account<'info, GameSession>,
+
+    #[account(
+        seeds = [PLAYER_BETS_SEED, game_session.key().as_ref(), player.key().as_ref()],
+        bump = player_bets.bump
+    )]
+    pub player_bets: Account<'info, PlayerBets>,
+
+    /// Stats of the round the player's bets belong to.
+    #[account(
+        seeds = [ROUND_STATS_SEED, game_session.key().as_ref(), &player_bets.round.to_le_bytes()],
+        bump = round_stats.bump
+    )]
+    pub round_stats: Account<'info, RoundStats>,
+
+    /// CHECK: The player's wallet. No signature is required as this is a read-only function.
+    /// It's used solely for deriving the `player_bets` PDA and no data is read from it.
+    pub player: UncheckedAccount<'info>,
+}
+
+// =================================================================================================
+// Player Let It Ride
+// =================================================================================================
+
+/// Marks bet `bet_index` of the current round to let its winnings ride: if it wins, claiming
+/// the round re-stakes its payout on the same layout in the next round, provided that round is
+/// taking bets and the re-staked bet is within its limits. `riding = false` takes the mark
+/// back. Bets carried into the round by a ride keep riding until they lose or are taken back.
+pub fn set_bet_riding(ctx: Context<SetBetRiding>, bet_index: u8, riding: bool) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+    let player_bets = &ctx.accounts.player_bets;
+    let carryover = &mut ctx.accounts.carryover;
+    let round = game_session.current_round;
+
+    require!(game_session.round_status == RoundStatus::AcceptingBets, RouletteError::BetsNotAccepted);
+    require!(
+        player_bets.round == round &&
+            (bet_index as usize) < player_bets.bets.len() &&
+            bet_index >= player_bets.imprisoned_count &&
+            u32::from(bet_index) < u8::BITS,
+        RouletteError::InvalidRidingBet
+    );
+
+    // `init_if_needed` leaves an existing record untouched, so only fill in a fresh one.
+    if carryover.player == Pubkey::default() {
+        carryover.player = player_bets.player;
+        carryover.game_session = game_session.key();
+        carryover.bump = ctx.bumps.carryover;
+    }
+    if carryover.round != round {
+        // Nothing was carried into this round, so any earlier streak is over.
+        carryover.end_streak();
+        carryover.round = round;
+    }
+    if riding {
+        carryover.riding_mask |= 1 << bet_index;
+    } else {
+        carryover.riding_mask &= !(1 << bet_index);
+    }
+
+    emit!(BetRidingChanged {
+        event_seq: game_session.next_event_seq(),
+        player: player_bets.player,
+        round,
+        bet_index,
+        riding,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetBetRiding<'info> {
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    #[account(mut, seeds = [GAME_SESSION_SEED, game_session.table_seed().as_slice()], bump = game_session.bump)]
+    pub game_session: Account<'info, GameSession>,
+
+    #[account(
+        seeds = [PLAYER_BETS_SEED, game_session.key().as_ref(), player.key().as_ref()],
+        bump = player_bets.bump
+    )]
+    pub player_bets: Account<'info, PlayerBets>,
+
+    #[account(
+        init_if_needed,
+        payer = player,
+        space = Carryover::SPACE,
+        seeds = [CARRYOVER_SEED, game_session.key().as_ref(), player.key().as_ref()],
+        bump
+    )]
+    pub carryover: Account<'info, Carryover>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// The accounts riding winnings are re-staked through by `claim_my_winnings`.
+struct RideAccounts<'a, 'info> {
+    game_session: &'a mut Account<'info, GameSession>,
+    player_bets: &'a mut Account<'info, PlayerBets>,
+    vault: &'a mut Account<'info, VaultAccount>,
+    vault_exposure: Option<&'a mut Account<'info, VaultExposure>>,
+    player: &'a Signer<'info>,
+    carryover: &'a mut Account<'info, Carryover>,
+    next_round_stats: Option<&'a mut Account<'info, RoundStats>>,
+    protocol_stats: Option<&'a mut Account<'info, ProtocolStats>>,
+    player_stats: Option<&'a mut Account<'info, PlayerStats>>,
+    config: Option<&'a Config>,
+    table_access_list: Option<&'a TableAccessList>,
+    mint_decimals: u8,
+    event_queue: Option<&'a mut Account<'info, EventQueue>>,
+    system_program: &'a Program<'info, System>,
+}
+
+/// Re-stakes `rides`, the winnings of the riding bets of `round`, on the same layouts in the
+/// round right after it, and returns the amount re-staked. Rides that round cannot take,
+/// because it is no longer taking bets or the bet breaks a limit, are left to be paid out,
+/// which ends the streak.
+fn let_winnings_ride(accounts: RideAccounts<'_, '_>, rides: Vec<Bet>, round: u64) -> Result<u64> {
+    let RideAccounts {
+        game_session,
+        player_bets,
+        vault,
+        vault_exposure,
+        player,
+        carryover,
+        next_round_stats,
+        protocol_stats,
+        player_stats,
+        config,
+        table_access_list,
+        mint_decimals,
+        mut event_queue,
+        system_program,
+    } = accounts;
+
+    if
+        game_session.current_round != round.saturating_add(1) ||
+        game_session.round_status != RoundStatus::AcceptingBets
+    {
+        carryover.end_streak();
+        return Ok(0);
+    }
+    let (Some(round_stats), Some(protocol_stats), Some(player_stats), Some(config), Some(vault_exposure)) = (
+        next_round_stats,
+        protocol_stats,
+        player_stats,
+        config,
+        vault_exposure,
+    ) else {
+        return err!(RouletteError::RideAccountsRequired);
+    };
+
+    let mut riding_mask = 0u8;
+    let mut ridden_bets = 0u8;
+    let mut ridden = 0u64;
+    for bet in rides {
+        let check = evaluate_bet(
+            game_session,
+            player_bets,
+            None,
+            vault,
+            &bet,
+            &round_stats.fee_schedule,
+            BetRules { config, access_list: table_access_list, exposure: Some(vault_exposure) }
+        )?;
+        if check.rejection.is_some() || bet.amount > vault.total_liquidity {
+            continue;
+        }
+        // Riding marks cover the first eight bets only.
+        let index = if player_bets.round == round { 0 } else { player_bets.bets.len() };
+        if index >= u8::BITS as usize {
+            continue;
+        }
+        let player_bets_info = player_bets.to_account_info();
+        let needed_space = PlayerBets::space(index + 1);
+        if player_bets_info.data_len() < needed_space {
+            grow_account(
+                &player_bets_info,
+                needed_space,
+                RentPayer::Cranker,
+                None,
+                &player.to_account_info(),
+                system_program
+            )?;
+            PlayerBets::stamp_layout_version(&player_bets_info)?;
+        }
+        // The claimed bets make way for the next round's, behind any a zero sent to prison.
+        if player_bets.round == round {
+            player_bets.clear_bets();
+            player_bets.round = game_session.current_round;
+        }
+        // The winnings leave the vault's liquidity as a payout would and come back as a stake.
+        vault.debit_liquidity(bet.amount)?;
+        riding_mask |= 1 << player_bets.bets.len();
+        ridden_bets += 1;
+        ridden = ridden.checked_add(bet.amount).ok_or(RouletteError::ArithmeticOverflow)?;
+        let player_stats_bump = player_stats.bump;
+        stake_bet(
+            StakeAccounts {
+                game_session,
+                player_bets,
+                vault,
+                vault_exposure,
+                player: &player.to_account_info(),
+                protocol_stats,
+                round_stats,
+                player_stats,
+                player_stats_bump,
+                mint_decimals,
+                event_queue: event_queue.as_deref_mut(),
+            },
+            bet
+        )?;
+    }
+
+    if ridden == 0 {
+        carryover.end_streak();
+        return Ok(0);
+    }
+    carryover.round = game_session.current_round;
+    carryover.riding_mask = riding_mask;
+    carryover.streak = carryover.streak.saturating_add(1);
+    carryover.streak_staked = carryover.streak_staked
+        .checked_add(ridden)
+        .ok_or(RouletteError::ArithmeticOverflow)?;
+
+    emit!(WinningsRidden {
+        event_seq: game_session.next_event_seq(),
+        player: player.key(),
+        token_mint: vault.token_mint,
+        round,
+        ridden_bets,
+        amount: ridden,
+        streak: carryover.streak,
+        streak_staked: carryover.streak_staked,
+    });
+    Ok(ridden)
+}
+
+// =================================================================================================
+// Player Claim Winnings
+// =================================================================================================
+
+pub fn claim_my_winnings(ctx: Context<ClaimMyWinnings>, round_to_claim: u64) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+    let player_bets_account = &mut ctx.accounts.player_bets;
+    let vault = &mut ctx.accounts.vault;
+    let player_key = ctx.accounts.player.key();
+
+    // Claims bypass pause checks by design; no pause state may block them.
+    // Payouts come from liquidity held by the vault itself; anyone may `recall_from_strategy`
+    // while bets are closed.
+    require!(vault.strategy_principal == 0, RouletteError::StrategyFundsNotRecalled);
+
+    let round_claimed = round_to_claim;
+    let round_stats = &ctx.accounts.round_stats;
+    let winning_number = claimable_winning_number(player_bets_account, round_stats, round_claimed)?;
+
+    // Retries of a claim that already went through succeed as no-ops, so wallets and bots
+    // retrying on timeouts don't produce failed transactions.
+    if player_bets_account.claimed_round >= round_to_claim {
+        if let Some(debt) = ctx.accounts.payout_debt.as_ref() {
+            // Created by this retry's account validation; hand its rent straight back.
+            debt.close(ctx.accounts.player.to_account_info())?;
+        }
+        emit!(AlreadyClaimed {
+            event_seq: game_session.next_event_seq(),
+            round: round_claimed,
+            player: player_key,
+            token_mint: vault.token_mint,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        return Ok(());
+    }
+
+    require!(
+        !game_session.claim_window_expired(&Clock::get()?, round_stats),
+        RouletteError::ClaimWindowExpired
+    );
+
+    let total_payout = player_bets_account.total_payout(winning_number, &round_stats.fee_schedule)?;
+    require!(
+        round_stats.finalized || !round_stats.requires_finality(total_payout),
+        RouletteError::RoundNotFinalized
+    );
+
+    // Let it ride: winnings of riding bets are re-staked in the next round instead.
+    let rides = match ctx.accounts.carryover.as_ref() {
+        Some(carryover) => carryover.rides(player_bets_account, winning_number, &round_stats.fee_schedule)?,
+        None => Vec::new(),
+    };
+
+    // En Prison: even-money bets lost to a zero move on to the next round instead.
+    let imprisoned = if player_bets_account.bets_to_imprison(round_stats) > 0 {
+        player_bets_account.claimed_round = round_to_claim;
+        let imprisoned = player_bets_account.imprison_bets(round_stats);
+        carry_imprisoned_bets(
+            game_session,
+            player_bets_account,
+            vault,
+            ctx.accounts.vault_exposure.as_deref_mut(),
+            round_claimed,
+            imprisoned
+        )?;
+        imprisoned
+    } else {
+        0
+    };
+
+    let ridden = match ctx.accounts.carryover.as_mut() {
+        Some(carryover) if !rides.is_empty() => {
+            player_bets_account.claimed_round = round_to_claim;
+            let ridden = let_winnings_ride(
+                RideAccounts {
+                    game_session,
+                    player_bets: player_bets_account,
+                    vault,
+                    vault_exposure: ctx.accounts.vault_exposure.as_mut(),
+                    player: &ctx.accounts.player,
+                    carryover,
+                    next_round_stats: ctx.accounts.next_round_stats.as_mut(),
+                    protocol_stats: ctx.accounts.protocol_stats.as_mut(),
+                    player_stats: ctx.accounts.player_stats.as_mut(),
+                    config: ctx.accounts.config.as_deref(),
+                    table_access_list: ctx.accounts.table_access_list.as_deref(),
+                    mint_decimals: ctx.accounts.token_mint.decimals,
+                    event_queue: ctx.accounts.event_queue.as_mut(),
+                    system_program: &ctx.accounts.system_program,
+                },
+                rides,
+                round_to_claim
+            )?;
+            ctx.accounts.round_history.record_payout(round_to_claim, ridden);
+            ridden
+        }
+        Some(carryover) => {
+            // None of the round's riding bets won.
+            if carryover.round == round_to_claim {
+                carryover.end_streak();
+            }
+            0
+        }
+        None => 0,
+    };
+    let total_payout = total_payout - ridden;
+
+    if total_payout == 0 {
+         player_bets_account.claimed_round = round_to_claim;
+         if imprisoned > 0 || ridden > 0 {
+             if let Some(debt) = ctx.accounts.payout_debt.as_ref() {
+                 debt.close(ctx.accounts.player.to_account_info())?;
+             }
+             return Ok(());
+         }
+         return err!(RouletteError::NoWinningsFound);
+    }
+
+    player_bets_account.claimed_round = round_to_claim;
+
+    prepare_payout_account(
+        &ctx.accounts.player,
+        &ctx.accounts.player_token_account,
+        &ctx.accounts.token_mint,
+        &ctx.accounts.token_program,
+        ctx.accounts.associated_token_program.as_ref(),
+        &ctx.accounts.system_program
+    )?;
+    pay_out_claim(
+        PayoutAccounts {
+            game_session,
+            round_history: &mut ctx.accounts.round_history,
+            vault,
+            vault_token_account: ctx.accounts.vault_token_account.to_account_info(),
+            player_token_account: ctx.accounts.player_token_account.to_account_info(),
+            token_mint: &ctx.accounts.token_mint,
+            token_program: &ctx.accounts.token_program,
+            payout_debt: ctx.accounts.payout_debt.as_mut(),
+            payout_debt_bump: ctx.bumps.payout_debt.unwrap_or_default(),
+            event_queue: ctx.accounts.event_queue.as_mut(),
+        },
+        player_key,
+        round_claimed,
+        total_payout
+    )
+}
+
+/// Checks the account a claim pays into: a token account of the vault's mint owned by the player.
+/// If it does not exist, e.g. because the player closed the account they bet from, it must be
+/// the player's associated token account, which is created here at the player's expense.
+fn prepare_payout_account<'info>(
+    player: &Signer<'info>,
+    player_token_account: &AccountInfo<'info>,
+    token_mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+    associated_token_program: Option<&Program<'info, AssociatedToken>>,
+    system_program: &Program<'info, System>
+) -> Result<()> {
+    if player_token_account.data_is_empty() {
+        let associated_token_program = associated_token_program
+            .ok_or(RouletteError::AssociatedTokenProgramRequired)?;
+        // The associated token program rejects any other address.
+        associated_token::create_idempotent(CpiContext::new(
+            associated_token_program.to_account_info(),
+            associated_token::Create {
+                payer: player.to_account_info(),
+                associated_token: player_token_account.clone(),
+                authority: player.to_account_info(),
+                mint: token_mint.to_account_info(),
+                system_program: system_program.to_account_info(),
+                token_program: token_program.to_account_info(),
+            }
+        ))?;
+    }
+
+    require_keys_eq!(*player_token_account.owner, token_program.key(), RouletteError::InvalidTokenAccount);
+    let destination = TokenAccount::try_deserialize(&mut &player_token_account.data.borrow()[..])?;
+    require_keys_eq!(destination.mint, token_mint.key(), RouletteError::InvalidTokenAccount);
+    require_keys_eq!(destination.owner, player.key(), RouletteError::InvalidTokenAccount);
+    Ok(())
+}
+
+/// Books bets of `round` that `imprison_bets` carried into the next round into the vault's
+/// exposure to the table, while that round is still the current one, and emits
+/// `BetsImprisoned`.
+fn carry_imprisoned_bets(
+    game_session: &mut GameSession,
+    player_bets: &PlayerBets,
+    vault: &VaultAccount,
+    vault_exposure: Option<&mut VaultExposure>,
+    round: u64,
+    imprisoned: u8
+) -> Result<()> {
+    if player_bets.round == game_session.current_round {
+        let vault_exposure = vault_exposure.ok_or(RouletteError::VaultExposureRequired)?;
+        for bet in &player_bets.bets {
+            let coverage_mask = PlayerBets::coverage_mask(bet.bet_type, &bet.numbers);
+            vault_exposure.record_imprisoned_bet(player_bets.round, coverage_mask, bet.amount)?;
+        }
+    }
+    emit!(BetsImprisoned {
+        event_seq: game_session.next_event_seq(),
+        player: player_bets.player,
+        token_mint: vault.token_mint,
+        round,
+        imprisoned_bets: imprisoned,
+        stake: player_bets.bets.iter().map(|bet| bet.amount).sum(),
+    });
+    Ok(())
+}
+
+/// Checks that `round` is a settled round the player's bets belong to and returns its winning
+/// number, as recorded in the round's `RoundStats`. Any settled round can be claimed, not only
+/// the most recent one.
+fn claimable_winning_number(player_bets: &PlayerBets, round_stats: &RoundStats, round: u64) -> Result<u8> {
+    require!(player_bets.round == round, RouletteError::BetsRoundMismatch);
+    round_stats.winning_number.ok_or(RouletteError::ClaimRoundMismatchOrNotCompleted.into())
+}
+
+/// The accounts a claim pays out through, shared by `claim_my_winnings` and `execute_claim`.
+struct PayoutAccounts<'a, 'info> {
+    game_session: &'a mut Account<'info, GameSession>,
+    round_history: &'a mut Account<'info, RoundHistory>,
+    vault: &'a mut Account<'info, VaultAccount>,
+    vault_token_account: AccountInfo<'info>,
+    player_token_account: AccountInfo<'info>,
+    token_mint: &'a InterfaceAccount<'info, Mint>,
+    token_program: &'a Interface<'info, TokenInterface>,
+    payout_debt: Option<&'a mut Account<'info, PayoutDebt>>,
+    payout_debt_bump: u8,
+    event_queue: Option<&'a mut Account<'info, EventQueue>>,
+}
+
+/// Pays `total_payout` to the player from the vault and adds it to the round's payouts in the
+/// table's `RoundHistory`. Whatever the vault cannot cover is owed to the player through the
+/// passed `PayoutDebt` record.
+fn pay_out_claim(
+    accounts: PayoutAccounts<'_, '_>,
+    player_key: Pubkey,
+    round_claimed: u64,
+    total_payout: u64
+) -> Result<()> {
+    let PayoutAccounts {
+        game_session,
+        round_history,
+        vault,
+        vault_token_account,
+        player_token_account,
+        token_mint,
+        token_program,
+        payout_debt,
+        payout_debt_bump,
+        event_queue,
+    } = accounts;
+
+    let actual_payout = total_payout.min(vault.total_liquidity);
+    require!(actual_payout > 0, RouletteError::InsufficientLiquidity);
+    round_history.record_payout(round_claimed, total_payout);
+
+    let seeds = &[VAULT_SEED, vault.token_mint.as_ref(), &[vault.bump]];
+    let signer_seeds = &[&seeds[..]];
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            TransferChecked {
+                from: vault_token_account,
+                mint: token_mint.to_account_info(),
+                to: player_token_account,
+                authority: vault.to_account_info(),
+            },
+            signer_seeds
+        ),
+        actual_payout,
+        token_mint.decimals,
+    )?;
+
+    vault.debit_liquidity(actual_payout)?;
+
+    let shortfall = total_payout - actual_payout;
+    match (payout_debt, shortfall > 0) {
+        (Some(debt), true) => {
+            debt.vault = vault.key();
+            debt.player = player_key;
+            debt.round = round_claimed;
+            debt.index = vault.debt_tail;
+            debt.amount_owed = shortfall;
+            debt.bump = payout_debt_bump;
+
+            vault.debt_tail = vault.debt_tail
+                .checked_add(1)
+                .ok_or(RouletteError::ArithmeticOverflow)?;
+            vault.outstanding_debt = vault.outstanding_debt
+                .checked_add(shortfall)
+                .ok_or(RouletteError::ArithmeticOverflow)?;
+
+            emit!(PayoutDebtRecorded {
+                event_seq: game_session.next_event_seq(),
+                token_mint: vault.token_mint,
+                player: player_key,
+                round: round_claimed,
+                debt_index: debt.index,
+                amount_owed: shortfall,
+                timestamp: Clock::get()?.unix_timestamp,
+            });
+        }
+        (None, true) => return err!(RouletteError::PayoutDebtRecordRequired),
+        (Some(_), false) => return err!(RouletteError::NoPayoutShortfall),
+        (None, false) => {}
+    }
+
+    push_event(game_session, event_queue, QueuedEvent {
+        kind: QueuedEventKind::Claim,
+        round: round_claimed,
+        player: player_key,
+        amount: actual_payout,
+        slot: Clock::get()?.slot,
+        ..Default::default()
+    })?;
+
+    emit!(WinningsClaimed {
+        event_seq: game_session.next_event_seq(),
+        round: round_claimed,
+        player: player_key,
+        token_mint: vault.token_mint,
+        amount: actual_payout,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    if vault.is_large_win(total_payout) {
+        emit!(LargeWinClaimed {
+            event_seq: game_session.next_event_seq(),
+            round: round_claimed,
+            player: player_key,
+            token_mint: vault.token_mint,
+            amount: total_payout,
+            threshold: vault.large_win_threshold,
+        });
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(round_to_claim: u64)]
+pub struct ClaimMyWinnings<'info> {
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    #[account(mut, seeds = [GAME_SESSION_SEED, game_session.table_seed().as_slice()], bump = game_session.bump)]
+    pub game_session: Account<'info, GameSession>,
+
+    /// The player's bets, which must belong to `round_to_claim`.
+    #[account(
+        mut,
+        seeds = [PLAYER_BETS_SEED, game_session.key().as_ref(), player.key().as_ref()],
+        bump = player_bets.bump,
+        constraint = player_bets.player == player.key() @ RouletteError::Unauthorized,
+        constraint = player_bets.game_session == game_session.key() @ RouletteError::TableMismatch,
+        constraint = player_bets.round == round_to_claim @ RouletteError::BetsRoundMismatch,
+    )]
+    pub player_bets: Account<'info, PlayerBets>,
+
+    /// Stats of the claimed round, which hold its winning number.
+    #[account(
+        seeds = [ROUND_STATS_SEED, game_session.key().as_ref(), &round_to_claim.to_le_bytes()],
+        bump = round_stats.bump
+    )]
+    pub round_stats: Account<'info, RoundStats>,
+
+    /// Results history of the table, which adds up the round's claimed payouts.
+    #[account(mut, seeds = [ROUND_HISTORY_SEED, game_session.key().as_ref()], bump = round_history.bump)]
+    pub round_history: Account<'info, RoundHistory>,
+
+    #[account(
+        mut,
+        address = player_bets.vault @ RouletteError::VaultMismatch,
+        seeds = [VAULT_SEED, vault.token_mint.as_ref()],
+        bump = vault.bump
+    )]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(
+        mut,
+        constraint = vault_token_account.key() == vault.token_account @ RouletteError::InvalidTokenAccount
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Validated in instruction logic: a token account of the vault's mint owned by the
+    /// player. When it no longer exists, it must be the player's associated token account,
+    /// which the claim creates.
+    #[account(mut)]
+    pub player_token_account: AccountInfo<'info>,
+
+    /// The mint of the token. Needed for transfer_checked and decimals.
+    #[account(address = vault.token_mint @ RouletteError::InvalidTokenAccount)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    /// Next payout debt record of the vault. Required only when the vault cannot cover the
+    /// claim in full.
+    #[account(
+        init,
+        payer = player,
+        space = 8 + std::mem::size_of::<PayoutDebt>(),
+        seeds = [PAYOUT_DEBT_SEED, vault.key().as_ref(), &vault.debt_tail.to_le_bytes()],
+        bump
+    )]
+    pub payout_debt: Option<Account<'info, PayoutDebt>>,
+
+    /// The table's event queue. Required once the table has one.
+    #[account(mut, seeds = [EVENT_QUEUE_SEED, game_session.key().as_ref()], bump = event_queue.bump)]
+    pub event_queue: Option<Account<'info, EventQueue>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// Required only to create a missing `player_token_account`.
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
+
+    /// The player's let-it-ride record. Without it, riding bets are paid out like any other.
+    #[account(
+        mut,
+        seeds = [CARRYOVER_SEED, game_session.key().as_ref(), player.key().as_ref()],
+        bump = carryover.bump
+    )]
+    pub carryover: Option<Account<'info, Carryover>>,
+
+    /// Stats of the round after the claimed one, which riding winnings are staked into.
+    /// Required, like `protocol_stats`, `player_stats` and `config`, only for a ride.
+    #[account(
+        mut,
+        seeds = [ROUND_STATS_SEED, game_session.key().as_ref(), &round_to_claim.wrapping_add(1).to_le_bytes()],
+        bump = next_round_stats.bump
+    )]
+    pub next_round_stats: Option<Account<'info, RoundStats>>,
+
+    /// The vault's exposure to the table, which the riding winnings and any bets a zero sends to
+    /// prison are booked into while the next round is in play. Required for a ride, and to carry
+    /// such bets into the round in play.
+    #[account(
+        mut,
+        seeds = [VAULT_EXPOSURE_SEED, vault.key().as_ref(), game_session.key().as_ref()],
+        bump = vault_exposure.bump
+    )]
+    pub vault_exposure: Option<Account<'info, VaultExposure>>,
+
+    #[account(mut, seeds = [PROTOCOL_STATS_SEED], bump = protocol_stats.bump)]
+    pub protocol_stats: Option<Account<'info, ProtocolStats>>,
+
+    #[account(
+        mut,
+        seeds = [PLAYER_STATS_SEED, player.key().as_ref(), token_mint.key().as_ref()],
+        bump = player_stats.bump
+    )]
+    pub player_stats: Option<Account<'info, PlayerStats>>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Option<Account<'info, Config>>,
+
+    /// The table's access list; required to let winnings ride on a private table.
+    #[account(seeds = [TABLE_ACCESS_LIST_SEED, game_session.key().as_ref()], bump = table_access_list.bump)]
+    pub table_access_list: Option<Account<'info, TableAccessList>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// =================================================================================================
+// Player Refund
+// =================================================================================================
+
+/// Returns the player's full stake for a voided round. Fees already credited to the vault's
+/// providers and owner on those bets stay earned; the vault bears the refund.
+pub fn refund_voided_bets(ctx: Context<RefundVoidedBets>, round: u64) -> Result<()> {
+    require!(
+        round > 0 &&
+            ctx.accounts.player_bets.round == round &&
+            ctx.accounts.round_stats.status == RoundStatus::Voided,
+        RouletteError::RoundNotVoided
+    );
+    refund_round_stake(ctx, round)
+}
+
+/// Returns the player's full stake for a cancelled round. The owner fee taken on those bets is
+/// reversed, since a cancelled round was never played; the provider fee already spread over the
+/// reward index stays earned.
+pub fn refund_bet(ctx: Context<RefundVoidedBets>, round: u64) -> Result<()> {
+    require!(
+        round > 0 &&
+            ctx.accounts.player_bets.round == round &&
+            ctx.accounts.round_stats.status == RoundStatus::Cancelled,
+        RouletteError::RoundNotCancelled
+    );
+    refund_round_stake(ctx, round)
+}
+
+fn refund_round_stake(ctx: Context<RefundVoidedBets>, round: u64) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+    let vault = &mut ctx.accounts.vault;
+    let player_key = ctx.accounts.player.key();
+
+    if ctx.accounts.player_bets.claimed_round >= round {
+        emit!(AlreadyClaimed {
+            event_seq: game_session.next_event_seq(),
+            round,
+            player: player_key,
+            token_mint: vault.token_mint,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        return Ok(());
+    }
+
+    let stake = refund_stake(RefundAccounts {
+        player_bets: &mut ctx.accounts.player_bets,
+        round_stats: &ctx.accounts.round_stats,
+        vault,
+        vault_token_account: ctx.accounts.vault_token_account.to_account_info(),
+        player_token_account: ctx.accounts.player_token_account.to_account_info(),
+        token_mint: &ctx.accounts.token_mint,
+        token_program: &ctx.accounts.token_program,
+    })?;
+
+    emit!(BetsRefunded {
+        event_seq: game_session.next_event_seq(),
+        round,
+        player: player_key,
+        token_mint: vault.token_mint,
+        amount: stake,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// The accounts a voided or cancelled round's stake is returned through.
+struct RefundAccounts<'a, 'info> {
+    player_bets: &'a mut PlayerBets,
+    /// Stats of the round the bets belong to.
+    round_stats: &'a RoundStats,
+    vault: &'a mut Account<'info, VaultAccount>,
+    vault_token_account: AccountInfo<'info>,
+    player_token_account: AccountInfo<'info>,
+    token_mint: &'a InterfaceAccount<'info, Mint>,
+    token_program: &'a Interface<'info, TokenInterface>,
+}
+
+/// Pays the full stake of the player's bets, which belong to a voided or cancelled round, back
+/// from the vault and marks them refunded, returning the stake. A cancelled round was never
+/// played, so the owner fee taken on its bets is reversed as well.
+fn refund_stake(accounts: RefundAccounts<'_, '_>) -> Result<u64> {
+    let RefundAccounts {
+        player_bets,
+        round_stats,
+        vault,
+        vault_token_account,
+        player_token_account,
+        token_mint,
+        token_program,
+    } = accounts;
+
+    require!(vault.strategy_principal == 0, RouletteError::StrategyFundsNotRecalled);
+    let stake = player_bets.total_staked()?;
+    require!(stake > 0, RouletteError::NothingToRefund);
+    player_bets.claimed_round = player_bets.round;
+
+    let seeds = &[VAULT_SEED, vault.token_mint.as_ref(), &[vault.bump]];
+    let signer_seeds = &[&seeds[..]];
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            TransferChecked {
+                from: vault_token_account,
+                mint: token_mint.to_account_info(),
+                to: player_token_account,
+                authority: vault.to_account_info(),
+            },
+            signer_seeds
+        ),
+        stake,
+        token_mint.decimals,
+    )?;
+    vault.debit_liquidity(stake)?;
+    if round_stats.status == RoundStatus::Cancelled {
+        let owner_fee = player_bets.bets
+            .iter()
+            .map(|bet| round_stats.fee_schedule.owner_fee(bet.amount))
+            .sum::<u64>();
+        vault.owner_reward = vault.owner_reward.saturating_sub(owner_fee);
+    }
+    Ok(stake)
+}
+
+#[derive(Accounts)]
+pub struct RefundVoidedBets<'info> {
+    pub player: Signer<'info>,
+
+    #[account(mut, seeds = [GAME_SESSION_SEED, game_session.table_seed().as_slice()], bump = game_session.bump)]
+    pub game_session: Account<'info, GameSession>,
+
+    #[account(
+        mut,
+        seeds = [PLAYER_BETS_SEED, game_session.key().as_ref(), player.key().as_ref()],
+        bump = player_bets.bump,
+        constraint = player_bets.player == player.key() @ RouletteError::Unauthorized,
+        constraint = player_bets.game_session == game_session.key() @ RouletteError::TableMismatch,
+    )]
+    pub player_bets: Account<'info, PlayerBets>,
+
+    /// Stats of the refunded round, which record how it ended and the fee schedule the reversed
+    /// owner fee is taken from.
+    #[account(
+        seeds = [ROUND_STATS_SEED, game_session.key().as_ref(), &player_bets.round.to_le_bytes()],
+        bump = round_stats.bump
+    )]
+    pub round_stats: Account<'info, RoundStats>,
+
+    #[account(
+        mut,
+        address = player_bets.vault @ RouletteError::VaultMismatch,
+        seeds = [VAULT_SEED, vault.token_mint.as_ref()],
+        bump = vault.bump
+    )]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(
+        mut,
+        constraint = vault_token_account.key() == vault.token_account @ RouletteError::InvalidTokenAccount
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = vault.token_mint,
+        token::authority = player,
+    )]
+    pub player_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = vault.token_mint @ RouletteError::InvalidTokenAccount)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+// =================================================================================================
+// Player Two-Step Claim
+// =================================================================================================
+
+/// First half of a two-step claim: runs the claim's verification and payout computation and
+/// records the result in a `PendingClaim`, marking the round as claimed. `execute_claim` then
+/// performs the transfer, possibly in a later transaction with different compute or priority
+/// settings.
+pub fn verify_claim(ctx: Context<VerifyClaim>, round_to_claim: u64) -> Result<()> {
+    let game_session = &mut ctx.accounts.game_session;
+    let player_bets = &mut ctx.accounts.player_bets;
+
+    let round_stats = &ctx.accounts.round_stats;
+    let winning_number = claimable_winning_number(player_bets, round_stats, round_to_claim)?;
+    require!(player_bets.claimed_round < round_to_claim, RouletteError::ClaimAlreadyRecorded);
+    require!(player_bets.bets_to_imprison(round_stats) == 0, RouletteError::EnPrisonClaimRequired);
+    require!(
+        !game_session.claim_window_expired(&Clock::get()?, round_stats),
+        RouletteError::ClaimWindowExpired
+    );
+
+    let total_payout = player_bets.total_payout(winning_number, &round_stats.fee_schedule)?;
+    require!(total_payout > 0, RouletteError::NoWinningsFound);
+    require!(
+        round_stats.finalized || !round_stats.requires_finality(total_payout),
+        RouletteError::RoundNotFinalized
+    );
+    player_bets.claimed_round = round_to_claim;
+
+    let pending_claim = &mut ctx.accounts.pending_claim;
+    pending_claim.player = player_bets.player;
+    pending_claim.game_session = game_session.key();
+    pending_claim.token_mint = ctx.accounts.vault.token_mint;
+    pending_claim.round = round_to_claim;
+    pending_claim.amount = total_payout;
+    pending_claim.bump = ctx.bumps.pending_claim;
+
+    emit!(ClaimVerified {
+        event_seq: game_session.next_event_seq(),
+        round: round_to_claim,
+        player: pending_claim.player,
+        token_mint: pending_claim.token_mint,
+        amount: total_payout,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(round_to_claim: u64)]
+pub struct VerifyClaim<'info> {
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    #[account(mut, seeds = [GAME_SESSION_SEED, game_session.table_seed().as_slice()], bump = game_session.bump)]
+    pub game_session: Account<'info, GameSession>,
+
+    #[account(
+        mut,
+        seeds = [PLAYER_BETS_SEED, game_session.key().as_ref(), player.key().as_ref()],
+        bump = player_bets.bump,
+        constraint = player_bets.player == player.key() @ RouletteError::Unauthorized,
+        constraint = player_bets.game_session == game_session.key() @ RouletteError::TableMismatch,
+    )]
+    pub player_bets: Account<'info, PlayerBets>,
+
+    /// Stats of the claimed round, which hold its winning number.
+    #[account(
+        seeds = [ROUND_STATS_SEED, game_session.key().as_ref(), &round_to_claim.to_le_bytes()],
+        bump = round_stats.bump
+    )]
+    pub round_stats: Account<'info, RoundStats>,
+
+    /// Vault the claimed bets were staked with, which names the token paid out.
+    #[account(
+        address = player_bets.vault @ RouletteError::VaultMismatch,
+        seeds = [VAULT_SEED, vault.token_mint.as_ref()],
+        bump = vault.bump
+    )]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(
+        init,
+        payer = player,
+        space = 8 + std::mem::size_of::<PendingClaim>(),
+        seeds = [PENDING_CLAIM_SEED, game_session.key().as_ref(), player.key().as_ref()],
+        bump
+    )]
+    pub pending_claim: Account<'info, PendingClaim>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Second half of a two-step claim: pays out a `PendingClaim` and closes it. Shortfalls are
+/// recorded as payout debt exactly as in `claim_my_winnings`.
+pub fn execute_claim(ctx: Context<ExecuteClaim>) -> Result<()> {
+    // Claims bypass pause checks by design; no pause state may block them.
+    require!(ctx.accounts.vault.strategy_principal == 0, RouletteError::StrategyFundsNotRecalled);
+
+    let pending_claim = &ctx.accounts.pending_claim;
+    pay_out_claim(
+        PayoutAccounts {
+            game_session: &mut ctx.accounts.game_session,
+            round_history: &mut ctx.accounts.round_history,
+            vault: &mut ctx.accounts.vault,
+            vault_token_account: ctx.accounts.vault_token_account.to_account_info(),
+            player_token_account: ctx.accounts.player_token_account.to_account_info(),
+            token_mint: &ctx.accounts.token_mint,
+            token_program: &ctx.accounts.token_program,
+            payout_debt: ctx.accounts.payout_debt.as_mut(),
+            payout_debt_bump: ctx.bumps.payout_debt.unwrap_or_default(),
+            event_queue: ctx.accounts.event_queue.as_mut(),
+        },
+        pending_claim.player,
+        pending_claim.round,
+        pending_claim.amount
+    )
+}
+
+#[derive(Accounts)]
+pub struct ExecuteClaim<'info> {
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    #[account(mut, seeds = [GAME_SESSION_SEED, game_session.table_seed().as_slice()], bump = game_session.bump)]
+    pub game_session: Account<'info, GameSession>,
+
+    #[account(
+        mut,
+        close = player,
+        seeds = [PENDING_CLAIM_SEED, game_session.key().as_ref(), player.key().as_ref()],
+        bump = pending_claim.bump,
+        has_one = player @ RouletteError::Unauthorized,
+    )]
+    pub pending_claim: Account<'info, PendingClaim>,
+
+    /// Results history of the table, which adds up the round's claimed payouts.
+    #[account(mut, seeds = [ROUND_HISTORY_SEED, game_session.key().as_ref()], bump = round_history.bump)]
+    pub round_history: Account<'info, RoundHistory>,
+
+    #[account(mut, seeds = [VAULT_SEED, pending_claim.token_mint.as_ref()], bump = vault.bump)]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(
+        mut,
+        constraint = vault_token_account.key() == vault.token_account @ RouletteError::InvalidTokenAccount
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = player_token_account.mint == vault.token_mint @ RouletteError::InvalidTokenAccount,
+        constraint = player_token_account.owner == player.key() @ RouletteError::InvalidTokenAccount
+    )]
+    pub player_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = vault.token_mint @ RouletteError::InvalidTokenAccount)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    /// Next payout debt record of the vault. Required only when the vault cannot cover the
+    /// claim in full.
+    #[account(
+        init,
+        payer = player,
+        space = 8 + std::mem::size_of::<PayoutDebt>(),
+        seeds = [PAYOUT_DEBT_SEED, vault.key().as_ref(), &vault.debt_tail.to_le_bytes()],
+        bump
+    )]
+    pub payout_debt: Option<Account<'info, PayoutDebt>>,
+
+    /// The table's event queue. Required once the table has one.
+    #[account(mut, seeds = [EVENT_QUEUE_SEED, game_session.key().as_ref()], bump = event_queue.bump)]
+    pub event_queue: Option<Account<'info, EventQueue>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// =================================================================================================
+// Player Acknowledge Loss
+// =================================================================================================
+
+/// Cheap settlement path for a losing round: marks the round as claimed and frees the bets
+/// without touching any token account.
+pub fn acknowledge_loss(ctx: Context<AcknowledgeLoss>, round: u64) -> Result<()> {
+    let player_bets = &mut ctx.accounts.player_bets;
+
+    let winning_number = claimable_winning_number(player_bets, &ctx.accounts.round_stats, round)?;
+    require!(player_bets.claimed_round < round, RouletteError::Unauthorized);
+
+    require!(
+        !player_bets.bets
+            .iter()
+            .any(|bet| PlayerBets::is_bet_winner(bet.bet_type, &bet.numbers, winning_number)),
+        RouletteError::RoundHasWinnings
+    );
+
+    player_bets.claimed_round = round;
+    player_bets.clear_bets();
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(round: u64)]
+pub struct AcknowledgeLoss<'info> {
+    pub player: Signer<'info>,
+
+    #[account(seeds = [GAME_SESSION_SEED, game_session.table_seed().as_slice()], bump = game_session.bump)]
+    pub game_session: Account<'info, GameSession>,
+
+    #[account(
+        mut,
+        seeds = [PLAYER_BETS_SEED, game_session.key().as_ref(), player.key().as_ref()],
+        bump = player_bets.bump,
+        constraint = player_bets.player == player.key() @ RouletteError::Unauthorized,
+        constraint = player_bets.game_session == game_session.key() @ RouletteError::TableMismatch,
+    )]
+    pub player_bets: Account<'info, PlayerBets>,
+
+    /// Stats of the acknowledged round, which hold its winning number.
+    #[account(
+        seeds = [ROUND_STATS_SEED, game_session.key().as_ref(), &round.to_le_bytes()],
+        bump = round_stats.bump
+    )]
+    pub round_stats: Account<'info, RoundStats>,
 }
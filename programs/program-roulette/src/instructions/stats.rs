@@ -0,0 +1,163 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Allocate, Assign};
+use crate::instructions::lamports::{debit_program_account, transfer_lamports};
+use crate::{
+    errors::RouletteError,
+    seeds::{GAME_SESSION_SEED, PROTOCOL_STATS_SEED, RENT_POOL_SEED},
+    state::*,
+};
+
+// =================================================================================================
+// Protocol Stats Initialization
+// =================================================================================================
+
+/// Creates the `ProtocolStats` account. The round counter is seeded from the game session;
+/// vault and player counts start at zero.
+pub fn initialize_protocol_stats(ctx: Context<InitializeProtocolStats>) -> Result<()> {
+    let stats = &mut ctx.accounts.protocol_stats;
+    stats.total_vaults = 0;
+    stats.total_rounds = ctx.accounts.game_session.current_round;
+    stats.total_players = 0;
+    stats.mint_volumes = Default::default();
+    stats.untracked_bets = 0;
+    stats.bump = ctx.bumps.protocol_stats;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeProtocolStats<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [GAME_SESSION_SEED],
+        bump = game_session.bump,
+        constraint = authority.key() == game_session.authority @ RouletteError::AdminOnly
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + std::mem::size_of::<ProtocolStats>(),
+        seeds = [PROTOCOL_STATS_SEED],
+        bump
+    )]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// =================================================================================================
+// Rent Pool
+// =================================================================================================
+
+/// Creates the protocol's `RentPool`. Fund it with plain system transfers.
+pub fn initialize_rent_pool(ctx: Context<InitializeRentPool>) -> Result<()> {
+    ctx.accounts.rent_pool.bump = ctx.bumps.rent_pool;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeRentPool<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [GAME_SESSION_SEED],
+        bump = game_session.bump,
+        constraint = authority.key() == game_session.authority @ RouletteError::AdminOnly
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + std::mem::size_of::<RentPool>(),
+        seeds = [RENT_POOL_SEED],
+        bump
+    )]
+    pub rent_pool: Account<'info, RentPool>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Resizes `account` to `new_len`, first topping its lamports up to the new rent-exempt
+/// minimum. With `RentPayer::Protocol` the top-up comes from the rent pool while it can cover
+/// it without dropping below its own rent-exempt minimum; otherwise the signer pays.
+pub(crate) fn grow_account<'info>(
+    account: &AccountInfo<'info>,
+    new_len: usize,
+    rent_payer: RentPayer,
+    rent_pool: Option<&Account<'info, RentPool>>,
+    signer: &AccountInfo<'info>,
+    system_program: &Program<'info, System>
+) -> Result<()> {
+    let rent = Rent::get()?;
+    let shortfall = rent.minimum_balance(new_len).saturating_sub(account.lamports());
+
+    if shortfall > 0 {
+        let pool = rent_pool
+            .filter(|_| rent_payer == RentPayer::Protocol)
+            .map(|pool| pool.to_account_info())
+            .filter(|pool| {
+                pool.lamports().saturating_sub(rent.minimum_balance(pool.data_len())) >= shortfall
+            });
+        match pool {
+            Some(pool) => {
+                **pool.try_borrow_mut_lamports()? -= shortfall;
+                **account.try_borrow_mut_lamports()? += shortfall;
+            }
+            None => transfer_lamports(signer, account, shortfall, false, system_program)?,
+        }
+    }
+
+    account.realloc(new_len, true)?;
+    Ok(())
+}
+
+/// Creates the program-owned PDA `account` with `space` bytes. Its rent comes from `rent_pool`
+/// when given, which must cover it without dropping below its own rent-exempt minimum, and from
+/// `payer` otherwise. Like Anchor's `init`, lamports already sent to the address are kept.
+pub(crate) fn create_pda_account<'info>(
+    account: &AccountInfo<'info>,
+    space: usize,
+    rent_pool: Option<&Account<'info, RentPool>>,
+    payer: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    signer_seeds: &[&[u8]]
+) -> Result<()> {
+    let rent = Rent::get()?;
+    let shortfall = rent.minimum_balance(space).saturating_sub(account.lamports());
+
+    if shortfall > 0 {
+        match rent_pool {
+            Some(pool) => {
+                let pool = pool.to_account_info();
+                require!(
+                    pool.lamports().saturating_sub(rent.minimum_balance(pool.data_len())) >= shortfall,
+                    RouletteError::RentPoolDepleted
+                );
+                debit_program_account(&pool, account, shortfall)?;
+            }
+            None => transfer_lamports(payer, account, shortfall, false, system_program)?,
+        }
+    }
+
+    system_program::allocate(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            Allocate { account_to_allocate: account.clone() },
+            &[signer_seeds]
+        ),
+        space as u64
+    )?;
+    system_program::assign(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            Assign { account_to_assign: account.clone() },
+            &[signer_seeds]
+        ),
+        &crate::ID
+    )
+}
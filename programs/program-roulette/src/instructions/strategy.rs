@@ -0,0 +1,283 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    hash,
+    instruction::{AccountMeta, Instruction},
+    program::invoke,
+};
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+use crate::{
+    constants::STRATEGY_CUSTODY_SEED,
+    errors::RouletteError,
+    events::*,
+    seeds::{CONFIG_SEED, GAME_SESSION_SEED, VAULT_SEED},
+    state::*,
+};
+
+// The strategy program is called with the Anchor discriminator of `global:deposit` or
+// `global:withdraw` followed by the vault's token mint (and the amount, for deposits). Its
+// first two accounts are the vault (read-only, never a signer) and the token account the
+// funds move through; every `remaining_accounts` entry is passed on after them. The strategy
+// holds each vault's funds in a token account owned by its `[STRATEGY_CUSTODY_SEED, vault]`
+// address, so deployed liquidity can only land in custody kept for that vault.
+
+/// The strategy's custody authority for `vault`.
+fn strategy_custody(strategy_program: &Pubkey, vault: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[STRATEGY_CUSTODY_SEED, vault.as_ref()], strategy_program).0
+}
+
+/// Builds and invokes a strategy call. The vault never signs, so the strategy cannot move
+/// anything beyond what was explicitly transferred to it.
+fn invoke_strategy<'info>(
+    strategy_program: &AccountInfo<'info>,
+    method: &str,
+    args: &[u8],
+    vault: &AccountInfo<'info>,
+    token_account: &AccountInfo<'info>,
+    remaining_accounts: &[AccountInfo<'info>]
+) -> Result<()> {
+    let mut data = hash::hash(format!("global:{method}").as_bytes()).to_bytes()[..8].to_vec();
+    data.extend_from_slice(args);
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(vault.key(), false),
+        AccountMeta::new(token_account.key(), false),
+    ];
+    let mut infos = vec![vault.clone(), token_account.clone()];
+    for info in remaining_accounts {
+        accounts.push(if info.is_writable {
+            AccountMeta::new(info.key(), info.is_signer)
+        } else {
+            AccountMeta::new_readonly(info.key(), info.is_signer)
+        });
+        infos.push(info.clone());
+    }
+    infos.push(strategy_program.clone());
+
+    invoke(&Instruction { program_id: strategy_program.key(), accounts, data }, &infos)?;
+    Ok(())
+}
+
+// =================================================================================================
+// Deploy To Strategy
+// =================================================================================================
+
+/// Moves `amount` of idle vault liquidity to the whitelisted yield strategy, within the vault's
+/// `strategy_max_bps` allocation. Deployed principal stays in `total_liquidity` for accounting
+/// but no longer backs bets, and claims on the vault wait until it is recalled.
+pub fn deploy_to_strategy<'info>(
+    ctx: Context<'_, '_, 'info, 'info, DeployToStrategy<'info>>,
+    amount: u64
+) -> Result<()> {
+    require!(amount > 0, RouletteError::AmountMustBeGreaterThanZero);
+    require!(!ctx.accounts.config.paused, RouletteError::ProgramPaused);
+    require!(
+        !matches!(
+            ctx.accounts.game_session.round_status,
+            RoundStatus::BetsClosed | RoundStatus::SeedLocked
+        ),
+        RouletteError::StrategyDeployDuringSettlement
+    );
+    let vault = &mut ctx.accounts.vault;
+    require!(amount <= vault.strategy_capacity()?, RouletteError::StrategyAllocationExceeded);
+
+    let seeds = &[VAULT_SEED, vault.token_mint.as_ref(), &[vault.bump]];
+    let signer_seeds = &[&seeds[..]];
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.vault_token_account.to_account_info(),
+                mint: ctx.accounts.token_mint.to_account_info(),
+                to: ctx.accounts.strategy_token_account.to_account_info(),
+                authority: vault.to_account_info(),
+            },
+            signer_seeds
+        ),
+        amount,
+        ctx.accounts.token_mint.decimals,
+    )?;
+
+    let vault_balance = ctx.accounts.vault_token_account.amount;
+    let mut args = vault.token_mint.try_to_vec()?;
+    args.extend_from_slice(&amount.to_le_bytes());
+    invoke_strategy(
+        &ctx.accounts.strategy_program,
+        "deposit",
+        &args,
+        &vault.to_account_info(),
+        &ctx.accounts.strategy_token_account.to_account_info(),
+        ctx.remaining_accounts
+    )?;
+    ctx.accounts.vault_token_account.reload()?;
+    require!(
+        ctx.accounts.vault_token_account.amount + amount == vault_balance,
+        RouletteError::StrategyDepositMismatch
+    );
+
+    vault.strategy_principal = vault.strategy_principal
+        .checked_add(amount)
+        .ok_or(RouletteError::ArithmeticOverflow)?;
+
+    emit!(StrategyDeployed {
+        event_seq: ctx.accounts.game_session.next_event_seq(),
+        token_mint: vault.token_mint,
+        strategy_program: ctx.accounts.strategy_program.key(),
+        amount,
+        strategy_principal: vault.strategy_principal,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct DeployToStrategy<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GAME_SESSION_SEED],
+        bump = game_session.bump,
+        constraint = authority.key() == game_session.authority @ RouletteError::AdminOnly
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, token_mint.key().as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Account<'info, VaultAccount>,
+
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = vault_token_account.key() == vault.token_account @ RouletteError::VaultMismatch
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// The strategy's token account receiving the deployed liquidity, held in its custody for
+    /// this vault.
+    #[account(
+        mut,
+        constraint = strategy_token_account.mint == token_mint.key() @ RouletteError::InvalidTokenAccount,
+        constraint = strategy_token_account.key() != vault_token_account.key() @ RouletteError::DuplicateTokenAccount,
+        constraint = strategy_token_account.owner == strategy_custody(&config.strategy_program, &vault.key()) @ RouletteError::StrategyCustodyMismatch
+    )]
+    pub strategy_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Must be the whitelisted `Config::strategy_program`.
+    #[account(
+        executable,
+        constraint = config.strategy_program != Pubkey::default() @ RouletteError::StrategyProgramMismatch,
+        address = config.strategy_program @ RouletteError::StrategyProgramMismatch
+    )]
+    pub strategy_program: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+// =================================================================================================
+// Recall From Strategy
+// =================================================================================================
+
+/// Withdraws what the vault has deployed to the strategy back into the vault token account.
+/// Principal is reduced by what actually came back, and anything above it is booked as yield.
+/// A shortfall stays deployed until the strategy's custody account for the vault is empty,
+/// when it is booked as a loss of liquidity. The authority may recall at any time; anyone may
+/// while bets are closed, so settlement and the claims after it never wait on the authority.
+pub fn recall_from_strategy<'info>(
+    ctx: Context<'_, '_, 'info, 'info, RecallFromStrategy<'info>>
+) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let principal = vault.strategy_principal;
+    let balance_before = ctx.accounts.vault_token_account.amount;
+
+    invoke_strategy(
+        &ctx.accounts.strategy_program,
+        "withdraw",
+        &vault.token_mint.try_to_vec()?,
+        &vault.to_account_info(),
+        &ctx.accounts.vault_token_account.to_account_info(),
+        ctx.remaining_accounts
+    )?;
+    ctx.accounts.vault_token_account.reload()?;
+    ctx.accounts.strategy_token_account.reload()?;
+    let returned = ctx.accounts.vault_token_account.amount.saturating_sub(balance_before);
+
+    let yield_amount = returned.saturating_sub(principal);
+    let outstanding = principal.saturating_sub(returned);
+    let loss = if ctx.accounts.strategy_token_account.amount == 0 { outstanding } else { 0 };
+    vault.total_liquidity = vault.total_liquidity
+        .checked_add(yield_amount)
+        .ok_or(RouletteError::ArithmeticOverflow)?;
+    let debited_loss = loss.min(vault.total_liquidity);
+    vault.debit_liquidity(debited_loss)?;
+    vault.strategy_yield = vault.strategy_yield
+        .checked_add(yield_amount)
+        .ok_or(RouletteError::ArithmeticOverflow)?;
+    vault.strategy_principal = outstanding - loss;
+
+    emit!(StrategyRecalled {
+        event_seq: ctx.accounts.game_session.next_event_seq(),
+        token_mint: vault.token_mint,
+        strategy_program: ctx.accounts.strategy_program.key(),
+        principal,
+        returned,
+        yield_amount,
+        loss,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RecallFromStrategy<'info> {
+    #[account(
+        mut,
+        seeds = [GAME_SESSION_SEED],
+        bump = game_session.bump,
+        constraint = cranker.key() == game_session.authority ||
+            matches!(game_session.round_status, RoundStatus::BetsClosed | RoundStatus::SeedLocked) @ RouletteError::AdminOnly
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    pub cranker: Signer<'info>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, vault.token_mint.as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(
+        mut,
+        constraint = vault_token_account.key() == vault.token_account @ RouletteError::VaultMismatch
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// The strategy's token account holding this vault's funds, read to tell a shortfall that
+    /// is still held from a loss.
+    #[account(
+        constraint = strategy_token_account.mint == vault.token_mint @ RouletteError::InvalidTokenAccount,
+        constraint = strategy_token_account.owner == strategy_custody(&config.strategy_program, &vault.key()) @ RouletteError::StrategyCustodyMismatch
+    )]
+    pub strategy_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Must be the whitelisted `Config::strategy_program`.
+    #[account(
+        executable,
+        constraint = config.strategy_program != Pubkey::default() @ RouletteError::StrategyProgramMismatch,
+        address = config.strategy_program @ RouletteError::StrategyProgramMismatch
+    )]
+    pub strategy_program: UncheckedAccount<'info>,
+}
@@ -1,12 +1,31 @@
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program::program::set_return_data;
-use anchor_lang::system_program;
-use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, SetAuthority, TransferChecked};
+use anchor_lang::solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program::{invoke_signed, set_return_data},
+};
+use anchor_spl::token_interface::{
+    self, CloseAccount, Mint, TokenAccount, TokenInterface, SetAuthority, TransferChecked,
+};
 use anchor_spl::token_2022::spl_token_2022::instruction::AuthorityType;
+use crate::instructions::lamports::transfer_lamports;
+use crate::instructions::stats::grow_account;
 use crate::{
     constants::*,
     errors::RouletteError,
     events::*,
+    seeds::{
+        CONFIG_SEED,
+        GAME_SESSION_SEED,
+        PAYOUT_DEBT_SEED,
+        PROTOCOL_STATS_SEED,
+        PROVIDER_STATE_SEED,
+        RAKE_ESCROW_SEED,
+        RENT_POOL_SEED,
+        SOL_FEE_VAULT_SEED,
+        VAULT_EXPOSURE_SEED,
+        VAULT_SEED,
+        WITHDRAWAL_REQUEST_SEED,
+    },
     state::*,
 };
 
@@ -19,33 +38,35 @@ pub fn initialize_and_provide_liquidity(
     amount: u64
 ) -> Result<()> {
     // Anchor's constraints now handle deserialization and validation automatically.
-
-    system_program::transfer(
-        CpiContext::new(
-            ctx.accounts.system_program.to_account_info(),
-            system_program::Transfer {
-                from: ctx.accounts.liquidity_provider.to_account_info(),
-                to: ctx.accounts.treasury_account.to_account_info(),
-            },
-        ),
-        CREATE_VAULT_FEE_SOL_LAMPORTS
+    require!(!ctx.accounts.config.paused, RouletteError::ProgramPaused);
+
+    transfer_lamports(
+        &ctx.accounts.liquidity_provider.to_account_info(),
+        &ctx.accounts.sol_fee_vault.to_account_info(),
+        CREATE_VAULT_FEE_SOL_LAMPORTS,
+        false,
+        &ctx.accounts.system_program
     )?;
+    let sol_fee_vault = &mut ctx.accounts.sol_fee_vault;
+    sol_fee_vault.total_collected = sol_fee_vault.total_collected
+        .checked_add(CREATE_VAULT_FEE_SOL_LAMPORTS)
+        .ok_or(RouletteError::ArithmeticOverflow)?;
 
-    // Initialize vault state (simplified, no vectors)
     let vault = &mut ctx.accounts.vault;
-    vault.token_mint = ctx.accounts.token_mint.key();
-    vault.token_account = ctx.accounts.vault_token_account.key();
-    vault.bump = ctx.bumps.vault;
-    vault.owner_reward = 0;
-    vault.reward_per_share_index = 0;
+    initialize_vault_state(vault, &ctx.accounts.token_mint, ctx.accounts.vault_token_account.key(), ctx.bumps.vault);
+
+    let stats = &mut ctx.accounts.protocol_stats;
+    stats.total_vaults = stats.total_vaults.saturating_add(1);
     
     // Initialize the first provider's state
+    vault.admit_provider()?;
     let provider_state = &mut ctx.accounts.provider_state;
-    provider_state.vault = vault.key();
-    provider_state.provider = ctx.accounts.liquidity_provider.key();
-    provider_state.unclaimed_rewards = 0;
-    provider_state.reward_per_share_index_last_claimed = 0; // Starts at 0
-    provider_state.bump = ctx.bumps.provider_state;
+    initialize_provider_state(
+        provider_state,
+        vault.key(),
+        ctx.accounts.liquidity_provider.key(),
+        ctx.bumps.provider_state
+    );
 
     // Transfer initial liquidity
     token_interface::transfer_checked(
@@ -75,9 +96,10 @@ pub fn initialize_and_provide_liquidity(
     // Update vault and provider state with the amount
     vault.total_liquidity = amount;
     vault.total_provider_capital = amount;
-    provider_state.amount = amount;
+    provider_state.record_deposit(amount, Clock::get()?.unix_timestamp)?;
 
     emit!(LiquidityProvided {
+        event_seq: ctx.accounts.game_session.next_event_seq(),
         provider: *ctx.accounts.liquidity_provider.key,
         token_mint: vault.token_mint,
         amount,
@@ -87,8 +109,74 @@ pub fn initialize_and_provide_liquidity(
     Ok(())
 }
 
+/// Sets up a new vault for `token_mint`, holding its liquidity in `token_account`, with no
+/// provider yet and every setting at its default.
+pub(crate) fn initialize_vault_state(
+    vault: &mut VaultAccount,
+    token_mint: &InterfaceAccount<Mint>,
+    token_account: Pubkey,
+    bump: u8
+) {
+    vault.token_mint = token_mint.key();
+    vault.token_account = token_account;
+    vault.bump = bump;
+    vault.owner_reward = 0;
+    vault.reward_per_share_index = 0;
+    vault.owner_reward_cap = 0;
+    vault.large_withdrawal_bps = 0;
+    vault.opted_out_from_round = 0;
+    vault.chip_size = 0;
+    vault.max_player_payout = 0;
+    vault.crank_reward = 0;
+    vault.table_maximum = 0;
+    vault.owner_share_recipients = [Pubkey::default(); MAX_OWNER_SHARES];
+    vault.owner_share_bps = [0; MAX_OWNER_SHARES];
+    vault.owner_share_owed = [0; MAX_OWNER_SHARES];
+    vault.provider_count = 0;
+    vault.max_providers = 0;
+    vault.mint_decimals = token_mint.decimals;
+    vault.large_bet_threshold = 0;
+    vault.large_win_threshold = 0;
+    vault.open_disputes = 0;
+    vault.rake_in_protocol_token = false;
+    vault.debt_head = 0;
+    vault.debt_tail = 0;
+    vault.outstanding_debt = 0;
+    vault.loyalty_budget_bps = 0;
+    vault.loyalty_max_boost_bps = 0;
+    vault.loyalty_maturity_secs = 0;
+    vault.loyalty_budget = 0;
+    vault.strategy_max_bps = 0;
+    vault.strategy_principal = 0;
+    vault.strategy_yield = 0;
+    vault.realized_losses = 0;
+    vault.confidential_payouts = false;
+    vault.layout_version = VAULT_LAYOUT_VERSION;
+}
+
+/// Sets up an empty position of `provider` in `vault`.
+pub(crate) fn initialize_provider_state(provider_state: &mut ProviderState, vault: Pubkey, provider: Pubkey, bump: u8) {
+    provider_state.vault = vault;
+    provider_state.provider = provider;
+    provider_state.unclaimed_rewards = 0;
+    provider_state.reward_per_share_index_last_claimed = 0; // Starts at 0
+    provider_state.bump = bump;
+    provider_state.referrer = None;
+    provider_state.referral_rewards = 0;
+    provider_state.amount = 0;
+    provider_state.position_asset = None;
+    provider_state.controller = None;
+    provider_state.layout_version = PROVIDER_STATE_LAYOUT_VERSION;
+}
+
 #[derive(Accounts)]
 pub struct InitializeAndProvideLiquidity<'info> {
+    #[account(mut, seeds = [GAME_SESSION_SEED, game_session.table_seed().as_slice()], bump = game_session.bump)]
+    pub game_session: Account<'info, GameSession>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
     /// The mint account of the SPL token for the new vault.
     pub token_mint: InterfaceAccount<'info, Mint>,
 
@@ -98,7 +186,7 @@ pub struct InitializeAndProvideLiquidity<'info> {
         init,
         payer = liquidity_provider,
         space = 8 + std::mem::size_of::<VaultAccount>(), // Becomes fixed size
-        seeds = [b"vault", token_mint.key().as_ref()],
+        seeds = [VAULT_SEED, token_mint.key().as_ref()],
         bump
     )]
     pub vault: Account<'info, VaultAccount>,
@@ -108,11 +196,14 @@ pub struct InitializeAndProvideLiquidity<'info> {
         init, // Always init, since the vault is new
         payer = liquidity_provider, // Provider pays for their own account
         space = 8 + std::mem::size_of::<ProviderState>(),
-        seeds = [b"provider_state", vault.key().as_ref(), liquidity_provider.key().as_ref()],
+        seeds = [PROVIDER_STATE_SEED, vault.key().as_ref(), liquidity_provider.key().as_ref()],
         bump
     )]
     pub provider_state: Account<'info, ProviderState>,
 
+    #[account(mut, seeds = [PROTOCOL_STATS_SEED], bump = protocol_stats.bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+
     /// The provider's token account. It must be for the same mint as `token_mint`.
     #[account(
         mut,
@@ -133,12 +224,9 @@ pub struct InitializeAndProvideLiquidity<'info> {
     #[account(mut)]
     pub liquidity_provider: Signer<'info>,
 
-    /// The treasury account that receives the vault creation fee.
-    #[account(
-        mut,
-        address = TREASURY_PUBKEY
-    )]
-    pub treasury_account: SystemAccount<'info>,
+    /// Collects the vault creation fee.
+    #[account(mut, seeds = [SOL_FEE_VAULT_SEED], bump = sol_fee_vault.bump)]
+    pub sol_fee_vault: Account<'info, SolFeeVault>,
 
     /// The Solana System Program.
     pub system_program: Program<'info, System>,
@@ -159,16 +247,15 @@ pub fn provide_liquidity(ctx: Context<ProvideLiquidity>, amount: u64) -> Result<
         RouletteError::InvalidTokenAccount
     );
     require!(amount > 0, RouletteError::AmountMustBeGreaterThanZero); // Can't provide 0 liquidity
+    require!(!ctx.accounts.config.paused, RouletteError::ProgramPaused);
 
     let vault = &mut ctx.accounts.vault;
     let provider_state = &mut ctx.accounts.provider_state;
     let current_reward_index = vault.reward_per_share_index;
+    let now = Clock::get()?.unix_timestamp;
 
     // --- Start of reward update logic ---
-    let newly_earned_reward = calculate_newly_earned_rewards(provider_state, current_reward_index)?;
-    provider_state.unclaimed_rewards = provider_state.unclaimed_rewards
-        .checked_add(newly_earned_reward)
-        .ok_or(RouletteError::ArithmeticOverflow)?;
+    accrue_rewards(provider_state, vault, now)?;
     // --- End of reward update logic ---
 
     // Transfer liquidity
@@ -184,10 +271,19 @@ pub fn provide_liquidity(ctx: Context<ProvideLiquidity>, amount: u64) -> Result<
     )?;
 
     // If the provider state account is being initialized, set its fixed data.
-    if provider_state.vault == Pubkey::default() {
-        provider_state.vault = vault.key();
-        provider_state.provider = ctx.accounts.liquidity_provider.key();
-        provider_state.bump = ctx.bumps.provider_state;
+    if provider_state.vault != Pubkey::default() {
+        require!(
+            provider_state.is_controlled_by(&ctx.accounts.liquidity_provider.key(), None),
+            RouletteError::LpPositionNotControlled
+        );
+    } else {
+        vault.admit_provider()?;
+        initialize_provider_state(
+            provider_state,
+            vault.key(),
+            ctx.accounts.liquidity_provider.key(),
+            ctx.bumps.provider_state
+        );
     }
 
     // Update vault state
@@ -200,14 +296,13 @@ pub fn provide_liquidity(ctx: Context<ProvideLiquidity>, amount: u64) -> Result<
         .ok_or(RouletteError::ArithmeticOverflow)?;
 
     // Update provider state
-    provider_state.amount = provider_state.amount
-        .checked_add(amount)
-        .ok_or(RouletteError::ArithmeticOverflow)?;
+    provider_state.record_deposit(amount, now)?;
     
     // Set the checkpoint to the current index for the next calculation.
     provider_state.reward_per_share_index_last_claimed = current_reward_index;
 
     emit!(LiquidityProvided {
+        event_seq: ctx.accounts.game_session.next_event_seq(),
         provider: ctx.accounts.liquidity_provider.key(),
         token_mint: vault.token_mint,
         amount,
@@ -219,10 +314,16 @@ pub fn provide_liquidity(ctx: Context<ProvideLiquidity>, amount: u64) -> Result<
 
 #[derive(Accounts)]
 pub struct ProvideLiquidity<'info> {
+    #[account(mut, seeds = [GAME_SESSION_SEED, game_session.table_seed().as_slice()], bump = game_session.bump)]
+    pub game_session: Account<'info, GameSession>,
+
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
     /// The vault account to which liquidity is being added. Mutable to update `total_liquidity`.
     #[account(
         mut,
-        seeds = [b"vault", token_mint.key().as_ref()],
+        seeds = [VAULT_SEED, token_mint.key().as_ref()],
         bump = vault.bump,
     )]
     pub vault: Account<'info, VaultAccount>,
@@ -235,7 +336,7 @@ pub struct ProvideLiquidity<'info> {
         init_if_needed,
         payer = liquidity_provider,
         space = 8 + std::mem::size_of::<ProviderState>(),
-        seeds = [b"provider_state", vault.key().as_ref(), liquidity_provider.key().as_ref()],
+        seeds = [PROVIDER_STATE_SEED, vault.key().as_ref(), liquidity_provider.key().as_ref()],
         bump
     )]
     pub provider_state: Account<'info, ProviderState>,
@@ -276,9 +377,19 @@ pub fn withdraw_liquidity(ctx: Context<WithdrawLiquidity>) -> Result<()> {
     let current_reward_index = vault.reward_per_share_index;
 
     // --- Start of reward calculation ---
-    let newly_earned_reward = calculate_newly_earned_rewards(provider_state, current_reward_index)?;
+    let (newly_earned_reward, newly_earned_referral) =
+        split_newly_earned_rewards(provider_state, current_reward_index)?;
+    let loyalty_boost = vault.loyalty_boost(
+        newly_earned_reward,
+        Clock::get()?.unix_timestamp.saturating_sub(provider_state.deposit_timestamp)
+    )?;
+    vault.loyalty_budget -= loyalty_boost;
     let final_unclaimed_rewards = provider_state.unclaimed_rewards
         .checked_add(newly_earned_reward)
+        .and_then(|rewards| rewards.checked_add(loyalty_boost))
+        .ok_or(RouletteError::ArithmeticOverflow)?;
+    let final_referral_rewards = provider_state.referral_rewards
+        .checked_add(newly_earned_referral)
         .ok_or(RouletteError::ArithmeticOverflow)?;
     // --- End of reward calculation ---
 
@@ -288,14 +399,31 @@ pub fn withdraw_liquidity(ctx: Context<WithdrawLiquidity>) -> Result<()> {
         .checked_add(final_unclaimed_rewards)
         .ok_or(RouletteError::ArithmeticOverflow)?;
 
+    // Large withdrawals must have been announced and the announcement must have matured.
+    if vault.is_large_withdrawal(total_withdrawal_amount) {
+        let request = ctx.accounts.withdrawal_request
+            .as_ref()
+            .ok_or(RouletteError::LargeWithdrawalNotAnnounced)?;
+        let game_session = &ctx.accounts.game_session;
+        let unlocked =
+            request.round_completed(&game_session.key(), game_session) ||
+            game_session.duration_elapsed(
+                &Clock::get()?,
+                request.requested_at,
+                request.requested_slot,
+                LARGE_WITHDRAWAL_TIMEOUT_SECS
+            );
+        require!(unlocked, RouletteError::LargeWithdrawalLocked);
+    }
+
     if total_withdrawal_amount > 0 {
         require!(
-            vault.total_liquidity >= total_withdrawal_amount,
+            vault.at_risk_liquidity() >= total_withdrawal_amount,
             RouletteError::InsufficientLiquidity
         );
 
         // Transfer tokens back to provider
-        let seeds = &[b"vault".as_ref(), vault.token_mint.as_ref(), &[vault.bump]];
+        let seeds = &[VAULT_SEED, vault.token_mint.as_ref(), &[vault.bump]];
         let signer_seeds = &[&seeds[..]];
         token_interface::transfer_checked(
             CpiContext::new_with_signer(
@@ -317,14 +445,51 @@ pub fn withdraw_liquidity(ctx: Context<WithdrawLiquidity>) -> Result<()> {
             .checked_sub(total_withdrawal_amount)
             .ok_or(RouletteError::ArithmeticOverflow)?;
     }
+
+    // The referrer's share is settled with the closing state, so a referred position cannot be
+    // closed without the referrer's token account.
+    require!(
+        provider_state.referrer.is_none() || ctx.accounts.referrer_token_account.is_some(),
+        RouletteError::ReferrerTokenAccountRequired
+    );
+    let referrer_token_account = ctx.accounts.referrer_token_account
+        .as_ref()
+        .filter(|_| final_referral_rewards > 0);
+    if let Some(referrer_token_account) = referrer_token_account {
+        require!(
+            vault.at_risk_liquidity() >= final_referral_rewards,
+            RouletteError::InsufficientLiquidity
+        );
+        let seeds = &[VAULT_SEED, vault.token_mint.as_ref(), &[vault.bump]];
+        let signer_seeds = &[&seeds[..]];
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.vault_token_account.to_account_info(),
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                    to: referrer_token_account.to_account_info(),
+                    authority: vault.to_account_info(),
+                },
+                signer_seeds
+            ),
+            final_referral_rewards,
+            ctx.accounts.token_mint.decimals,
+        )?;
+        vault.total_liquidity = vault.total_liquidity
+            .checked_sub(final_referral_rewards)
+            .ok_or(RouletteError::ArithmeticOverflow)?;
+    }
     
     vault.total_provider_capital = vault.total_provider_capital
         .checked_sub(total_capital_to_withdraw) // Only subtract the capital part
         .ok_or(RouletteError::ArithmeticOverflow)?;
 
     // provider_state account is automatically closed by Anchor via the `close` constraint.
+    vault.release_provider();
 
     emit!(LiquidityWithdrawn {
+        event_seq: ctx.accounts.game_session.next_event_seq(),
         provider: ctx.accounts.liquidity_provider.key(),
         token_mint: vault.token_mint,
         amount: total_capital_to_withdraw, // Emitting the capital amount withdrawn
@@ -339,7 +504,7 @@ pub struct WithdrawLiquidity<'info> {
     /// The vault account from which liquidity is being withdrawn.
     #[account(
         mut,
-        seeds = [b"vault", token_mint.key().as_ref()],
+        seeds = [VAULT_SEED, token_mint.key().as_ref()],
         bump = vault.bump,
     )]
     pub vault: Account<'info, VaultAccount>,
@@ -349,15 +514,43 @@ pub struct WithdrawLiquidity<'info> {
         mut,
         // The provider's state account must belong to the vault.
         constraint = provider_state.vault == vault.key() @ RouletteError::VaultMismatch,
-        // It must also belong to the signer.
-        constraint = provider_state.provider == liquidity_provider.key() @ RouletteError::Unauthorized,
-        seeds = [b"provider_state", vault.key().as_ref(), liquidity_provider.key().as_ref()],
+        // It must also be controlled by the signer.
+        constraint = provider_state.is_controlled_by(&liquidity_provider.key(), position_asset.as_deref()) @ RouletteError::Unauthorized,
+        seeds = [PROVIDER_STATE_SEED, vault.key().as_ref(), provider_state.provider.as_ref()],
         bump = provider_state.bump,
         // Close the account and return rent to the provider.
         close = liquidity_provider
     )]
     pub provider_state: Account<'info, ProviderState>,
 
+    /// CHECK: Asset of the position, required only while it is wrapped; checked by
+    /// `is_controlled_by`.
+    pub position_asset: Option<UncheckedAccount<'info>>,
+
+    /// The provider's withdrawal announcement. Required only for large withdrawals; consumed here.
+    #[account(
+        mut,
+        seeds = [WITHDRAWAL_REQUEST_SEED, provider_state.key().as_ref()],
+        bump = withdrawal_request.bump,
+        close = liquidity_provider
+    )]
+    pub withdrawal_request: Option<Account<'info, WithdrawalRequest>>,
+
+    /// A table session. A large withdrawal unlocks early only through the table it was
+    /// announced against.
+    #[account(mut, seeds = [GAME_SESSION_SEED, game_session.table_seed().as_slice()], bump = game_session.bump)]
+    pub game_session: Account<'info, GameSession>,
+
+    /// The registered referrer's token account, receiving their accrued share on closure.
+    /// Required when the position has a referrer.
+    #[account(
+        mut,
+        constraint = Some(referrer_token_account.owner) == provider_state.referrer @ RouletteError::Unauthorized,
+        constraint = referrer_token_account.mint == token_mint.key() @ RouletteError::InvalidTokenAccount,
+        constraint = referrer_token_account.key() != vault_token_account.key() @ RouletteError::DuplicateTokenAccount
+    )]
+    pub referrer_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
     /// The mint account for the token.
     pub token_mint: InterfaceAccount<'info, Mint>,
 
@@ -385,32 +578,123 @@ pub struct WithdrawLiquidity<'info> {
     pub token_program: Interface<'info, TokenInterface>,
 }
 
+// =================================================================================================
+// Request Large Withdrawal
+// =================================================================================================
+
+/// Announces a full withdrawal whose size exceeds the vault's `large_withdrawal_bps`. It can be
+/// executed once the next round of the passed table has completed (or after
+/// `LARGE_WITHDRAWAL_TIMEOUT_SECS`), so the backing of a round in progress cannot disappear from
+/// under its bettors. The table's rounds only count when the vault's `VaultExposure` to it is
+/// passed; otherwise the request waits for the timeout.
+pub fn request_large_withdrawal(ctx: Context<RequestLargeWithdrawal>) -> Result<()> {
+    let vault = &ctx.accounts.vault;
+    let provider_state = &ctx.accounts.provider_state;
+    let game_session = &ctx.accounts.game_session;
+    let clock = Clock::get()?;
+
+    let unlock_round = if ctx.accounts.vault_exposure.is_some() {
+        game_session.current_round.checked_add(1).ok_or(RouletteError::ArithmeticOverflow)?
+    } else {
+        u64::MAX
+    };
+
+    let request = &mut ctx.accounts.withdrawal_request;
+    request.provider_state = provider_state.key();
+    request.game_session = game_session.key();
+    request.unlock_round = unlock_round;
+    request.requested_at = clock.unix_timestamp;
+    request.requested_slot = clock.slot;
+    request.bump = ctx.bumps.withdrawal_request;
+
+    emit!(LargeWithdrawalRequested {
+        event_seq: ctx.accounts.game_session.next_event_seq(),
+        provider: ctx.accounts.liquidity_provider.key(),
+        token_mint: vault.token_mint,
+        amount: provider_state.amount,
+        game_session: ctx.accounts.game_session.key(),
+        unlock_round,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RequestLargeWithdrawal<'info> {
+    #[account(
+        seeds = [VAULT_SEED, token_mint.key().as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Account<'info, VaultAccount>,
+
+    /// The provider's state account for this vault.
+    #[account(
+        constraint = provider_state.vault == vault.key() @ RouletteError::VaultMismatch,
+        constraint = provider_state.is_controlled_by(&liquidity_provider.key(), position_asset.as_deref()) @ RouletteError::Unauthorized,
+        seeds = [PROVIDER_STATE_SEED, vault.key().as_ref(), provider_state.provider.as_ref()],
+        bump = provider_state.bump
+    )]
+    pub provider_state: Account<'info, ProviderState>,
+
+    /// CHECK: Asset of the position, required only while it is wrapped; checked by
+    /// `is_controlled_by`.
+    pub position_asset: Option<UncheckedAccount<'info>>,
+
+    /// The announcement. Re-requesting restarts the waiting period.
+    #[account(
+        init_if_needed,
+        payer = liquidity_provider,
+        space = 8 + std::mem::size_of::<WithdrawalRequest>(),
+        seeds = [WITHDRAWAL_REQUEST_SEED, provider_state.key().as_ref()],
+        bump
+    )]
+    pub withdrawal_request: Account<'info, WithdrawalRequest>,
+
+    /// The table whose next round the withdrawal waits on.
+    #[account(mut, seeds = [GAME_SESSION_SEED, game_session.table_seed().as_slice()], bump = game_session.bump)]
+    pub game_session: Account<'info, GameSession>,
+
+    /// The vault's exposure to the table; without it only the timeout unlocks the withdrawal.
+    #[account(
+        seeds = [VAULT_EXPOSURE_SEED, vault.key().as_ref(), game_session.key().as_ref()],
+        bump = vault_exposure.bump
+    )]
+    pub vault_exposure: Option<Account<'info, VaultExposure>>,
+
+    /// The mint account for the vault's token.
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub liquidity_provider: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
 // =================================================================================================
 // Withdraw Provider Revenue
 // =================================================================================================
 
 pub fn withdraw_provider_revenue(ctx: Context<WithdrawProviderRevenue>) -> Result<()> {
+    // Reward withdrawals bypass pause checks by design; no pause state may block them.
     let vault = &mut ctx.accounts.vault;
     let provider_state = &mut ctx.accounts.provider_state;
     let current_reward_index = vault.reward_per_share_index;
 
     // --- Start of reward calculation ---
-    let newly_earned_reward = calculate_newly_earned_rewards(provider_state, current_reward_index)?;
-    provider_state.unclaimed_rewards = provider_state.unclaimed_rewards
-        .checked_add(newly_earned_reward)
-        .ok_or(RouletteError::ArithmeticOverflow)?;
+    accrue_rewards(provider_state, vault, Clock::get()?.unix_timestamp)?;
     // --- End of reward calculation ---
 
     let total_rewards_to_claim = provider_state.unclaimed_rewards;
 
     require!(total_rewards_to_claim > 0, RouletteError::NoReward);
     require!(
-        vault.total_liquidity >= total_rewards_to_claim,
+        vault.at_risk_liquidity() >= total_rewards_to_claim,
         RouletteError::InsufficientLiquidity
     );
 
     // Transfer rewards to the provider
-    let seeds = &[b"vault".as_ref(), vault.token_mint.as_ref(), &[vault.bump]];
+    let seeds = &[VAULT_SEED, vault.token_mint.as_ref(), &[vault.bump]];
     let signer_seeds = &[&seeds[..]];
     token_interface::transfer_checked(
         CpiContext::new_with_signer(
@@ -437,6 +721,7 @@ pub fn withdraw_provider_revenue(ctx: Context<WithdrawProviderRevenue>) -> Resul
     provider_state.reward_per_share_index_last_claimed = current_reward_index;
 
     emit!(ProviderRevenueWithdrawn {
+        event_seq: ctx.accounts.game_session.next_event_seq(),
         provider: ctx.accounts.liquidity_provider.key(),
         token_mint: vault.token_mint,
         amount: total_rewards_to_claim,
@@ -448,10 +733,13 @@ pub fn withdraw_provider_revenue(ctx: Context<WithdrawProviderRevenue>) -> Resul
 
 #[derive(Accounts)]
 pub struct WithdrawProviderRevenue<'info> {
+    #[account(mut, seeds = [GAME_SESSION_SEED, game_session.table_seed().as_slice()], bump = game_session.bump)]
+    pub game_session: Account<'info, GameSession>,
+
     /// The vault account holding the rewards.
     #[account(
         mut,
-        seeds = [b"vault", token_mint.key().as_ref()],
+        seeds = [VAULT_SEED, token_mint.key().as_ref()],
         bump = vault.bump,
     )]
     pub vault: Account<'info, VaultAccount>,
@@ -461,13 +749,17 @@ pub struct WithdrawProviderRevenue<'info> {
         mut,
         // The provider's state account must belong to the vault.
         constraint = provider_state.vault == vault.key() @ RouletteError::VaultMismatch,
-        // It must also belong to the signer.
-        constraint = provider_state.provider == liquidity_provider.key() @ RouletteError::Unauthorized,
-        seeds = [b"provider_state", vault.key().as_ref(), liquidity_provider.key().as_ref()],
+        // It must also be controlled by the signer.
+        constraint = provider_state.is_controlled_by(&liquidity_provider.key(), position_asset.as_deref()) @ RouletteError::Unauthorized,
+        seeds = [PROVIDER_STATE_SEED, vault.key().as_ref(), provider_state.provider.as_ref()],
         bump = provider_state.bump
     )]
     pub provider_state: Account<'info, ProviderState>,
 
+    /// CHECK: Asset of the position, required only while it is wrapped; checked by
+    /// `is_controlled_by`.
+    pub position_asset: Option<UncheckedAccount<'info>>,
+
     /// The mint account for the token being withdrawn
     pub token_mint: InterfaceAccount<'info, Mint>,
 
@@ -501,13 +793,16 @@ pub struct WithdrawProviderRevenue<'info> {
 
 pub fn withdraw_owner_revenue(ctx: Context<WithdrawOwnerRevenue>) -> Result<()> {
     // Anchor's constraints now handle token_mint and treasury account validation.
+    ctx.accounts.config.require_admin_approval(ctx.remaining_accounts)?;
     let vault = &mut ctx.accounts.vault;
+    require!(!vault.rake_in_protocol_token, RouletteError::RakeMustBeSwapped);
+    require!(!vault.has_owner_shares(), RouletteError::OwnerRevenueShared);
     let reward_amount = vault.owner_reward;
 
     require!(reward_amount > 0, RouletteError::NoReward);
-    require!(vault.total_liquidity >= reward_amount, RouletteError::InsufficientLiquidity);
+    require!(vault.at_risk_liquidity() >= reward_amount, RouletteError::InsufficientLiquidity);
 
-    let seeds = &[b"vault".as_ref(), vault.token_mint.as_ref(), &[vault.bump]];
+    let seeds = &[VAULT_SEED, vault.token_mint.as_ref(), &[vault.bump]];
     let signer_seeds = &[&seeds[..]];
 
     token_interface::transfer_checked(
@@ -540,16 +835,20 @@ pub struct WithdrawOwnerRevenue<'info> {
     pub authority: Signer<'info>,
 
     #[account(
-        seeds = [b"game_session"], 
+        seeds = [GAME_SESSION_SEED], 
         bump = game_session.bump,
         constraint = authority.key() == game_session.authority @ RouletteError::AdminOnly
     )]
     pub game_session: Account<'info, GameSession>,
 
+    /// Treasury withdrawals need the native admin multisig, if enabled.
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
     /// The vault account holding the owner revenue. Mutable to update `total_liquidity` and `owner_reward`.
     #[account(
         mut,
-        seeds = [b"vault", token_mint.key().as_ref()],
+        seeds = [VAULT_SEED, token_mint.key().as_ref()],
         bump = vault.bump,
     )]
     pub vault: Account<'info, VaultAccount>,
@@ -578,56 +877,97 @@ pub struct WithdrawOwnerRevenue<'info> {
 }
 
 // =================================================================================================
-// Distribute Payout Reserve
+// Withdraw Owner Revenue Swapped
 // =================================================================================================
 
-pub fn distribute_payout_reserve(ctx: Context<DistributePayoutReserve>) -> Result<()> {
+/// Withdraws the owner fees of a vault opted into the protocol token rake, swapping them through
+/// the whitelisted AMM into the treasury's protocol token account.
+///
+/// The fees are first moved to a token account of the `rake_escrow` PDA, and only that PDA
+/// signs the swap, so the AMM never gets authority over vault funds. The first `approval_count`
+/// remaining accounts are multisig co-signers; `swap_data` and the rest form the AMM
+/// instruction. The escrow must end up fully spent and the treasury must receive at least
+/// `min_amount_out`.
+pub fn withdraw_owner_revenue_swapped<'info>(
+    ctx: Context<'_, '_, 'info, 'info, WithdrawOwnerRevenueSwapped<'info>>,
+    approval_count: u8,
+    min_amount_out: u64,
+    swap_data: Vec<u8>
+) -> Result<()> {
+    require!(
+        (approval_count as usize) <= ctx.remaining_accounts.len(),
+        RouletteError::AdminThresholdNotMet
+    );
+    let (approvals, swap_accounts) = ctx.remaining_accounts.split_at(approval_count as usize);
+    ctx.accounts.config.require_admin_approval(approvals)?;
     let vault = &mut ctx.accounts.vault;
+    require!(vault.rake_in_protocol_token, RouletteError::RakeSwapNotEnabled);
+    require!(!vault.has_owner_shares(), RouletteError::OwnerRevenueShared);
 
-    // 1. Calculate the payout reserve.
-    let payout_reserve = vault.total_liquidity
-        .checked_sub(vault.total_provider_capital)
-        .ok_or(RouletteError::ArithmeticOverflow)?;
-
-    // Ensure there's a reserve to distribute.
-    require!(payout_reserve > 0, RouletteError::NoReward);
+    let reward_amount = vault.owner_reward;
+    require!(reward_amount > 0, RouletteError::NoReward);
+    require!(vault.at_risk_liquidity() >= reward_amount, RouletteError::InsufficientLiquidity);
 
-    // 2. Determine the amount to distribute (50% of the reserve).
-    let amount_to_distribute = payout_reserve
-        .checked_div(2)
-        .ok_or(RouletteError::ArithmeticOverflow)?;
-    require!(amount_to_distribute > 0, RouletteError::NoReward);
+    let escrow_balance_before = ctx.accounts.escrow_token_account.amount;
+    let treasury_balance_before = ctx.accounts.treasury_protocol_token_account.amount;
 
-    // 3. Split the amount 50/50.
-    let owner_share = amount_to_distribute
-        .checked_div(2)
-        .ok_or(RouletteError::ArithmeticOverflow)?;
-    let providers_share = amount_to_distribute
-        .checked_sub(owner_share)
-        .ok_or(RouletteError::ArithmeticOverflow)?; // To avoid dust loss from integer division
+    let seeds = &[VAULT_SEED, vault.token_mint.as_ref(), &[vault.bump]];
+    let signer_seeds = &[&seeds[..]];
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.vault_token_account.to_account_info(),
+                mint: ctx.accounts.token_mint.to_account_info(),
+                to: ctx.accounts.escrow_token_account.to_account_info(),
+                authority: vault.to_account_info(),
+            },
+            signer_seeds
+        ),
+        reward_amount,
+        ctx.accounts.token_mint.decimals,
+    )?;
 
-    // 4. Distribute the shares.
-    // Add to owner's rewards.
-    vault.owner_reward = vault.owner_reward
-        .checked_add(owner_share)
-        .ok_or(RouletteError::ArithmeticOverflow)?;
+    let escrow_key = ctx.accounts.rake_escrow.key();
+    let swap = Instruction {
+        program_id: ctx.accounts.amm_program.key(),
+        accounts: swap_accounts
+            .iter()
+            .map(|info| AccountMeta {
+                pubkey: info.key(),
+                is_signer: info.is_signer || info.key() == escrow_key,
+                is_writable: info.is_writable,
+            })
+            .collect(),
+        data: swap_data,
+    };
+    invoke_signed(
+        &swap,
+        swap_accounts,
+        &[&[RAKE_ESCROW_SEED, &[ctx.bumps.rake_escrow]]]
+    )?;
 
-    // Distribute to providers via the reward index.
-    if vault.total_provider_capital > 0 {
-        let reward_index_increase = (providers_share as u128)
-            .checked_mul(REWARD_PRECISION)
-            .ok_or(RouletteError::ArithmeticOverflow)?
-            .checked_div(vault.total_provider_capital as u128)
-            .ok_or(RouletteError::ArithmeticOverflow)?;
+    ctx.accounts.escrow_token_account.reload()?;
+    ctx.accounts.treasury_protocol_token_account.reload()?;
+    require!(
+        ctx.accounts.escrow_token_account.amount <= escrow_balance_before,
+        RouletteError::RakeSwapIncomplete
+    );
+    let amount_out = ctx.accounts.treasury_protocol_token_account.amount
+        .saturating_sub(treasury_balance_before);
+    require!(amount_out >= min_amount_out, RouletteError::RakeSwapSlippage);
 
-        vault.reward_per_share_index = vault.reward_per_share_index
-            .checked_add(reward_index_increase)
-            .ok_or(RouletteError::ArithmeticOverflow)?;
-    }
+    vault.total_liquidity = vault.total_liquidity
+        .checked_sub(reward_amount)
+        .ok_or(RouletteError::ArithmeticOverflow)?;
+    vault.owner_reward = 0;
 
-    emit!(PayoutReserveDistributed {
+    emit!(OwnerRevenueSwapped {
+        event_seq: ctx.accounts.game_session.next_event_seq(),
         token_mint: vault.token_mint,
-        amount_distributed: amount_to_distribute,
+        amount_in: reward_amount,
+        protocol_token_mint: ctx.accounts.config.protocol_token_mint,
+        amount_out,
         timestamp: Clock::get()?.unix_timestamp,
     });
 
@@ -635,65 +975,1141 @@ pub fn distribute_payout_reserve(ctx: Context<DistributePayoutReserve>) -> Resul
 }
 
 #[derive(Accounts)]
-pub struct DistributePayoutReserve<'info> {
+pub struct WithdrawOwnerRevenueSwapped<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
 
     #[account(
-        seeds = [b"game_session"],
+        mut,
+        seeds = [GAME_SESSION_SEED],
         bump = game_session.bump,
         constraint = authority.key() == game_session.authority @ RouletteError::AdminOnly
     )]
     pub game_session: Account<'info, GameSession>,
 
-    /// The vault account to distribute revenue from.
+    /// Treasury withdrawals need the native admin multisig, if enabled.
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
     #[account(
         mut,
-        seeds = [b"vault", token_mint.key().as_ref()],
+        seeds = [VAULT_SEED, token_mint.key().as_ref()],
         bump = vault.bump,
     )]
     pub vault: Account<'info, VaultAccount>,
 
-    /// The mint account for the token.
+    /// The mint account for the vault's token.
     pub token_mint: InterfaceAccount<'info, Mint>,
+
+    /// The vault's token account.
+    #[account(
+        mut,
+        constraint = vault_token_account.key() == vault.token_account @ RouletteError::VaultMismatch,
+        constraint = vault_token_account.mint == token_mint.key() @ RouletteError::InvalidTokenAccount
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: PDA signing the swap; it holds no data.
+    #[account(seeds = [RAKE_ESCROW_SEED], bump)]
+    pub rake_escrow: UncheckedAccount<'info>,
+
+    /// The escrow's token account the owner fees are swapped from.
+    #[account(
+        mut,
+        constraint = escrow_token_account.owner == rake_escrow.key() @ RouletteError::InvalidTokenAccount,
+        constraint = escrow_token_account.mint == token_mint.key() @ RouletteError::InvalidTokenAccount
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// The treasury's protocol token account receiving the swap output.
+    #[account(
+        mut,
+        constraint = treasury_protocol_token_account.mint == config.protocol_token_mint @ RouletteError::TreasuryAccountMintMismatch,
+        constraint = treasury_protocol_token_account.owner == TREASURY_PUBKEY @ RouletteError::InvalidTreasuryAccountOwner
+    )]
+    pub treasury_protocol_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Must be the AMM whitelisted in `Config`.
+    #[account(
+        executable,
+        constraint = amm_program.key() == config.rake_swap_program @ RouletteError::RakeSwapNotEnabled
+    )]
+    pub amm_program: UncheckedAccount<'info>,
+
+    /// The SPL Token Program, needed for the token transfer CPI.
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 // =================================================================================================
-// Get Unclaimed Rewards (Read-Only via Simulation)
+// Owner Revenue Shares
 // =================================================================================================
 
-pub fn get_unclaimed_rewards(ctx: Context<GetUnclaimedRewards>) -> Result<()> {
-    let vault = &ctx.accounts.vault;
-    let provider_state = &ctx.accounts.provider_state;
-    let current_reward_index = vault.reward_per_share_index;
+/// Splits the vault's owner revenue between up to `MAX_OWNER_SHARES` recipients with fixed
+/// basis-point weights, or back to the treasury alone when every slot is `Pubkey::default()`.
+/// Revenue accrued so far is split under the previous weights first. A recipient that is
+/// dropped must have withdrawn their share.
+pub fn set_owner_shares(
+    ctx: Context<SetOwnerShares>,
+    recipients: [Pubkey; MAX_OWNER_SHARES],
+    weights_bps: [u16; MAX_OWNER_SHARES]
+) -> Result<()> {
+    ctx.accounts.config.require_admin_approval(ctx.remaining_accounts)?;
+
+    let mut total_bps = 0u32;
+    for (i, (recipient, bps)) in recipients.iter().zip(weights_bps).enumerate() {
+        if *recipient == Pubkey::default() {
+            require!(bps == 0, RouletteError::InvalidOwnerShares);
+            continue;
+        }
+        require!(bps > 0 && !recipients[..i].contains(recipient), RouletteError::InvalidOwnerShares);
+        total_bps += bps as u32;
+    }
+    require!(
+        total_bps == 0 || total_bps == BPS_DENOMINATOR as u32,
+        RouletteError::InvalidOwnerShares
+    );
 
-    // Use the helper to calculate rewards earned since the last action.
-    let newly_earned_reward = calculate_newly_earned_rewards(provider_state, current_reward_index)?;
-    
-    // Add them to the already accumulated (but not yet claimed) rewards.
-    let total_unclaimed_rewards = provider_state.unclaimed_rewards
-        .checked_add(newly_earned_reward)
-        .ok_or(RouletteError::ArithmeticOverflow)?;
+    let vault = &mut ctx.accounts.vault;
+    vault.distribute_owner_reward()?;
+
+    let mut owed = [0u64; MAX_OWNER_SHARES];
+    for (recipient, amount) in vault.owner_share_recipients.iter().zip(vault.owner_share_owed) {
+        if amount == 0 {
+            continue;
+        }
+        let slot = recipients
+            .iter()
+            .position(|new_recipient| new_recipient == recipient)
+            .ok_or(RouletteError::OwnerShareOutstanding)?;
+        owed[slot] = amount;
+    }
+    vault.owner_share_recipients = recipients;
+    vault.owner_share_bps = weights_bps;
+    vault.owner_share_owed = owed;
 
-    // Set the return data so the client can read it from the simulation result.
-    set_return_data(&total_unclaimed_rewards.to_le_bytes());
+    emit!(OwnerSharesChanged {
+        event_seq: ctx.accounts.game_session.next_event_seq(),
+        token_mint: vault.token_mint,
+        recipients,
+        weights_bps,
+    });
 
     Ok(())
 }
 
 #[derive(Accounts)]
-pub struct GetUnclaimedRewards<'info> {
-    /// The vault account.
+pub struct SetOwnerShares<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GAME_SESSION_SEED],
+        bump = game_session.bump,
+        constraint = authority.key() == game_session.authority @ RouletteError::AdminOnly
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    /// Redirecting owner revenue needs the native admin multisig, if enabled.
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
     #[account(
-        seeds = [b"vault", token_mint.key().as_ref()],
+        mut,
+        seeds = [VAULT_SEED, token_mint.key().as_ref()],
         bump = vault.bump,
     )]
     pub vault: Account<'info, VaultAccount>,
 
-    /// The provider's state account.
-    #[account(
+    pub token_mint: InterfaceAccount<'info, Mint>,
+}
+
+/// Lets an owner share recipient withdraw their share of the vault's owner revenue, after
+/// splitting whatever accrued since the last split.
+pub fn withdraw_owner_share(ctx: Context<WithdrawOwnerShare>) -> Result<()> {
+    let recipient = ctx.accounts.recipient.key();
+    let vault = &mut ctx.accounts.vault;
+    let slot = vault.owner_share_recipients
+        .iter()
+        .position(|key| *key == recipient && recipient != Pubkey::default())
+        .ok_or(RouletteError::NotOwnerShareRecipient)?;
+    vault.distribute_owner_reward()?;
+
+    let amount = vault.owner_share_owed[slot];
+    require!(amount > 0, RouletteError::NoReward);
+    require!(vault.at_risk_liquidity() >= amount, RouletteError::InsufficientLiquidity);
+
+    let seeds = &[VAULT_SEED, vault.token_mint.as_ref(), &[vault.bump]];
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.vault_token_account.to_account_info(),
+                mint: ctx.accounts.token_mint.to_account_info(),
+                to: ctx.accounts.recipient_token_account.to_account_info(),
+                authority: vault.to_account_info(),
+            },
+            &[&seeds[..]]
+        ),
+        amount,
+        ctx.accounts.token_mint.decimals,
+    )?;
+
+    vault.total_liquidity = vault.total_liquidity
+        .checked_sub(amount)
+        .ok_or(RouletteError::ArithmeticOverflow)?;
+    vault.owner_share_owed[slot] = 0;
+
+    emit!(OwnerShareWithdrawn {
+        event_seq: ctx.accounts.game_session.next_event_seq(),
+        token_mint: vault.token_mint,
+        recipient,
+        amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct WithdrawOwnerShare<'info> {
+    pub recipient: Signer<'info>,
+
+    #[account(mut, seeds = [GAME_SESSION_SEED, game_session.table_seed().as_slice()], bump = game_session.bump)]
+    pub game_session: Account<'info, GameSession>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, token_mint.key().as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Account<'info, VaultAccount>,
+
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    /// The vault's token account.
+    #[account(
+        mut,
+        constraint = vault_token_account.key() == vault.token_account @ RouletteError::VaultMismatch,
+        constraint = vault_token_account.mint == token_mint.key() @ RouletteError::InvalidTokenAccount
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// The recipient's token account receiving the share.
+    #[account(mut, token::mint = token_mint, token::authority = recipient)]
+    pub recipient_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// The SPL Token Program, needed for the token transfer CPI.
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+// =================================================================================================
+// Fund Rent Pool
+// =================================================================================================
+
+/// Tops the `RentPool` up with `amount` of a wrapped-SOL vault's owner fees. The fees move to
+/// a wrapped-SOL token account of the pool, which is then closed into the pool, unwrapping them
+/// into lamports that sponsor player account rent.
+pub fn fund_rent_pool(ctx: Context<FundRentPool>, amount: u64) -> Result<()> {
+    ctx.accounts.config.require_admin_approval(ctx.remaining_accounts)?;
+    let vault = &mut ctx.accounts.vault;
+    require!(amount > 0, RouletteError::AmountMustBeGreaterThanZero);
+    require!(vault.owner_reward >= amount, RouletteError::NoReward);
+    require!(vault.at_risk_liquidity() >= amount, RouletteError::InsufficientLiquidity);
+
+    let seeds = &[VAULT_SEED, vault.token_mint.as_ref(), &[vault.bump]];
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.vault_token_account.to_account_info(),
+                mint: ctx.accounts.token_mint.to_account_info(),
+                to: ctx.accounts.pool_token_account.to_account_info(),
+                authority: vault.to_account_info(),
+            },
+            &[&seeds[..]]
+        ),
+        amount,
+        ctx.accounts.token_mint.decimals,
+    )?;
+
+    let rent_pool = ctx.accounts.rent_pool.to_account_info();
+    token_interface::close_account(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: ctx.accounts.pool_token_account.to_account_info(),
+                destination: rent_pool.clone(),
+                authority: rent_pool.clone(),
+            },
+            &[&[RENT_POOL_SEED, &[ctx.accounts.rent_pool.bump]]]
+        )
+    )?;
+
+    vault.total_liquidity = vault.total_liquidity
+        .checked_sub(amount)
+        .ok_or(RouletteError::ArithmeticOverflow)?;
+    vault.owner_reward -= amount;
+
+    emit!(RentPoolFunded {
+        event_seq: ctx.accounts.game_session.next_event_seq(),
+        token_mint: vault.token_mint,
+        amount,
+        pool_balance: rent_pool.lamports(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct FundRentPool<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GAME_SESSION_SEED],
+        bump = game_session.bump,
+        constraint = authority.key() == game_session.authority @ RouletteError::AdminOnly
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    /// Treasury movements need the native admin multisig, if enabled.
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, token_mint.key().as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Account<'info, VaultAccount>,
+
+    /// The vault's token mint, which must be wrapped SOL.
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    /// The vault's token account.
+    #[account(
+        mut,
+        constraint = vault_token_account.key() == vault.token_account @ RouletteError::VaultMismatch,
+        constraint = vault_token_account.mint == token_mint.key() @ RouletteError::InvalidTokenAccount
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, seeds = [RENT_POOL_SEED], bump = rent_pool.bump)]
+    pub rent_pool: Account<'info, RentPool>,
+
+    /// Wrapped-SOL token account of the rent pool, closed into it once funded.
+    #[account(
+        mut,
+        constraint = pool_token_account.owner == rent_pool.key() @ RouletteError::InvalidTokenAccount,
+        constraint = pool_token_account.mint == token_mint.key() @ RouletteError::InvalidTokenAccount,
+        constraint = pool_token_account.is_native() @ RouletteError::NativeMintRequired
+    )]
+    pub pool_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// The SPL Token Program, needed for the transfer and close CPIs.
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+// =================================================================================================
+// Distribute Payout Reserve
+// =================================================================================================
+
+pub fn distribute_payout_reserve(ctx: Context<DistributePayoutReserve>) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    require!(vault.open_disputes == 0, RouletteError::RoundDisputeOpen);
+
+    let amount_to_distribute = apply_payout_reserve_distribution(vault)?;
+    // Ensure there's a reserve to distribute.
+    require!(amount_to_distribute > 0, RouletteError::NoReward);
+
+    emit!(PayoutReserveDistributed {
+        event_seq: ctx.accounts.game_session.next_event_seq(),
+        token_mint: vault.token_mint,
+        amount_distributed: amount_to_distribute,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Splits 50% of the vault's payout reserve between the owner and the providers.
+/// Returns the amount distributed, or 0 if there was nothing to distribute.
+fn apply_payout_reserve_distribution(vault: &mut VaultAccount) -> Result<u64> {
+    // 1. Calculate the payout reserve. A vault whose liquidity has dipped below the provider
+    // capital has no reserve to distribute.
+    let payout_reserve = vault.total_liquidity.saturating_sub(vault.total_provider_capital);
+
+    // 2. Determine the amount to distribute (50% of the reserve).
+    let amount_to_distribute = payout_reserve
+        .checked_div(2)
+        .ok_or(RouletteError::ArithmeticOverflow)?;
+    if amount_to_distribute == 0 {
+        return Ok(0);
+    }
+
+    // 3. Split the amount 50/50.
+    let owner_share = amount_to_distribute
+        .checked_div(2)
+        .ok_or(RouletteError::ArithmeticOverflow)?;
+    let providers_share = amount_to_distribute
+        .checked_sub(owner_share)
+        .ok_or(RouletteError::ArithmeticOverflow)?; // To avoid dust loss from integer division
+
+    // 4. Distribute the shares.
+    // Part of the owner's share funds loyalty boosts for long-term providers.
+    let loyalty_share = ((owner_share as u128)
+        .checked_mul(vault.loyalty_budget_bps as u128)
+        .ok_or(RouletteError::ArithmeticOverflow)? / (BPS_DENOMINATOR as u128)) as u64;
+    vault.loyalty_budget = vault.loyalty_budget
+        .checked_add(loyalty_share)
+        .ok_or(RouletteError::ArithmeticOverflow)?;
+    // Add the rest to owner's rewards; anything above the vault's cap stays in the reserve.
+    vault.credit_owner_reward(owner_share - loyalty_share)?;
+
+    // Distribute to providers via the reward index.
+    if vault.total_provider_capital > 0 {
+        let reward_index_increase = (providers_share as u128)
+            .checked_mul(REWARD_PRECISION)
+            .ok_or(RouletteError::ArithmeticOverflow)?
+            .checked_div(vault.total_provider_capital as u128)
+            .ok_or(RouletteError::ArithmeticOverflow)?;
+
+        vault.reward_per_share_index = vault.reward_per_share_index
+            .checked_add(reward_index_increase)
+            .ok_or(RouletteError::ArithmeticOverflow)?;
+    }
+
+    Ok(amount_to_distribute)
+}
+
+#[derive(Accounts)]
+pub struct DistributePayoutReserve<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GAME_SESSION_SEED],
+        bump = game_session.bump,
+        constraint = authority.key() == game_session.authority @ RouletteError::AdminOnly
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    /// The vault account to distribute revenue from.
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, token_mint.key().as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Account<'info, VaultAccount>,
+
+    /// The mint account for the token.
+    pub token_mint: InterfaceAccount<'info, Mint>,
+}
+
+// =================================================================================================
+// Distribute All Reserves (Batched)
+// =================================================================================================
+
+/// Applies the payout reserve distribution to every vault passed in `remaining_accounts`.
+/// Vaults without a distributable reserve, or with an open dispute, are skipped rather than
+/// failing the whole batch.
+pub fn distribute_all_reserves<'info>(
+    ctx: Context<'_, '_, 'info, 'info, DistributeAllReserves<'info>>
+) -> Result<()> {
+    require!(!ctx.remaining_accounts.is_empty(), RouletteError::NoReward);
+    let timestamp = Clock::get()?.unix_timestamp;
+
+    for vault_info in ctx.remaining_accounts.iter() {
+        require!(vault_info.is_writable, RouletteError::VaultPDAMismatch);
+        let mut vault = Account::<VaultAccount>::try_from(vault_info)?;
+
+        let expected_vault = Pubkey::create_program_address(
+            &[VAULT_SEED, vault.token_mint.as_ref(), &[vault.bump]],
+            ctx.program_id
+        ).map_err(|_| RouletteError::VaultPDAMismatch)?;
+        require_keys_eq!(expected_vault, vault_info.key(), RouletteError::VaultPDAMismatch);
+        if vault.open_disputes > 0 {
+            continue;
+        }
+
+        let amount_to_distribute = apply_payout_reserve_distribution(&mut vault)?;
+        if amount_to_distribute == 0 {
+            continue;
+        }
+        vault.exit(ctx.program_id)?;
+
+        emit!(PayoutReserveDistributed {
+            event_seq: ctx.accounts.game_session.next_event_seq(),
+            token_mint: vault.token_mint,
+            amount_distributed: amount_to_distribute,
+            timestamp,
+        });
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct DistributeAllReserves<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GAME_SESSION_SEED],
+        bump = game_session.bump,
+        constraint = authority.key() == game_session.authority @ RouletteError::AdminOnly
+    )]
+    pub game_session: Account<'info, GameSession>,
+    // The vaults to distribute are passed as writable `remaining_accounts`.
+}
+
+// =================================================================================================
+// Repay Payout Debt
+// =================================================================================================
+
+/// Pays down the vault's outstanding `PayoutDebt` records, oldest first, with up to `amount`
+/// tokens from the repayer. Anyone may repay; the tokens go straight to each creditor.
+///
+/// `remaining_accounts` are triples of `[payout_debt, creditor_token_account, creditor]`
+/// starting at `vault.debt_head`. Fully repaid records are closed and their rent returned to
+/// the creditor who paid it.
+pub fn repay_payout_debt<'info>(
+    ctx: Context<'_, '_, 'info, 'info, RepayPayoutDebt<'info>>,
+    amount: u64
+) -> Result<()> {
+    require!(amount > 0, RouletteError::NoOutstandingDebt);
+    require!(
+        !ctx.remaining_accounts.is_empty() && ctx.remaining_accounts.len() % 3 == 0,
+        RouletteError::NoOutstandingDebt
+    );
+    let vault = &mut ctx.accounts.vault;
+    let vault_key = vault.key();
+    let repayer_key = ctx.accounts.repayer.key();
+    let timestamp = Clock::get()?.unix_timestamp;
+    let mut remaining = amount;
+
+    for accounts in ctx.remaining_accounts.chunks(3) {
+        if remaining == 0 {
+            break;
+        }
+        let (debt_info, creditor_token_info, creditor_info) = (&accounts[0], &accounts[1], &accounts[2]);
+        require!(debt_info.is_writable, RouletteError::PayoutDebtOutOfOrder);
+        let mut debt = Account::<PayoutDebt>::try_from(debt_info)?;
+
+        let (expected_debt, _) = Pubkey::find_program_address(
+            &[PAYOUT_DEBT_SEED, vault_key.as_ref(), &vault.debt_head.to_le_bytes()],
+            ctx.program_id
+        );
+        require_keys_eq!(expected_debt, debt_info.key(), RouletteError::PayoutDebtOutOfOrder);
+        require_keys_eq!(creditor_info.key(), debt.player, RouletteError::Unauthorized);
+
+        let creditor_token_account = InterfaceAccount::<TokenAccount>::try_from(creditor_token_info)?;
+        require_keys_eq!(
+            creditor_token_account.mint,
+            vault.token_mint,
+            RouletteError::InvalidTokenAccount
+        );
+        require_keys_eq!(
+            creditor_token_account.owner,
+            debt.player,
+            RouletteError::InvalidTokenAccountOwner
+        );
+
+        let repayment = remaining.min(debt.amount_owed);
+        token_interface::transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.repayer_token_account.to_account_info(),
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                    to: creditor_token_account.to_account_info(),
+                    authority: ctx.accounts.repayer.to_account_info(),
+                },
+            ),
+            repayment,
+            ctx.accounts.token_mint.decimals,
+        )?;
+
+        remaining -= repayment;
+        debt.amount_owed -= repayment;
+        vault.outstanding_debt = vault.outstanding_debt
+            .checked_sub(repayment)
+            .ok_or(RouletteError::ArithmeticOverflow)?;
+
+        emit!(PayoutDebtRepaid {
+            event_seq: ctx.accounts.game_session.next_event_seq(),
+            token_mint: vault.token_mint,
+            player: debt.player,
+            repayer: repayer_key,
+            debt_index: debt.index,
+            amount: repayment,
+            remaining_owed: debt.amount_owed,
+            timestamp,
+        });
+
+        if debt.amount_owed == 0 {
+            vault.debt_head = vault.debt_head
+                .checked_add(1)
+                .ok_or(RouletteError::ArithmeticOverflow)?;
+            debt.close(creditor_info.clone())?;
+        } else {
+            debt.exit(ctx.program_id)?;
+        }
+    }
+
+    require!(remaining < amount, RouletteError::NoOutstandingDebt);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RepayPayoutDebt<'info> {
+    #[account(mut, seeds = [GAME_SESSION_SEED, game_session.table_seed().as_slice()], bump = game_session.bump)]
+    pub game_session: Account<'info, GameSession>,
+
+    /// Anyone may repay, typically the treasury.
+    pub repayer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, token_mint.key().as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Account<'info, VaultAccount>,
+
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = repayer_token_account.mint == token_mint.key() @ RouletteError::InvalidTokenAccount,
+        constraint = repayer_token_account.owner == repayer.key() @ RouletteError::InvalidTokenAccountOwner
+    )]
+    pub repayer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    // The debt records to repay are passed as `remaining_accounts` triples.
+}
+
+// =================================================================================================
+// Assert Vault Invariants
+// =================================================================================================
+
+/// Permissionless canary keepers can run each round. Emits `VaultInvariantViolated` for every
+/// broken invariant and then fails, so the events only show up in the failed transaction's logs.
+pub fn assert_vault_invariants(ctx: Context<AssertVaultInvariants>) -> Result<()> {
+    let vault = &ctx.accounts.vault;
+    let violations = vault.invariant_violations(ctx.accounts.vault_token_account.amount);
+    if violations.is_empty() {
+        return Ok(());
+    }
+
+    let timestamp = Clock::get()?.unix_timestamp;
+    for violation in violations {
+        emit!(VaultInvariantViolated {
+            event_seq: ctx.accounts.game_session.next_event_seq(),
+            token_mint: vault.token_mint,
+            invariant: violation.invariant,
+            required: violation.required,
+            actual: violation.actual,
+            timestamp,
+        });
+    }
+    err!(RouletteError::VaultInvariantViolated)
+}
+
+#[derive(Accounts)]
+pub struct AssertVaultInvariants<'info> {
+    #[account(mut, seeds = [GAME_SESSION_SEED, game_session.table_seed().as_slice()], bump = game_session.bump)]
+    pub game_session: Account<'info, GameSession>,
+
+    #[account(
+        seeds = [VAULT_SEED, vault.token_mint.as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(
+        constraint = vault_token_account.key() == vault.token_account @ RouletteError::InvalidTokenAccount
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+}
+
+// =================================================================================================
+// Update Vault Config
+// =================================================================================================
+
+pub fn update_vault_config(ctx: Context<UpdateVaultConfig>, update: VaultConfigUpdate) -> Result<()> {
+    ctx.accounts.config.require_admin_approval(ctx.remaining_accounts)?;
+    let vault = &mut ctx.accounts.vault;
+
+    if let Some(owner_reward_cap) = update.owner_reward_cap {
+        vault.owner_reward_cap = owner_reward_cap;
+    }
+    if let Some(large_withdrawal_bps) = update.large_withdrawal_bps {
+        require!(
+            (large_withdrawal_bps as u64) <= BPS_DENOMINATOR,
+            RouletteError::InvalidBasisPoints
+        );
+        vault.large_withdrawal_bps = large_withdrawal_bps;
+    }
+    if let Some(chip_size) = update.chip_size {
+        vault.chip_size = chip_size;
+    }
+    if let Some(rake_in_protocol_token) = update.rake_in_protocol_token {
+        vault.rake_in_protocol_token = rake_in_protocol_token;
+    }
+    if let Some(loyalty_budget_bps) = update.loyalty_budget_bps {
+        require!(
+            (loyalty_budget_bps as u64) <= BPS_DENOMINATOR,
+            RouletteError::InvalidBasisPoints
+        );
+        vault.loyalty_budget_bps = loyalty_budget_bps;
+    }
+    if let Some(loyalty_max_boost_bps) = update.loyalty_max_boost_bps {
+        vault.loyalty_max_boost_bps = loyalty_max_boost_bps;
+    }
+    if let Some(loyalty_maturity_secs) = update.loyalty_maturity_secs {
+        vault.loyalty_maturity_secs = loyalty_maturity_secs;
+    }
+    if let Some(strategy_max_bps) = update.strategy_max_bps {
+        require!(
+            (strategy_max_bps as u64) <= BPS_DENOMINATOR,
+            RouletteError::InvalidBasisPoints
+        );
+        vault.strategy_max_bps = strategy_max_bps;
+    }
+    if let Some(confidential_payouts) = update.confidential_payouts {
+        vault.confidential_payouts = confidential_payouts;
+    }
+    if let Some(max_player_payout) = update.max_player_payout {
+        vault.max_player_payout = max_player_payout;
+    }
+    if let Some(crank_reward) = update.crank_reward {
+        vault.crank_reward = crank_reward;
+    }
+    if let Some(table_maximum) = update.table_maximum {
+        vault.table_maximum = table_maximum;
+    }
+    if let Some(max_providers) = update.max_providers {
+        vault.max_providers = max_providers;
+    }
+    if let Some(large_bet_threshold) = update.large_bet_threshold {
+        vault.large_bet_threshold = large_bet_threshold;
+    }
+    if let Some(large_win_threshold) = update.large_win_threshold {
+        vault.large_win_threshold = large_win_threshold;
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateVaultConfig<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [GAME_SESSION_SEED],
+        bump = game_session.bump,
+        constraint = authority.key() == game_session.authority @ RouletteError::AdminOnly
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    /// Fee and limit changes need the native admin multisig, if enabled.
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    /// The vault whose parameters are updated.
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, token_mint.key().as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Account<'info, VaultAccount>,
+
+    /// The mint account for the vault's token.
+    pub token_mint: InterfaceAccount<'info, Mint>,
+}
+
+// =================================================================================================
+// Vault Round Participation
+// =================================================================================================
+
+/// Opts the vault out of (or back into) betting without a global pause. Opting out takes effect
+/// from the next round, so a round already in progress keeps its backing; claims and LP
+/// withdrawals are unaffected.
+pub fn set_vault_round_participation(
+    ctx: Context<SetVaultRoundParticipation>,
+    participating: bool
+) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let next_round = ctx.accounts.game_session.current_round
+        .checked_add(1)
+        .ok_or(RouletteError::ArithmeticOverflow)?;
+
+    vault.opted_out_from_round = if participating { 0 } else { next_round };
+
+    emit!(VaultParticipationChanged {
+        event_seq: ctx.accounts.game_session.next_event_seq(),
+        token_mint: vault.token_mint,
+        participating,
+        effective_round: next_round,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetVaultRoundParticipation<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GAME_SESSION_SEED],
+        bump = game_session.bump,
+        constraint = authority.key() == game_session.authority @ RouletteError::AdminOnly
+    )]
+    pub game_session: Account<'info, GameSession>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, token_mint.key().as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Account<'info, VaultAccount>,
+
+    /// The mint account for the vault's token.
+    pub token_mint: InterfaceAccount<'info, Mint>,
+}
+
+// =================================================================================================
+// Liquidity Provider Referrals
+// =================================================================================================
+
+/// Registers the referrer of a liquidity provider. Only rewards accrued from now on are shared,
+/// and the referrer cannot be changed afterwards.
+pub fn set_lp_referrer(ctx: Context<SetLpReferrer>, referrer: Pubkey) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let provider_state = &mut ctx.accounts.provider_state;
+
+    require!(provider_state.referrer.is_none(), RouletteError::ReferrerAlreadySet);
+    require!(referrer != provider_state.provider, RouletteError::SelfReferral);
+
+    // Settle everything earned so far before the split starts applying.
+    let current_reward_index = vault.reward_per_share_index;
+    accrue_rewards(provider_state, vault, Clock::get()?.unix_timestamp)?;
+    provider_state.reward_per_share_index_last_claimed = current_reward_index;
+    provider_state.referrer = Some(referrer);
+
+    emit!(LpReferrerSet {
+        event_seq: ctx.accounts.game_session.next_event_seq(),
+        provider: provider_state.provider,
+        referrer,
+        token_mint: vault.token_mint,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetLpReferrer<'info> {
+    #[account(mut, seeds = [GAME_SESSION_SEED, game_session.table_seed().as_slice()], bump = game_session.bump)]
+    pub game_session: Account<'info, GameSession>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, token_mint.key().as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Account<'info, VaultAccount>,
+
+    #[account(
+        mut,
+        constraint = provider_state.vault == vault.key() @ RouletteError::VaultMismatch,
+        constraint = provider_state.is_controlled_by(&liquidity_provider.key(), position_asset.as_deref()) @ RouletteError::Unauthorized,
+        seeds = [PROVIDER_STATE_SEED, vault.key().as_ref(), provider_state.provider.as_ref()],
+        bump = provider_state.bump
+    )]
+    pub provider_state: Account<'info, ProviderState>,
+
+    /// CHECK: Asset of the position, required only while it is wrapped; checked by
+    /// `is_controlled_by`.
+    pub position_asset: Option<UncheckedAccount<'info>>,
+
+    /// The mint account for the vault's token.
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    pub liquidity_provider: Signer<'info>,
+}
+
+/// Pays a referrer their accrued share of one referred provider's rewards.
+pub fn claim_lp_referral_rewards(ctx: Context<ClaimLpReferralRewards>) -> Result<()> {
+    // Referral payouts bypass pause checks by design; no pause state may block them.
+    let vault = &mut ctx.accounts.vault;
+    let provider_state = &mut ctx.accounts.provider_state;
+    let current_reward_index = vault.reward_per_share_index;
+
+    accrue_rewards(provider_state, vault, Clock::get()?.unix_timestamp)?;
+    provider_state.reward_per_share_index_last_claimed = current_reward_index;
+
+    let amount = provider_state.referral_rewards;
+    require!(amount > 0, RouletteError::NoReward);
+    require!(vault.at_risk_liquidity() >= amount, RouletteError::InsufficientLiquidity);
+
+    let seeds = &[VAULT_SEED, vault.token_mint.as_ref(), &[vault.bump]];
+    let signer_seeds = &[&seeds[..]];
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.vault_token_account.to_account_info(),
+                mint: ctx.accounts.token_mint.to_account_info(),
+                to: ctx.accounts.referrer_token_account.to_account_info(),
+                authority: vault.to_account_info(),
+            },
+            signer_seeds
+        ),
+        amount,
+        ctx.accounts.token_mint.decimals,
+    )?;
+
+    vault.total_liquidity = vault.total_liquidity
+        .checked_sub(amount)
+        .ok_or(RouletteError::ArithmeticOverflow)?;
+    provider_state.referral_rewards = 0;
+
+    emit!(LpReferralRewardsClaimed {
+        event_seq: ctx.accounts.game_session.next_event_seq(),
+        referrer: ctx.accounts.referrer.key(),
+        provider: provider_state.provider,
+        token_mint: vault.token_mint,
+        amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClaimLpReferralRewards<'info> {
+    #[account(mut, seeds = [GAME_SESSION_SEED, game_session.table_seed().as_slice()], bump = game_session.bump)]
+    pub game_session: Account<'info, GameSession>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, token_mint.key().as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Account<'info, VaultAccount>,
+
+    /// The referred provider's state account.
+    #[account(
+        mut,
+        constraint = provider_state.vault == vault.key() @ RouletteError::VaultMismatch,
+        constraint = provider_state.referrer == Some(referrer.key()) @ RouletteError::Unauthorized,
+        seeds = [PROVIDER_STATE_SEED, vault.key().as_ref(), provider_state.provider.as_ref()],
+        bump = provider_state.bump
+    )]
+    pub provider_state: Account<'info, ProviderState>,
+
+    /// The mint account for the vault's token.
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    /// The referrer's token account to receive the rewards.
+    #[account(
+        mut,
+        constraint = referrer_token_account.mint == token_mint.key() @ RouletteError::InvalidTokenAccount,
+        constraint = referrer_token_account.key() != vault_token_account.key() @ RouletteError::DuplicateTokenAccount
+    )]
+    pub referrer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// The vault's token account.
+    #[account(
+        mut,
+        constraint = vault_token_account.key() == vault.token_account @ RouletteError::VaultMismatch,
+        constraint = vault_token_account.mint == token_mint.key() @ RouletteError::InvalidTokenAccount
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub referrer: Signer<'info>,
+
+    /// The SPL Token Program, needed for the token transfer CPI.
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+// =================================================================================================
+// Vault and Provider Migration
+// =================================================================================================
+
+/// Upgrades a vault in place to the current layout after a program upgrade added fields to
+/// `VaultAccount`: the account is grown at the payer's expense and the new fields are
+/// backfilled. Anyone can migrate a vault; it must be current before its positions are
+/// migrated with `migrate_provider_state`.
+pub fn migrate_vault(ctx: Context<MigrateVault>) -> Result<()> {
+    let info = ctx.accounts.vault.to_account_info();
+    let new_len = 8 + std::mem::size_of::<VaultAccount>();
+    if info.data_len() < new_len {
+        grow_account(
+            &info,
+            new_len,
+            RentPayer::Cranker,
+            None,
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.system_program
+        )?;
+    }
+
+    let mut data = info.try_borrow_mut_data()?;
+    let mut vault = VaultAccount::try_deserialize(&mut &data[..])?;
+    let from_layout_version = vault.layout_version;
+    require!(from_layout_version < VAULT_LAYOUT_VERSION, RouletteError::VaultAlreadyMigrated);
+    vault.backfill_layout(from_layout_version, ctx.accounts.token_mint.decimals);
+    vault.layout_version = VAULT_LAYOUT_VERSION;
+
+    emit!(VaultMigrated {
+        vault: info.key(),
+        from_layout_version,
+        layout_version: vault.layout_version,
+    });
+
+    vault.try_serialize(&mut &mut data[..])?;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigrateVault<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: An outdated vault does not deserialize until it has been grown; its address is
+    /// checked by the seeds.
+    #[account(mut, owner = crate::ID, seeds = [VAULT_SEED, token_mint.key().as_ref()], bump)]
+    pub vault: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Upgrades a liquidity position in place to the current layout, like `migrate_vault`. Its
+/// vault must already be current.
+pub fn migrate_provider_state(ctx: Context<MigrateProviderState>) -> Result<()> {
+    let info = ctx.accounts.provider_state.to_account_info();
+    let new_len = 8 + std::mem::size_of::<ProviderState>();
+    if info.data_len() < new_len {
+        grow_account(
+            &info,
+            new_len,
+            RentPayer::Cranker,
+            None,
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.system_program
+        )?;
+    }
+
+    let mut data = info.try_borrow_mut_data()?;
+    let mut provider_state = ProviderState::try_deserialize(&mut &data[..])?;
+    let from_layout_version = provider_state.layout_version;
+    require!(
+        from_layout_version < PROVIDER_STATE_LAYOUT_VERSION,
+        RouletteError::ProviderStateAlreadyMigrated
+    );
+    provider_state.backfill_layout(from_layout_version, Clock::get()?.unix_timestamp);
+    provider_state.layout_version = PROVIDER_STATE_LAYOUT_VERSION;
+    // Original vaults did not count their providers, so each of their open positions is counted
+    // as it is migrated, which it must be before it can be used.
+    if from_layout_version < 1 {
+        let vault = &mut ctx.accounts.vault;
+        vault.provider_count = vault.provider_count.saturating_add(1);
+    }
+
+    emit!(ProviderStateMigrated {
+        vault: provider_state.vault,
+        provider: provider_state.provider,
+        from_layout_version,
+        layout_version: provider_state.layout_version,
+    });
+
+    provider_state.try_serialize(&mut &mut data[..])?;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigrateProviderState<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Counts the position if it predates provider counting.
+    #[account(mut)]
+    pub vault: Account<'info, VaultAccount>,
+
+    /// CHECK: Only its key is used, to derive the position's address.
+    pub provider: UncheckedAccount<'info>,
+
+    /// CHECK: An outdated position does not deserialize until it has been grown; its address is
+    /// checked by the seeds.
+    #[account(
+        mut,
+        owner = crate::ID,
+        seeds = [PROVIDER_STATE_SEED, vault.key().as_ref(), provider.key().as_ref()],
+        bump
+    )]
+    pub provider_state: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// =================================================================================================
+// Get Unclaimed Rewards (Read-Only via Simulation)
+// =================================================================================================
+
+pub fn get_unclaimed_rewards(ctx: Context<GetUnclaimedRewards>) -> Result<()> {
+    let total_unclaimed_rewards = pending_rewards(
+        &ctx.accounts.vault,
+        &ctx.accounts.provider_state,
+        Clock::get()?.unix_timestamp
+    )?;
+
+    // Set the return data so the client can read it from the simulation result.
+    set_return_data(&total_unclaimed_rewards.to_le_bytes());
+
+    Ok(())
+}
+
+/// Rewards `withdraw_provider_revenue` would pay out at `now`: those already accumulated plus
+/// those earned since the last checkpoint, including the loyalty boost.
+fn pending_rewards(vault: &VaultAccount, provider_state: &ProviderState, now: i64) -> Result<u64> {
+    // Use the helper to calculate rewards earned since the last action.
+    let (newly_earned_reward, _) = split_newly_earned_rewards(provider_state, vault.reward_per_share_index)?;
+    let loyalty_boost = vault.loyalty_boost(
+        newly_earned_reward,
+        now.saturating_sub(provider_state.deposit_timestamp)
+    )?;
+
+    // Add them to the already accumulated (but not yet claimed) rewards.
+    provider_state.unclaimed_rewards
+        .checked_add(newly_earned_reward)
+        .and_then(|rewards| rewards.checked_add(loyalty_boost))
+        .ok_or(RouletteError::ArithmeticOverflow.into())
+}
+
+#[derive(Accounts)]
+pub struct GetUnclaimedRewards<'info> {
+    /// The vault account.
+    #[account(
+        seeds = [VAULT_SEED, token_mint.key().as_ref()],
+        bump = vault.bump,
+    )]
+    pub vault: Account<'info, VaultAccount>,
+
+    /// The provider's state account.
+    #[account(
         constraint = provider_state.vault == vault.key() @ RouletteError::VaultMismatch,
-        seeds = [b"provider_state", vault.key().as_ref(), provider.key().as_ref()],
+        seeds = [PROVIDER_STATE_SEED, vault.key().as_ref(), provider.key().as_ref()],
         bump = provider_state.bump
     )]
     pub provider_state: Account<'info, ProviderState>,
@@ -706,6 +2122,84 @@ pub struct GetUnclaimedRewards<'info> {
     pub provider: UncheckedAccount<'info>,
 }
 
+// =================================================================================================
+// Get Provider Portfolio (Read-Only via Simulation)
+// =================================================================================================
+
+/// Batch version of `get_unclaimed_rewards` for LP dashboards. `remaining_accounts` holds
+/// `(ProviderState, VaultAccount)` pairs, all belonging to `provider`; the return data is a
+/// Borsh `Vec<u64>` of pending rewards in the same order, so one simulation covers every vault.
+pub fn get_provider_portfolio<'info>(
+    ctx: Context<'_, '_, 'info, 'info, GetProviderPortfolio<'info>>
+) -> Result<()> {
+    let positions = ctx.remaining_accounts;
+    require!(
+        !positions.is_empty() && positions.len() % 2 == 0,
+        RouletteError::InvalidPortfolioAccounts
+    );
+    let now = Clock::get()?.unix_timestamp;
+
+    let mut pending = Vec::with_capacity(positions.len() / 2);
+    for pair in positions.chunks_exact(2) {
+        let provider_state = Account::<ProviderState>::try_from(&pair[0])?;
+        let vault = Account::<VaultAccount>::try_from(&pair[1])?;
+        require_keys_eq!(provider_state.vault, vault.key(), RouletteError::VaultMismatch);
+        require_keys_eq!(
+            provider_state.provider,
+            ctx.accounts.provider.key(),
+            RouletteError::InvalidPortfolioAccounts
+        );
+        pending.push(pending_rewards(&vault, &provider_state, now)?);
+    }
+
+    set_return_data(&pending.try_to_vec()?);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct GetProviderPortfolio<'info> {
+    /// CHECK: The provider whose positions are read. No signature is required as this is a
+    /// read-only function; every passed `ProviderState` must belong to it.
+    pub provider: UncheckedAccount<'info>,
+}
+
+/// Splits the rewards earned since the last checkpoint into the provider's part and the
+/// referrer's `LP_REFERRAL_BPS` slice.
+fn split_newly_earned_rewards(
+    provider_state: &ProviderState,
+    current_reward_index: u128
+) -> Result<(u64, u64)> {
+    let newly_earned_reward = calculate_newly_earned_rewards(provider_state, current_reward_index)?;
+    let referral_share = if provider_state.referrer.is_some() {
+        ((newly_earned_reward as u128)
+            .checked_mul(LP_REFERRAL_BPS as u128)
+            .ok_or(RouletteError::ArithmeticOverflow)? / (BPS_DENOMINATOR as u128)) as u64
+    } else {
+        0
+    };
+    Ok((newly_earned_reward - referral_share, referral_share))
+}
+
+/// Moves rewards earned since the last checkpoint into the provider's and referrer's balances,
+/// plus the provider's loyalty boost out of the vault's budget. The caller updates the checkpoint.
+fn accrue_rewards(provider_state: &mut ProviderState, vault: &mut VaultAccount, now: i64) -> Result<()> {
+    let (newly_earned_reward, referral_share) =
+        split_newly_earned_rewards(provider_state, vault.reward_per_share_index)?;
+    let loyalty_boost = vault.loyalty_boost(
+        newly_earned_reward,
+        now.saturating_sub(provider_state.deposit_timestamp)
+    )?;
+    vault.loyalty_budget -= loyalty_boost;
+    provider_state.unclaimed_rewards = provider_state.unclaimed_rewards
+        .checked_add(newly_earned_reward)
+        .and_then(|rewards| rewards.checked_add(loyalty_boost))
+        .ok_or(RouletteError::ArithmeticOverflow)?;
+    provider_state.referral_rewards = provider_state.referral_rewards
+        .checked_add(referral_share)
+        .ok_or(RouletteError::ArithmeticOverflow)?;
+    Ok(())
+}
+
 // A private helper function to calculate rewards without modifying state.
 fn calculate_newly_earned_rewards(
     provider_state: &ProviderState,
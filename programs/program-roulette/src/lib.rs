@@ -1,15 +1,20 @@
 use anchor_lang::prelude::*;
 
 // 1. Declare all our modules
+#[cfg(not(target_os = "solana"))]
+pub mod client;
 pub mod constants;
+#[cfg(not(target_os = "solana"))]
+pub mod display;
 pub mod errors;
 pub mod events;
 pub mod instructions;
+pub mod seeds;
 pub mod state;
 
 // 2. Make everything from them accessible
 use instructions::*;
-use state::Bet; // Needed for the place_bet function signature
+use state::{Bet, RandomnessMode, RentPayer, VaultConfigUpdate, WheelType}; // Needed for instruction signatures
 
 #[cfg(not(feature = "no-entrypoint"))]
 solana_security_txt::security_txt! {
@@ -24,71 +29,560 @@ solana_security_txt::security_txt! {
 
 declare_id!("Rou1svrgkcuo1rBNkP1XaESrD9xRpukx2uLY5MsgK14");
 
-#[program]
-pub mod program_roulette {
+// `#[program]` emits its IDL handlers next to the program module, and they still call the
+// deprecated `AccountInfo::realloc`. Wrapping it keeps the allowance off the rest of the crate.
+#[allow(deprecated)]
+mod handlers {
     use super::*;
 
-    // ========== VAULT INSTRUCTIONS ==========
-    pub fn initialize_and_provide_liquidity(ctx: Context<InitializeAndProvideLiquidity>, amount: u64) -> Result<()> {
-        instructions::vault::initialize_and_provide_liquidity(ctx, amount)
-    }
+    #[program]
+    pub mod program_roulette {
+        use super::*;
 
-    pub fn provide_liquidity(ctx: Context<ProvideLiquidity>, amount: u64) -> Result<()> {
-        instructions::vault::provide_liquidity(ctx, amount)
-    }
+        // ========== VAULT INSTRUCTIONS ==========
+        pub fn initialize_and_provide_liquidity(ctx: Context<InitializeAndProvideLiquidity>, amount: u64) -> Result<()> {
+            instructions::vault::initialize_and_provide_liquidity(ctx, amount)
+        }
 
-    pub fn withdraw_liquidity(ctx: Context<WithdrawLiquidity>) -> Result<()> {
-        instructions::vault::withdraw_liquidity(ctx)
-    }
+        pub fn migrate_vault(ctx: Context<MigrateVault>) -> Result<()> {
+            instructions::vault::migrate_vault(ctx)
+        }
 
-    pub fn withdraw_provider_revenue(ctx: Context<WithdrawProviderRevenue>) -> Result<()> {
-        instructions::vault::withdraw_provider_revenue(ctx)
-    }
+        pub fn migrate_provider_state(ctx: Context<MigrateProviderState>) -> Result<()> {
+            instructions::vault::migrate_provider_state(ctx)
+        }
 
-    pub fn withdraw_owner_revenue(ctx: Context<WithdrawOwnerRevenue>) -> Result<()> {
-        instructions::vault::withdraw_owner_revenue(ctx)
-    }
+        pub fn provide_liquidity(ctx: Context<ProvideLiquidity>, amount: u64) -> Result<()> {
+            instructions::vault::provide_liquidity(ctx, amount)
+        }
 
-    pub fn distribute_payout_reserve(ctx: Context<DistributePayoutReserve>) -> Result<()> {
-        instructions::vault::distribute_payout_reserve(ctx)
-    }
+        pub fn withdraw_liquidity(ctx: Context<WithdrawLiquidity>) -> Result<()> {
+            instructions::vault::withdraw_liquidity(ctx)
+        }
 
-    // ========== GAME INSTRUCTIONS ==========
-    pub fn initialize_game_session(ctx: Context<InitializeGameSession>) -> Result<()> {
-        instructions::game::initialize_game_session(ctx)
-    }
+        pub fn request_large_withdrawal(ctx: Context<RequestLargeWithdrawal>) -> Result<()> {
+            instructions::vault::request_large_withdrawal(ctx)
+        }
 
-    pub fn start_new_round(ctx: Context<StartNewRound>) -> Result<()> {
-        instructions::game::start_new_round(ctx)
-    }
+        pub fn withdraw_provider_revenue(ctx: Context<WithdrawProviderRevenue>) -> Result<()> {
+            instructions::vault::withdraw_provider_revenue(ctx)
+        }
 
-    pub fn close_bets(ctx: Context<CloseBets>) -> Result<()> {
-        instructions::game::close_bets(ctx)
-    }
+        pub fn withdraw_owner_revenue(ctx: Context<WithdrawOwnerRevenue>) -> Result<()> {
+            instructions::vault::withdraw_owner_revenue(ctx)
+        }
 
-    pub fn get_random(ctx: Context<GetRandom>) -> Result<()> {
-        instructions::game::get_random(ctx)
-    }
+        pub fn withdraw_owner_revenue_swapped<'info>(
+            ctx: Context<'_, '_, 'info, 'info, WithdrawOwnerRevenueSwapped<'info>>,
+            approval_count: u8,
+            min_amount_out: u64,
+            swap_data: Vec<u8>
+        ) -> Result<()> {
+            instructions::vault::withdraw_owner_revenue_swapped(ctx, approval_count, min_amount_out, swap_data)
+        }
 
-    // ========== PLAYER INSTRUCTIONS ==========
-    pub fn initialize_player_bets(ctx: Context<InitializePlayerBets>) -> Result<()> {
-        instructions::player::initialize_player_bets(ctx)
-    }
+        pub fn set_owner_shares(
+            ctx: Context<SetOwnerShares>,
+            recipients: [Pubkey; constants::MAX_OWNER_SHARES],
+            weights_bps: [u16; constants::MAX_OWNER_SHARES]
+        ) -> Result<()> {
+            instructions::vault::set_owner_shares(ctx, recipients, weights_bps)
+        }
 
-    pub fn close_player_bets_account(ctx: Context<ClosePlayerBetsAccount>) -> Result<()> {
-        instructions::player::close_player_bets_account(ctx)
-    }
+        pub fn withdraw_owner_share(ctx: Context<WithdrawOwnerShare>) -> Result<()> {
+            instructions::vault::withdraw_owner_share(ctx)
+        }
 
-    pub fn place_bet(ctx: Context<PlaceBets>, bet: Bet) -> Result<()> {
-        instructions::player::place_bet(ctx, bet)
-    }
+        pub fn fund_rent_pool(ctx: Context<FundRentPool>, amount: u64) -> Result<()> {
+            instructions::vault::fund_rent_pool(ctx, amount)
+        }
 
-    pub fn claim_my_winnings(ctx: Context<ClaimMyWinnings>, round_to_claim: u64) -> Result<()> {
-        instructions::player::claim_my_winnings(ctx, round_to_claim)
-    }
+        pub fn distribute_payout_reserve(ctx: Context<DistributePayoutReserve>) -> Result<()> {
+            instructions::vault::distribute_payout_reserve(ctx)
+        }
+
+        pub fn assert_vault_invariants(ctx: Context<AssertVaultInvariants>) -> Result<()> {
+            instructions::vault::assert_vault_invariants(ctx)
+        }
+
+        pub fn distribute_all_reserves<'info>(
+            ctx: Context<'_, '_, 'info, 'info, DistributeAllReserves<'info>>
+        ) -> Result<()> {
+            instructions::vault::distribute_all_reserves(ctx)
+        }
+
+        pub fn repay_payout_debt<'info>(
+            ctx: Context<'_, '_, 'info, 'info, RepayPayoutDebt<'info>>,
+            amount: u64
+        ) -> Result<()> {
+            instructions::vault::repay_payout_debt(ctx, amount)
+        }
+
+        pub fn update_vault_config(ctx: Context<UpdateVaultConfig>, update: VaultConfigUpdate) -> Result<()> {
+            instructions::vault::update_vault_config(ctx, update)
+        }
+
+        pub fn set_vault_round_participation(
+            ctx: Context<SetVaultRoundParticipation>,
+            participating: bool
+        ) -> Result<()> {
+            instructions::vault::set_vault_round_participation(ctx, participating)
+        }
+
+        pub fn set_lp_referrer(ctx: Context<SetLpReferrer>, referrer: Pubkey) -> Result<()> {
+            instructions::vault::set_lp_referrer(ctx, referrer)
+        }
+
+        pub fn claim_lp_referral_rewards(ctx: Context<ClaimLpReferralRewards>) -> Result<()> {
+            instructions::vault::claim_lp_referral_rewards(ctx)
+        }
+
+        pub fn deploy_to_strategy<'info>(
+            ctx: Context<'_, '_, 'info, 'info, DeployToStrategy<'info>>,
+            amount: u64
+        ) -> Result<()> {
+            instructions::strategy::deploy_to_strategy(ctx, amount)
+        }
+
+        pub fn recall_from_strategy<'info>(
+            ctx: Context<'_, '_, 'info, 'info, RecallFromStrategy<'info>>
+        ) -> Result<()> {
+            instructions::strategy::recall_from_strategy(ctx)
+        }
+
+        pub fn wrap_lp_position(ctx: Context<WrapLpPosition>, name: String, uri: String) -> Result<()> {
+            instructions::lp_position::wrap_lp_position(ctx, name, uri)
+        }
+
+        pub fn unwrap_lp_position(ctx: Context<UnwrapLpPosition>) -> Result<()> {
+            instructions::lp_position::unwrap_lp_position(ctx)
+        }
+
+        #[cfg(feature = "fast-mode")]
+        pub fn test_faucet(ctx: Context<TestFaucet>, amount: u64) -> Result<()> {
+            instructions::fast_mode::test_faucet(ctx, amount)
+        }
+
+        #[cfg(feature = "fast-mode")]
+        pub fn seed_demo_vault(ctx: Context<SeedDemoVault>, liquidity: u64) -> Result<()> {
+            instructions::fast_mode::seed_demo_vault(ctx, liquidity)
+        }
+
+        #[cfg(feature = "fast-mode")]
+        pub fn seed_demo_player(ctx: Context<SeedDemoPlayer>, amount: u64) -> Result<()> {
+            instructions::fast_mode::seed_demo_player(ctx, amount)
+        }
+
+        // ========== STATS INSTRUCTIONS ==========
+        pub fn initialize_protocol_stats(ctx: Context<InitializeProtocolStats>) -> Result<()> {
+            instructions::stats::initialize_protocol_stats(ctx)
+        }
+
+        pub fn initialize_rent_pool(ctx: Context<InitializeRentPool>) -> Result<()> {
+            instructions::stats::initialize_rent_pool(ctx)
+        }
+
+        // ========== CONFIG INSTRUCTIONS ==========
+        pub fn initialize_config(ctx: Context<InitializeConfig>) -> Result<()> {
+            instructions::config::initialize_config(ctx)
+        }
+
+        pub fn migrate_config(ctx: Context<MigrateConfig>) -> Result<()> {
+            instructions::config::migrate_config(ctx)
+        }
+
+        pub fn set_admin_multisig(
+            ctx: Context<SetAdminMultisig>,
+            admin_keys: [Pubkey; constants::MAX_ADMIN_KEYS],
+            admin_threshold: u8
+        ) -> Result<()> {
+            instructions::config::set_admin_multisig(ctx, admin_keys, admin_threshold)
+        }
+
+        pub fn set_round_stats_payer(ctx: Context<SetRoundStatsPayer>, payer: RentPayer) -> Result<()> {
+            instructions::config::set_round_stats_payer(ctx, payer)
+        }
+
+        pub fn set_hedging_program(ctx: Context<SetHedgingProgram>, hedging_program: Pubkey) -> Result<()> {
+            instructions::config::set_hedging_program(ctx, hedging_program)
+        }
+
+        pub fn set_rake_swap_config(
+            ctx: Context<SetRakeSwapConfig>,
+            protocol_token_mint: Pubkey,
+            rake_swap_program: Pubkey
+        ) -> Result<()> {
+            instructions::config::set_rake_swap_config(ctx, protocol_token_mint, rake_swap_program)
+        }
+
+        pub fn set_max_bets_per_round(ctx: Context<SetMaxBetsPerRound>, max_bets_per_round: u8) -> Result<()> {
+            instructions::config::set_max_bets_per_round(ctx, max_bets_per_round)
+        }
+
+        pub fn set_strategy_program(ctx: Context<SetStrategyProgram>, strategy_program: Pubkey) -> Result<()> {
+            instructions::config::set_strategy_program(ctx, strategy_program)
+        }
+
+        pub fn set_random_initiators(
+            ctx: Context<SetRandomInitiators>,
+            random_initiators: [Pubkey; constants::MAX_RANDOM_INITIATORS],
+            count: u8
+        ) -> Result<()> {
+            instructions::game::set_random_initiators(ctx, random_initiators, count)
+        }
+
+        pub fn set_experimental_bet_types(
+            ctx: Context<SetExperimentalBetTypes>,
+            experimental_bet_types: u32
+        ) -> Result<()> {
+            instructions::config::set_experimental_bet_types(ctx, experimental_bet_types)
+        }
+
+        pub fn set_beta_testers(
+            ctx: Context<SetBetaTesters>,
+            beta_testers: [Pubkey; constants::MAX_BETA_TESTERS],
+            count: u8
+        ) -> Result<()> {
+            instructions::config::set_beta_testers(ctx, beta_testers, count)
+        }
+
+        pub fn initialize_sol_fee_vault(ctx: Context<InitializeSolFeeVault>) -> Result<()> {
+            instructions::config::initialize_sol_fee_vault(ctx)
+        }
+
+        pub fn set_sol_fee_recipient(ctx: Context<SetSolFeeRecipient>, recipient: Pubkey) -> Result<()> {
+            instructions::config::set_sol_fee_recipient(ctx, recipient)
+        }
+
+        pub fn withdraw_sol_fees(ctx: Context<WithdrawSolFees>, amount: u64) -> Result<()> {
+            instructions::config::withdraw_sol_fees(ctx, amount)
+        }
+
+        // ========== GAME INSTRUCTIONS ==========
+        pub fn initialize_game_session(ctx: Context<InitializeGameSession>, table_id: u64) -> Result<()> {
+            instructions::game::initialize_game_session(ctx, table_id)
+        }
+
+        pub fn migrate_session_to_table(ctx: Context<MigrateSessionToTable>) -> Result<()> {
+            instructions::game::migrate_session_to_table(ctx)
+        }
+
+        pub fn migrate_game_session(ctx: Context<MigrateGameSession>, table_id: u64) -> Result<()> {
+            instructions::game::migrate_game_session(ctx, table_id)
+        }
+
+        pub fn migrate_round_stats(ctx: Context<MigrateRoundStats>, round: u64) -> Result<()> {
+            instructions::game::migrate_round_stats(ctx, round)
+        }
+
+        pub fn start_new_round(
+            ctx: Context<StartNewRound>,
+            seed_commitment: [u8; 32],
+            label: Option<String>,
+            uri: Option<String>
+        ) -> Result<()> {
+            instructions::game::start_new_round(ctx, seed_commitment, label, uri)
+        }
+
+        pub fn close_bets<'info>(ctx: Context<'_, '_, 'info, 'info, CloseBets<'info>>) -> Result<()> {
+            instructions::game::close_bets(ctx)
+        }
+
+        pub fn get_random(ctx: Context<GetRandom>, server_seed: [u8; 32]) -> Result<()> {
+            instructions::game::get_random(ctx, server_seed)
+        }
+
+        pub fn settle_and_restart(
+            ctx: Context<GetRandom>,
+            server_seed: [u8; 32],
+            next_seed_commitment: [u8; 32]
+        ) -> Result<()> {
+            instructions::game::settle_and_restart(ctx, server_seed, next_seed_commitment)
+        }
+
+        pub fn verify_round_result(ctx: Context<VerifyRoundResult>, round: u64) -> Result<()> {
+            instructions::game::verify_round_result(ctx, round)
+        }
 
-    // ========== READ-ONLY INSTRUCTIONS ==========
-    pub fn get_unclaimed_rewards(ctx: Context<GetUnclaimedRewards>) -> Result<()> {
-        instructions::vault::get_unclaimed_rewards(ctx)
+        pub fn set_randomness_mode(ctx: Context<SetRandomnessMode>, mode: RandomnessMode) -> Result<()> {
+            instructions::game::set_randomness_mode(ctx, mode)
+        }
+
+        pub fn set_randomness_fallback(
+            ctx: Context<SetRandomnessMode>,
+            fallback_mode: Option<RandomnessMode>,
+            timeout_slots: u64
+        ) -> Result<()> {
+            instructions::game::set_randomness_fallback(ctx, fallback_mode, timeout_slots)
+        }
+
+        pub fn set_randomness_oracles(
+            ctx: Context<SetRandomnessOracles>,
+            oracles: [Pubkey; constants::MAX_RANDOMNESS_ORACLES]
+        ) -> Result<()> {
+            instructions::game::set_randomness_oracles(ctx, oracles)
+        }
+
+        pub fn lock_randomness_seed(ctx: Context<LockRandomnessSeed>, server_seed: [u8; 32]) -> Result<()> {
+            instructions::game::lock_randomness_seed(ctx, server_seed)
+        }
+
+        pub fn reveal_winning_number(ctx: Context<RevealWinningNumber>) -> Result<()> {
+            instructions::game::reveal_winning_number(ctx)
+        }
+
+        pub fn request_vrf_randomness(ctx: Context<RequestVrfRandomness>) -> Result<()> {
+            instructions::game::request_vrf_randomness(ctx)
+        }
+
+        pub fn settle_round_vrf(ctx: Context<SettleRoundVrf>) -> Result<()> {
+            instructions::game::settle_round_vrf(ctx)
+        }
+
+        #[cfg(feature = "orao-vrf")]
+        pub fn request_random_orao(ctx: Context<RequestRandomOrao>) -> Result<()> {
+            instructions::orao::request_random_orao(ctx)
+        }
+
+        #[cfg(feature = "orao-vrf")]
+        pub fn fulfill_random_orao(ctx: Context<FulfillRandomOrao>) -> Result<()> {
+            instructions::orao::fulfill_random_orao(ctx)
+        }
+
+        pub fn update_timing_config(
+            ctx: Context<UpdateTimingConfig>,
+            min_random_delay_secs: u32,
+            claim_window_secs: u32,
+            slot_drift_tolerance_bps: u16,
+            void_timeout_slots: u64,
+            spin_timeout_secs: u32
+        ) -> Result<()> {
+            instructions::game::update_timing_config(
+                ctx,
+                min_random_delay_secs,
+                claim_window_secs,
+                slot_drift_tolerance_bps,
+                void_timeout_slots,
+                spin_timeout_secs
+            )
+        }
+
+        pub fn update_round_timing(
+            ctx: Context<UpdateTimingConfig>,
+            min_betting_duration_secs: u32,
+            max_betting_duration_secs: u32,
+            cooldown_between_rounds_secs: u32
+        ) -> Result<()> {
+            instructions::game::update_round_timing(
+                ctx,
+                min_betting_duration_secs,
+                max_betting_duration_secs,
+                cooldown_between_rounds_secs
+            )
+        }
+
+        pub fn void_round(ctx: Context<VoidRound>) -> Result<()> {
+            instructions::game::void_round(ctx)
+        }
+
+        pub fn cancel_round(ctx: Context<CancelRound>) -> Result<()> {
+            instructions::game::cancel_round(ctx)
+        }
+
+        pub fn set_pause(ctx: Context<SetPause>, paused: bool) -> Result<()> {
+            instructions::game::set_pause(ctx, paused)
+        }
+
+        pub fn freeze_table(ctx: Context<FreezeTable>, table_id: u64) -> Result<()> {
+            instructions::game::freeze_table(ctx, table_id)
+        }
+
+        pub fn unfreeze_table(ctx: Context<FreezeTable>, table_id: u64) -> Result<()> {
+            instructions::game::unfreeze_table(ctx, table_id)
+        }
+
+        pub fn set_operator(ctx: Context<SetOperator>, operator: Pubkey) -> Result<()> {
+            instructions::game::set_operator(ctx, operator)
+        }
+
+        pub fn set_wheel_type(ctx: Context<UpdateTableConfig>, wheel_type: WheelType) -> Result<()> {
+            instructions::game::set_wheel_type(ctx, wheel_type)
+        }
+
+        pub fn set_en_prison(ctx: Context<UpdateTableConfig>, en_prison: bool) -> Result<()> {
+            instructions::game::set_en_prison(ctx, en_prison)
+        }
+
+        pub fn set_participation_minimums(
+            ctx: Context<UpdateTableConfig>,
+            min_total_bets: u32,
+            min_unique_bettors: u32
+        ) -> Result<()> {
+            instructions::game::set_participation_minimums(ctx, min_total_bets, min_unique_bettors)
+        }
+
+        pub fn set_round_schedule(
+            ctx: Context<UpdateTableConfig>,
+            round_interval_slots: u64,
+            next_round_slot: u64
+        ) -> Result<()> {
+            instructions::game::set_round_schedule(ctx, round_interval_slots, next_round_slot)
+        }
+
+        pub fn set_finality_config(
+            ctx: Context<SetFinalityConfig>,
+            finality_slots: u64,
+            finality_payout_threshold: u64
+        ) -> Result<()> {
+            instructions::game::set_finality_config(ctx, finality_slots, finality_payout_threshold)
+        }
+
+        pub fn finalize_round(ctx: Context<FinalizeRound>, round: u64) -> Result<()> {
+            instructions::game::finalize_round(ctx, round)
+        }
+
+        pub fn set_table_metadata(
+            ctx: Context<SetTableMetadata>,
+            name: String,
+            description_hash: [u8; 32],
+            banner_mint: Option<Pubkey>
+        ) -> Result<()> {
+            instructions::game::set_table_metadata(ctx, name, description_hash, banner_mint)
+        }
+
+        pub fn set_table_access_list(ctx: Context<SetTableAccessList>, players: Vec<Pubkey>) -> Result<()> {
+            instructions::game::set_table_access_list(ctx, players)
+        }
+
+        pub fn close_table_access_list(ctx: Context<CloseTableAccessList>) -> Result<()> {
+            instructions::game::close_table_access_list(ctx)
+        }
+
+        pub fn set_bound_vault(ctx: Context<SetBoundVault>) -> Result<()> {
+            instructions::game::set_bound_vault(ctx)
+        }
+
+        // ========== JACKPOT INSTRUCTIONS ==========
+        pub fn publish_jackpot_root(
+            ctx: Context<PublishJackpotRoot>,
+            round: u64,
+            merkle_root: [u8; 32],
+            total_amount: u64
+        ) -> Result<()> {
+            instructions::jackpot::publish_jackpot_root(ctx, round, merkle_root, total_amount)
+        }
+
+        pub fn claim_jackpot(
+            ctx: Context<ClaimJackpot>,
+            round: u64,
+            index: u64,
+            amount: u64,
+            proof: Vec<[u8; 32]>
+        ) -> Result<()> {
+            instructions::jackpot::claim_jackpot(ctx, round, index, amount, proof)
+        }
+
+        // ========== DISPUTE INSTRUCTIONS ==========
+        pub fn flag_round(ctx: Context<FlagRound>, round: u64, reason_hash: [u8; 32]) -> Result<()> {
+            instructions::dispute::flag_round(ctx, round, reason_hash)
+        }
+
+        pub fn resolve_dispute(ctx: Context<ResolveDispute>, round: u64, upheld: bool) -> Result<()> {
+            instructions::dispute::resolve_dispute(ctx, round, upheld)
+        }
+
+        // ========== EVENT QUEUE INSTRUCTIONS ==========
+        pub fn initialize_event_queue(ctx: Context<InitializeEventQueue>, consumer: Pubkey) -> Result<()> {
+            instructions::event_queue::initialize_event_queue(ctx, consumer)
+        }
+
+        pub fn set_event_queue_consumer(ctx: Context<SetEventQueueConsumer>, consumer: Pubkey) -> Result<()> {
+            instructions::event_queue::set_event_queue_consumer(ctx, consumer)
+        }
+
+        pub fn close_event_queue(ctx: Context<CloseEventQueue>) -> Result<()> {
+            instructions::event_queue::close_event_queue(ctx)
+        }
+
+        pub fn consume_events(ctx: Context<ConsumeEvents>, count: u64) -> Result<()> {
+            instructions::event_queue::consume_events(ctx, count)
+        }
+
+        // ========== PLAYER INSTRUCTIONS ==========
+        pub fn initialize_player_bets(ctx: Context<InitializePlayerBets>, sponsored: bool) -> Result<()> {
+            instructions::player::initialize_player_bets(ctx, sponsored)
+        }
+
+        pub fn close_player_bets_account(ctx: Context<ClosePlayerBetsAccount>) -> Result<()> {
+            instructions::player::close_player_bets_account(ctx)
+        }
+
+        pub fn migrate_player_bets(ctx: Context<MigratePlayerBets>) -> Result<()> {
+            instructions::player::migrate_player_bets(ctx)
+        }
+
+        pub fn place_bet(ctx: Context<PlaceBets>, bet: Bet) -> Result<()> {
+            instructions::player::place_bet(ctx, bet)
+        }
+
+        pub fn join_and_bet(ctx: Context<JoinAndBet>, bet: Bet) -> Result<()> {
+            instructions::player::join_and_bet(ctx, bet)
+        }
+
+        pub fn check_bet(ctx: Context<CheckBet>, bet: Bet) -> Result<()> {
+            instructions::player::check_bet(ctx, bet)
+        }
+
+        pub fn set_bet_riding(ctx: Context<SetBetRiding>, bet_index: u8, riding: bool) -> Result<()> {
+            instructions::player::set_bet_riding(ctx, bet_index, riding)
+        }
+
+        // Declared before `claim_my_winnings`: Anchor exports a shared context under the `cfg` of
+        // its last instruction, which would hide `accounts::ClaimMyWinnings` without the feature.
+        #[cfg(feature = "confidential-payouts")]
+        pub fn claim_my_winnings_confidential(ctx: Context<ClaimMyWinnings>, round_to_claim: u64) -> Result<()> {
+            instructions::confidential::claim_my_winnings_confidential(ctx, round_to_claim)
+        }
+
+        pub fn claim_my_winnings(ctx: Context<ClaimMyWinnings>, round_to_claim: u64) -> Result<()> {
+            instructions::player::claim_my_winnings(ctx, round_to_claim)
+        }
+
+        pub fn verify_claim(ctx: Context<VerifyClaim>, round_to_claim: u64) -> Result<()> {
+            instructions::player::verify_claim(ctx, round_to_claim)
+        }
+
+        pub fn refund_voided_bets(ctx: Context<RefundVoidedBets>, round: u64) -> Result<()> {
+            instructions::player::refund_voided_bets(ctx, round)
+        }
+
+        pub fn refund_bet(ctx: Context<RefundVoidedBets>, round: u64) -> Result<()> {
+            instructions::player::refund_bet(ctx, round)
+        }
+
+        pub fn execute_claim(ctx: Context<ExecuteClaim>) -> Result<()> {
+            instructions::player::execute_claim(ctx)
+        }
+
+        pub fn acknowledge_loss(ctx: Context<AcknowledgeLoss>, round: u64) -> Result<()> {
+            instructions::player::acknowledge_loss(ctx, round)
+        }
+
+        // ========== READ-ONLY INSTRUCTIONS ==========
+        pub fn get_unclaimed_rewards(ctx: Context<GetUnclaimedRewards>) -> Result<()> {
+            instructions::vault::get_unclaimed_rewards(ctx)
+        }
+
+        pub fn get_provider_portfolio<'info>(
+            ctx: Context<'_, '_, 'info, 'info, GetProviderPortfolio<'info>>
+        ) -> Result<()> {
+            instructions::vault::get_provider_portfolio(ctx)
+        }
+
+        pub fn get_player_position(ctx: Context<GetPlayerPosition>) -> Result<()> {
+            instructions::player::get_player_position(ctx)
+        }
     }
-}
\ No newline at end of file
+}
+
+pub use handlers::*;
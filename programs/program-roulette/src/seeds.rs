@@ -0,0 +1,211 @@
+//! PDA seeds and address derivation for every account owned by the program.
+//!
+//! Integrating programs should derive addresses through this module rather than hardcoding the
+//! seed strings, so they keep resolving the same accounts if a layout ever changes.
+
+use anchor_lang::prelude::*;
+
+pub const GAME_SESSION_SEED: &[u8] = b"game_session";
+pub const CONFIG_SEED: &[u8] = b"config";
+pub const VAULT_SEED: &[u8] = b"vault";
+pub const PROVIDER_STATE_SEED: &[u8] = b"provider_state";
+pub const PLAYER_BETS_SEED: &[u8] = b"player_bets";
+pub const WITHDRAWAL_REQUEST_SEED: &[u8] = b"withdrawal_request";
+pub const PROTOCOL_STATS_SEED: &[u8] = b"protocol_stats";
+pub const OPERATOR_STATS_SEED: &[u8] = b"operator_stats";
+pub const ROUND_STATS_SEED: &[u8] = b"round_stats";
+pub const ROUND_RESULT_SEED: &[u8] = b"round_result";
+pub const RENT_POOL_SEED: &[u8] = b"rent_pool";
+pub const RAKE_ESCROW_SEED: &[u8] = b"rake_escrow";
+pub const SOL_FEE_VAULT_SEED: &[u8] = b"sol_fee_vault";
+pub const PLAYER_STATS_SEED: &[u8] = b"player_stats";
+pub const PAYOUT_DEBT_SEED: &[u8] = b"payout_debt";
+pub const PENDING_CLAIM_SEED: &[u8] = b"pending_claim";
+pub const WHEEL_HISTOGRAM_SEED: &[u8] = b"wheel_histogram";
+pub const ROUND_HISTORY_SEED: &[u8] = b"round_history";
+pub const EVENT_QUEUE_SEED: &[u8] = b"event_queue";
+pub const TABLE_METADATA_SEED: &[u8] = b"table_metadata";
+pub const JACKPOT_SEED: &[u8] = b"jackpot";
+pub const JACKPOT_CLAIM_SEED: &[u8] = b"jackpot_claim";
+pub const ROUND_DISPUTE_SEED: &[u8] = b"round_dispute";
+pub const TEST_MINT_SEED: &[u8] = b"test_mint";
+pub const CARRYOVER_SEED: &[u8] = b"carryover";
+pub const TABLE_ACCESS_LIST_SEED: &[u8] = b"table_access_list";
+pub const VAULT_EXPOSURE_SEED: &[u8] = b"vault_exposure";
+
+/// The game session, which also carries the state of the current round.
+pub fn game_session() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[GAME_SESSION_SEED], &crate::ID)
+}
+
+/// Game session of a table. Table `LEGACY_TABLE_ID` is the legacy session at
+/// `[GAME_SESSION_SEED]`, so its `PlayerBets` and claim records keep their addresses; other
+/// tables add the id to the seeds.
+pub fn table_game_session(table_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[GAME_SESSION_SEED, &table_seed(table_id)], &crate::ID)
+}
+
+/// Seed following `GAME_SESSION_SEED` in a table's game session address: empty for
+/// `LEGACY_TABLE_ID`, which keeps it at `[GAME_SESSION_SEED]`, the id otherwise.
+pub fn table_seed(table_id: u64) -> Vec<u8> {
+    if table_id == crate::constants::LEGACY_TABLE_ID {
+        return Vec::new();
+    }
+    table_id.to_le_bytes().to_vec()
+}
+
+/// Alias of [`game_session`]: rounds are tracked on the game session account.
+pub fn round() -> (Pubkey, u8) {
+    game_session()
+}
+
+pub fn table_metadata(game_session: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[TABLE_METADATA_SEED, game_session.as_ref()], &crate::ID)
+}
+
+/// Players admitted to a private table.
+pub fn table_access_list(game_session: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[TABLE_ACCESS_LIST_SEED, game_session.as_ref()], &crate::ID)
+}
+
+/// Winning-number histogram of a table.
+pub fn wheel_histogram(game_session: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[WHEEL_HISTOGRAM_SEED, game_session.as_ref()], &crate::ID)
+}
+
+/// Results of a table's last `ROUND_HISTORY_LEN` rounds.
+pub fn round_history(game_session: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ROUND_HISTORY_SEED, game_session.as_ref()], &crate::ID)
+}
+
+/// Queue of a table's bets, settlements and claims for on-chain consumers.
+pub fn event_queue(game_session: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[EVENT_QUEUE_SEED, game_session.as_ref()], &crate::ID)
+}
+
+pub fn config() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CONFIG_SEED], &crate::ID)
+}
+
+pub fn protocol_stats() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PROTOCOL_STATS_SEED], &crate::ID)
+}
+
+pub fn operator_stats(operator: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[OPERATOR_STATS_SEED, operator.as_ref()], &crate::ID)
+}
+
+pub fn round_stats(game_session: &Pubkey, round: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[ROUND_STATS_SEED, game_session.as_ref(), &round.to_le_bytes()],
+        &crate::ID,
+    )
+}
+
+/// Settlement proof of a round settled by `get_random`.
+pub fn round_result(game_session: &Pubkey, round: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[ROUND_RESULT_SEED, game_session.as_ref(), &round.to_le_bytes()],
+        &crate::ID,
+    )
+}
+
+pub fn rent_pool() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[RENT_POOL_SEED], &crate::ID)
+}
+
+/// Program-owned account collecting vault-creation SOL fees.
+pub fn sol_fee_vault() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[SOL_FEE_VAULT_SEED], &crate::ID)
+}
+
+/// Authority of the token accounts owner fees pass through while being swapped.
+pub fn rake_escrow() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[RAKE_ESCROW_SEED], &crate::ID)
+}
+
+pub fn vault(token_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[VAULT_SEED, token_mint.as_ref()], &crate::ID)
+}
+
+pub fn provider_state(vault: &Pubkey, provider: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[PROVIDER_STATE_SEED, vault.as_ref(), provider.as_ref()],
+        &crate::ID,
+    )
+}
+
+pub fn player_bets(game_session: &Pubkey, player: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[PLAYER_BETS_SEED, game_session.as_ref(), player.as_ref()],
+        &crate::ID,
+    )
+}
+
+pub fn player_stats(player: &Pubkey, token_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[PLAYER_STATS_SEED, player.as_ref(), token_mint.as_ref()],
+        &crate::ID,
+    )
+}
+
+pub fn pending_claim(game_session: &Pubkey, player: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[PENDING_CLAIM_SEED, game_session.as_ref(), player.as_ref()],
+        &crate::ID,
+    )
+}
+
+/// Let-it-ride record of a player on a table.
+pub fn carryover(game_session: &Pubkey, player: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[CARRYOVER_SEED, game_session.as_ref(), player.as_ref()],
+        &crate::ID,
+    )
+}
+
+/// Exposure of a vault to the current round of a table.
+pub fn vault_exposure(vault: &Pubkey, game_session: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[VAULT_EXPOSURE_SEED, vault.as_ref(), game_session.as_ref()],
+        &crate::ID,
+    )
+}
+
+pub fn payout_debt(vault: &Pubkey, index: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[PAYOUT_DEBT_SEED, vault.as_ref(), &index.to_le_bytes()],
+        &crate::ID,
+    )
+}
+
+/// Jackpot of one round. Also the authority of the token account holding its funds.
+pub fn jackpot(game_session: &Pubkey, round: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[JACKPOT_SEED, game_session.as_ref(), &round.to_le_bytes()],
+        &crate::ID,
+    )
+}
+
+pub fn jackpot_claim(jackpot: &Pubkey, index: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[JACKPOT_CLAIM_SEED, jackpot.as_ref(), &index.to_le_bytes()],
+        &crate::ID,
+    )
+}
+
+pub fn round_dispute(game_session: &Pubkey, round: u64, vault: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[ROUND_DISPUTE_SEED, game_session.as_ref(), &round.to_le_bytes(), vault.as_ref()],
+        &crate::ID,
+    )
+}
+
+/// Mint of the `fast-mode` test faucet, which is also its own mint authority.
+pub fn test_mint() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[TEST_MINT_SEED], &crate::ID)
+}
+
+pub fn withdrawal_request(provider_state: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[WITHDRAWAL_REQUEST_SEED, provider_state.as_ref()], &crate::ID)
+}
@@ -1,4 +1,49 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash;
+use crate::constants::{
+    bet_coverage,
+    BET_TYPE_COUNT,
+    BETTOR_FILTER_HASHES,
+    BETTOR_FILTER_WORDS,
+    BPS_DENOMINATOR,
+    DEFAULT_MAX_BETS_PER_ROUND,
+    DEFAULT_FINALITY_SLOTS,
+    DEFAULT_SLOT_DRIFT_TOLERANCE_BPS,
+    DEFAULT_VOID_TIMEOUT_SLOTS,
+    DISPUTE_BOND_LAMPORTS,
+    DOUBLE_ZERO,
+    EVENT_QUEUE_LEN,
+    GAME_ADMIN_PUBKEY,
+    MAX_BET_PERCENTAGE,
+    MAX_BET_PERCENTAGE_DIVISOR,
+    MAX_BETA_TESTERS,
+    MAX_BETS_PER_ROUND,
+    MAX_ADMIN_KEYS,
+    MAX_OWNER_SHARES,
+    MAX_RANDOMNESS_ORACLES,
+    MAX_RANDOM_INITIATORS,
+    MAX_ROUND_LABEL_LEN,
+    MAX_ROUND_URI_LEN,
+    MAX_SPONSORED_PER_WINDOW,
+    MAX_TABLE_ACCESS_LIST_LEN,
+    MAX_TABLE_NAME_LEN,
+    NO_RECENT_NUMBER,
+    RECENT_NUMBERS_LEN,
+    MAX_TRACKED_MINTS,
+    MPL_CORE_PROGRAM_ID,
+    OWNER_DIVISOR,
+    PAYOUT_MULTIPLIERS,
+    PLAYER_BETS_LAYOUT_VERSION,
+    PROVIDER_DIVISOR,
+    RENT_SPONSOR_WINDOW_SLOTS,
+    ROUND_HISTORY_LEN,
+    WHEEL_NUMBERS,
+    WHEEL_POCKETS,
+    SLOT_DURATION_MS,
+    SLOT_HASHES_MIXED,
+    TREASURY_PUBKEY,
+};
+use crate::errors::RouletteError;
 
 /// Represents a single bet placed by a player.
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
@@ -8,7 +53,19 @@ pub struct Bet {
     pub numbers: [u8; 4],
 }
 
-/// Defines the possible states of a roulette game round.
+impl Bet {
+    /// Serialized size; `size_of` would count the in-memory padding as well.
+    pub const SIZE: usize = 8 + 1 + 4;
+
+    /// Whether the bet pays even money (Red, Black, Even, Odd, Manque, Passe), the bets the En
+    /// Prison rule applies to.
+    pub fn is_even_money(&self) -> bool {
+        matches!(self.bet_type, 6..=11)
+    }
+}
+
+/// Defines the possible states of a roulette game round. Stored sessions encode it by
+/// position, so new statuses are only ever appended.
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Default)]
 pub enum RoundStatus {
     #[default]
@@ -16,6 +73,177 @@ pub enum RoundStatus {
     AcceptingBets,
     BetsClosed,
     Completed,
+    /// Ended without a winning number because the target slot hash could no longer be read.
+    Voided,
+    /// Called off by `cancel_round`; its bets are refunded through `refund_bet`.
+    Cancelled,
+    /// `lock_randomness_seed` fixed a future target slot; waiting for `reveal_winning_number`.
+    SeedLocked,
+}
+
+/// Why `place_bet` would turn a prospective bet away.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BetRejectionReason {
+    /// The stake exceeds the vault's maximum bet.
+    ExceedsMaxBet,
+    /// The player already holds the maximum number of bets for the round.
+    TooManyBets,
+    /// The round is not accepting bets.
+    BetsNotAccepted,
+    /// Unknown bet type or numbers that do not form a valid layout.
+    InvalidGeometry,
+    /// The stake is zero.
+    ZeroAmount,
+    /// The player already bet from a different vault this round.
+    VaultMismatch,
+    /// The vault has been opted out of the current round.
+    VaultOptedOut,
+    /// The amount is not a whole number of the vault's chips.
+    InvalidChipAmount,
+    /// The player's bets this round could together pay out more than the vault's
+    /// `max_player_payout`.
+    ExceedsPlayerPayoutCap,
+    /// The game is paused by `set_pause`.
+    ProgramPaused,
+    /// All players' bets this round would owe more than the vault's `table_maximum` for some
+    /// winning number.
+    ExceedsTableMaximum,
+    /// The player's bets of an earlier round may still be owed a payout or refund, and betting
+    /// now would replace them.
+    UnclaimedPreviousRound,
+    /// The bet type is experimental and the player is not one of its beta testers.
+    BetTypeInBeta,
+    /// The table is private and the player is not on its access list.
+    NotOnAccessList,
+    /// The table is bound to another vault.
+    VaultNotBound,
+    /// The table is frozen.
+    TableFrozen,
+}
+
+impl BetRejectionReason {
+    /// Rejections driven by exposure/limit settings rather than by a malformed bet.
+    pub fn is_limit(&self) -> bool {
+        matches!(
+            self,
+            BetRejectionReason::ExceedsMaxBet |
+                BetRejectionReason::TooManyBets |
+                BetRejectionReason::ExceedsPlayerPayoutCap |
+                BetRejectionReason::ExceedsTableMaximum
+        )
+    }
+
+    /// The error `place_bet` fails with for this rejection.
+    pub fn error(&self) -> RouletteError {
+        match self {
+            BetRejectionReason::ExceedsMaxBet => RouletteError::BetAmountExceedsLimit,
+            BetRejectionReason::TooManyBets => RouletteError::InvalidNumberOfBets,
+            BetRejectionReason::BetsNotAccepted => RouletteError::BetsNotAccepted,
+            BetRejectionReason::InvalidGeometry | BetRejectionReason::ZeroAmount => {
+                RouletteError::InvalidBet
+            }
+            BetRejectionReason::VaultMismatch => RouletteError::VaultMismatch,
+            BetRejectionReason::VaultOptedOut => RouletteError::VaultNotAcceptingBets,
+            BetRejectionReason::InvalidChipAmount => RouletteError::InvalidChipAmount,
+            BetRejectionReason::ExceedsPlayerPayoutCap => RouletteError::PlayerPayoutCapExceeded,
+            BetRejectionReason::ProgramPaused => RouletteError::ProgramPaused,
+            BetRejectionReason::ExceedsTableMaximum => RouletteError::TableMaximumExceeded,
+            BetRejectionReason::UnclaimedPreviousRound => RouletteError::UnclaimedPreviousRound,
+            BetRejectionReason::BetTypeInBeta => RouletteError::BetTypeInBeta,
+            BetRejectionReason::NotOnAccessList => RouletteError::NotOnAccessList,
+            BetRejectionReason::VaultNotBound => RouletteError::VaultNotBoundToTable,
+            BetRejectionReason::TableFrozen => RouletteError::TableFrozen,
+        }
+    }
+}
+
+/// Return data of `check_bet`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct BetCheck {
+    /// The largest stake the vault currently accepts for a single bet.
+    pub max_bet_amount: u64,
+    /// `None` if `place_bet` would accept the bet as submitted.
+    pub rejection: Option<BetRejectionReason>,
+}
+
+/// Return data of `place_bet`: how the new bet's coverage relates to the player's other bets
+/// this round. Informational only; overlapping bets are accepted.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct BetCoverage {
+    /// Winning numbers of the new bet, bit `n` standing for number `n`.
+    pub coverage_mask: u64,
+    /// Numbers the new bet shares with the player's earlier bets in the round.
+    pub overlap_mask: u64,
+    /// Every number the new bet covers was already covered by an earlier bet.
+    pub fully_overlapped: bool,
+}
+
+/// Return data of `get_player_position`: everything a player still has at stake or to collect.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct PlayerPosition {
+    /// Round of the player's recorded bets.
+    pub round: u64,
+    /// Total stake of those bets.
+    pub bets_total: u64,
+    /// The round has a winning number and can be claimed.
+    pub settled: bool,
+    /// Payout still claimable for a settled round (0 once claimed or if nothing won).
+    pub pending_payout: u64,
+    /// Unix time after which the payout can no longer be claimed, if a claim window is set.
+    pub claim_deadline: Option<i64>,
+}
+
+/// Backend `get_random` settles rounds with; see `set_randomness_mode`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RandomnessMode {
+    /// Server seed, entropy accumulator and recent slot hashes, hashed on-chain.
+    #[default]
+    InternalHash,
+    /// The Switchboard On-Demand randomness account bound by `request_vrf_randomness`.
+    SwitchboardVrf,
+    /// The ORAO request made by `request_random_orao` (requires the `orao-vrf` feature).
+    OraoVrf,
+    /// Two-phase slot hash: the first call locks a target slot, the next one reveals.
+    CommitReveal,
+    /// Hash of every `randomness_oracles` value, once all of them are fulfilled.
+    MultiOracle,
+}
+
+impl RandomnessMode {
+    /// Whether settling needs the server seed committed at round start.
+    pub fn uses_server_seed(&self) -> bool {
+        matches!(self, RandomnessMode::InternalHash | RandomnessMode::CommitReveal)
+    }
+}
+
+/// An accounting invariant checked by `assert_vault_invariants`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VaultInvariant {
+    /// `total_liquidity >= owner_reward`.
+    OwnerRewardBacked,
+    /// `total_liquidity >= total_provider_capital - realized_losses`.
+    ProviderCapitalBacked,
+    /// The vault token account holds at least the liquidity not deployed to the strategy.
+    TokenBalanceBacked,
+}
+
+/// A broken [`VaultInvariant`] with the amounts that violate it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VaultInvariantViolation {
+    pub invariant: VaultInvariant,
+    /// Smallest value the checked side must have.
+    pub required: u64,
+    /// Value the checked side actually has.
+    pub actual: u64,
+}
+
+/// A retryable reason why a settlement attempt did not produce a winning number.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SettlementFailure {
+    /// `min_random_delay_secs` has not elapsed since bets were closed.
+    RandomDelayNotElapsed,
+    /// Fewer than `MIN_SETTLEMENT_SLOTS` slots have passed since bets were closed.
+    SlotDelayNotElapsed,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
@@ -49,6 +277,32 @@ pub enum BetType {
     P12, // 1-12
     M12, // 13-24
     D12, // 25-36
+    Basket, // 0, 00, 1, 2, 3 (American wheel only)
+}
+
+/// Wheel layout a table spins.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum WheelType {
+    /// Single zero, pockets 0-36.
+    #[default]
+    European,
+    /// Zero and double zero, pockets 0-36 plus `DOUBLE_ZERO` for 00.
+    American,
+}
+
+impl WheelType {
+    /// Number of pockets on the wheel.
+    pub fn pockets(&self) -> usize {
+        match self {
+            WheelType::European => WHEEL_NUMBERS,
+            WheelType::American => WHEEL_POCKETS,
+        }
+    }
+
+    /// Whether every number in `coverage_mask` is a pocket on this wheel.
+    pub fn covers(&self, coverage_mask: u64) -> bool {
+        coverage_mask >> self.pockets() == 0
+    }
 }
 
 #[account]
@@ -60,6 +314,146 @@ pub struct VaultAccount {
     pub bump: u8,
     pub owner_reward: u64,
     pub reward_per_share_index: u128,
+    /// Ceiling on accumulated `owner_reward` (0 = uncapped). Owner fees above the cap stay in
+    /// the payout reserve until the owner withdraws.
+    pub owner_reward_cap: u64,
+    /// Capital withdrawals above this share of `total_liquidity` must be announced a round in
+    /// advance via `request_large_withdrawal` (0 = disabled).
+    pub large_withdrawal_bps: u16,
+    /// First round in which the vault no longer accepts bets (0 = participating).
+    pub opted_out_from_round: u64,
+    /// Bets must be whole multiples of this many base units (0 = any amount).
+    pub chip_size: u64,
+    /// Owner fees are withdrawn swapped into `Config::protocol_token_mint`.
+    pub rake_in_protocol_token: bool,
+    /// Index of the oldest unpaid `PayoutDebt` record.
+    pub debt_head: u64,
+    /// Index the next `PayoutDebt` record is created at.
+    pub debt_tail: u64,
+    /// Sum still owed across all `PayoutDebt` records.
+    pub outstanding_debt: u64,
+    /// Share of the owner's part of each reserve distribution set aside for loyalty boosts.
+    pub loyalty_budget_bps: u16,
+    /// Extra reward accrual, in bps of the base accrual, for deposits at least
+    /// `loyalty_maturity_secs` old. Younger deposits get a linear fraction of it.
+    pub loyalty_max_boost_bps: u16,
+    pub loyalty_maturity_secs: u32,
+    /// Tokens set aside for loyalty boosts and not yet paid out.
+    pub loyalty_budget: u64,
+    /// Largest share of `total_liquidity` that may be deployed to the yield strategy.
+    pub strategy_max_bps: u16,
+    /// Liquidity currently deployed to the yield strategy. It is still counted in
+    /// `total_liquidity` but cannot back bets or pay claims until recalled.
+    pub strategy_principal: u64,
+    /// Cumulative yield the strategy has returned on top of principal.
+    pub strategy_yield: u64,
+    /// Cumulative outflows, from claims and strategy losses, that exceeded the payout reserve
+    /// at the time and were therefore borne by provider capital.
+    pub realized_losses: u64,
+    /// Whether winners may take payouts into their confidential balance through
+    /// `claim_my_winnings_confidential`. Requires a Token-2022 mint with confidential transfers.
+    pub confidential_payouts: bool,
+    /// Largest payout a single player's bets in one round may add up to, over every winning
+    /// number (0 = uncapped).
+    pub max_player_payout: u64,
+    /// Paid from `owner_reward` to whoever settles a round through `get_random` after the spin
+    /// timeout in place of its initiator (0 = no incentive).
+    pub crank_reward: u64,
+    /// Largest gross payout all players' bets in a round may owe for any single winning number
+    /// (0 = uncapped). Bounds the vault's variance per outcome, like a casino table maximum.
+    pub table_maximum: u64,
+    /// Recipients the owner revenue is split between, e.g. for revenue-sharing deals. Unused
+    /// slots are `Pubkey::default()`; all unused means the treasury takes it all as before.
+    pub owner_share_recipients: [Pubkey; MAX_OWNER_SHARES],
+    /// Weight of each recipient in basis points, summing to `BPS_DENOMINATOR` when in use.
+    pub owner_share_bps: [u16; MAX_OWNER_SHARES],
+    /// Owner revenue already split off to each recipient and not yet withdrawn. It stays in
+    /// `total_liquidity`, like `owner_reward`.
+    pub owner_share_owed: [u64; MAX_OWNER_SHARES],
+    /// Open `ProviderState` positions, counted when created and released when withdrawn.
+    pub provider_count: u32,
+    /// Ceiling on `provider_count` (0 = unlimited). Lowering it below the current count only
+    /// blocks new providers.
+    pub max_providers: u32,
+    /// Decimals of `token_mint`, for converting the vault's raw amounts with `display`.
+    pub mint_decimals: u8,
+    /// Bets of at least this amount also emit `LargeBetPlaced` (0 = never).
+    pub large_bet_threshold: u64,
+    /// Claims paying at least this amount also emit `LargeWinClaimed` (0 = never).
+    pub large_win_threshold: u64,
+    /// `RoundDispute` accounts against the vault awaiting resolution. Its reserve distribution
+    /// is blocked while any are open.
+    pub open_disputes: u32,
+    /// Account layout version; vaults below `VAULT_LAYOUT_VERSION` must be migrated with
+    /// `migrate_vault`.
+    pub layout_version: u8,
+}
+
+/// A vault's exposure to the current round of one table, so bets on one table never reset or
+/// dilute the figures of another. Created by the first bet the vault takes on the table; the
+/// figures start over with the first bet of each new round.
+#[account]
+pub struct VaultExposure {
+    pub vault: Pubkey,
+    pub game_session: Pubkey,
+    pub token_mint: Pubkey,
+    /// Round of the table the figures belong to.
+    pub round: u64,
+    /// Total staked with the vault on the table in `round`.
+    pub round_wagered: u64,
+    /// Gross payout owed per winning number in `round`, 00 last.
+    pub payout_exposure: [u64; WHEEL_POCKETS],
+    pub bump: u8,
+}
+
+/// Pause states a vault can be in.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PauseState {
+    Active,
+    /// Opted out of rounds via `set_vault_round_participation`.
+    OptedOut,
+}
+
+/// Vault operations, as seen by pause checks.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VaultOperation {
+    PlaceBet,
+    ClaimWinnings,
+    WithdrawProviderRevenue,
+    ClaimReferralRewards,
+}
+
+impl VaultOperation {
+    /// Invariant: operations paying out what players and LPs are already owed keep working in
+    /// every pause state. Pausing may only stop new risk from entering a vault, never trap funds.
+    pub fn is_pause_exempt(self) -> bool {
+        matches!(
+            self,
+            VaultOperation::ClaimWinnings |
+                VaultOperation::WithdrawProviderRevenue |
+                VaultOperation::ClaimReferralRewards
+        )
+    }
+}
+
+/// Authority-settable per-vault parameters. `None` leaves a setting unchanged.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default)]
+pub struct VaultConfigUpdate {
+    pub owner_reward_cap: Option<u64>,
+    pub large_withdrawal_bps: Option<u16>,
+    pub chip_size: Option<u64>,
+    pub rake_in_protocol_token: Option<bool>,
+    pub loyalty_budget_bps: Option<u16>,
+    pub loyalty_max_boost_bps: Option<u16>,
+    pub loyalty_maturity_secs: Option<u32>,
+    pub strategy_max_bps: Option<u16>,
+    pub confidential_payouts: Option<bool>,
+    pub max_player_payout: Option<u64>,
+    pub crank_reward: Option<u64>,
+    pub table_maximum: Option<u64>,
+    pub max_providers: Option<u32>,
+    pub large_bet_threshold: Option<u64>,
+    pub large_win_threshold: Option<u64>,
 }
 
 #[account]
@@ -75,24 +469,164 @@ pub struct GameSession {
     pub bump: u8,
     pub last_bettor: Option<Pubkey>,
     pub last_completed_round: u64,
+    pub round_start_slot: u64,
+    pub bets_closed_slot: u64,
+    /// When `last_completed_round` was settled; anchors its claim window.
+    pub last_completed_timestamp: i64,
+    pub last_completed_slot: u64,
+    /// Minimum delay between `close_bets` and `get_random` (0 = none).
+    pub min_random_delay_secs: u32,
+    /// How long winnings stay claimable after the round completes (0 = forever).
+    pub claim_window_secs: u32,
+    /// How far the slot count may fall short of the nominal slots for a duration.
+    pub slot_drift_tolerance_bps: u16,
+    /// Why the last settlement attempt of the current round failed, if it did.
+    pub last_settlement_failure: Option<SettlementFailure>,
+    /// Failed settlement attempts in the current round.
+    pub settlement_retries: u32,
+    /// SHA-256 of the operator's server seed for the current round, published at round start
+    /// and revealed in `get_random`.
+    pub seed_commitment: [u8; 32],
+    /// Switchboard On-Demand randomness account bound to the current round by
+    /// `request_vrf_randomness`, if any. While set, only `settle_round_vrf` can settle the round.
+    pub randomness_account: Option<Pubkey>,
+    /// Slot the bound randomness account was committed at.
+    pub randomness_seed_slot: u64,
+    /// Seed of the ORAO VRF request made for the current round, if any. While set, only
+    /// `fulfill_random_orao` can settle the round.
+    pub orao_request_seed: Option<[u8; 32]>,
+    /// Sequence number of the last emitted event; see `next_event_seq`.
+    pub event_seq: u64,
+    /// Running hash of every bet placed in the current round; see `absorb_bet_entropy`.
+    pub entropy_accumulator: [u8; 32],
+    /// Slot whose hash decides the round once `lock_randomness_seed` has run (0 = not locked).
+    pub reveal_target_slot: u64,
+    /// Table this session runs; see `seeds::table_game_session`.
+    pub table_id: u64,
+    /// Account layout version; sessions below `GAME_SESSION_LAYOUT_VERSION` must be migrated
+    /// with `migrate_session_to_table` (legacy session) or `migrate_game_session` (tables).
+    pub layout_version: u8,
+    /// Backend `get_random` dispatches to.
+    pub randomness_mode: RandomnessMode,
+    /// Switchboard randomness accounts aggregated in `MultiOracle` mode. Unused slots are
+    /// `Pubkey::default()`.
+    pub randomness_oracles: [Pubkey; MAX_RANDOMNESS_ORACLES],
+    /// Number of registered `randomness_oracles`, all of which must be fulfilled to settle
+    /// (0 = not configured).
+    pub oracle_quorum: u8,
+    /// Slots after `close_bets` after which `void_round` may void an unsettled round (0 = never).
+    pub void_timeout_slots: u64,
+    /// Most recent voided round. Refunds go by `RoundStats::status`; this only tells
+    /// `migrate_round_stats` how older rounds ended.
+    pub last_voided_round: u64,
+    /// How long a round accepts bets before anyone may call `close_bets` (0 = only the game
+    /// admin closes).
+    pub max_betting_duration_secs: u32,
+    /// Mode `get_random` falls back to when `randomness_mode` has not settled the round within
+    /// `fallback_timeout_slots` of `close_bets` (None = no fallback).
+    pub fallback_randomness_mode: Option<RandomnessMode>,
+    pub fallback_timeout_slots: u64,
+    /// Slot from which the current round settles with the fallback mode, recorded at
+    /// `close_bets` (0 = no fallback) and copied to the round's `RoundStats::fallback_slot`.
+    pub round_fallback_slot: u64,
+    /// Slot from which `void_round` may void the current round, recorded at `close_bets`
+    /// (0 = never). `void_round` reads the round's own `RoundStats::void_slot`.
+    pub round_void_slot: u64,
+    /// Whether the current round has switched to `fallback_randomness_mode`.
+    pub fallback_active: bool,
+    /// How long after `close_bets` anyone may call `get_random` instead of the round's
+    /// initiator (0 = only the initiator).
+    pub spin_timeout_secs: u32,
+    /// How long a round accepts bets at least before `close_bets`, even by the game admin.
+    pub min_betting_duration_secs: u32,
+    /// How long after a round completes the next one may start.
+    pub cooldown_between_rounds_secs: u32,
+    /// Most recent cancelled round. Refunds go by `RoundStats::status`; this only tells
+    /// `migrate_round_stats` how older rounds ended.
+    pub last_cancelled_round: u64,
+    /// Low-privilege key that may drive rounds (`start_new_round`, `close_bets`, `get_random`)
+    /// alongside the game admin, but not touch funds (default = none).
+    pub operator: Pubkey,
+    /// Wheel the table spins; changed by `set_wheel_type` between rounds.
+    pub wheel_type: WheelType,
+    /// Whether even-money bets losing to a zero are imprisoned into the next round instead,
+    /// changed by `set_en_prison` between rounds.
+    pub en_prison: bool,
+    /// Whether the table has an `EventQueue`, which bets, settlements and claims must then pass.
+    pub event_queue_enabled: bool,
+    /// Slots after settlement before `finalize_round` can mark a round final.
+    pub finality_slots: u64,
+    /// Claims paying at least this much need a finalized round (0 = never). Each round keeps
+    /// the value it settled under.
+    pub finality_payout_threshold: u64,
+    /// Bets the current round needs before `close_bets` may run (0 = no minimum). Counted
+    /// rather than summed, since a table takes bets in several tokens.
+    pub min_total_bets: u32,
+    /// Distinct bettors the current round needs before `close_bets` may run (0 = no minimum).
+    pub min_unique_bettors: u32,
+    /// Bets placed in the current round, mirrored from its `RoundStats`.
+    pub round_bet_count: u32,
+    /// Distinct bettors of the current round, mirrored from the bloom filter count of its
+    /// `RoundStats`.
+    pub round_unique_bettors: u32,
+    /// Slots between scheduled round starts (0 = rounds start whenever an operator starts them).
+    /// While set, rounds start at `next_round_slot` at the earliest.
+    pub round_interval_slots: u64,
+    /// Earliest slot the next scheduled round can start at.
+    pub next_round_slot: u64,
+    /// Winning numbers of the last `RECENT_NUMBERS_LEN` settled rounds, most recent first, for
+    /// hot/cold displays. Slots no round has filled yet hold `NO_RECENT_NUMBER`.
+    pub recent_numbers: [u8; RECENT_NUMBERS_LEN],
+    /// Whether the table is private: only players on its `TableAccessList` may bet, and bets
+    /// must pass the list.
+    pub access_list_enabled: bool,
+    /// The only vault the table accepts bets from, for single-currency tables. `None` accepts
+    /// any vault.
+    pub bound_vault: Option<Pubkey>,
+    /// Set by `freeze_table`: the table takes no bets and starts no rounds, while claims,
+    /// refunds and the vaults it uses are unaffected.
+    pub frozen: bool,
+    /// Server seed of the current round, revealed by `lock_randomness_seed` so anyone can
+    /// complete the reveal once the target slot has passed.
+    pub locked_server_seed: [u8; 32],
+    /// Operators taking turns drawing the table's round randomness; see
+    /// `random_initiator_for`.
+    pub random_initiators: [Pubkey; MAX_RANDOM_INITIATORS],
+    /// Number of `random_initiators` in the rotation (0 = `GAME_ADMIN_PUBKEY` draws every round).
+    pub random_initiator_count: u8,
 }
 
 #[account]
 pub struct PlayerBets {
     pub player: Pubkey,
     pub round: u64,
+    /// Vault staked with in `round`; its token mint is read from the vault.
     pub vault: Pubkey,
-    pub token_mint: Pubkey,
     pub bets: Vec<Bet>,
     pub claimed_round: u64,
     pub bump: u8,
+    /// Table (game session) these bets belong to. Claims check it, so bets on one table can
+    /// never be claimed against another table's identically numbered round.
+    pub game_session: Pubkey,
+    /// Whether the rent was paid by the `RentPool`, which then gets it back on close.
+    pub rent_sponsored: bool,
+    /// Number of leading `bets` carried over from the previous round by the En Prison rule. They
+    /// only return their stake if they win, and are lost on another zero.
+    pub imprisoned_count: u8,
 }
 
-/// Record to prevent double-claiming winnings for a specific player and round.
-#[account]
-#[derive(Default)]
-pub struct ClaimRecord {
-    pub claimed: bool,
+/// `PlayerBets` as laid out by the original program (layout version 0), read by
+/// `migrate_player_bets`. Those accounts are larger than `PlayerBets::INITIAL_SPACE`, since
+/// bets were sized with their padding, and their serialized data never reaches the last byte, which
+/// therefore reads as version 0.
+#[derive(AnchorDeserialize)]
+pub struct LegacyPlayerBets {
+    pub player: Pubkey,
+    pub round: u64,
+    pub vault: Pubkey,
+    pub token_mint: Pubkey,
+    pub bets: Vec<Bet>,
+    pub claimed_round: u64,
     pub bump: u8,
 }
 
@@ -105,87 +639,2410 @@ pub struct ProviderState {
     pub unclaimed_rewards: u64,
     pub reward_per_share_index_last_claimed: u128,
     pub bump: u8,
+    /// Referrer earning `LP_REFERRAL_BPS` of this provider's reward accrual, set once.
+    pub referrer: Option<Pubkey>,
+    /// Referrer's accrued, unclaimed share of this provider's rewards.
+    pub referral_rewards: u64,
+    /// Capital-weighted average time of this provider's deposits, which ages the loyalty boost.
+    pub deposit_timestamp: i64,
+    /// Metaplex Core asset the position is wrapped into. While set, its holder controls the
+    /// position instead of `provider`.
+    pub position_asset: Option<Pubkey>,
+    /// Account that took over the position by unwrapping it, when it differs from `provider`.
+    /// `provider` keeps naming the account the state's address is derived from.
+    pub controller: Option<Pubkey>,
+    /// Account layout version; positions below `PROVIDER_STATE_LAYOUT_VERSION` must be
+    /// migrated with `migrate_provider_state`.
+    pub layout_version: u8,
 }
 
-impl PlayerBets {
-    pub fn calculate_payout_multiplier(bet_type: u8) -> u64 {
-        match bet_type {
-            0 => 36, // Straight
-            1 => 18, // Split
-            2 => 9, // Corner
-            3 => 12, // Street
-            4 => 6, // SixLine
-            5 => 9, // FirstFour
-            6 | 7 | 8 | 9 | 10 | 11 => 2, // Red/Black/Even/Odd/Manque/Passe
-            12 | 13 | 14 | 15 => 3, // Column/Dozens
-            _ => 0, // Unknown
+impl ProviderState {
+    /// Sets the fields added after layout `from_layout_version` whose default is not zero.
+    /// Original positions start aging for the loyalty boost at the migration, `now`.
+    pub fn backfill_layout(&mut self, from_layout_version: u8, now: i64) {
+        if from_layout_version < 1 && self.amount > 0 {
+            self.deposit_timestamp = now;
         }
     }
 
-    pub fn is_bet_winner(bet_type: u8, numbers: &[u8; 4], winning_number: u8) -> bool {
-        const RED_NUMBERS: [u8; 18] = [
-            1, 3, 5, 7, 9, 12, 14, 16, 18, 19, 21, 23, 25, 27, 30, 32, 34, 36,
-        ];
+    /// Whether `signer` controls the position: the holder of `position_asset` while wrapped,
+    /// otherwise the controller, or `provider` if it was never handed over.
+    pub fn is_controlled_by(&self, signer: &Pubkey, position_asset: Option<&AccountInfo>) -> bool {
+        match self.position_asset {
+            Some(asset_key) => position_asset.is_some_and(|asset| {
+                asset.key() == asset_key && core_asset_owner(asset) == Some(*signer)
+            }),
+            None => self.controller.unwrap_or(self.provider) == *signer,
+        }
+    }
 
-        match bet_type {
-            0 => numbers[0] == winning_number, // Straight
-            1 => numbers[0] == winning_number || numbers[1] == winning_number, // Split
-            2 => {
-                // Corner
-                let top_left = numbers[0];
-                if top_left == 0 || top_left > 34 || top_left % 3 == 0 {
-                    return false;
-                }
-                let corner_numbers = [top_left, top_left + 1, top_left + 3, top_left + 4];
-                corner_numbers.contains(&winning_number)
-            }
-            3 => {
-                // Street
-                let start_street = numbers[0];
-                if
-                    start_street == 0 ||
-                    start_street > 34 ||
-                    (start_street > 0 && (start_street - 1) % 3 != 0)
-                {
-                    return false;
-                }
-                winning_number > 0 &&
-                    winning_number >= start_street &&
-                    winning_number < start_street + 3
-            }
-            4 => {
-                // Six Line
-                let start_six_line = numbers[0];
-                if
-                    start_six_line == 0 ||
-                    start_six_line > 31 ||
-                    (start_six_line > 0 && (start_six_line - 1) % 3 != 0)
-                {
-                    return false;
-                }
-                winning_number > 0 &&
-                    winning_number >= start_six_line &&
-                    winning_number < start_six_line + 6
-            }
-            5 => [0, 1, 2, 3].contains(&winning_number), // First Four
-            6 => RED_NUMBERS.contains(&winning_number), // Red
-            7 => winning_number != 0 && !RED_NUMBERS.contains(&winning_number), // Black
-            8 => winning_number != 0 && winning_number % 2 == 0, // Even
-            9 => winning_number != 0 && winning_number % 2 == 1, // Odd
-            10 => winning_number >= 1 && winning_number <= 18, // Manque (1-18)
-            11 => winning_number >= 19 && winning_number <= 36, // Passe (19-36)
-            12 => {
-                // Column
-                let column = numbers[0];
-                if column < 1 || column > 3 {
-                    return false;
-                }
-                winning_number != 0 && winning_number % 3 == column % 3
+    /// Adds `amount` to the provider's capital. The deposit timestamp moves towards `now` in
+    /// proportion to the new capital, so topping up cannot inherit the age of a small deposit.
+    pub fn record_deposit(&mut self, amount: u64, now: i64) -> Result<()> {
+        let total = self.amount
+            .checked_add(amount)
+            .ok_or(RouletteError::ArithmeticOverflow)?;
+        let weighted = (self.deposit_timestamp as i128) * (self.amount as i128) +
+            (now as i128) * (amount as i128);
+        self.deposit_timestamp = if total == 0 { now } else { (weighted / (total as i128)) as i64 };
+        self.amount = total;
+        Ok(())
+    }
+}
+
+/// Owner of a Metaplex Core asset, read from its `AssetV1` header (key byte, then owner).
+pub fn core_asset_owner(asset: &AccountInfo) -> Option<Pubkey> {
+    const ASSET_V1_KEY: u8 = 1;
+    if *asset.owner != MPL_CORE_PROGRAM_ID {
+        return None;
+    }
+    let data = asset.try_borrow_data().ok()?;
+    if data.len() < 33 || data[0] != ASSET_V1_KEY {
+        return None;
+    }
+    Some(Pubkey::new_from_array(data[1..33].try_into().unwrap()))
+}
+
+/// A claim verified by `verify_claim` and waiting for `execute_claim` to transfer it.
+#[account]
+pub struct PendingClaim {
+    pub player: Pubkey,
+    pub game_session: Pubkey,
+    pub token_mint: Pubkey,
+    pub round: u64,
+    /// Full payout owed, stakes included.
+    pub amount: u64,
+    pub bump: u8,
+}
+
+/// Let-it-ride record of a player on a table: which of the player's bets of `round` re-stake
+/// their winnings on the same layout in the following round instead of being paid out.
+/// `claim_my_winnings` carries the winnings along while that round is taking bets; otherwise,
+/// or once a riding bet loses, the streak ends and the winnings are paid as usual.
+#[account]
+pub struct Carryover {
+    pub player: Pubkey,
+    pub game_session: Pubkey,
+    /// Round of the bets `riding_mask` refers to.
+    pub round: u64,
+    /// Bit `i` marks `PlayerBets::bets[i]` of `round` as riding.
+    pub riding_mask: u8,
+    /// Consecutive rounds winnings have been carried into so far.
+    pub streak: u16,
+    /// Winnings re-staked over the current streak.
+    pub streak_staked: u64,
+    pub bump: u8,
+}
+
+impl Carryover {
+    pub const SPACE: usize = 8 + 32 + 32 + 8 + 1 + 2 + 8 + 1;
+
+    /// Whether bet `index` of `round` rides.
+    pub fn is_riding(&self, round: u64, index: usize) -> bool {
+        self.round == round && index < 8 && self.riding_mask & (1 << index) != 0
+    }
+
+    /// The bets re-staking the winnings of the riding bets among `player_bets` for
+    /// `winning_number`: each on the same layout, staked with its payout under the round's
+    /// `fee_schedule`. Imprisoned bets never ride.
+    pub fn rides(
+        &self,
+        player_bets: &PlayerBets,
+        winning_number: u8,
+        fee_schedule: &FeeSchedule
+    ) -> Result<Vec<Bet>> {
+        let mut rides = Vec::new();
+        for (index, bet) in player_bets.bets.iter().enumerate() {
+            if
+                index < player_bets.imprisoned_count as usize ||
+                !self.is_riding(player_bets.round, index) ||
+                !PlayerBets::is_bet_winner(bet.bet_type, &bet.numbers, winning_number)
+            {
+                continue;
+            }
+            let amount = bet.amount
+                .checked_mul(fee_schedule.payout_multiplier(bet.bet_type))
+                .ok_or(RouletteError::ArithmeticOverflow)?;
+            rides.push(Bet { amount, ..bet.clone() });
+        }
+        Ok(rides)
+    }
+
+    /// Ends the current streak.
+    pub fn end_streak(&mut self) {
+        self.riding_mask = 0;
+        self.streak = 0;
+        self.streak_staked = 0;
+    }
+}
+
+/// Unpaid remainder of a claim the vault could not cover in full. Records form a per-vault FIFO
+/// queue (`VaultAccount::debt_head..debt_tail`) paid down by `repay_payout_debt`.
+#[account]
+pub struct PayoutDebt {
+    pub vault: Pubkey,
+    pub player: Pubkey,
+    pub round: u64,
+    pub index: u64,
+    pub amount_owed: u64,
+    pub bump: u8,
+}
+
+/// Announcement of a large capital withdrawal, created by `request_large_withdrawal` and
+/// consumed (closed) by `withdraw_liquidity`.
+#[account]
+pub struct WithdrawalRequest {
+    pub provider_state: Pubkey,
+    /// The table whose rounds the request waits on.
+    pub game_session: Pubkey,
+    /// The withdrawal unlocks once this round of `game_session` has completed (`u64::MAX` when
+    /// the vault had no exposure to the table, so only the timeout unlocks it).
+    pub unlock_round: u64,
+    pub requested_at: i64,
+    pub requested_slot: u64,
+    pub bump: u8,
+}
+
+impl WithdrawalRequest {
+    /// Whether `unlock_round` has completed on `game_session`, whose address is `table`. Rounds
+    /// of any other table do not count.
+    pub fn round_completed(&self, table: &Pubkey, game_session: &GameSession) -> bool {
+        self.game_session == *table && game_session.last_completed_round >= self.unlock_round
+    }
+}
+
+/// Protocol-wide configuration, shared by every vault and game session.
+#[account]
+pub struct Config {
+    /// Keys of the native admin multisig. Unused slots are `Pubkey::default()`.
+    pub admin_keys: [Pubkey; MAX_ADMIN_KEYS],
+    /// Number of `admin_keys` that must co-sign sensitive instructions (0 = disabled).
+    pub admin_threshold: u8,
+    pub bump: u8,
+    /// Who funds the rent of growing `RoundStats` accounts.
+    pub round_stats_payer: RentPayer,
+    /// Program notified of each vault's net exposure at `close_bets` (default = disabled).
+    pub hedging_program: Pubkey,
+    /// Token the treasury consolidates owner fees into, for vaults that opt in.
+    pub protocol_token_mint: Pubkey,
+    /// The only AMM program `withdraw_owner_revenue_swapped` may route through
+    /// (default = disabled).
+    pub rake_swap_program: Pubkey,
+    /// Bets a player may place per round, at most `MAX_BETS_PER_ROUND`.
+    pub max_bets_per_round: u8,
+    /// The only yield strategy program idle vault liquidity may be deployed to
+    /// (default = disabled).
+    pub strategy_program: Pubkey,
+    /// Wallet `withdraw_sol_fees` pays out to. Starts as `TREASURY_PUBKEY`.
+    pub sol_fee_recipient: Pubkey,
+    /// Bit `n` marks bet type `n` as experimental: only `beta_testers` may place it.
+    pub experimental_bet_types: u32,
+    /// Wallets allowed to bet on experimental bet types. Unused slots are `Pubkey::default()`.
+    pub beta_testers: [Pubkey; MAX_BETA_TESTERS],
+    /// Number of `beta_testers` in use.
+    pub beta_tester_count: u8,
+    /// Emergency circuit breaker set by `set_pause`: blocks new rounds and bets on every table,
+    /// liquidity deposits and strategy deployments. Withdrawals and claims keep working.
+    pub paused: bool,
+    /// Sequence number of the last protocol-wide event, which belongs to no table; see
+    /// `next_event_seq`.
+    pub event_seq: u64,
+    /// Account layout version; a config below `CONFIG_LAYOUT_VERSION` must be migrated with
+    /// `migrate_config`.
+    pub layout_version: u8,
+}
+
+/// Program-owned account holding vault-creation SOL fees and slashed dispute bonds until the
+/// treasury withdraws them.
+/// Everything above its rent-exempt minimum is withdrawable.
+#[account]
+pub struct SolFeeVault {
+    pub total_collected: u64,
+    pub total_withdrawn: u64,
+    pub bump: u8,
+}
+
+/// Payload of the `report_exposure` CPI sent to the hedging program at `close_bets`. The
+/// instruction data is the Anchor discriminator of `global:report_exposure` followed by this
+/// struct; accounts are the game session and the vault's `VaultExposure` for the table, both
+/// read-only.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct ExposureReport {
+    pub round: u64,
+    pub token_mint: Pubkey,
+    pub round_wagered: u64,
+    pub net_exposure: [i64; WHEEL_POCKETS],
+}
+
+/// Funding source for account growth.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum RentPayer {
+    /// The signer of the instruction that grows the account (the player in `place_bet`).
+    #[default]
+    Cranker,
+    /// The protocol's `RentPool`, falling back to the signer when the pool runs dry.
+    Protocol,
+}
+
+/// Program-owned lamport pool funding account growth when `RentPayer::Protocol` is selected,
+/// and the `PlayerBets` accounts of sponsored players. Topped up with plain system transfers
+/// or from a wrapped-SOL vault's owner fees with `fund_rent_pool`.
+#[account]
+pub struct RentPool {
+    pub bump: u8,
+    /// First slot of the current sponsorship window.
+    pub window_start_slot: u64,
+    /// `PlayerBets` accounts sponsored since `window_start_slot`.
+    pub window_sponsored: u16,
+}
+
+impl RentPool {
+    /// Counts one sponsored account at `slot`, starting a new window once the current one is
+    /// over, and fails once the window's `MAX_SPONSORED_PER_WINDOW` accounts are used up.
+    pub fn record_sponsorship(&mut self, slot: u64) -> Result<()> {
+        if slot >= self.window_start_slot.saturating_add(RENT_SPONSOR_WINDOW_SLOTS) {
+            self.window_start_slot = slot;
+            self.window_sponsored = 0;
+        }
+        require!(self.window_sponsored < MAX_SPONSORED_PER_WINDOW, RouletteError::SponsorshipLimitReached);
+        self.window_sponsored += 1;
+        Ok(())
+    }
+}
+
+impl Config {
+    /// Advances and returns the sequence number of protocol-wide events such as
+    /// `GamePauseChanged`, counted apart from any table's.
+    pub fn next_event_seq(&mut self) -> u64 {
+        self.event_seq = self.event_seq.wrapping_add(1);
+        self.event_seq
+    }
+
+    /// Sets the fields added after layout `from_layout_version` whose default is not zero.
+    /// Version 0 is a config created before the layout was versioned.
+    pub fn backfill_layout(&mut self, from_layout_version: u8) {
+        if from_layout_version < 1 {
+            if self.max_bets_per_round == 0 {
+                self.max_bets_per_round = DEFAULT_MAX_BETS_PER_ROUND as u8;
+            }
+            if self.sol_fee_recipient == Pubkey::default() {
+                self.sol_fee_recipient = TREASURY_PUBKEY;
+            }
+        }
+    }
+
+    /// Effective per-player bet limit, never above the `MAX_BETS_PER_ROUND` ceiling.
+    pub fn bet_limit(&self) -> usize {
+        (self.max_bets_per_round as usize).min(MAX_BETS_PER_ROUND)
+    }
+
+    /// Whether bet type `bet_type` is still in its rollout.
+    pub fn is_experimental(&self, bet_type: u8) -> bool {
+        bet_type < 32 && self.experimental_bet_types & (1 << bet_type) != 0
+    }
+
+    /// Whether `player` may place bets of type `bet_type`: any wallet once the type is generally
+    /// available, only `beta_testers` while it is experimental.
+    pub fn allows_bet_type(&self, bet_type: u8, player: &Pubkey) -> bool {
+        !self.is_experimental(bet_type) ||
+            self.beta_testers[..self.beta_tester_count as usize].contains(player)
+    }
+
+    /// Checks that at least `admin_threshold` distinct admin keys signed the transaction.
+    /// Co-signers are passed as extra (signer) accounts.
+    pub fn require_admin_approval(&self, signers: &[AccountInfo]) -> Result<()> {
+        if self.admin_threshold == 0 {
+            return Ok(());
+        }
+        let approvals = self.admin_keys
+            .iter()
+            .filter(|admin| **admin != Pubkey::default())
+            .filter(|admin| signers.iter().any(|info| info.is_signer && info.key == *admin))
+            .count();
+        require!(
+            approvals >= (self.admin_threshold as usize),
+            RouletteError::AdminThresholdNotMet
+        );
+        Ok(())
+    }
+}
+
+/// Betting volume of a single mint, in its base units.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default)]
+pub struct MintVolume {
+    pub token_mint: Pubkey,
+    pub volume: u128,
+}
+
+/// Protocol-wide counters, maintained incrementally so dashboards need no indexer. Counts
+/// start from the account's initialization.
+#[account]
+pub struct ProtocolStats {
+    pub total_vaults: u64,
+    pub total_rounds: u64,
+    pub total_players: u64,
+    /// Volume per mint, filled in first-bet order. Unused buckets have a default mint.
+    pub mint_volumes: [MintVolume; MAX_TRACKED_MINTS],
+    /// Bets on mints that found no free bucket.
+    pub untracked_bets: u64,
+    pub bump: u8,
+}
+
+impl ProtocolStats {
+    /// Adds `amount` to the volume bucket of `token_mint`, claiming a free bucket if needed.
+    pub fn record_volume(&mut self, token_mint: Pubkey, amount: u64) -> Result<()> {
+        let bucket = self.mint_volumes
+            .iter()
+            .position(|b| b.token_mint == token_mint)
+            .or_else(|| self.mint_volumes.iter().position(|b| b.token_mint == Pubkey::default()));
+        match bucket {
+            Some(index) => {
+                let bucket = &mut self.mint_volumes[index];
+                bucket.token_mint = token_mint;
+                bucket.volume = bucket.volume
+                    .checked_add(amount as u128)
+                    .ok_or(RouletteError::ArithmeticOverflow)?;
+            }
+            None => {
+                self.untracked_bets = self.untracked_bets.saturating_add(1);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Per-operator keeper performance, updated by the round lifecycle instructions the operator
+/// signs.
+#[account]
+#[derive(Default)]
+pub struct OperatorStats {
+    pub operator: Pubkey,
+    pub rounds_started: u64,
+    pub rounds_closed: u64,
+    pub rounds_settled: u64,
+    /// `get_random` calls that were deferred as retryable failures.
+    pub settlement_deferrals: u64,
+    /// Sum over settled rounds of the time between `close_bets` and settlement.
+    pub total_settlement_latency_secs: u64,
+    pub total_settlement_latency_slots: u64,
+    pub last_active_slot: u64,
+    pub bump: u8,
+}
+
+impl OperatorStats {
+    /// Fills in a freshly created account and records activity at `slot`.
+    pub fn touch(&mut self, operator: Pubkey, bump: u8, slot: u64) {
+        if self.operator == Pubkey::default() {
+            self.operator = operator;
+            self.bump = bump;
+        }
+        self.last_active_slot = slot;
+    }
+
+    pub fn record_settlement(&mut self, latency_secs: u64, latency_slots: u64) {
+        self.rounds_settled = self.rounds_settled.saturating_add(1);
+        self.total_settlement_latency_secs = self.total_settlement_latency_secs.saturating_add(latency_secs);
+        self.total_settlement_latency_slots = self.total_settlement_latency_slots.saturating_add(latency_slots);
+    }
+
+    pub fn average_settlement_latency_secs(&self) -> u64 {
+        self.total_settlement_latency_secs.checked_div(self.rounds_settled).unwrap_or(0)
+    }
+
+    pub fn average_settlement_latency_slots(&self) -> u64 {
+        self.total_settlement_latency_slots.checked_div(self.rounds_settled).unwrap_or(0)
+    }
+}
+
+/// A player's lifetime betting totals in one token, so they can audit the rake they paid.
+#[account]
+#[derive(Default)]
+pub struct PlayerStats {
+    pub player: Pubkey,
+    pub token_mint: Pubkey,
+    pub total_wagered: u64,
+    pub total_provider_fees: u64,
+    pub total_owner_fees: u64,
+    pub bet_count: u64,
+    pub bump: u8,
+}
+
+impl PlayerStats {
+    pub fn record_bet(&mut self, amount: u64, provider_fee: u64, owner_fee: u64) {
+        self.total_wagered = self.total_wagered.saturating_add(amount);
+        self.total_provider_fees = self.total_provider_fees.saturating_add(provider_fee);
+        self.total_owner_fees = self.total_owner_fees.saturating_add(owner_fee);
+        self.bet_count = self.bet_count.saturating_add(1);
+    }
+}
+
+/// Fixed-size bloom filter over bettor keys, used to count unique bettors without a list.
+///
+/// With 1024 bits and 3 hashes, the chance that a new bettor is mistaken for a known one is
+/// (1 - e^(-3n/1024))^3 after `n` bettors: about 0.04% at 25, 0.25% at 50, 1.6% at 100 and 8.7%
+/// at 200. Mistakes only ever undercount; there are no false negatives.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BettorFilter {
+    pub bits: [u64; BETTOR_FILTER_WORDS],
+}
+
+impl BettorFilter {
+    const BITS: usize = BETTOR_FILTER_WORDS * 64;
+
+    fn positions(key: &Pubkey) -> [usize; BETTOR_FILTER_HASHES] {
+        let digest = hash::hash(key.as_ref()).to_bytes();
+        let mut positions = [0usize; BETTOR_FILTER_HASHES];
+        for (i, position) in positions.iter_mut().enumerate() {
+            let word = u32::from_le_bytes(digest[i * 4..i * 4 + 4].try_into().unwrap());
+            *position = word as usize % Self::BITS;
+        }
+        positions
+    }
+
+    pub fn contains(&self, key: &Pubkey) -> bool {
+        Self::positions(key)
+            .iter()
+            .all(|&bit| self.bits[bit / 64] & (1u64 << (bit % 64)) != 0)
+    }
+
+    /// Adds `key`, returning whether it was (probably) not in the filter before.
+    pub fn insert(&mut self, key: &Pubkey) -> bool {
+        let mut added = false;
+        for bit in Self::positions(key) {
+            let mask = 1u64 << (bit % 64);
+            added |= self.bits[bit / 64] & mask == 0;
+            self.bits[bit / 64] |= mask;
+        }
+        added
+    }
+}
+
+/// Fee divisors and payout table a round is played under, snapshotted into its `RoundStats` at
+/// `start_new_round`. Bets, claims and refunds of the round read this instead of the compiled-in
+/// constants, so a program upgrade mid-round cannot change the economics of bets already placed.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FeeSchedule {
+    pub provider_divisor: u64,
+    pub owner_divisor: u64,
+    pub payout_multipliers: [u64; BET_TYPE_COUNT],
+}
+
+impl FeeSchedule {
+    /// The schedule compiled into the program, taken by new rounds.
+    pub const CURRENT: FeeSchedule = FeeSchedule {
+        provider_divisor: PROVIDER_DIVISOR,
+        owner_divisor: OWNER_DIVISOR,
+        payout_multipliers: PAYOUT_MULTIPLIERS,
+    };
+
+    /// Share of a stake credited to liquidity providers.
+    pub fn provider_fee(&self, amount: u64) -> u64 {
+        amount.checked_div(self.provider_divisor).unwrap_or(0)
+    }
+
+    /// Share of a stake credited to the vault owner.
+    pub fn owner_fee(&self, amount: u64) -> u64 {
+        amount.checked_div(self.owner_divisor).unwrap_or(0)
+    }
+
+    /// Gross payout per unit staked on a winning bet of `bet_type`, stake included.
+    pub fn payout_multiplier(&self, bet_type: u8) -> u64 {
+        self.payout_multipliers.get(bet_type as usize).copied().unwrap_or(0)
+    }
+}
+
+/// Per-round aggregates, created by `start_new_round` at their full size, so bets never grow
+/// the account.
+#[account]
+pub struct RoundStats {
+    pub round: u64,
+    pub total_wagered: u64,
+    pub bet_count: u32,
+    /// Snapshot of `Config::round_stats_payer` at round start.
+    pub rent_payer: RentPayer,
+    pub bump: u8,
+    /// Approximate number of distinct bettors, counted through `bettor_filter`.
+    pub unique_bettors: u32,
+    pub bettor_filter: BettorFilter,
+    /// Winning number once the round is settled, kept so the round stays claimable after later
+    /// rounds complete.
+    pub winning_number: Option<u8>,
+    pub settled_timestamp: i64,
+    pub settled_slot: u64,
+    /// Total staked per bet type.
+    pub bet_type_wagered: [u64; BET_TYPE_COUNT],
+    /// Fees and payouts the round's bets are placed and settled under.
+    pub fee_schedule: FeeSchedule,
+    /// Snapshot of `GameSession::en_prison` at round start.
+    pub en_prison: bool,
+    /// Set by `finalize_round` once `GameSession::finality_slots` have passed since settlement.
+    ///
+    /// Settlement lands in a slot that may still be abandoned by a fork, taking the winning
+    /// number with it. A validator-confirmed slot is effectively never rolled back, but the
+    /// program cannot observe confirmation, so finality is approximated by the settlement slot
+    /// being old enough: a `finalize_round` landing `finality_slots` later sits on the same fork
+    /// as the settlement, so a claim that needs it cannot act on a settlement that was
+    /// reverted. `DEFAULT_FINALITY_SLOTS` matches the cluster's own finalization depth.
+    pub finalized: bool,
+    /// `bet_type_exposure[bet_type][n]` is the gross payout owed to bets of that type if `n`
+    /// wins. `BET_TYPE_COUNT` entries while the round is in play, emptied at settlement so
+    /// claims do not load it; kept in a `Vec` so it lives on the heap.
+    pub bet_type_exposure: Vec<[u64; WHEEL_POCKETS]>,
+    /// Label given to `start_new_round` by an operator, such as a tournament name; empty when
+    /// none was given.
+    pub label: String,
+    /// URI given to `start_new_round` by an operator, such as a stream link; empty when none was
+    /// given.
+    pub uri: String,
+    /// Account layout version; stats below `ROUND_STATS_LAYOUT_VERSION` must be migrated with
+    /// `migrate_round_stats`.
+    pub layout_version: u8,
+    /// `AcceptingBets` while the round is open, then how it ended: `Completed`, `Voided` once its
+    /// bets are refundable through `refund_voided_bets`, or `Cancelled` once they are refundable
+    /// through `refund_bet`.
+    pub status: RoundStatus,
+    /// Snapshot of `GameSession::finality_payout_threshold` at settlement: claims of the round
+    /// paying at least this much need it finalized (0 = never).
+    pub finality_payout_threshold: u64,
+    /// Slot from which the round settles with the table's fallback mode, recorded at
+    /// `close_bets` (0 = no fallback).
+    pub fallback_slot: u64,
+    /// Slot from which `void_round` may void the round, recorded at `close_bets` (0 = never).
+    pub void_slot: u64,
+}
+
+impl RoundStats {
+    /// Serialized size with the label and URI at their maximum length.
+    pub const SPACE: usize = 8 + 8 + 8 + 4 + 1 + 1 + 4 + std::mem::size_of::<BettorFilter>() + 2 + 8 + 8 +
+        8 * BET_TYPE_COUNT + 8 + 8 + 8 * BET_TYPE_COUNT + 1 + 1 +
+        4 + BET_TYPE_COUNT * 8 * WHEEL_POCKETS +
+        4 + MAX_ROUND_LABEL_LEN + 4 + MAX_ROUND_URI_LEN + 1 + 1 + 8 + 8 + 8;
+
+    /// Fills in fields added after layout version `from_layout_version` that must not stay at
+    /// their zero value. `game_session` is the round's table, which knows how older rounds
+    /// ended.
+    pub fn backfill_layout(&mut self, from_layout_version: u8, game_session: &GameSession) {
+        if from_layout_version < 1 {
+            self.bet_type_exposure.resize(BET_TYPE_COUNT, [0; WHEEL_POCKETS]);
+        }
+        if from_layout_version < 2 {
+            self.status = if self.winning_number.is_some() {
+                RoundStatus::Completed
+            } else if self.round == game_session.current_round {
+                game_session.round_status.clone()
+            } else if self.round == game_session.last_cancelled_round {
+                RoundStatus::Cancelled
+            } else {
+                // An earlier round that never settled was voided.
+                RoundStatus::Voided
+            };
+        }
+        if from_layout_version < 3 {
+            self.finality_payout_threshold = game_session.finality_payout_threshold;
+        }
+        if from_layout_version < 4 && self.round == game_session.current_round {
+            self.fallback_slot = game_session.round_fallback_slot;
+            self.void_slot = game_session.round_void_slot;
+        }
+    }
+
+    /// Copies the deadlines `GameSession::record_settlement_deadlines` set for the round.
+    pub fn record_settlement_deadlines(&mut self, game_session: &GameSession) {
+        self.fallback_slot = game_session.round_fallback_slot;
+        self.void_slot = game_session.round_void_slot;
+    }
+
+    /// Whether the round's even-money bets went to prison: it was played En Prison and a zero
+    /// (0, or 00 on an American wheel) won.
+    pub fn imprisons_even_money(&self) -> bool {
+        self.en_prison && matches!(self.winning_number, Some(number) if number == 0 || number == DOUBLE_ZERO)
+    }
+
+    /// Whether the round was voided or cancelled, so its bets are refunded rather than settled
+    /// by a winning number.
+    pub fn is_refundable(&self) -> bool {
+        matches!(self.status, RoundStatus::Voided | RoundStatus::Cancelled)
+    }
+
+    /// Records the round's winning number at settlement, along with the table's
+    /// `finality_payout_threshold`, which then holds for the round's claims whatever the table
+    /// is set to later.
+    pub fn record_settlement(&mut self, winning_number: u8, clock: &Clock, finality_payout_threshold: u64) {
+        self.winning_number = Some(winning_number);
+        self.settled_timestamp = clock.unix_timestamp;
+        self.settled_slot = clock.slot;
+        self.status = RoundStatus::Completed;
+        self.finality_payout_threshold = finality_payout_threshold;
+    }
+
+    /// Whether a claim of the round paying `payout` must wait for it to be finalized.
+    pub fn requires_finality(&self, payout: u64) -> bool {
+        self.finality_payout_threshold > 0 && payout >= self.finality_payout_threshold
+    }
+
+    /// Adds a bet to the aggregates.
+    pub fn record_bet(&mut self, player: Pubkey, amount: u64) -> Result<()> {
+        self.total_wagered = self.total_wagered
+            .checked_add(amount)
+            .ok_or(RouletteError::ArithmeticOverflow)?;
+        self.bet_count = self.bet_count.saturating_add(1);
+        if self.bettor_filter.insert(&player) {
+            self.unique_bettors = self.unique_bettors.saturating_add(1);
+        }
+        Ok(())
+    }
+
+    /// Adds a bet covering `coverage_mask` to the per-bet-type aggregates.
+    pub fn record_bet_type(&mut self, bet_type: u8, coverage_mask: u64, amount: u64) -> Result<()> {
+        let index = bet_type as usize;
+        if self.bet_type_exposure.len() < BET_TYPE_COUNT {
+            self.bet_type_exposure.resize(BET_TYPE_COUNT, [0; WHEEL_POCKETS]);
+        }
+        let (Some(wagered), Some(exposure)) =
+            (self.bet_type_wagered.get_mut(index), self.bet_type_exposure.get_mut(index)) else {
+            return Ok(());
+        };
+        *wagered = wagered.checked_add(amount).ok_or(RouletteError::ArithmeticOverflow)?;
+
+        let payout = amount
+            .checked_mul(self.fee_schedule.payout_multiplier(bet_type))
+            .ok_or(RouletteError::ArithmeticOverflow)?;
+        for (number, owed) in exposure.iter_mut().enumerate() {
+            if coverage_mask & (1u64 << number) != 0 {
+                *owed = owed.checked_add(payout).ok_or(RouletteError::ArithmeticOverflow)?;
             }
-            13 => winning_number >= 1 && winning_number <= 12, // P12 (Dozen 1)
-            14 => winning_number >= 13 && winning_number <= 24, // M12 (Dozen 2)
-            15 => winning_number >= 25 && winning_number <= 36, // D12 (Dozen 3)
-            _ => false, // Unknown
         }
+        Ok(())
+    }
+
+    /// Gross payout owed per bet type once `winning_number` has won.
+    pub fn bet_type_payouts(&self, winning_number: u8) -> [u64; BET_TYPE_COUNT] {
+        let mut payouts = [0; BET_TYPE_COUNT];
+        for (payout, exposure) in payouts.iter_mut().zip(&self.bet_type_exposure) {
+            *payout = exposure.get(winning_number as usize).copied().unwrap_or(0);
+        }
+        payouts
+    }
+}
+
+/// Count of every winning number a table has produced, updated at settlement, so the wheel's
+/// distribution can be checked for bias from chain state alone.
+#[account]
+pub struct WheelHistogram {
+    pub game_session: Pubkey,
+    /// Wins per pocket, indexed by winning number, 00 at `DOUBLE_ZERO`. The last slot stays zero
+    /// on a single-zero wheel.
+    pub counts: [u32; WHEEL_POCKETS],
+    pub total_spins: u64,
+    pub bump: u8,
+}
+
+impl WheelHistogram {
+    pub fn record(&mut self, winning_number: u8) {
+        if let Some(count) = self.counts.get_mut(winning_number as usize) {
+            *count = count.saturating_add(1);
+            self.total_spins = self.total_spins.saturating_add(1);
+        }
+    }
+}
+
+/// Results of a table's last `ROUND_HISTORY_LEN` rounds, so frontends can render a results
+/// strip without replaying event logs. Round `r` lives at `entries[r % ROUND_HISTORY_LEN]`,
+/// written at settlement and overwritten `ROUND_HISTORY_LEN` rounds later.
+#[account]
+pub struct RoundHistory {
+    pub game_session: Pubkey,
+    /// Most recently settled round.
+    pub latest_round: u64,
+    /// Ring of past results; a `Vec` so it lives on the heap. Empty until the first settlement.
+    pub entries: Vec<RoundHistoryEntry>,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug, PartialEq)]
+pub struct RoundHistoryEntry {
+    /// Round of this entry; zero while the slot was never written.
+    pub round: u64,
+    pub winning_number: u8,
+    pub total_wagered: u64,
+    /// Payouts claimed so far through any claim path, including riding winnings and any part
+    /// owed as debt.
+    pub total_paid: u64,
+}
+
+impl RoundHistory {
+    pub const SPACE: usize =
+        8 + 32 + 8 + (4 + std::mem::size_of::<RoundHistoryEntry>() * ROUND_HISTORY_LEN) + 1;
+
+    /// Writes the result of a settled round over the entry it replaces.
+    pub fn record_round(&mut self, round: u64, winning_number: u8, total_wagered: u64) {
+        if self.entries.len() != ROUND_HISTORY_LEN {
+            self.entries.resize(ROUND_HISTORY_LEN, RoundHistoryEntry::default());
+        }
+        self.entries[(round % ROUND_HISTORY_LEN as u64) as usize] = RoundHistoryEntry {
+            round,
+            winning_number,
+            total_wagered,
+            total_paid: 0,
+        };
+        self.latest_round = self.latest_round.max(round);
+    }
+
+    /// Adds a claimed payout to `round`, if the round is still in the history.
+    pub fn record_payout(&mut self, round: u64, amount: u64) {
+        if let Some(entry) = self.entry_mut(round) {
+            entry.total_paid = entry.total_paid.saturating_add(amount);
+        }
+    }
+
+    pub fn entry(&self, round: u64) -> Option<&RoundHistoryEntry> {
+        self.entries
+            .get((round % ROUND_HISTORY_LEN as u64) as usize)
+            .filter(|entry| round != 0 && entry.round == round)
+    }
+
+    fn entry_mut(&mut self, round: u64) -> Option<&mut RoundHistoryEntry> {
+        self.entries
+            .get_mut((round % ROUND_HISTORY_LEN as u64) as usize)
+            .filter(|entry| round != 0 && entry.round == round)
+    }
+}
+
+/// What a `QueuedEvent` records.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum QueuedEventKind {
+    #[default]
+    Bet,
+    Settlement,
+    Claim,
+}
+
+/// Fixed-size record of an `EventQueue`. Fields a kind has no use for are zero.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct QueuedEvent {
+    /// Position in the queue, assigned on push.
+    pub seq: u64,
+    pub kind: QueuedEventKind,
+    pub round: u64,
+    /// Bettor or claimant; default for settlements.
+    pub player: Pubkey,
+    /// Stake of a bet, payout of a claim, or total wagered in a settled round.
+    pub amount: u64,
+    /// Bet type of a bet, or the winning number of a settlement.
+    pub detail: u8,
+    pub slot: u64,
+}
+
+impl QueuedEvent {
+    /// Serialized size; `size_of` would count the in-memory padding as well.
+    pub const SIZE: usize = 8 + 1 + 8 + 32 + 8 + 1 + 8;
+}
+
+/// Ring buffer of a table's bets, settlements and claims as fixed-size records, so other
+/// programs can consume them on-chain instead of parsing logs. Record `seq` lives at
+/// `entries[seq % EVENT_QUEUE_LEN]`; records `head..tail` are pending. Once full, a push
+/// overwrites the oldest pending record and counts it in `dropped`, so a stalled consumer can
+/// never block the game.
+#[account]
+pub struct EventQueue {
+    pub game_session: Pubkey,
+    /// Only key that may acknowledge records with `consume_events`, typically a PDA of the
+    /// consuming program.
+    pub consumer: Pubkey,
+    /// Sequence of the oldest pending record.
+    pub head: u64,
+    /// Sequence the next record is written at.
+    pub tail: u64,
+    /// Records overwritten before they were consumed.
+    pub dropped: u64,
+    /// Ring of records; a `Vec` so it lives on the heap. Empty until the first push.
+    pub entries: Vec<QueuedEvent>,
+    pub bump: u8,
+}
+
+impl EventQueue {
+    pub const SPACE: usize = 8 + 32 + 32 + 8 + 8 + 8 + (4 + QueuedEvent::SIZE * EVENT_QUEUE_LEN) + 1;
+
+    pub fn pending(&self) -> u64 {
+        self.tail - self.head
+    }
+
+    /// Appends `event`, overwriting the oldest pending record when the queue is full.
+    pub fn push(&mut self, mut event: QueuedEvent) {
+        if self.entries.len() != EVENT_QUEUE_LEN {
+            self.entries.resize(EVENT_QUEUE_LEN, QueuedEvent::default());
+        }
+        if self.pending() == EVENT_QUEUE_LEN as u64 {
+            self.head += 1;
+            self.dropped += 1;
+        }
+        event.seq = self.tail;
+        self.entries[(self.tail % EVENT_QUEUE_LEN as u64) as usize] = event;
+        self.tail += 1;
+    }
+
+    /// Acknowledges up to `count` pending records and returns how many were.
+    pub fn consume(&mut self, count: u64) -> u64 {
+        let consumed = count.min(self.pending());
+        self.head += consumed;
+        consumed
+    }
+
+    /// Pending record `seq`, if it has not been consumed or overwritten.
+    pub fn get(&self, seq: u64) -> Option<&QueuedEvent> {
+        if !(self.head..self.tail).contains(&seq) {
+            return None;
+        }
+        self.entries.get((seq % EVENT_QUEUE_LEN as u64) as usize)
+    }
+}
+
+/// On-chain proof of a round settled by `get_random`: every input of the settlement hash plus
+/// its output, so anyone can re-derive the winning number with `verify_round_result`.
+#[account]
+pub struct RoundResult {
+    pub game_session: Pubkey,
+    pub round: u64,
+    pub last_bettor: Pubkey,
+    pub generation_time: i64,
+    pub slot: u64,
+    pub server_seed: [u8; 32],
+    /// Commitment the server seed was checked against.
+    pub seed_commitment: [u8; 32],
+    pub entropy_accumulator: [u8; 32],
+    /// Most recent slot hashes at settlement, newest first.
+    pub slot_hashes: [[u8; 32]; SLOT_HASHES_MIXED],
+    pub hash_result: [u8; 32],
+    pub winning_number: u8,
+    pub bump: u8,
+    /// Wheel the round was spun on, which fixes the modulus of the winning number.
+    pub wheel_type: WheelType,
+}
+
+impl RoundResult {
+    /// The settlement hash over the stored inputs, in the order `get_random` hashes them.
+    pub fn compute_hash(&self) -> [u8; 32] {
+        let generation_time = self.generation_time.to_le_bytes();
+        let slot = self.slot.to_le_bytes();
+        let mut input: Vec<&[u8]> = vec![
+            self.last_bettor.as_ref(),
+            &generation_time,
+            &slot,
+            &self.server_seed,
+            &self.entropy_accumulator,
+        ];
+        input.extend(self.slot_hashes.iter().map(|slot_hash| &slot_hash[..]));
+        hash::hashv(&input).to_bytes()
+    }
+
+    /// Winning number for a settlement hash: its first 8 bytes, little-endian, modulo the
+    /// wheel's pocket count (37 or 38, with 37 standing for 00).
+    pub fn winning_number_for(hash_result: &[u8; 32], wheel_type: WheelType) -> u8 {
+        (u64::from_le_bytes(hash_result[0..8].try_into().unwrap()) % (wheel_type.pockets() as u64)) as u8
+    }
+
+    /// Whether the stored seed, hash and winning number all follow from the stored inputs.
+    pub fn verify(&self) -> bool {
+        let hash_result = self.compute_hash();
+        hash::hash(&self.server_seed).to_bytes() == self.seed_commitment &&
+            hash_result == self.hash_result &&
+            Self::winning_number_for(&hash_result, self.wheel_type) == self.winning_number
+    }
+}
+
+/// Optional display identity of a table, so explorers and frontends can render it from chain
+/// data alone.
+#[account]
+pub struct TableMetadata {
+    pub game_session: Pubkey,
+    /// UTF-8 display name, at most `MAX_TABLE_NAME_LEN` bytes.
+    pub name: String,
+    /// Hash of the off-chain description text.
+    pub description_hash: [u8; 32],
+    /// Mint whose metadata carries the table's banner image.
+    pub banner_mint: Option<Pubkey>,
+    pub bump: u8,
+}
+
+impl TableMetadata {
+    pub const SPACE: usize = 8 + 32 + 4 + MAX_TABLE_NAME_LEN + 32 + 1 + 32 + 1;
+}
+
+/// Players admitted to a private table, for invite-only or high-roller tables. While the table
+/// has one, only these players may bet on it.
+#[account]
+pub struct TableAccessList {
+    pub game_session: Pubkey,
+    /// At most `MAX_TABLE_ACCESS_LIST_LEN` distinct players.
+    pub players: Vec<Pubkey>,
+    pub bump: u8,
+}
+
+impl TableAccessList {
+    pub const SPACE: usize = 8 + 32 + 4 + 32 * MAX_TABLE_ACCESS_LIST_LEN + 1;
+}
+
+/// Jackpot of one round, settled through a Merkle root of `(index, player, amount)` leaves
+/// published after the round. Winners claim individually, so on-chain rent does not grow with
+/// the number of participants.
+#[account]
+pub struct Jackpot {
+    pub round: u64,
+    pub token_mint: Pubkey,
+    /// Token account owned by this PDA that holds the jackpot funds.
+    pub token_account: Pubkey,
+    pub merkle_root: [u8; 32],
+    pub total_amount: u64,
+    pub claimed_amount: u64,
+    pub bump: u8,
+}
+
+impl Jackpot {
+    /// Leaf of the eligibility tree. The `0x00` prefix keeps leaves distinct from inner nodes.
+    pub fn leaf(index: u64, player: &Pubkey, amount: u64) -> [u8; 32] {
+        hash::hashv(&[&[0], &index.to_le_bytes(), player.as_ref(), &amount.to_le_bytes()]).to_bytes()
+    }
+
+    /// Checks `leaf` against `merkle_root`. Inner nodes hash their children in sorted order
+    /// behind a `0x01` prefix, so proofs carry no left/right flags.
+    pub fn verify(&self, proof: &[[u8; 32]], leaf: [u8; 32]) -> bool {
+        let computed = proof.iter().fold(leaf, |node, sibling| {
+            let (left, right) = if node <= *sibling { (node, *sibling) } else { (*sibling, node) };
+            hash::hashv(&[&[1], &left, &right]).to_bytes()
+        });
+        computed == self.merkle_root
+    }
+}
+
+/// Flag raised against a vault's part in a completed round, backed by a bond held in this
+/// account on top of its rent; see `VaultAccount::dispute_bond`. Closed by `resolve_dispute`.
+#[account]
+pub struct RoundDispute {
+    pub game_session: Pubkey,
+    pub round: u64,
+    /// Vault whose reserve distribution the dispute blocks.
+    pub vault: Pubkey,
+    pub flagger: Pubkey,
+    /// Hash of the off-chain write-up of the suspected problem.
+    pub reason_hash: [u8; 32],
+    pub bond: u64,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+impl VaultExposure {
+    pub const SPACE: usize = 8 + 32 + 32 + 32 + 8 + 8 + 8 * WHEEL_POCKETS + 1;
+
+    /// Binds a freshly created account to `vault` on the table of `game_session`.
+    pub fn bind(&mut self, vault: &Account<VaultAccount>, game_session: Pubkey, bump: u8) {
+        if self.vault == Pubkey::default() {
+            self.vault = vault.key();
+            self.game_session = game_session;
+            self.token_mint = vault.token_mint;
+            self.bump = bump;
+        }
+    }
+
+    /// Whether the figures belong to `round`.
+    pub fn tracks(&self, round: u64) -> bool {
+        self.round == round && self.vault != Pubkey::default()
+    }
+
+    /// Adds a bet covering `coverage_mask` to the exposure for `round`.
+    pub fn record_bet(&mut self, round: u64, coverage_mask: u64, amount: u64, multiplier: u64) -> Result<()> {
+        self.start_round(round);
+        self.round_wagered = self.round_wagered
+            .checked_add(amount)
+            .ok_or(RouletteError::ArithmeticOverflow)?;
+        let payout = amount.checked_mul(multiplier).ok_or(RouletteError::ArithmeticOverflow)?;
+        self.add_payout(coverage_mask, payout)
+    }
+
+    /// Adds a bet a zero sent to prison to `round`, the round it is carried into. Its stake was
+    /// wagered in the round before, so only the `amount` it gets back by winning there is owed
+    /// on each number of `coverage_mask`.
+    pub fn record_imprisoned_bet(&mut self, round: u64, coverage_mask: u64, amount: u64) -> Result<()> {
+        self.start_round(round);
+        self.add_payout(coverage_mask, amount)
+    }
+
+    /// Moves the figures to `round`, starting from zero unless they already track it.
+    fn start_round(&mut self, round: u64) {
+        if self.round != round {
+            self.round = round;
+            self.round_wagered = 0;
+            self.payout_exposure = [0; WHEEL_POCKETS];
+        }
+    }
+
+    fn add_payout(&mut self, coverage_mask: u64, payout: u64) -> Result<()> {
+        for (number, exposure) in self.payout_exposure.iter_mut().enumerate() {
+            if coverage_mask & (1u64 << number) != 0 {
+                *exposure = exposure.checked_add(payout).ok_or(RouletteError::ArithmeticOverflow)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The highest gross payout owed for any winning number covered by `coverage_mask` once a
+    /// bet of `amount` at `multiplier` is added to `round`. `exposure` is `None` before the
+    /// vault's first bet on the table.
+    pub fn outcome_exposure_with(
+        exposure: Option<&Self>,
+        round: u64,
+        coverage_mask: u64,
+        amount: u64,
+        multiplier: u64
+    ) -> Result<u64> {
+        let payout = amount.checked_mul(multiplier).ok_or(RouletteError::ArithmeticOverflow)?;
+        let current = exposure.filter(|exposure| exposure.tracks(round)).map(|exposure| exposure.payout_exposure);
+        let mut highest = 0;
+        for number in 0..WHEEL_POCKETS {
+            if coverage_mask & (1u64 << number) != 0 {
+                let owed = current.map_or(0, |payouts| payouts[number]);
+                highest = highest.max(owed.checked_add(payout).ok_or(RouletteError::ArithmeticOverflow)?);
+            }
+        }
+        Ok(highest)
+    }
+
+    /// The vault's net result on the table per winning number for `round` (positive = the
+    /// vault pays out more than it took in).
+    pub fn net_exposure(&self, round: u64) -> Result<[i64; WHEEL_POCKETS]> {
+        let mut net = [0i64; WHEEL_POCKETS];
+        if self.tracks(round) {
+            for (net, payout) in net.iter_mut().zip(self.payout_exposure) {
+                *net = i64::try_from(payout as i128 - self.round_wagered as i128)
+                    .map_err(|_| RouletteError::ArithmeticOverflow)?;
+            }
+        }
+        Ok(net)
+    }
+}
+
+/// Receipt of a jackpot claim; its existence prevents claiming the same leaf twice.
+#[account]
+pub struct JackpotClaim {
+    pub jackpot: Pubkey,
+    pub index: u64,
+    pub player: Pubkey,
+    pub amount: u64,
+    pub bump: u8,
+}
+
+impl VaultAccount {
+    /// Sets the fields added after layout `from_layout_version` whose default is not zero.
+    /// `mint_decimals` are the decimals of the vault's mint.
+    pub fn backfill_layout(&mut self, from_layout_version: u8, mint_decimals: u8) {
+        if from_layout_version < 1 {
+            self.mint_decimals = mint_decimals;
+        }
+    }
+
+    /// Liquidity that can back bets and pay claims right now, i.e. not deployed to the strategy.
+    pub fn at_risk_liquidity(&self) -> u64 {
+        self.total_liquidity.saturating_sub(self.strategy_principal)
+    }
+
+    /// Removes `amount` of paid-out liquidity, recording whatever exceeds the payout reserve in
+    /// `realized_losses`.
+    pub fn debit_liquidity(&mut self, amount: u64) -> Result<()> {
+        let payout_reserve = self.total_liquidity.saturating_sub(self.total_provider_capital);
+        self.realized_losses = self.realized_losses
+            .checked_add(amount.saturating_sub(payout_reserve))
+            .ok_or(RouletteError::ArithmeticOverflow)?;
+        self.total_liquidity = self.total_liquidity
+            .checked_sub(amount)
+            .ok_or(RouletteError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Accounting invariants that do not hold, given the vault token account's `token_balance`.
+    pub fn invariant_violations(&self, token_balance: u64) -> Vec<VaultInvariantViolation> {
+        [
+            VaultInvariantViolation {
+                invariant: VaultInvariant::OwnerRewardBacked,
+                required: self.owner_reward.saturating_add(self.owner_share_owed.iter().sum()),
+                actual: self.total_liquidity,
+            },
+            VaultInvariantViolation {
+                invariant: VaultInvariant::ProviderCapitalBacked,
+                required: self.total_provider_capital.saturating_sub(self.realized_losses),
+                actual: self.total_liquidity,
+            },
+            VaultInvariantViolation {
+                invariant: VaultInvariant::TokenBalanceBacked,
+                required: self.at_risk_liquidity(),
+                actual: token_balance,
+            },
+        ]
+            .into_iter()
+            .filter(|check| check.actual < check.required)
+            .collect()
+    }
+
+    /// Counts a new liquidity provider, failing once `max_providers` is reached.
+    pub fn admit_provider(&mut self) -> Result<()> {
+        require!(
+            self.max_providers == 0 || self.provider_count < self.max_providers,
+            RouletteError::ProviderLimitReached
+        );
+        self.provider_count += 1;
+        Ok(())
+    }
+
+    /// `raw` base units of the vault's token as an exact decimal string.
+    #[cfg(not(target_os = "solana"))]
+    pub fn format_amount(&self, raw: u64) -> String {
+        crate::display::format_amount(raw, self.mint_decimals)
+    }
+
+    /// `raw` base units of the vault's token, decimal-adjusted.
+    #[cfg(not(target_os = "solana"))]
+    pub fn ui_amount(&self, raw: u64) -> f64 {
+        crate::display::to_ui_amount(raw, self.mint_decimals)
+    }
+
+    /// Whether a bet of `amount` is large enough for `LargeBetPlaced`.
+    pub fn is_large_bet(&self, amount: u64) -> bool {
+        self.large_bet_threshold > 0 && amount >= self.large_bet_threshold
+    }
+
+    /// Whether a payout of `amount` is large enough for `LargeWinClaimed`.
+    pub fn is_large_win(&self, amount: u64) -> bool {
+        self.large_win_threshold > 0 && amount >= self.large_win_threshold
+    }
+
+    /// Bond a new dispute against the vault must stake: `DISPUTE_BOND_LAMPORTS`, doubled for
+    /// each dispute already open on it, so blocking a vault's distributions gets dearer the
+    /// longer it is kept up.
+    pub fn dispute_bond(&self) -> u64 {
+        DISPUTE_BOND_LAMPORTS.saturating_mul(2u64.saturating_pow(self.open_disputes))
+    }
+
+    /// Releases a provider's slot when its position is closed.
+    pub fn release_provider(&mut self) {
+        self.provider_count = self.provider_count.saturating_sub(1);
+    }
+
+    /// Whether the owner revenue is split between `owner_share_recipients`.
+    pub fn has_owner_shares(&self) -> bool {
+        self.owner_share_recipients.iter().any(|recipient| *recipient != Pubkey::default())
+    }
+
+    /// Splits the accrued `owner_reward` between the share recipients by weight. Rounding dust
+    /// stays in `owner_reward` for the next split. Does nothing without shares.
+    pub fn distribute_owner_reward(&mut self) -> Result<()> {
+        let total = self.owner_reward as u128;
+        for (owed, bps) in self.owner_share_owed.iter_mut().zip(self.owner_share_bps) {
+            let share = (total * bps as u128 / BPS_DENOMINATOR as u128) as u64;
+            *owed = owed.checked_add(share).ok_or(RouletteError::ArithmeticOverflow)?;
+            self.owner_reward -= share;
+        }
+        Ok(())
+    }
+
+    /// How much more may be deployed to the strategy under `strategy_max_bps`.
+    pub fn strategy_capacity(&self) -> Result<u64> {
+        let cap = (self.total_liquidity as u128)
+            .checked_mul(self.strategy_max_bps as u128)
+            .ok_or(RouletteError::ArithmeticOverflow)? / (BPS_DENOMINATOR as u128);
+        Ok((cap as u64).saturating_sub(self.strategy_principal))
+    }
+
+    /// The largest single bet the vault accepts, as a share of its at-risk liquidity, rounded
+    /// down to a whole number of chips.
+    pub fn max_bet_amount(&self) -> Result<u64> {
+        let max_bet_amount = (self.at_risk_liquidity() as u128)
+            .checked_mul(MAX_BET_PERCENTAGE as u128)
+            .ok_or(RouletteError::ArithmeticOverflow)?
+            .checked_div(MAX_BET_PERCENTAGE_DIVISOR as u128)
+            .ok_or(RouletteError::ArithmeticOverflow)? as u64;
+        Ok(max_bet_amount - max_bet_amount.checked_rem(self.chip_size).unwrap_or(0))
+    }
+
+    /// Whether `amount` is a whole number of chips.
+    pub fn is_chip_multiple(&self, amount: u64) -> bool {
+        amount.checked_rem(self.chip_size).unwrap_or(0) == 0
+    }
+
+    /// The vault's pause state during `round`.
+    pub fn pause_state(&self, round: u64) -> PauseState {
+        if self.opted_out_from_round != 0 && round >= self.opted_out_from_round {
+            PauseState::OptedOut
+        } else {
+            PauseState::Active
+        }
+    }
+
+    /// Whether `operation` may run against the vault during `round`.
+    pub fn allows(&self, operation: VaultOperation, round: u64) -> bool {
+        operation.is_pause_exempt() || self.pause_state(round) == PauseState::Active
+    }
+
+    /// Whether withdrawing `amount` needs to be announced in advance.
+    pub fn is_large_withdrawal(&self, amount: u64) -> bool {
+        self.large_withdrawal_bps > 0 &&
+            (amount as u128) * (BPS_DENOMINATOR as u128) >
+                (self.total_liquidity as u128) * (self.large_withdrawal_bps as u128)
+    }
+
+    /// Loyalty boost owed on `earned` base rewards for a deposit `deposit_age_secs` old,
+    /// limited by the remaining loyalty budget.
+    pub fn loyalty_boost(&self, earned: u64, deposit_age_secs: i64) -> Result<u64> {
+        if self.loyalty_max_boost_bps == 0 || self.loyalty_maturity_secs == 0 {
+            return Ok(0);
+        }
+        let maturity = self.loyalty_maturity_secs as u128;
+        let age = (deposit_age_secs.max(0) as u128).min(maturity);
+        let boost = (earned as u128)
+            .checked_mul(self.loyalty_max_boost_bps as u128)
+            .and_then(|value| value.checked_mul(age))
+            .ok_or(RouletteError::ArithmeticOverflow)? / ((BPS_DENOMINATOR as u128) * maturity);
+        Ok((boost as u64).min(self.loyalty_budget))
+    }
+
+    /// Credits up to `amount` to `owner_reward`, respecting `owner_reward_cap`.
+    /// Whatever is not credited remains part of the payout reserve. Returns the credited amount.
+    pub fn credit_owner_reward(&mut self, amount: u64) -> Result<u64> {
+        let credited = if self.owner_reward_cap == 0 {
+            amount
+        } else {
+            amount.min(self.owner_reward_cap.saturating_sub(self.owner_reward))
+        };
+        self.owner_reward = self.owner_reward
+            .checked_add(credited)
+            .ok_or(RouletteError::ArithmeticOverflow)?;
+        Ok(credited)
+    }
+}
+
+impl GameSession {
+    /// Advances and returns the program-wide event sequence number. Every emitted event carries
+    /// one, so consecutive events differ by exactly one.
+    pub fn next_event_seq(&mut self) -> u64 {
+        self.event_seq = self.event_seq.wrapping_add(1);
+        self.event_seq
+    }
+
+    /// Folds a bet into `entropy_accumulator`, so `get_random` depends on every bettor of the
+    /// round rather than only the last one.
+    pub fn absorb_bet_entropy(&mut self, player: &Pubkey, bet: &Bet, slot: u64) -> Result<()> {
+        self.entropy_accumulator = hash::hashv(&[
+            &self.entropy_accumulator,
+            player.as_ref(),
+            &bet.try_to_vec()?,
+            &slot.to_le_bytes(),
+        ]).to_bytes();
+        Ok(())
+    }
+
+    /// Mode the current round settles with: the fallback once it has been activated.
+    pub fn active_randomness_mode(&self) -> RandomnessMode {
+        match self.fallback_randomness_mode {
+            Some(fallback) if self.fallback_active => fallback,
+            _ => self.randomness_mode,
+        }
+    }
+
+    /// Switches a closed round to the fallback mode once `round_fallback_slot` has passed,
+    /// dropping any VRF request of the primary mode. Returns whether it switched now.
+    pub fn activate_fallback_if_due(&mut self, slot: u64) -> bool {
+        if self.fallback_active ||
+            self.fallback_randomness_mode.is_none() ||
+            self.round_fallback_slot == 0 ||
+            slot < self.round_fallback_slot ||
+            self.round_status != RoundStatus::BetsClosed
+        {
+            return false;
+        }
+        self.fallback_active = true;
+        self.randomness_account = None;
+        self.randomness_seed_slot = 0;
+        self.orao_request_seed = None;
+        true
+    }
+
+    /// Records the current round's fallback and void deadlines when bets close, so later
+    /// configuration changes do not move them.
+    pub fn record_settlement_deadlines(&mut self) {
+        let deadline = |timeout: u64| match timeout {
+            0 => 0,
+            timeout => self.bets_closed_slot.saturating_add(timeout),
+        };
+        self.round_fallback_slot = if self.fallback_randomness_mode.is_some() {
+            deadline(self.fallback_timeout_slots)
+        } else {
+            0
+        };
+        self.round_void_slot = deadline(self.void_timeout_slots);
+    }
+
+    /// This table's seed after `GAME_SESSION_SEED`; see `seeds::table_seed`.
+    pub fn table_seed(&self) -> Vec<u8> {
+        crate::seeds::table_seed(self.table_id)
+    }
+
+    /// Whether the current round is waiting on an external VRF backend.
+    pub fn vrf_pending(&self) -> bool {
+        self.randomness_account.is_some() || self.orao_request_seed.is_some()
+    }
+
+    /// Ends the current round, whose stats are `round_stats`, without a winning number. Its bets
+    /// become refundable through `refund_voided_bets`.
+    pub fn void_current_round(&mut self, round_stats: &mut RoundStats) {
+        self.round_status = RoundStatus::Voided;
+        self.winning_number = None;
+        self.last_voided_round = self.current_round;
+        round_stats.status = RoundStatus::Voided;
+    }
+
+    /// Sets the fields added after layout `from_layout_version` whose default is not zero, which
+    /// a migration leaves zeroed otherwise. Version 0 is the original, unversioned session.
+    pub fn backfill_layout(&mut self, from_layout_version: u8) {
+        if from_layout_version < 1 {
+            self.slot_drift_tolerance_bps = DEFAULT_SLOT_DRIFT_TOLERANCE_BPS;
+        }
+        // Version 1 sessions predate the settlement timeout as well.
+        if from_layout_version < 2 {
+            self.void_timeout_slots = DEFAULT_VOID_TIMEOUT_SLOTS;
+            self.finality_slots = DEFAULT_FINALITY_SLOTS;
+        }
+        if from_layout_version < 3 {
+            self.recent_numbers = [NO_RECENT_NUMBER; RECENT_NUMBERS_LEN];
+        }
+    }
+
+    /// The only key allowed to draw the randomness of `round` on this table: operator
+    /// `round % count` of the rotation, or `GAME_ADMIN_PUBKEY` while no rotation is configured.
+    pub fn random_initiator_for(&self, round: u64) -> Pubkey {
+        match self.random_initiator_count {
+            0 => GAME_ADMIN_PUBKEY,
+            count => self.random_initiators[(round % count as u64) as usize],
+        }
+    }
+
+    /// Whether the table accepts bets staked with `vault`.
+    pub fn accepts_vault(&self, vault: &Pubkey) -> bool {
+        self.bound_vault.map_or(true, |bound_vault| bound_vault == *vault)
+    }
+
+    /// Whether `player` may bet on the table, given its `TableAccessList` if one was passed.
+    pub fn admits(&self, player: &Pubkey, access_list: Option<&TableAccessList>) -> bool {
+        !self.access_list_enabled || access_list.is_some_and(|list| list.players.contains(player))
+    }
+
+    /// Pushes a settled round's winning number onto `recent_numbers`, dropping the oldest.
+    pub fn record_recent_number(&mut self, winning_number: u8) {
+        self.recent_numbers.copy_within(..RECENT_NUMBERS_LEN - 1, 1);
+        self.recent_numbers[0] = winning_number;
+    }
+
+    /// Whether anyone may start rounds: the table runs on a schedule and settles without the
+    /// operator's server seed, which a permissionless starter could not commit to.
+    pub fn open_to_any_starter(&self) -> bool {
+        self.round_interval_slots > 0 &&
+            !self.active_randomness_mode().uses_server_seed() &&
+            !self.fallback_randomness_mode.is_some_and(|mode| mode.uses_server_seed())
+    }
+
+    /// Moves `next_round_slot` to the first point of the schedule after `slot`, skipping the
+    /// starts that were missed so the cadence never drifts.
+    pub fn schedule_next_round(&mut self, slot: u64) {
+        let missed = slot.saturating_sub(self.next_round_slot) / self.round_interval_slots;
+        self.next_round_slot = self.next_round_slot
+            .saturating_add(missed.saturating_add(1).saturating_mul(self.round_interval_slots));
+    }
+
+    /// Whether the current round meets `min_total_bets` and `min_unique_bettors`.
+    pub fn participation_met(&self) -> bool {
+        self.round_bet_count >= self.min_total_bets && self.round_unique_bettors >= self.min_unique_bettors
+    }
+
+    /// Whether `key` may drive this table's rounds: the game admin or the table's operator.
+    pub fn is_round_operator(&self, key: &Pubkey) -> bool {
+        *key == GAME_ADMIN_PUBKEY || (self.operator != Pubkey::default() && *key == self.operator)
+    }
+
+    /// Calls off the current round, whose stats are `round_stats`. Its bets become refundable
+    /// through `refund_bet`.
+    pub fn cancel_current_round(&mut self, round_stats: &mut RoundStats) {
+        self.round_status = RoundStatus::Cancelled;
+        self.winning_number = None;
+        self.last_cancelled_round = self.current_round;
+        round_stats.status = RoundStatus::Cancelled;
+    }
+
+    /// Whether anyone may cancel the current round once `CANCEL_ROUND_TIMEOUT_SECS` have passed:
+    /// only while its outcome cannot be known yet, i.e. it still accepts bets, or bets are closed
+    /// in a non-oracle mode before any seed is locked. Rounds whose randomness may be out are
+    /// left to `void_round`, so a losing player cannot front-run settlement with a cancel.
+    pub fn cancellable_by_anyone(&self) -> bool {
+        match self.round_status {
+            RoundStatus::AcceptingBets => true,
+            RoundStatus::BetsClosed => {
+                !matches!(
+                    self.active_randomness_mode(),
+                    RandomnessMode::SwitchboardVrf | RandomnessMode::OraoVrf | RandomnessMode::MultiOracle
+                ) &&
+                    self.randomness_account.is_none() &&
+                    self.orao_request_seed.is_none() &&
+                    self.reveal_target_slot == 0
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns true once `duration_secs` have passed since `start_time`/`start_slot`.
+    ///
+    /// `unix_timestamp` is voted on by validators and can drift within bounds, so a duration only
+    /// counts as elapsed when the clock agrees *and* enough slots have been produced, allowing the
+    /// slot count to fall short of the nominal amount by `slot_drift_tolerance_bps`.
+    pub fn duration_elapsed(
+        &self,
+        clock: &Clock,
+        start_time: i64,
+        start_slot: u64,
+        duration_secs: u32
+    ) -> bool {
+        let duration_secs = duration_secs as u64;
+        let nominal_slots = duration_secs.saturating_mul(1_000) / SLOT_DURATION_MS;
+        let tolerance_bps = (self.slot_drift_tolerance_bps as u64).min(BPS_DENOMINATOR);
+        let required_slots = nominal_slots.saturating_mul(BPS_DENOMINATOR - tolerance_bps) /
+            BPS_DENOMINATOR;
+
+        let secs_elapsed = clock.unix_timestamp.saturating_sub(start_time);
+        let slots_elapsed = clock.slot.saturating_sub(start_slot);
+
+        secs_elapsed >= 0 && (secs_elapsed as u64) >= duration_secs && slots_elapsed >= required_slots
+    }
+
+    /// Whether the current round has accepted bets for its minimum duration.
+    pub fn min_betting_duration_elapsed(&self, clock: &Clock) -> bool {
+        self.duration_elapsed(clock, self.round_start_time, self.round_start_slot, self.min_betting_duration_secs)
+    }
+
+    /// Whether the current round's betting window has passed, letting anyone close bets.
+    pub fn betting_window_elapsed(&self, clock: &Clock) -> bool {
+        self.max_betting_duration_secs > 0 &&
+            self.duration_elapsed(clock, self.round_start_time, self.round_start_slot, self.max_betting_duration_secs)
+    }
+
+    /// Whether the cooldown after the last completed round is over.
+    pub fn cooldown_elapsed(&self, clock: &Clock) -> bool {
+        self.round_status != RoundStatus::Completed ||
+            self.duration_elapsed(
+                clock,
+                self.last_completed_timestamp,
+                self.last_completed_slot,
+                self.cooldown_between_rounds_secs
+            )
+    }
+
+    /// Whether the round's initiator has missed the spin timeout, letting anyone call
+    /// `get_random`.
+    pub fn spin_timeout_elapsed(&self, clock: &Clock) -> bool {
+        self.spin_timeout_secs > 0 &&
+            matches!(self.round_status, RoundStatus::BetsClosed | RoundStatus::SeedLocked) &&
+            self.duration_elapsed(clock, self.bets_closed_timestamp, self.bets_closed_slot, self.spin_timeout_secs)
+    }
+
+    /// Approximate unix time the claim window for the settled round closes, if one is set.
+    pub fn claim_deadline(&self, round_stats: &RoundStats) -> Option<i64> {
+        (self.claim_window_secs > 0)
+            .then(|| round_stats.settled_timestamp.saturating_add(self.claim_window_secs as i64))
+    }
+
+    /// Minimum delay between `close_bets` and `get_random` actually enforced. `fast-mode` builds
+    /// cap it so devnet rounds settle within seconds.
+    pub fn random_delay_secs(&self) -> u32 {
+        #[cfg(feature = "fast-mode")]
+        return self.min_random_delay_secs.min(crate::constants::FAST_MODE_MAX_RANDOM_DELAY_SECS);
+        #[cfg(not(feature = "fast-mode"))]
+        self.min_random_delay_secs
+    }
+
+    /// Whether the claim window for the settled round has closed.
+    pub fn claim_window_expired(&self, clock: &Clock, round_stats: &RoundStats) -> bool {
+        self.claim_window_secs > 0 &&
+            self.duration_elapsed(
+                clock,
+                round_stats.settled_timestamp,
+                round_stats.settled_slot,
+                self.claim_window_secs
+            )
+    }
+}
+
+impl PlayerBets {
+    /// Size of a new account, with room for `DEFAULT_MAX_BETS_PER_ROUND` bets.
+    pub const INITIAL_SPACE: usize = Self::space(DEFAULT_MAX_BETS_PER_ROUND);
+
+    /// Account size with room for `bet_capacity` bets, plus the trailing layout version byte,
+    /// which serialization never reaches.
+    pub const fn space(bet_capacity: usize) -> usize {
+        8 + 32 + 8 + 32 + (4 + Bet::SIZE * bet_capacity) + 8 + 1 + 32 + 1 + 1 + 1
+    }
+
+    /// Layout version of the account at `info`, kept in its last byte.
+    pub fn layout_version(info: &AccountInfo) -> Result<u8> {
+        let data = info.try_borrow_data()?;
+        data.last().copied().ok_or_else(|| error!(RouletteError::InvalidPlayerBetsAccount))
+    }
+
+    /// Stamps `PLAYER_BETS_LAYOUT_VERSION` into the last byte of a freshly laid out account.
+    pub fn stamp_layout_version(info: &AccountInfo) -> Result<()> {
+        let mut data = info.try_borrow_mut_data()?;
+        let last = data.last_mut().ok_or_else(|| error!(RouletteError::InvalidPlayerBetsAccount))?;
+        *last = PLAYER_BETS_LAYOUT_VERSION;
+        Ok(())
+    }
+
+    /// Rebuilds an original account of `game_session`, which predates sponsorship and En
+    /// Prison.
+    pub fn from_legacy(legacy: LegacyPlayerBets, game_session: Pubkey) -> Self {
+        Self {
+            player: legacy.player,
+            round: legacy.round,
+            vault: legacy.vault,
+            bets: legacy.bets,
+            claimed_round: legacy.claimed_round,
+            bump: legacy.bump,
+            game_session,
+            rent_sponsored: false,
+            imprisoned_count: 0,
+        }
+    }
+
+    /// Recorded bets that `round_stats` (the stats of their round) sends to prison: the
+    /// even-money bets not already imprisoned once.
+    pub fn bets_to_imprison(&self, round_stats: &RoundStats) -> usize {
+        if !round_stats.imprisons_even_money() {
+            return 0;
+        }
+        self.bets
+            .iter()
+            .skip(self.imprisoned_count as usize)
+            .filter(|bet| bet.is_even_money())
+            .count()
+    }
+
+    /// Carries the bets `round_stats` sends to prison into the following round, dropping the
+    /// rest, and returns how many were carried.
+    pub fn imprison_bets(&mut self, round_stats: &RoundStats) -> u8 {
+        let carried = self.bets_to_imprison(round_stats);
+        let already_imprisoned = self.imprisoned_count as usize;
+        let mut index = 0;
+        self.bets.retain(|bet| {
+            let keep = carried > 0 && index >= already_imprisoned && bet.is_even_money();
+            index += 1;
+            keep
+        });
+        self.imprisoned_count = self.bets.len() as u8;
+        self.round += 1;
+        self.imprisoned_count
+    }
+
+    /// Whether the first bet of `round` carries the recorded bets a zero sent to prison along,
+    /// `round_stats` being the stats of their round.
+    pub fn carries_imprisoned_into(&self, round_stats: Option<&RoundStats>, round: u64) -> bool {
+        self.claimed_round < self.round &&
+            self.round.saturating_add(1) == round &&
+            round_stats.is_some_and(|stats| self.bets_to_imprison(stats) > 0)
+    }
+
+    /// Empties the bets, including any imprisoned ones.
+    pub fn clear_bets(&mut self) {
+        self.bets.clear();
+        self.imprisoned_count = 0;
+    }
+
+    /// Whether the recorded bets can be replaced by bets of a new round without losing
+    /// anything: there are none, they were claimed or refunded, or `round_stats` (the stats of
+    /// their round) shows the round settled and the bets either lost or can no longer be
+    /// claimed on `game_session`. A voided or cancelled round counts as settled too, since the
+    /// bet replacing its bets refunds them first. Bets a zero sent to prison count as settled
+    /// only when the new round is the one right after theirs, which they are carried into.
+    pub fn previous_bets_settled(
+        &self,
+        round_stats: Option<&RoundStats>,
+        game_session: &GameSession,
+        clock: &Clock
+    ) -> Result<bool> {
+        if self.bets.is_empty() || self.claimed_round >= self.round {
+            return Ok(true);
+        }
+        let Some(round_stats) = round_stats else {
+            return Ok(false);
+        };
+        if round_stats.is_refundable() {
+            return Ok(true);
+        }
+        if game_session.claim_window_expired(clock, round_stats) {
+            return Ok(true);
+        }
+        if self.bets_to_imprison(round_stats) > 0 && self.round.saturating_add(1) != game_session.current_round {
+            return Ok(false);
+        }
+        match round_stats.winning_number {
+            Some(winning_number) => Ok(self.total_payout(winning_number, &round_stats.fee_schedule)? == 0),
+            None => Ok(false),
+        }
+    }
+
+    /// Total stake of the recorded bets.
+    pub fn total_staked(&self) -> Result<u64> {
+        self.bets.iter().try_fold(0u64, |total, bet| {
+            total.checked_add(bet.amount).ok_or(RouletteError::ArithmeticOverflow.into())
+        })
+    }
+
+    /// Total payout of the recorded bets for `winning_number` under their round's
+    /// `fee_schedule`, stakes included. Imprisoned bets that win only return their stake.
+    pub fn total_payout(&self, winning_number: u8, fee_schedule: &FeeSchedule) -> Result<u64> {
+        let mut total_payout: u64 = 0;
+        for (index, bet) in self.bets.iter().enumerate() {
+            if Self::is_bet_winner(bet.bet_type, &bet.numbers, winning_number) {
+                let multiplier = if index < self.imprisoned_count as usize {
+                    1
+                } else {
+                    fee_schedule.payout_multiplier(bet.bet_type)
+                };
+                let payout_for_bet = bet.amount
+                    .checked_mul(multiplier)
+                    .ok_or(RouletteError::ArithmeticOverflow)?;
+                total_payout = total_payout
+                    .checked_add(payout_for_bet)
+                    .ok_or(RouletteError::ArithmeticOverflow)?;
+            }
+        }
+        Ok(total_payout)
+    }
+
+    /// The most `bets` could pay out together under `fee_schedule`, over every winning number.
+    pub fn max_payout<'a>(
+        bets: impl IntoIterator<Item = &'a Bet>,
+        fee_schedule: &FeeSchedule
+    ) -> Result<u64> {
+        let mut payouts = [0u64; WHEEL_POCKETS];
+        for bet in bets {
+            let coverage_mask = Self::coverage_mask(bet.bet_type, &bet.numbers);
+            let payout = bet.amount
+                .checked_mul(fee_schedule.payout_multiplier(bet.bet_type))
+                .ok_or(RouletteError::ArithmeticOverflow)?;
+            for (number, total) in payouts.iter_mut().enumerate() {
+                if coverage_mask & (1u64 << number) != 0 {
+                    *total = total.checked_add(payout).ok_or(RouletteError::ArithmeticOverflow)?;
+                }
+            }
+        }
+        Ok(payouts.into_iter().max().unwrap_or(0))
+    }
+
+    /// Whether `numbers` form a valid layout for `bet_type`. Only the leading numbers a bet type
+    /// uses are checked; the rest are ignored.
+    pub fn is_valid_bet(bet_type: u8, numbers: &[u8; 4]) -> bool {
+        bet_coverage(bet_type, numbers).is_some()
+    }
+
+    /// Bitmask of the numbers a bet wins on, bit `n` standing for number `n`.
+    pub fn coverage_mask(bet_type: u8, numbers: &[u8; 4]) -> u64 {
+        bet_coverage(bet_type, numbers).unwrap_or(0)
+    }
+
+    pub fn is_bet_winner(bet_type: u8, numbers: &[u8; 4], winning_number: u8) -> bool {
+        (winning_number as usize) < WHEEL_POCKETS &&
+            Self::coverage_mask(bet_type, numbers) & (1u64 << winning_number) != 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::{BET_TYPE_MAX, ROUND_STATS_LAYOUT_VERSION, VAULT_LAYOUT_VERSION};
+
+    fn keys(count: usize) -> Vec<Pubkey> {
+        (0..count).map(|_| Pubkey::new_unique()).collect()
+    }
+
+    #[test]
+    fn bettor_filter_has_no_false_negatives() {
+        let mut filter = BettorFilter::default();
+        let bettors = keys(200);
+        for key in &bettors {
+            filter.insert(key);
+        }
+        assert!(bettors.iter().all(|key| filter.contains(key)));
+        assert!(bettors.iter().all(|key| !filter.insert(key)));
+    }
+
+    /// Measured false-positive rate stays within twice the documented rate.
+    #[test]
+    fn bettor_filter_false_positive_rate_matches_documentation() {
+        for (bettors, documented_rate) in [(50usize, 0.0025f64), (100, 0.016), (200, 0.087)] {
+            let mut filter = BettorFilter::default();
+            for key in keys(bettors) {
+                filter.insert(&key);
+            }
+            let probes = 20_000;
+            let false_positives = keys(probes).iter().filter(|key| filter.contains(key)).count();
+            let rate = false_positives as f64 / probes as f64;
+            assert!(
+                rate <= documented_rate * 2.0,
+                "{bettors} bettors: measured {rate}, documented {documented_rate}"
+            );
+        }
+    }
+
+    fn vault_in(state: PauseState, round: u64) -> VaultAccount {
+        let mut vault = VaultAccount {
+            token_mint: Pubkey::new_unique(),
+            token_account: Pubkey::new_unique(),
+            total_liquidity: 1_000,
+            total_provider_capital: 1_000,
+            bump: 255,
+            owner_reward: 0,
+            reward_per_share_index: 0,
+            owner_reward_cap: 0,
+            large_withdrawal_bps: 0,
+            opted_out_from_round: 0,
+            chip_size: 0,
+            rake_in_protocol_token: false,
+            debt_head: 0,
+            debt_tail: 0,
+            outstanding_debt: 0,
+            loyalty_budget_bps: 0,
+            loyalty_max_boost_bps: 0,
+            loyalty_maturity_secs: 0,
+            loyalty_budget: 0,
+            strategy_max_bps: 0,
+            strategy_principal: 0,
+            strategy_yield: 0,
+            realized_losses: 0,
+            confidential_payouts: false,
+            max_player_payout: 0,
+            crank_reward: 0,
+            table_maximum: 0,
+            owner_share_recipients: [Pubkey::default(); MAX_OWNER_SHARES],
+            owner_share_bps: [0; MAX_OWNER_SHARES],
+            owner_share_owed: [0; MAX_OWNER_SHARES],
+            provider_count: 0,
+            max_providers: 0,
+            mint_decimals: 6,
+            large_bet_threshold: 0,
+            large_win_threshold: 0,
+            open_disputes: 0,
+            layout_version: VAULT_LAYOUT_VERSION,
+        };
+        if state == PauseState::OptedOut {
+            vault.opted_out_from_round = round;
+        }
+        assert_eq!(vault.pause_state(round), state);
+        vault
+    }
+
+    const PAUSE_STATES: [PauseState; 2] = [PauseState::Active, PauseState::OptedOut];
+
+    #[test]
+    fn claims_and_lp_reward_withdrawals_work_in_every_pause_state() {
+        for state in PAUSE_STATES {
+            let vault = vault_in(state, 7);
+            for operation in [
+                VaultOperation::ClaimWinnings,
+                VaultOperation::WithdrawProviderRevenue,
+                VaultOperation::ClaimReferralRewards,
+            ] {
+                assert!(vault.allows(operation, 7), "{operation:?} blocked while {state:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn betting_stops_only_when_paused() {
+        assert!(vault_in(PauseState::Active, 7).allows(VaultOperation::PlaceBet, 7));
+        let opted_out = vault_in(PauseState::OptedOut, 7);
+        assert!(!opted_out.allows(VaultOperation::PlaceBet, 7));
+        // The opt-out only applies from its effective round on.
+        assert!(opted_out.allows(VaultOperation::PlaceBet, 6));
+    }
+
+    /// Payouts only count as realized losses once they eat into provider capital, and a loss
+    /// the accounting never saw trips the capital invariant.
+    #[test]
+    fn vault_invariants_track_realized_losses() {
+        let mut vault = vault_in(PauseState::Active, 7);
+        vault.total_liquidity = 1_200;
+        vault.debit_liquidity(500).unwrap();
+        assert_eq!(vault.realized_losses, 300);
+        assert!(vault.invariant_violations(700).is_empty());
+
+        vault.total_liquidity -= 100;
+        vault.owner_reward = 700;
+        let broken: Vec<_> = vault.invariant_violations(500)
+            .iter()
+            .map(|violation| violation.invariant)
+            .collect();
+        assert_eq!(broken, [
+            VaultInvariant::OwnerRewardBacked,
+            VaultInvariant::ProviderCapitalBacked,
+            VaultInvariant::TokenBalanceBacked,
+        ]);
+    }
+
+    #[test]
+    fn vault_amounts_format_exactly_with_the_mint_decimals() {
+        let vault = vault_in(PauseState::Active, 7);
+        assert_eq!(vault.format_amount(1_500_000), "1.5");
+        assert_eq!(vault.format_amount(42), "0.000042");
+        assert_eq!(vault.format_amount(u64::MAX), "18446744073709.551615");
+        assert_eq!(crate::display::format_amount(1_000, 0), "1000");
+        assert_eq!(crate::display::parse_amount("1.5", 6), Some(1_500_000));
+        assert_eq!(crate::display::parse_amount(".000042", 6), Some(42));
+        assert_eq!(crate::display::parse_amount("1.0000001", 6), None);
+        assert_eq!(crate::display::parse_amount("18446744073709.551616", 6), None);
+    }
+
+    #[test]
+    fn max_payout_takes_the_worst_winning_number() {
+        let bet = |amount, bet_type, anchor| Bet { amount, bet_type, numbers: [anchor, 0, 0, 0] };
+        // Straight on 1 (red) and red: 1 pays both, other red numbers only the red bet.
+        let bets = [bet(10, 0, 1), bet(100, 6, 0)];
+        assert_eq!(PlayerBets::max_payout(&bets, &FeeSchedule::CURRENT).unwrap(), 10 * 36 + 100 * 2);
+        // Straights on 1 and 2 never win together.
+        let bets = [bet(10, 0, 1), bet(20, 0, 2)];
+        assert_eq!(PlayerBets::max_payout(&bets, &FeeSchedule::CURRENT).unwrap(), 20 * 36);
+        assert_eq!(PlayerBets::max_payout(&[], &FeeSchedule::CURRENT).unwrap(), 0);
+    }
+
+    fn exposure_on(vault: &VaultAccount, game_session: Pubkey) -> VaultExposure {
+        VaultExposure {
+            vault: Pubkey::new_unique(),
+            game_session,
+            token_mint: vault.token_mint,
+            round: 0,
+            round_wagered: 0,
+            payout_exposure: [0; WHEEL_POCKETS],
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn rounds_with_a_locked_seed_are_not_cancellable_by_anyone() {
+        let mut game_session = GameSession { round_status: RoundStatus::BetsClosed, ..Default::default() };
+        assert!(game_session.cancellable_by_anyone());
+        game_session.reveal_target_slot = 1_000;
+        assert!(!game_session.cancellable_by_anyone());
+        game_session.round_status = RoundStatus::SeedLocked;
+        assert!(!game_session.cancellable_by_anyone());
+    }
+
+    #[test]
+    fn closed_oracle_rounds_are_not_cancellable_by_anyone() {
+        let mut game_session = GameSession {
+            round_status: RoundStatus::AcceptingBets,
+            randomness_mode: RandomnessMode::SwitchboardVrf,
+            ..Default::default()
+        };
+        assert!(game_session.cancellable_by_anyone());
+        game_session.round_status = RoundStatus::BetsClosed;
+        assert!(!game_session.cancellable_by_anyone());
+        // A server-seed fallback that has taken over still counts as an oracle-free round.
+        game_session.fallback_randomness_mode = Some(RandomnessMode::InternalHash);
+        game_session.fallback_active = true;
+        assert!(game_session.cancellable_by_anyone());
+        game_session.randomness_account = Some(Pubkey::new_unique());
+        assert!(!game_session.cancellable_by_anyone());
+    }
+
+    #[test]
+    fn dispute_bond_doubles_per_open_dispute() {
+        let mut vault = vault_in(PauseState::Active, 1);
+        assert_eq!(vault.dispute_bond(), DISPUTE_BOND_LAMPORTS);
+        vault.open_disputes = 3;
+        assert_eq!(vault.dispute_bond(), DISPUTE_BOND_LAMPORTS * 8);
+        vault.open_disputes = u32::MAX;
+        assert_eq!(vault.dispute_bond(), u64::MAX);
+    }
+
+    #[test]
+    fn exposure_is_kept_per_table_and_round() {
+        let vault = vault_in(PauseState::Active, 1);
+        let mut table_a = exposure_on(&vault, Pubkey::new_unique());
+        let mut table_b = exposure_on(&vault, Pubkey::new_unique());
+        table_a.record_bet(1, 1 << 17, 10, 36).unwrap();
+        // Bets alternating between the tables add up on each one's own figures.
+        table_b.record_bet(1, 1 << 17, 10, 36).unwrap();
+        table_a.record_bet(1, 1 << 17, 10, 36).unwrap();
+        assert_eq!(table_a.payout_exposure[17], 720);
+        assert_eq!(table_b.payout_exposure[17], 360);
+
+        // A split on 17/18 adds 10 * 18 to both numbers; 17 already owes 720 on table A.
+        let split = (1 << 17) | (1 << 18);
+        assert_eq!(VaultExposure::outcome_exposure_with(Some(&table_a), 1, split, 10, 18).unwrap(), 720 + 180);
+        // The table's next round, or a vault without bets on the table yet, starts from zero.
+        assert_eq!(VaultExposure::outcome_exposure_with(Some(&table_a), 2, split, 10, 18).unwrap(), 180);
+        assert_eq!(VaultExposure::outcome_exposure_with(None, 1, split, 10, 18).unwrap(), 180);
+        table_a.record_bet(2, 1 << 18, 10, 36).unwrap();
+        assert_eq!(table_a.payout_exposure[17], 0);
+        assert_eq!(table_a.round_wagered, 10);
+    }
+
+    #[test]
+    fn imprisoned_exposure_owes_the_stake_without_wagering() {
+        let vault = vault_in(PauseState::Active, 1);
+        let mut exposure = exposure_on(&vault, Pubkey::new_unique());
+        exposure.record_bet(2, 1 << 17, 10, 36).unwrap();
+        // An imprisoned bet on odd numbers, shown here on 1 and 17 only.
+        exposure.record_imprisoned_bet(2, (1 << 1) | (1 << 17), 50).unwrap();
+        assert_eq!(exposure.round_wagered, 10);
+        assert_eq!(exposure.payout_exposure[1], 50);
+        assert_eq!(exposure.payout_exposure[17], 360 + 50);
+        assert_eq!(exposure.payout_exposure[2], 0);
+    }
+
+    fn round_stats(winning_number: Option<u8>, en_prison: bool) -> RoundStats {
+        RoundStats {
+            round: 1,
+            total_wagered: 0,
+            bet_count: 0,
+            rent_payer: RentPayer::Cranker,
+            bump: 0,
+            unique_bettors: 0,
+            bettor_filter: BettorFilter::default(),
+            winning_number,
+            settled_timestamp: 0,
+            settled_slot: 0,
+            bet_type_wagered: [0; BET_TYPE_COUNT],
+            fee_schedule: FeeSchedule::CURRENT,
+            en_prison,
+            finalized: false,
+            bet_type_exposure: Vec::new(),
+            label: String::new(),
+            uri: String::new(),
+            layout_version: ROUND_STATS_LAYOUT_VERSION,
+            status: RoundStatus::AcceptingBets,
+            finality_payout_threshold: 0,
+            fallback_slot: 0,
+            void_slot: 0,
+        }
+    }
+
+    #[test]
+    fn round_stats_with_full_label_and_uri_fit_their_space() {
+        let mut stats = round_stats(None, false);
+        stats.label = "L".repeat(MAX_ROUND_LABEL_LEN);
+        stats.uri = "U".repeat(MAX_ROUND_URI_LEN);
+        stats.bet_type_exposure = vec![[0; WHEEL_POCKETS]; BET_TYPE_COUNT];
+        stats.record_bet(Pubkey::new_unique(), 10).unwrap();
+
+        let mut data = Vec::new();
+        stats.try_serialize(&mut data).unwrap();
+        assert!(data.len() <= RoundStats::SPACE);
+    }
+
+    #[test]
+    fn unversioned_round_stats_read_as_version_zero_once_grown() {
+        let mut stats = round_stats(None, false);
+        stats.label = "L".repeat(MAX_ROUND_LABEL_LEN);
+        stats.uri = "U".repeat(MAX_ROUND_URI_LEN);
+        let mut data = Vec::new();
+        stats.try_serialize(&mut data).unwrap();
+        // Drop the version byte and the fields appended after it, which stats written before the
+        // version existed lack.
+        data.truncate(data.len() - 1 - 1 - 8 - 8 - 8);
+        assert!(RoundStats::try_deserialize(&mut &data[..]).is_err());
+
+        data.resize(RoundStats::SPACE, 0);
+        let mut migrated = RoundStats::try_deserialize(&mut &data[..]).unwrap();
+        assert_eq!(migrated.layout_version, 0);
+        migrated.backfill_layout(migrated.layout_version, &GameSession::default());
+        assert_eq!(migrated.bet_type_exposure.len(), BET_TYPE_COUNT);
+        assert_eq!(migrated.uri, stats.uri);
+        // Round 1 is behind the table's current round and never settled, so it was voided.
+        assert_eq!(migrated.status, RoundStatus::Voided);
+    }
+
+    /// A vault of the original program reads as layout version 0 once grown, with its fields
+    /// intact.
+    #[test]
+    fn original_vault_reads_as_version_zero_once_grown() {
+        let token_mint = Pubkey::new_unique();
+        let mut data = VaultAccount::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(token_mint.as_ref());
+        data.extend_from_slice(Pubkey::new_unique().as_ref());
+        data.extend_from_slice(&1_000u64.to_le_bytes());
+        data.extend_from_slice(&900u64.to_le_bytes());
+        data.push(253);
+        data.extend_from_slice(&100u64.to_le_bytes());
+        data.extend_from_slice(&7u128.to_le_bytes());
+        data.resize(8 + 112, 0);
+        assert!(VaultAccount::try_deserialize(&mut &data[..]).is_err());
+
+        data.resize(8 + std::mem::size_of::<VaultAccount>(), 0);
+        let mut vault = VaultAccount::try_deserialize(&mut &data[..]).unwrap();
+        assert_eq!(vault.layout_version, 0);
+        vault.backfill_layout(vault.layout_version, 9);
+        assert_eq!(vault.token_mint, token_mint);
+        assert_eq!((vault.total_liquidity, vault.owner_reward, vault.reward_per_share_index), (1_000, 100, 7));
+        assert_eq!(vault.mint_decimals, 9);
+    }
+
+    #[test]
+    fn round_stats_counts_unique_bettors() {
+        let mut stats = round_stats(None, false);
+        let players = keys(3);
+        for player in players.iter().chain(players.iter()) {
+            stats.record_bet(*player, 10).unwrap();
+            stats.record_bet_type(0, 1 << 17, 10).unwrap();
+        }
+        assert_eq!(stats.unique_bettors, 3);
+        assert_eq!(stats.bet_count, 6);
+        assert_eq!(stats.total_wagered, 60);
+        assert_eq!(stats.bet_type_wagered[0], 60);
+        assert_eq!(stats.bet_type_payouts(17)[0], 60 * PAYOUT_MULTIPLIERS[0]);
+        assert_eq!(stats.bet_type_payouts(18)[0], 0);
+    }
+
+    /// Every valid bet pays 36 units across the numbers it covers (35 for the five-number
+    /// basket, as on a real double-zero table), which pins the coverage table and the
+    /// multipliers to each other.
+    #[test]
+    fn coverage_table_and_multipliers_agree_for_every_valid_bet() {
+        let mut valid_bets = 0;
+        for bet_type in 0..=BET_TYPE_MAX {
+            for first in 0..=40u8 {
+                for second in 0..=40u8 {
+                    let numbers = [first, second, 0, 0];
+                    if !PlayerBets::is_valid_bet(bet_type, &numbers) {
+                        continue;
+                    }
+                    valid_bets += 1;
+                    let covered = (0..WHEEL_POCKETS as u8)
+                        .filter(|&n| PlayerBets::is_bet_winner(bet_type, &numbers, n))
+                        .count() as u64;
+                    assert_eq!(
+                        covered * FeeSchedule::CURRENT.payout_multiplier(bet_type),
+                        if covered == 5 { 35 } else { 36 },
+                        "bet type {bet_type} at {numbers:?}"
+                    );
+                }
+            }
+        }
+        assert!(valid_bets > 0);
+    }
+
+    #[test]
+    fn round_history_overwrites_rounds_after_a_full_cycle() {
+        let mut history = RoundHistory {
+            game_session: Pubkey::default(),
+            latest_round: 0,
+            entries: Vec::new(),
+            bump: 0,
+        };
+        history.record_round(1, 17, 500);
+        history.record_payout(1, 360);
+        assert_eq!(history.entry(1).map(|entry| (entry.winning_number, entry.total_paid)), Some((17, 360)));
+
+        let later = 1 + ROUND_HISTORY_LEN as u64;
+        history.record_round(later, 0, 100);
+        history.record_payout(1, 360);
+        assert!(history.entry(1).is_none());
+        assert_eq!(history.entry(later).map(|entry| entry.total_paid), Some(0));
+        assert_eq!(history.latest_round, later);
+        assert_eq!(history.entries.len(), ROUND_HISTORY_LEN);
+    }
+
+    /// Splitting the owner revenue keeps rounding dust in `owner_reward`, and what was split
+    /// off still has to be backed by the vault's liquidity.
+    #[test]
+    fn owner_reward_split_keeps_dust_and_stays_backed() {
+        let mut vault = vault_in(PauseState::Active, 7);
+        for recipient in &mut vault.owner_share_recipients[..3] {
+            *recipient = Pubkey::new_unique();
+        }
+        vault.owner_share_bps[..3].copy_from_slice(&[3_334, 3_333, 3_333]);
+        vault.total_liquidity = 1_000;
+        vault.owner_reward = 100;
+        vault.distribute_owner_reward().unwrap();
+        assert_eq!(vault.owner_share_owed, [33, 33, 33, 0]);
+        assert_eq!(vault.owner_reward, 1);
+
+        vault.total_liquidity = 90;
+        assert_eq!(
+            vault.invariant_violations(90).first().map(|violation| violation.invariant),
+            Some(VaultInvariant::OwnerRewardBacked)
+        );
+    }
+
+    /// An account full to the ceiling serializes to exactly its space minus the version byte,
+    /// so it never overwrites the version.
+    #[test]
+    fn player_bets_space_fits_a_full_account_exactly() {
+        let player_bets = PlayerBets {
+            player: Pubkey::new_unique(),
+            game_session: Pubkey::new_unique(),
+            round: 1,
+            vault: Pubkey::new_unique(),
+            bets: vec![Bet { amount: 1, bet_type: 0, numbers: [7, 0, 0, 0] }; MAX_BETS_PER_ROUND],
+            claimed_round: 0,
+            bump: 255,
+            rent_sponsored: false,
+            imprisoned_count: 0,
+        };
+        let mut data = Vec::new();
+        player_bets.try_serialize(&mut data).unwrap();
+        assert_eq!(data.len(), PlayerBets::space(MAX_BETS_PER_ROUND) - 1);
+    }
+
+    /// An account of the original program, sized with padded bets, decodes as
+    /// `LegacyPlayerBets` and its last byte reads as layout version 0 even when full.
+    #[test]
+    fn original_player_bets_read_as_layout_version_zero() {
+        let player = Pubkey::new_unique();
+        let mut data = PlayerBets::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(player.as_ref());
+        data.extend_from_slice(&7u64.to_le_bytes());
+        data.extend_from_slice(Pubkey::new_unique().as_ref());
+        data.extend_from_slice(Pubkey::new_unique().as_ref());
+        vec![Bet { amount: u64::MAX, bet_type: 0, numbers: [7, 0, 0, 0] }; 6]
+            .serialize(&mut data)
+            .unwrap();
+        data.extend_from_slice(&6u64.to_le_bytes());
+        data.push(254);
+        // The original program allowed 6 bets and sized them with their padding.
+        data.resize(8 + 32 + 8 + 32 + 32 + (4 + std::mem::size_of::<Bet>() * 6) + 1, 0);
+        assert_eq!(data.last(), Some(&0));
+
+        let legacy = LegacyPlayerBets::deserialize(&mut &data[8..]).unwrap();
+        let game_session = Pubkey::new_unique();
+        let player_bets = PlayerBets::from_legacy(legacy, game_session);
+        assert_eq!(player_bets.player, player);
+        assert_eq!(player_bets.round, 7);
+        assert_eq!(player_bets.bets.len(), 6);
+        assert_eq!(player_bets.claimed_round, 6);
+        assert_eq!(player_bets.bump, 254);
+        assert_eq!(player_bets.game_session, game_session);
+    }
+
+    #[test]
+    fn en_prison_carries_even_money_bets_once_and_returns_only_their_stake() {
+        let bet = |amount, bet_type, anchor| Bet { amount, bet_type, numbers: [anchor, 0, 0, 0] };
+        let mut player_bets = PlayerBets {
+            player: Pubkey::new_unique(),
+            game_session: Pubkey::new_unique(),
+            round: 1,
+            vault: Pubkey::new_unique(),
+            // Red, a straight on 17 and Odd.
+            bets: vec![bet(100, 6, 0), bet(10, 0, 17), bet(50, 9, 0)],
+            claimed_round: 0,
+            bump: 255,
+            rent_sponsored: false,
+            imprisoned_count: 0,
+        };
+        assert_eq!(player_bets.bets_to_imprison(&round_stats(Some(0), false)), 0);
+        assert_eq!(player_bets.bets_to_imprison(&round_stats(Some(17), true)), 0);
+
+        let zero = round_stats(Some(0), true);
+        assert_eq!(player_bets.imprison_bets(&zero), 2);
+        assert_eq!(player_bets.round, 2);
+        // Red wins on 1 but only returns its stake; Odd does too.
+        assert_eq!(player_bets.total_payout(1, &FeeSchedule::CURRENT).unwrap(), 150);
+        // A second zero loses them for good.
+        assert_eq!(player_bets.bets_to_imprison(&zero), 0);
+    }
+
+    #[test]
+    fn recent_numbers_keep_the_last_rounds_most_recent_first() {
+        let mut game_session = GameSession::default();
+        game_session.backfill_layout(2);
+        game_session.record_recent_number(0);
+        assert_eq!(game_session.recent_numbers[..2], [0, NO_RECENT_NUMBER]);
+
+        for winning_number in 1..25u8 {
+            game_session.record_recent_number(winning_number);
+        }
+        assert_eq!(game_session.recent_numbers[0], 24);
+        assert_eq!(game_session.recent_numbers[RECENT_NUMBERS_LEN - 1], 5);
+    }
+
+    /// A session written by the original program, grown with zeros as `migrate_session_to_table`
+    /// does, keeps its round status and gets every non-zero default backfilled.
+    #[test]
+    fn original_sessions_migrate_with_their_status_and_defaults() {
+        #[derive(AnchorSerialize)]
+        struct OriginalSession {
+            authority: Pubkey,
+            current_round: u64,
+            round_start_time: i64,
+            round_status: u8,
+            winning_number: Option<u8>,
+            bets_closed_timestamp: i64,
+            get_random_timestamp: i64,
+            bump: u8,
+            last_bettor: Option<Pubkey>,
+            last_completed_round: u64,
+        }
+        let original = OriginalSession {
+            authority: Pubkey::new_unique(),
+            current_round: 42,
+            round_start_time: 1,
+            // `Completed` in the original enum.
+            round_status: 3,
+            winning_number: Some(17),
+            bets_closed_timestamp: 2,
+            get_random_timestamp: 3,
+            bump: 254,
+            last_bettor: None,
+            last_completed_round: 42,
+        };
+        let mut data = GameSession::DISCRIMINATOR.to_vec();
+        original.serialize(&mut data).unwrap();
+        data.resize(8 + std::mem::size_of::<GameSession>(), 0);
+
+        let mut game_session = GameSession::try_deserialize(&mut &data[..]).unwrap();
+        assert_eq!(game_session.layout_version, 0);
+        game_session.backfill_layout(game_session.layout_version);
+        assert_eq!(game_session.round_status, RoundStatus::Completed);
+        assert_eq!((game_session.current_round, game_session.winning_number), (42, Some(17)));
+        assert_eq!(game_session.slot_drift_tolerance_bps, DEFAULT_SLOT_DRIFT_TOLERANCE_BPS);
+        assert_eq!(game_session.void_timeout_slots, DEFAULT_VOID_TIMEOUT_SLOTS);
+        assert_eq!(game_session.finality_slots, DEFAULT_FINALITY_SLOTS);
+        assert_eq!(game_session.recent_numbers, [NO_RECENT_NUMBER; RECENT_NUMBERS_LEN]);
+    }
+
+    #[test]
+    fn private_tables_admit_only_listed_players() {
+        let (listed, outsider) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut game_session = GameSession::default();
+        assert!(game_session.admits(&outsider, None));
+
+        let access_list = TableAccessList { game_session: Pubkey::new_unique(), players: vec![listed], bump: 255 };
+        game_session.access_list_enabled = true;
+        assert!(game_session.admits(&listed, Some(&access_list)));
+        assert!(!game_session.admits(&outsider, Some(&access_list)));
+        // The list must be passed once the table is private.
+        assert!(!game_session.admits(&listed, None));
+    }
+
+    #[test]
+    fn carryover_rides_only_winning_riding_bets_with_their_payout() {
+        let bet = |amount, bet_type, anchor| Bet { amount, bet_type, numbers: [anchor, 0, 0, 0] };
+        let player_bets = PlayerBets {
+            player: Pubkey::new_unique(),
+            game_session: Pubkey::new_unique(),
+            round: 4,
+            vault: Pubkey::new_unique(),
+            // A straight on 17, Red and a straight on 5.
+            bets: vec![bet(10, 0, 17), bet(100, 6, 0), bet(20, 0, 5)],
+            claimed_round: 0,
+            bump: 255,
+            rent_sponsored: false,
+            imprisoned_count: 0,
+        };
+        let mut carryover = Carryover {
+            player: player_bets.player,
+            game_session: player_bets.game_session,
+            round: 4,
+            riding_mask: 0b101,
+            streak: 0,
+            streak_staked: 0,
+            bump: 255,
+        };
+
+        let rides = carryover.rides(&player_bets, 17, &FeeSchedule::CURRENT).unwrap();
+        assert_eq!(rides.len(), 1);
+        assert_eq!((rides[0].amount, rides[0].numbers), (360, [17, 0, 0, 0]));
+        // Red wins on 1 but does not ride.
+        assert!(carryover.rides(&player_bets, 1, &FeeSchedule::CURRENT).unwrap().is_empty());
+
+        // Marks of another round do not apply.
+        carryover.round = 3;
+        assert!(carryover.rides(&player_bets, 17, &FeeSchedule::CURRENT).unwrap().is_empty());
+    }
+
+    /// Heap taken by deserializing a `Vec` of `len` elements: Borsh reserves at most 4 KiB up
+    /// front and then grows by doubling, and the program's bump allocator never frees the
+    /// buffers it outgrows.
+    fn vec_heap<T>(len: usize) -> usize {
+        let size = std::mem::size_of::<T>();
+        let mut capacity = len.min(4096 / size).max(1);
+        let mut heap = capacity * size;
+        while capacity < len {
+            capacity *= 2;
+            heap += capacity * size;
+        }
+        heap
+    }
+
+    #[test]
+    fn bets_and_claims_fit_the_default_heap() {
+        // A quarter of the heap stays free for the runtime's account infos, the instruction
+        // data and the CPIs.
+        let budget = anchor_lang::solana_program::entrypoint::HEAP_LENGTH * 3 / 4;
+        let metadata = vec_heap::<u8>(MAX_ROUND_LABEL_LEN) + vec_heap::<u8>(MAX_ROUND_URI_LEN);
+        let open_round_stats = vec_heap::<[u64; WHEEL_POCKETS]>(BET_TYPE_COUNT) + metadata;
+        let settled_round_stats = metadata;
+        // Every heap-backed account each instruction can deserialize, each at its largest.
+        let shared = vec_heap::<Bet>(MAX_BETS_PER_ROUND) +
+            vec_heap::<QueuedEvent>(EVENT_QUEUE_LEN) +
+            vec_heap::<Pubkey>(MAX_TABLE_ACCESS_LIST_LEN);
+        let place_bet = shared + open_round_stats + settled_round_stats;
+        // The claimed round's stats, and the next round's when winnings ride.
+        let claim_my_winnings = shared +
+            settled_round_stats +
+            open_round_stats +
+            vec_heap::<RoundHistoryEntry>(ROUND_HISTORY_LEN);
+
+        for (instruction, heap) in [("place_bet", place_bet), ("claim_my_winnings", claim_my_winnings)] {
+            assert!(heap <= budget, "{instruction} needs {heap} bytes of heap, over its {budget} budget");
+        }
+    }
+
+    #[test]
+    fn full_event_queue_overwrites_the_oldest_record_and_fits_its_space() {
+        let mut event_queue = EventQueue {
+            game_session: Pubkey::new_unique(),
+            consumer: Pubkey::new_unique(),
+            head: 0,
+            tail: 0,
+            dropped: 0,
+            entries: Vec::new(),
+            bump: 255,
+        };
+        for round in 0..=EVENT_QUEUE_LEN as u64 {
+            event_queue.push(QueuedEvent { round, player: Pubkey::new_unique(), ..Default::default() });
+        }
+        assert_eq!(event_queue.pending(), EVENT_QUEUE_LEN as u64);
+        assert_eq!(event_queue.dropped, 1);
+        assert!(event_queue.get(0).is_none());
+        assert_eq!(event_queue.get(1).unwrap().round, 1);
+
+        assert_eq!(event_queue.consume(u64::MAX), EVENT_QUEUE_LEN as u64);
+        assert!(event_queue.get(EVENT_QUEUE_LEN as u64).is_none());
+
+        let mut data = Vec::new();
+        event_queue.try_serialize(&mut data).unwrap();
+        assert_eq!(data.len(), EventQueue::SPACE);
+    }
+
+    #[test]
+    fn withdrawal_requests_only_unlock_on_their_own_table() {
+        let (table, other_table) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let request = WithdrawalRequest {
+            provider_state: Pubkey::new_unique(),
+            game_session: table,
+            unlock_round: 5,
+            requested_at: 0,
+            requested_slot: 0,
+            bump: 255,
+        };
+        let mut game_session = GameSession { last_completed_round: 4, ..Default::default() };
+        assert!(!request.round_completed(&table, &game_session));
+        game_session.last_completed_round = 5;
+        assert!(request.round_completed(&table, &game_session));
+        assert!(!request.round_completed(&other_table, &game_session));
+
+        let timeout_only = WithdrawalRequest { unlock_round: u64::MAX, ..request };
+        assert!(!timeout_only.round_completed(&table, &game_session));
     }
 }
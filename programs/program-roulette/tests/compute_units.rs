@@ -0,0 +1,434 @@
+//! Compute units of the hot-path instructions, measured on the built program.
+//!
+//! Runs the real SBF program in litesvm through a full round: vault creation and deposits, the
+//! bets of a player, settlement and the claim. Each measured instruction is sent alone, so the
+//! transaction's `compute_units_consumed` is the instruction's own cost, and the test fails when
+//! any of them exceeds its budget in `COMPUTE_BUDGETS`. Build the program first (`anchor build`);
+//! the binary is read from `SBF_OUT_DIR`, or `target/deploy` by default.
+
+use std::path::{Path, PathBuf};
+
+use anchor_lang::prelude::{Clock, Pubkey};
+use anchor_lang::solana_program::{
+    hash::hash,
+    instruction::Instruction,
+    message::Message,
+    program_option::COption,
+    program_pack::Pack,
+    slot_hashes::SlotHashes,
+    system_program,
+    sysvar,
+};
+use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
+use anchor_spl::associated_token::{self, get_associated_token_address};
+use anchor_spl::token::spl_token;
+use litesvm::{types::TransactionMetadata, LiteSVM};
+use program_roulette::{
+    client::BetSessionBuilder,
+    constants::{GAME_ADMIN_PUBKEY, MIN_SETTLEMENT_SLOTS},
+    seeds,
+    state::{Bet, GameSession, VaultAccount},
+};
+use solana_account::Account;
+use solana_transaction::Transaction;
+
+/// Most compute units each instruction may use. A regression in per-bet or per-claim
+/// bookkeeping shows up here before it reaches the 200k default limit clients rely on.
+const COMPUTE_BUDGETS: &[(&str, u64)] = &[
+    ("initialize_and_provide_liquidity", 60_000),
+    ("provide_liquidity", 40_000),
+    ("withdraw_liquidity", 50_000),
+    ("join_and_bet", 120_000),
+    ("place_bet", 90_000),
+    ("claim_my_winnings", 90_000),
+];
+
+const LIQUIDITY: u64 = 1_000_000_000_000;
+const DEPOSIT: u64 = 1_000_000_000;
+const BET: u64 = 1_000_000;
+const SERVER_SEED: [u8; 32] = [7; 32];
+
+/// Red, Black and a straight bet on 0 together win whatever the wheel lands on.
+const BETS: [Bet; 3] = [
+    Bet { amount: BET, bet_type: 6, numbers: [0; 4] },
+    Bet { amount: BET, bet_type: 7, numbers: [0; 4] },
+    Bet { amount: BET, bet_type: 0, numbers: [0; 4] },
+];
+
+#[test]
+fn hot_path_instructions_stay_within_their_compute_budgets() {
+    let program = std::env::var_os("SBF_OUT_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../target/deploy"))
+        .join("program_roulette.so");
+    if !program.exists() {
+        eprintln!("skipping: {} not found; build the program with `anchor build`", program.display());
+        return;
+    }
+
+    let mut table = Table::new(&program);
+    let mut consumed = Vec::new();
+
+    let creator = table.funded_signer();
+    consumed.push(("initialize_and_provide_liquidity", table.create_vault(creator)));
+    let provider = table.funded_signer();
+    consumed.push(("provide_liquidity", table.provide_liquidity(provider)));
+    consumed.push(("withdraw_liquidity", table.withdraw_liquidity(provider)));
+
+    table.start_round();
+    let player = table.funded_signer();
+    let bets = table.place_bets(player);
+    consumed.push(("join_and_bet", bets[0]));
+    consumed.extend(bets[1..].iter().map(|&units| ("place_bet", units)));
+    table.settle_round();
+    consumed.push(("claim_my_winnings", table.claim(player)));
+
+    for (instruction, units) in &consumed {
+        println!("{instruction}: {units} CU");
+    }
+    let over_budget: Vec<_> = consumed
+        .iter()
+        .filter(|(instruction, units)| *units > budget(instruction))
+        .map(|(instruction, units)| format!("{instruction}: {units} CU (budget {})", budget(instruction)))
+        .collect();
+    assert!(over_budget.is_empty(), "over their compute budget: {over_budget:?}");
+}
+
+fn budget(instruction: &str) -> u64 {
+    COMPUTE_BUDGETS.iter().find(|(name, _)| *name == instruction).unwrap().1
+}
+
+/// Table 0 with one vault of a fresh mint, driven by the game admin. Signatures are not
+/// verified, so every account can sign without its key.
+struct Table {
+    svm: LiteSVM,
+    game_session: Pubkey,
+    token_mint: Pubkey,
+    vault: Pubkey,
+    vault_token_account: Pubkey,
+}
+
+impl Table {
+    fn new(program: &Path) -> Self {
+        let mut svm = LiteSVM::new().with_sigverify(false).with_transaction_history(0);
+        svm.add_program_from_file(program_roulette::ID, program).unwrap();
+        let token_mint = Pubkey::new_unique();
+        let vault = seeds::vault(&token_mint).0;
+        let mut table = Self {
+            svm,
+            game_session: seeds::game_session().0,
+            token_mint,
+            vault,
+            vault_token_account: Pubkey::new_unique(),
+        };
+        table.svm.airdrop(&GAME_ADMIN_PUBKEY, 100_000_000_000).unwrap();
+        table.set_mint();
+
+        let admin = GAME_ADMIN_PUBKEY;
+        let game_session = table.game_session;
+        table.send(admin, vec![
+            instruction(
+                program_roulette::accounts::InitializeGameSession {
+                    authority: admin,
+                    game_session,
+                    system_program: system_program::ID,
+                    rent: sysvar::rent::ID,
+                },
+                program_roulette::instruction::InitializeGameSession { table_id: 0 }.data()
+            ),
+            instruction(
+                program_roulette::accounts::InitializeProtocolStats {
+                    authority: admin,
+                    game_session,
+                    protocol_stats: seeds::protocol_stats().0,
+                    system_program: system_program::ID,
+                },
+                program_roulette::instruction::InitializeProtocolStats {}.data()
+            ),
+            instruction(
+                program_roulette::accounts::InitializeConfig {
+                    authority: admin,
+                    game_session,
+                    config: seeds::config().0,
+                    system_program: system_program::ID,
+                },
+                program_roulette::instruction::InitializeConfig {}.data()
+            ),
+            instruction(
+                program_roulette::accounts::InitializeSolFeeVault {
+                    authority: admin,
+                    game_session,
+                    sol_fee_vault: seeds::sol_fee_vault().0,
+                    system_program: system_program::ID,
+                },
+                program_roulette::instruction::InitializeSolFeeVault {}.data()
+            ),
+        ]);
+        table
+    }
+
+    /// A new signer with SOL for fees and rent, and `LIQUIDITY` tokens in its associated
+    /// token account.
+    fn funded_signer(&mut self) -> Pubkey {
+        let signer = Pubkey::new_unique();
+        self.svm.airdrop(&signer, 10_000_000_000).unwrap();
+        self.set_token_account(get_associated_token_address(&signer, &self.token_mint), signer, LIQUIDITY);
+        signer
+    }
+
+    fn create_vault(&mut self, creator: Pubkey) -> u64 {
+        // The vault takes over a token account of the creator's.
+        self.set_token_account(self.vault_token_account, creator, 0);
+        self.send(creator, vec![instruction(
+            program_roulette::accounts::InitializeAndProvideLiquidity {
+                game_session: self.game_session,
+                config: seeds::config().0,
+                token_mint: self.token_mint,
+                vault: self.vault,
+                provider_state: seeds::provider_state(&self.vault, &creator).0,
+                protocol_stats: seeds::protocol_stats().0,
+                provider_token_account: get_associated_token_address(&creator, &self.token_mint),
+                vault_token_account: self.vault_token_account,
+                liquidity_provider: creator,
+                sol_fee_vault: seeds::sol_fee_vault().0,
+                system_program: system_program::ID,
+                token_program: spl_token::ID,
+                rent: sysvar::rent::ID,
+            },
+            program_roulette::instruction::InitializeAndProvideLiquidity { amount: LIQUIDITY }.data()
+        )])
+    }
+
+    fn provide_liquidity(&mut self, provider: Pubkey) -> u64 {
+        self.send(provider, vec![instruction(
+            program_roulette::accounts::ProvideLiquidity {
+                game_session: self.game_session,
+                config: seeds::config().0,
+                vault: self.vault,
+                token_mint: self.token_mint,
+                provider_state: seeds::provider_state(&self.vault, &provider).0,
+                provider_token_account: get_associated_token_address(&provider, &self.token_mint),
+                vault_token_account: self.vault_token_account,
+                liquidity_provider: provider,
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+            },
+            program_roulette::instruction::ProvideLiquidity { amount: DEPOSIT }.data()
+        )])
+    }
+
+    fn withdraw_liquidity(&mut self, provider: Pubkey) -> u64 {
+        self.send(provider, vec![instruction(
+            program_roulette::accounts::WithdrawLiquidity {
+                vault: self.vault,
+                provider_state: seeds::provider_state(&self.vault, &provider).0,
+                position_asset: None,
+                withdrawal_request: None,
+                game_session: self.game_session,
+                referrer_token_account: None,
+                token_mint: self.token_mint,
+                provider_token_account: get_associated_token_address(&provider, &self.token_mint),
+                vault_token_account: self.vault_token_account,
+                liquidity_provider: provider,
+                token_program: spl_token::ID,
+            },
+            program_roulette::instruction::WithdrawLiquidity {}.data()
+        )])
+    }
+
+    fn start_round(&mut self) {
+        let admin = GAME_ADMIN_PUBKEY;
+        let round = self.game_session_state().current_round + 1;
+        self.send(admin, vec![instruction(
+            program_roulette::accounts::StartNewRound {
+                game_session: self.game_session,
+                starter: admin,
+                protocol_stats: seeds::protocol_stats().0,
+                operator_stats: seeds::operator_stats(&admin).0,
+                round_stats: seeds::round_stats(&self.game_session, round).0,
+                config: seeds::config().0,
+                system_program: system_program::ID,
+            },
+            program_roulette::instruction::StartNewRound {
+                seed_commitment: hash(&SERVER_SEED).to_bytes(),
+                label: None,
+                uri: None,
+            }.data()
+        )]);
+    }
+
+    /// Places `BETS` for `player`, one per transaction, and returns their compute units.
+    fn place_bets(&mut self, player: Pubkey) -> Vec<u64> {
+        let vault = self.state::<VaultAccount>(&self.vault);
+        BetSessionBuilder::new(player, &self.game_session_state(), &vault, spl_token::ID)
+            .bets(BETS)
+            .instructions()
+            .into_iter()
+            .map(|instruction| self.send(player, vec![instruction]))
+            .collect()
+    }
+
+    /// Closes the round's bets and draws its winning number with the server seed.
+    fn settle_round(&mut self) {
+        let admin = GAME_ADMIN_PUBKEY;
+        let game_session = self.game_session;
+        let round = self.game_session_state().current_round;
+        let round_stats = seeds::round_stats(&game_session, round).0;
+        self.send(admin, vec![instruction(
+            program_roulette::accounts::CloseBets {
+                game_session,
+                closer: admin,
+                round_stats,
+                config: seeds::config().0,
+                operator_stats: seeds::operator_stats(&admin).0,
+                system_program: system_program::ID,
+            },
+            program_roulette::instruction::CloseBets {}.data()
+        )]);
+
+        self.advance_slots(MIN_SETTLEMENT_SLOTS + 1);
+        self.send(admin, vec![
+            set_compute_unit_limit(1_400_000),
+            instruction(
+                program_roulette::accounts::GetRandom {
+                    game_session,
+                    config: seeds::config().0,
+                    random_initiator: admin,
+                    operator_stats: seeds::operator_stats(&admin).0,
+                    round_stats,
+                    wheel_histogram: seeds::wheel_histogram(&game_session).0,
+                    round_history: seeds::round_history(&game_session).0,
+                    event_queue: None,
+                    round_result: Some(seeds::round_result(&game_session, round).0),
+                    randomness_account: None,
+                    slot_hashes: sysvar::slot_hashes::ID,
+                    crank_vault: None,
+                    crank_vault_exposure: None,
+                    crank_token_mint: None,
+                    crank_vault_token_account: None,
+                    cranker_token_account: None,
+                    token_program: None,
+                    next_round_stats: None,
+                    protocol_stats: None,
+                    system_program: system_program::ID,
+                },
+                program_roulette::instruction::GetRandom { server_seed: SERVER_SEED }.data()
+            ),
+        ]);
+        assert_eq!(self.game_session_state().last_completed_round, round, "the round did not settle");
+    }
+
+    fn claim(&mut self, player: Pubkey) -> u64 {
+        let game_session = self.game_session;
+        let round = self.game_session_state().last_completed_round;
+        self.send(player, vec![instruction(
+            program_roulette::accounts::ClaimMyWinnings {
+                player,
+                game_session,
+                player_bets: seeds::player_bets(&game_session, &player).0,
+                round_stats: seeds::round_stats(&game_session, round).0,
+                round_history: seeds::round_history(&game_session).0,
+                vault: self.vault,
+                vault_token_account: self.vault_token_account,
+                player_token_account: get_associated_token_address(&player, &self.token_mint),
+                token_mint: self.token_mint,
+                payout_debt: None,
+                event_queue: None,
+                token_program: spl_token::ID,
+                associated_token_program: Some(associated_token::ID),
+                carryover: None,
+                next_round_stats: None,
+                vault_exposure: None,
+                protocol_stats: None,
+                player_stats: None,
+                config: None,
+                table_access_list: None,
+                system_program: system_program::ID,
+            },
+            program_roulette::instruction::ClaimMyWinnings { round_to_claim: round }.data()
+        )])
+    }
+
+    /// Moves the clock on by `slots` slots, half a second each, with as many slot hashes
+    /// behind it as settlement mixes in.
+    fn advance_slots(&mut self, slots: u64) {
+        let mut clock = self.svm.get_sysvar::<Clock>();
+        clock.slot += slots;
+        clock.unix_timestamp += (slots / 2 + 1) as i64;
+        self.svm.set_sysvar(&clock);
+        let slot_hashes: Vec<_> = (0..4)
+            .map(|age| (clock.slot - 1 - age, hash(&(clock.slot - 1 - age).to_le_bytes())))
+            .collect();
+        self.svm.set_sysvar(&SlotHashes::new(&slot_hashes));
+    }
+
+    /// Sends `instructions` in one transaction paid by `payer` and returns its compute units.
+    fn send(&mut self, payer: Pubkey, instructions: Vec<Instruction>) -> u64 {
+        let message = Message::new_with_blockhash(&instructions, Some(&payer), &self.svm.latest_blockhash());
+        let result = self.svm.send_transaction(Transaction::new_unsigned(message));
+        let meta: TransactionMetadata = result
+            .unwrap_or_else(|failed| panic!("{:?}\n{}", failed.err, failed.meta.pretty_logs()));
+        meta.compute_units_consumed
+    }
+
+    fn game_session_state(&self) -> GameSession {
+        self.state(&self.game_session)
+    }
+
+    fn state<T: AccountDeserialize>(&self, address: &Pubkey) -> T {
+        let account = self.svm.get_account(address).unwrap();
+        T::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    fn set_mint(&mut self) {
+        let mint = spl_token::state::Mint {
+            mint_authority: COption::Some(GAME_ADMIN_PUBKEY),
+            supply: u64::MAX / 2,
+            decimals: 9,
+            is_initialized: true,
+            freeze_authority: COption::None,
+        };
+        self.set_packed(self.token_mint, mint);
+    }
+
+    fn set_token_account(&mut self, address: Pubkey, owner: Pubkey, amount: u64) {
+        let token_account = spl_token::state::Account {
+            mint: self.token_mint,
+            owner,
+            amount,
+            delegate: COption::None,
+            state: spl_token::state::AccountState::Initialized,
+            is_native: COption::None,
+            delegated_amount: 0,
+            close_authority: COption::None,
+        };
+        self.set_packed(address, token_account);
+    }
+
+    fn set_packed<T: Pack>(&mut self, address: Pubkey, state: T) {
+        let mut data = vec![0; T::LEN];
+        state.pack_into_slice(&mut data);
+        let account = Account {
+            lamports: self.svm.minimum_balance_for_rent_exemption(T::LEN),
+            data,
+            owner: spl_token::ID,
+            executable: false,
+            rent_epoch: 0,
+        };
+        self.svm.set_account(address, account).unwrap();
+    }
+}
+
+fn instruction(accounts: impl ToAccountMetas, data: Vec<u8>) -> Instruction {
+    Instruction {
+        program_id: program_roulette::ID,
+        accounts: accounts.to_account_metas(None),
+        data,
+    }
+}
+
+/// `ComputeBudgetInstruction::SetComputeUnitLimit`, for the unmeasured settlement.
+fn set_compute_unit_limit(units: u32) -> Instruction {
+    let data = [&[2u8][..], &units.to_le_bytes()].concat();
+    Instruction::new_with_bytes(program_roulette::client::COMPUTE_BUDGET_PROGRAM_ID, &data, vec![])
+}
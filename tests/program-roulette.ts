@@ -3,8 +3,19 @@ import { Program } from "@coral-xyz/anchor";
 import { RouletteGame } from "../target/types/roulette_game"; // Path to the IDL types
 import { assert } from "chai";
 import { PublicKey, SystemProgram, Keypair, SYSVAR_RENT_PUBKEY } from "@solana/web3.js";
-import { TOKEN_PROGRAM_ID, createMint, createAssociatedTokenAccount, mintTo, getAccount, createAssociatedTokenAccountInstruction } from "@solana/spl-token";
+import { TOKEN_PROGRAM_ID, createMint, createAssociatedTokenAccount, mintTo, getAccount, approve, createAssociatedTokenAccountInstruction } from "@solana/spl-token";
 import { BN } from "bn.js";
+import { createHash } from "crypto";
+
+// Compute-unit ceilings for the hot-path instructions. A test fails when an instruction exceeds
+// its budget, so regressions from new per-bet bookkeeping show up before they reach the
+// 200k default limit that clients rely on.
+const COMPUTE_BUDGETS = {
+  provideLiquidity: 40_000,
+  placeBet: 90_000,
+  withdrawProviderRevenue: 40_000,
+  withdrawLiquidity: 50_000,
+};
 
 describe("roulette-game", () => {
   // Configure the client to use the local cluster.
@@ -14,9 +25,6 @@ describe("roulette-game", () => {
   const program = anchor.workspace.RouletteGame as Program<RouletteGame>;
   const payer = provider.wallet as anchor.Wallet; // Use the wallet from Anchor.toml
 
-  // Static constant from the contract
-  const TREASURY_PUBKEY = new PublicKey("DRqMriKY4X3ggiFdx27Fotu5HebQFyRZhNasWFTzaQ78");
-
   // Test keys
   const mintAuthority = Keypair.generate();
   let tokenMint: PublicKey;
@@ -39,6 +47,13 @@ describe("roulette-game", () => {
   let claimRecordPda: PublicKey;
   const roundToCheck = new BN(1);
 
+  async function assertComputeWithin(instruction: keyof typeof COMPUTE_BUDGETS, signature: string) {
+    const tx = await provider.connection.getTransaction(signature, { commitment: "confirmed", maxSupportedTransactionVersion: 0 });
+    const consumed = tx.meta.computeUnitsConsumed;
+    console.log(`${instruction}: ${consumed} CU (budget ${COMPUTE_BUDGETS[instruction]})`);
+    assert.isAtMost(consumed, COMPUTE_BUDGETS[instruction], `${instruction} exceeded its compute budget.`);
+  }
+
   before(async () => {
     // --- Step 0: Airdrops and Token setup ---
     await provider.connection.requestAirdrop(mintAuthority.publicKey, 1 * anchor.web3.LAMPORTS_PER_SOL);
@@ -75,7 +90,7 @@ describe("roulette-game", () => {
 
     // --- Step 2: Initialization ---
     try {
-      await program.methods.initializeGameSession().accounts({
+      await program.methods.initializeGameSession(new BN(0)).accounts({
         authority: payer.publicKey,
         gameSession: gameSessionPda,
         systemProgram: SystemProgram.programId,
@@ -99,7 +114,6 @@ describe("roulette-game", () => {
           providerTokenAccount: providerOneTokenAccount,
           vaultTokenAccount: vaultTokenAccount,
           liquidityProvider: providerOne.publicKey,
-          treasuryAccount: TREASURY_PUBKEY,
           systemProgram: SystemProgram.programId,
           tokenProgram: TOKEN_PROGRAM_ID,
           rent: SYSVAR_RENT_PUBKEY,
@@ -116,7 +130,7 @@ describe("roulette-game", () => {
     }
 
     try {
-      await program.methods.initializePlayerBets().accounts({
+      await program.methods.initializePlayerBets(false).accounts({
         player: providerOne.publicKey,
         gameSession: gameSessionPda,
         playerBets: playerBetsPda,
@@ -130,7 +144,7 @@ describe("roulette-game", () => {
   it("Allows a second provider to add liquidity", async () => {
     const vaultBefore = await program.account.vaultAccount.fetch(vaultPda);
 
-    await program.methods.provideLiquidity(new BN(500_000_000_000))
+    const signature = await program.methods.provideLiquidity(new BN(500_000_000_000))
       .accounts({
         vault: vaultPda,
         tokenMint: tokenMint,
@@ -145,6 +159,7 @@ describe("roulette-game", () => {
       .rpc();
     
     console.log("Provider Two added liquidity.");
+    await assertComputeWithin("provideLiquidity", signature);
     
     const vaultAfter = await program.account.vaultAccount.fetch(vaultPda);
     const providerTwoState = await program.account.providerState.fetch(providerTwoStatePda);
@@ -156,7 +171,9 @@ describe("roulette-game", () => {
 
   it("Conducts a game round and generates rewards", async () => {
     console.log("Starting round 1...");
-    await program.methods.startNewRound().accounts({
+    const serverSeed = Keypair.generate().publicKey.toBuffer();
+    const seedCommitment = Array.from(createHash("sha256").update(serverSeed).digest());
+    await program.methods.startNewRound(seedCommitment, null, null).accounts({
       gameSession: gameSessionPda,
       starter: providerOne.publicKey,
       systemProgram: SystemProgram.programId,
@@ -167,28 +184,116 @@ describe("roulette-game", () => {
     console.log("Placing a bet...");
     const betAmount = new BN(100_000_000);
     const betOnRed = { amount: betAmount, betType: 6, numbers: [0, 0, 0, 0] };
-    await program.methods.placeBet(betOnRed).accounts({
+    const signature = await program.methods.placeBet(betOnRed).accounts({
       vault: vaultPda,
       gameSession: gameSessionPda,
       playerTokenAccount: providerOneTokenAccount,
       vaultTokenAccount: vaultTokenAccount,
       player: providerOne.publicKey,
       playerBets: playerBetsPda,
+      tokenMint: tokenMint,
       tokenProgram: TOKEN_PROGRAM_ID,
     }).rpc();
     console.log("Bet placed.");
+    await assertComputeWithin("placeBet", signature);
     
     const vaultAfterBet = await program.account.vaultAccount.fetch(vaultPda);
     // After a bet, reward_per_share_index should be > 0
     assert.ok(vaultAfterBet.rewardPerShareIndex.gt(new BN(0)), "Reward index should increase after a bet.");
   });
   
+  describe("betting from smart-wallet token accounts", () => {
+    // The smart wallet owns the tokens; bets are signed by a separate key standing in for the
+    // wallet's transaction PDA, which the wallet approves as delegate.
+    const smartWallet = Keypair.generate();
+    const walletSigner = Keypair.generate();
+    let smartWalletTokenAccount: PublicKey;
+    let walletSignerBetsPda: PublicKey;
+    const betOnBlack = { amount: new BN(50_000_000), betType: 7, numbers: [0, 0, 0, 0] };
+
+    before(async () => {
+      await provider.connection.requestAirdrop(smartWallet.publicKey, 1 * anchor.web3.LAMPORTS_PER_SOL);
+      await provider.connection.requestAirdrop(walletSigner.publicKey, 1 * anchor.web3.LAMPORTS_PER_SOL);
+      await new Promise(resolve => setTimeout(resolve, 500));
+
+      smartWalletTokenAccount = await createAssociatedTokenAccount(provider.connection, payer.payer, tokenMint, smartWallet.publicKey);
+      await mintTo(provider.connection, payer.payer, tokenMint, smartWalletTokenAccount, mintAuthority, 1_000_000_000);
+
+      [walletSignerBetsPda] = PublicKey.findProgramAddressSync([Buffer.from("player_bets"), gameSessionPda.toBuffer(), walletSigner.publicKey.toBuffer()], program.programId);
+      await program.methods.initializePlayerBets(false).accounts({
+        player: walletSigner.publicKey,
+        gameSession: gameSessionPda,
+        playerBets: walletSignerBetsPda,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      }).signers([walletSigner]).rpc();
+    });
+
+    it("Rejects a signer that is neither owner nor delegate of the token account", async () => {
+      try {
+        await program.methods.placeBet(betOnBlack).accounts({
+          vault: vaultPda,
+          gameSession: gameSessionPda,
+          playerTokenAccount: smartWalletTokenAccount,
+          vaultTokenAccount: vaultTokenAccount,
+          player: walletSigner.publicKey,
+          playerBets: walletSignerBetsPda,
+          tokenMint: tokenMint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        }).signers([walletSigner]).rpc();
+        assert.fail("Bet from an unauthorized token account should fail.");
+      } catch (e) {
+        assert.include(e.toString(), "BetSourceNotAuthorized");
+      }
+    });
+
+    it("Accepts a bet signed by an approved delegate of the smart wallet", async () => {
+      await approve(provider.connection, payer.payer, smartWalletTokenAccount, walletSigner.publicKey, smartWallet, 100_000_000);
+      const before = await getAccount(provider.connection, smartWalletTokenAccount);
+
+      await program.methods.placeBet(betOnBlack).accounts({
+        vault: vaultPda,
+        gameSession: gameSessionPda,
+        playerTokenAccount: smartWalletTokenAccount,
+        vaultTokenAccount: vaultTokenAccount,
+        player: walletSigner.publicKey,
+        playerBets: walletSignerBetsPda,
+        tokenMint: tokenMint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      }).signers([walletSigner]).rpc();
+
+      const after = await getAccount(provider.connection, smartWalletTokenAccount);
+      assert.equal(before.amount - after.amount, BigInt(50_000_000), "Bet should be debited from the smart wallet's account.");
+      const bets = await program.account.playerBets.fetch(walletSignerBetsPda);
+      assert.equal(bets.bets.length, 1, "Bet should be recorded for the signer.");
+    });
+
+    it("Rejects a bet above the delegated amount", async () => {
+      const tooLarge = { ...betOnBlack, amount: new BN(60_000_000) };
+      try {
+        await program.methods.placeBet(tooLarge).accounts({
+          vault: vaultPda,
+          gameSession: gameSessionPda,
+          playerTokenAccount: smartWalletTokenAccount,
+          vaultTokenAccount: vaultTokenAccount,
+          player: walletSigner.publicKey,
+          playerBets: walletSignerBetsPda,
+          tokenMint: tokenMint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        }).signers([walletSigner]).rpc();
+        assert.fail("Bet above the remaining allowance should fail.");
+      } catch (e) {
+        assert.include(e.toString(), "BetSourceNotAuthorized");
+      }
+    });
+  });
+
   it("Allows a provider to withdraw only revenue", async () => {
     const providerStateBefore = await program.account.providerState.fetch(providerOneStatePda);
     const providerTokenAccBefore = await getAccount(provider.connection, providerOneTokenAccount);
 
     // Call withdraw revenue
-    await program.methods.withdrawProviderRevenue()
+    const signature = await program.methods.withdrawProviderRevenue()
       .accounts({
         vault: vaultPda,
         providerState: providerOneStatePda,
@@ -201,6 +306,7 @@ describe("roulette-game", () => {
       .rpc();
     
     console.log("Provider One withdrew revenue.");
+    await assertComputeWithin("withdrawProviderRevenue", signature);
       
     const providerStateAfter = await program.account.providerState.fetch(providerOneStatePda);
     const providerTokenAccAfter = await getAccount(provider.connection, providerOneTokenAccount);
@@ -216,7 +322,7 @@ describe("roulette-game", () => {
     const providerStateBefore = await program.account.providerState.fetch(providerTwoStatePda);
     
     // Call withdraw all liquidity
-    await program.methods.withdrawLiquidity()
+    const signature = await program.methods.withdrawLiquidity()
       .accounts({
         vault: vaultPda,
         providerState: providerTwoStatePda,
@@ -230,6 +336,7 @@ describe("roulette-game", () => {
       .rpc();
 
     console.log("Provider Two withdrew all liquidity.");
+    await assertComputeWithin("withdrawLiquidity", signature);
 
     const vaultAfter = await program.account.vaultAccount.fetch(vaultPda);
     